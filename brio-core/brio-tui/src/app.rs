@@ -1,4 +1,7 @@
 use ratatui::widgets::ListState;
+use std::collections::HashMap;
+
+use crate::messages::InferenceDelta;
 
 pub struct App {
     pub messages: Vec<String>,
@@ -7,6 +10,8 @@ pub struct App {
     pub scroll_state: ListState,
     pub is_running: bool,
     pub connection_status: ConnectionStatus,
+    /// Index of the message each live inference stream is writing to.
+    pub live_streams: HashMap<u64, usize>,
 }
 
 #[derive(PartialEq)]
@@ -35,10 +40,30 @@ impl App {
             scroll_state: ListState::default(),
             is_running: true,
             connection_status: ConnectionStatus::Disconnected,
+            live_streams: HashMap::new(),
         }
     }
 
     pub fn add_message(&mut self, msg: String) {
         self.messages.push(msg);
     }
+
+    /// Appends streamed text to the stream's message, starting a new one if needed.
+    pub fn apply_delta(&mut self, delta: InferenceDelta) {
+        let index = match self.live_streams.get(&delta.stream_id) {
+            Some(&index) => index,
+            None => {
+                let agent = delta.agent_id.as_deref().unwrap_or("Agent");
+                self.add_message(format!("{}: ", agent));
+                let index = self.messages.len() - 1;
+                self.live_streams.insert(delta.stream_id, index);
+                index
+            }
+        };
+
+        self.messages[index].push_str(&delta.delta);
+        if delta.done {
+            self.live_streams.remove(&delta.stream_id);
+        }
+    }
 }
@@ -18,7 +18,7 @@ mod ui;
 
 use app::{App, ConnectionStatus, InputMode};
 use network::{Network, NetworkEvent};
-use messages::{ClientMessage, SessionAction, SessionParams};
+use messages::{ClientMessage, KernelMessage, SessionAction, SessionParams};
 use ui::ui;
 
 #[tokio::main]
@@ -131,9 +131,10 @@ async fn run_app<B: Backend>(
         // Process network events
         while let Ok(net_event) = rx.try_recv() {
             match net_event {
-                NetworkEvent::MessageReceived(msg) => {
-                    app.add_message(format!("Kernel: {}", msg));
-                }
+                NetworkEvent::MessageReceived(msg) => match serde_json::from_str(&msg) {
+                    Ok(KernelMessage::InferenceDelta(delta)) => app.apply_delta(delta),
+                    Err(_) => app.add_message(format!("Kernel: {}", msg)),
+                },
                 NetworkEvent::ConnectionEstablished => {
                     app.connection_status = ConnectionStatus::Connected;
                     app.add_message("Connected to Brio Kernel.".into());
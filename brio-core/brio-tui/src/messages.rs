@@ -29,3 +29,18 @@ pub struct SessionParams {
     pub base_path: Option<String>,
     pub session_id: Option<String>,
 }
+
+/// Structured messages pushed by the kernel.
+#[derive(Debug, Clone, Deserialize)]
+pub enum KernelMessage {
+    InferenceDelta(InferenceDelta),
+}
+
+/// A piece of text streamed from an agent's chat completion.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InferenceDelta {
+    pub stream_id: u64,
+    pub agent_id: Option<String>,
+    pub delta: String,
+    pub done: bool,
+}
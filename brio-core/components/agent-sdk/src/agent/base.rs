@@ -7,7 +7,7 @@ use crate::config::AgentConfig;
 use crate::engine::AgentEngineBuilder;
 use crate::error::{AgentError, InferenceError};
 use crate::tools::ToolRegistry;
-use crate::types::{InferenceResponse, Message, TaskContext};
+use crate::types::{InferenceResponse, Message, RequestOptions, TaskContext, ToolDefinition};
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -106,6 +106,7 @@ pub enum StandardAgentError {
 ///             model: "test".to_string(),
 ///             tokens_used: None,
 ///             finish_reason: Some("stop".to_string()),
+///             tool_calls: Vec::new(),
 ///         })
 ///     }
 /// }
@@ -145,6 +146,15 @@ pub trait StandardAgent: Clone + Send + Sync {
         ToolRegistry::new()
     }
 
+    /// Returns the sampling and output controls for this agent's requests.
+    ///
+    /// Implementations of [`perform_inference`](Self::perform_inference)
+    /// should send these with every request. The default uses the provider's
+    /// defaults.
+    fn request_options(&self) -> RequestOptions {
+        RequestOptions::default()
+    }
+
     /// Performs inference using the AI interface.
     ///
     /// # Arguments
@@ -160,6 +170,25 @@ pub trait StandardAgent: Clone + Send + Sync {
         model: &str,
         history: &[Message],
     ) -> Result<InferenceResponse, AgentError>;
+
+    /// Performs inference offering the given tools as structured definitions.
+    ///
+    /// Agents whose inference interface supports native tool calling should
+    /// override this. The default ignores the tools and calls
+    /// [`perform_inference`](Self::perform_inference), leaving the model to
+    /// invoke tools through the prompt's text format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inference fails.
+    fn perform_inference_with_tools(
+        &self,
+        model: &str,
+        history: &[Message],
+        _tools: &[ToolDefinition],
+    ) -> Result<InferenceResponse, AgentError> {
+        self.perform_inference(model, history)
+    }
 }
 
 /// Runs a standard agent with the given context and configuration.
@@ -220,6 +249,13 @@ where
     // Build system prompt (must be done before moving tools)
     let system_prompt = agent.build_prompt(context, &tools, config);
 
+    // Tools offered to the model as structured definitions
+    let tool_definitions = if agent_config.native_tools {
+        tools.definitions()
+    } else {
+        Vec::new()
+    };
+
     // Clone agent for the inference closure (moved into the closure)
     let agent_arc = Arc::new(agent.clone());
 
@@ -233,7 +269,11 @@ where
     // Create inference function that captures the Arc
     let inference_fn =
         move |model: &str, history: &[Message]| -> Result<InferenceResponse, AgentError> {
-            agent_arc.perform_inference(model, history)
+            if tool_definitions.is_empty() {
+                agent_arc.perform_inference(model, history)
+            } else {
+                agent_arc.perform_inference_with_tools(model, history, &tool_definitions)
+            }
         };
 
     // Run the agent
@@ -306,6 +346,7 @@ mod tests {
                 model: "test-model".to_string(),
                 tokens_used: None,
                 finish_reason: Some("stop".to_string()),
+                tool_calls: Vec::new(),
             })
         }
     }
@@ -4,8 +4,8 @@ use crate::agent::tools::branch::callbacks::{
     BranchCreationCallback, BranchCreationConfig, BranchListCallback, BranchToolError,
 };
 use crate::error::ToolError;
-use crate::tools::Tool;
 use crate::tools::constants::branch;
+use crate::tools::{Tool, string_arguments_schema};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Write as _;
@@ -112,6 +112,20 @@ Limitations:
         )
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(
+            &[
+                ("name", "Unique name for the branch"),
+                ("parent", "Parent branch ID to branch from"),
+                (
+                    "inherit_config",
+                    "\"false\" to start without the parent configuration",
+                ),
+            ],
+            &["name"],
+        )
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         // Parse configuration
         let config = parse_branch_config(args).map_err(|e| ToolError::ExecutionFailed {
@@ -172,6 +186,10 @@ Example:
         )
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(&[], &[])
+    }
+
     fn execute(&self, _args: &HashMap<String, String>) -> Result<String, ToolError> {
         let branches = (self.list_callback)().map_err(|e| ToolError::ExecutionFailed {
             tool: branch::LIST_BRANCHES.to_string(),
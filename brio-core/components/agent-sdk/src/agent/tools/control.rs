@@ -4,8 +4,8 @@
 //! such as marking tasks as complete.
 
 use crate::error::ToolError;
-use crate::tools::Tool;
 use crate::tools::constants::control;
+use crate::tools::{Tool, string_arguments_schema};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -41,6 +41,13 @@ impl Tool for DoneTool {
         Cow::Borrowed("<done>summary of completion</done> - Mark task as complete")
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(
+            &[("summary", "Summary of the completed work")],
+            &["summary"],
+        )
+    }
+
     fn execute(&self, _args: &HashMap<String, String>) -> Result<String, ToolError> {
         Ok("Task marked as complete".to_string())
     }
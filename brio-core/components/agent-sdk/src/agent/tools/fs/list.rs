@@ -3,7 +3,7 @@
 use crate::agent::tools::fs::get_base_dir;
 use crate::error::ToolError;
 use crate::tools::constants::fs;
-use crate::tools::{Tool, validate_path};
+use crate::tools::{Tool, string_arguments_schema, validate_path};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -55,6 +55,10 @@ impl Tool for ListDirectoryTool {
         Cow::Borrowed(r#"<ls path="path/to/directory" /> - List directory contents"#)
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(&[("path", "Path of the directory to list")], &["path"])
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         let path_str = args
             .get("path")
@@ -3,7 +3,7 @@
 use crate::agent::tools::fs::get_base_dir;
 use crate::error::{FileSystemError, ToolError};
 use crate::tools::constants::fs;
-use crate::tools::{Tool, validate_file_size, validate_path};
+use crate::tools::{Tool, string_arguments_schema, validate_file_size, validate_path};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -54,6 +54,10 @@ impl Tool for ReadFileTool {
         Cow::Borrowed(r#"<read_file path="path/to/file" /> - Read content from a file"#)
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(&[("path", "Path of the file to read")], &["path"])
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         let path_str = args
             .get("path")
@@ -3,7 +3,7 @@
 use crate::agent::tools::fs::get_base_dir;
 use crate::error::ToolError;
 use crate::tools::constants::fs;
-use crate::tools::{Tool, validate_path};
+use crate::tools::{Tool, string_arguments_schema, validate_path};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -42,6 +42,16 @@ impl Tool for WriteFileTool {
         )
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(
+            &[
+                ("path", "Path of the file to write"),
+                ("content", "Full content to write to the file"),
+            ],
+            &["path", "content"],
+        )
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         let path_str = args
             .get("path")
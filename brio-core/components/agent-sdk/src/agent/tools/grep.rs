@@ -7,7 +7,7 @@
 use crate::agent::tools::fs::get_base_dir;
 use crate::error::{FileSystemError, ToolError};
 use crate::tools::constants::grep;
-use crate::tools::{Tool, validate_file_size, validate_path};
+use crate::tools::{Tool, string_arguments_schema, validate_file_size, validate_path};
 use regex::RegexBuilder;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -194,6 +194,18 @@ impl Tool for GrepTool {
         )
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(
+            &[
+                ("pattern", "Regular expression to search for"),
+                ("path", "File or directory to search"),
+                ("case_insensitive", "\"true\" to ignore case"),
+                ("max_results", "Maximum number of matches to return"),
+            ],
+            &["pattern", "path"],
+        )
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         // Extract required arguments
         let pattern_str = args
@@ -5,7 +5,7 @@
 
 use crate::error::ToolError;
 use crate::tools::constants::shell;
-use crate::tools::{Tool, validate_shell_command};
+use crate::tools::{Tool, string_arguments_schema, validate_shell_command};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
@@ -74,6 +74,10 @@ impl Tool for ShellTool {
         Cow::Borrowed("<shell>command</shell> - Execute a shell command")
     }
 
+    fn parameters(&self) -> serde_json::Value {
+        string_arguments_schema(&[("command", "Shell command to execute")], &["command"])
+    }
+
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError> {
         let command = args
             .get("command")
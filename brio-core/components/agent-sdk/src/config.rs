@@ -41,6 +41,13 @@ pub struct AgentConfig {
     /// Tool-specific configurations.
     #[serde(default)]
     pub tool_config: ToolConfig,
+
+    /// Offer tools to the model as structured definitions.
+    ///
+    /// Tool invocations in the response text are still executed when the
+    /// model returns no structured tool calls.
+    #[serde(default = "default_true")]
+    pub native_tools: bool,
 }
 
 /// Tool-specific configuration.
@@ -101,6 +108,10 @@ impl AgentConfig {
             config.verbose = val == "1" || val.to_lowercase() == "true";
         }
 
+        if let Ok(val) = std::env::var("BRIO_AGENT_NATIVE_TOOLS") {
+            config.native_tools = val == "1" || val.to_lowercase() == "true";
+        }
+
         if let Ok(val) = std::env::var("BRIO_AGENT_MAX_FILE_SIZE") {
             config.max_file_size = val.parse().map_err(|_| TaskError::InvalidConfiguration {
                 key: "max_file_size".to_string(),
@@ -169,6 +180,7 @@ impl Default for AgentConfig {
             max_depth: default_max_depth(),
             shell_allowlist: default_shell_allowlist(),
             tool_config: ToolConfig::default(),
+            native_tools: default_true(),
         }
     }
 }
@@ -184,6 +196,7 @@ pub struct AgentConfigBuilder {
     max_depth: Option<usize>,
     shell_allowlist: Option<Vec<String>>,
     tool_config: Option<ToolConfig>,
+    native_tools: Option<bool>,
 }
 
 impl AgentConfigBuilder {
@@ -243,6 +256,13 @@ impl AgentConfigBuilder {
         self
     }
 
+    /// Sets whether tools are offered to the model as structured definitions.
+    #[must_use]
+    pub fn native_tools(mut self, enabled: bool) -> Self {
+        self.native_tools = Some(enabled);
+        self
+    }
+
     /// Builds the configuration, validating all values.
     ///
     /// # Errors
@@ -275,6 +295,9 @@ impl AgentConfigBuilder {
         if let Some(v) = self.tool_config {
             config.tool_config = v;
         }
+        if let Some(v) = self.native_tools {
+            config.native_tools = v;
+        }
 
         // Validate first
         config.validate()?;
@@ -358,6 +381,13 @@ mod tests {
         assert_eq!(config.max_iterations, 20);
         assert_eq!(config.model, "best-available");
         assert_eq!(config.max_file_size, 10 * 1024 * 1024);
+        assert!(config.native_tools);
+    }
+
+    #[test]
+    fn test_builder_disables_native_tools() {
+        let config = AgentConfig::builder().native_tools(false).build().unwrap();
+        assert!(!config.native_tools);
     }
 
     #[test]
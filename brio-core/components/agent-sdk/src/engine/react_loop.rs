@@ -55,7 +55,7 @@ impl AgentEngine {
     /// Returns an error if:
     /// - The timeout is exceeded
     /// - The maximum number of iterations is exceeded
-    /// - Tool execution fails
+    /// - A tool invoked through text tags fails
     /// - Inference fails
     pub fn run(&mut self, inference_fn: &InferenceFn) -> Result<String, AgentError> {
         // Add initial user message
@@ -93,11 +93,14 @@ impl AgentEngine {
             // Get model response
             let response = self.model_response(inference_fn)?;
 
-            // Execute tools from response
-            let execution_result = self
-                .tools
-                .execute_all(&response.content)
-                .map_err(AgentError::ToolExecution)?;
+            // Execute tools from response, preferring structured tool calls
+            let execution_result = if response.tool_calls.is_empty() {
+                self.tools
+                    .execute_all(&response.content)
+                    .map_err(AgentError::ToolExecution)?
+            } else {
+                self.tools.execute_calls(&response.tool_calls)
+            };
 
             // Check for completion
             if execution_result.is_complete {
@@ -107,7 +110,11 @@ impl AgentEngine {
             }
 
             // Update state with results
-            self.update_state(&response.content, &execution_result);
+            if response.tool_calls.is_empty() {
+                self.update_state(&response.content, &execution_result);
+            } else {
+                self.record_tool_calls(response, execution_result);
+            }
         }
     }
 
@@ -159,6 +166,29 @@ impl AgentEngine {
         }
     }
 
+    /// Records structured tool calls and answers each with its result.
+    ///
+    /// Failed calls are answered with their error, so the model sees why a
+    /// call did not work and can retry it.
+    fn record_tool_calls(
+        &mut self,
+        response: InferenceResponse,
+        execution_result: ExecutionResult,
+    ) {
+        let results: Vec<Message> = response
+            .tool_calls
+            .iter()
+            .zip(execution_result.tool_results)
+            .map(|(call, result)| Message::tool_result(call.id.clone(), result.output))
+            .collect();
+
+        self.state.history.push(Message::assistant_tool_calls(
+            response.content,
+            response.tool_calls,
+        ));
+        self.state.history.extend(results);
+    }
+
     /// Returns the current iteration count.
     #[must_use]
     pub fn iteration(&self) -> u32 {
@@ -185,7 +215,7 @@ mod tests {
     use crate::error::ToolError;
     use crate::tools::constants::control;
     use crate::tools::{Tool, ToolParser};
-    use crate::types::TaskContext;
+    use crate::types::{TaskContext, ToolCall};
     use regex::Captures;
     use std::borrow::Cow;
     use std::collections::HashMap;
@@ -209,17 +239,13 @@ mod tests {
         move |_model, _history| {
             let idx = counter.fetch_add(1, Ordering::SeqCst);
             match responses.get(idx) {
-                Some(resp) => Ok(InferenceResponse {
-                    content: resp.content.clone(),
-                    model: resp.model.clone(),
-                    tokens_used: resp.tokens_used,
-                    finish_reason: resp.finish_reason.clone(),
-                }),
+                Some(resp) => Ok(resp.clone()),
                 None => Ok(InferenceResponse {
                     content: "<done>Done</done>".to_string(),
                     model: "test".to_string(),
                     tokens_used: None,
                     finish_reason: None,
+                    tool_calls: Vec::new(),
                 }),
             }
         }
@@ -365,6 +391,7 @@ mod tests {
             model: "test".to_string(),
             tokens_used: Some(100),
             finish_reason: Some("stop".to_string()),
+            tool_calls: Vec::new(),
         }]);
 
         let result = engine.run(&mock_inference);
@@ -394,12 +421,14 @@ mod tests {
                 model: "test".to_string(),
                 tokens_used: Some(50),
                 finish_reason: None,
+                tool_calls: Vec::new(),
             },
             InferenceResponse {
                 content: "<done>All done</done>".to_string(),
                 model: "test".to_string(),
                 tokens_used: Some(100),
                 finish_reason: Some("stop".to_string()),
+                tool_calls: Vec::new(),
             },
         ]);
 
@@ -431,6 +460,7 @@ mod tests {
                 model: "test".to_string(),
                 tokens_used: None,
                 finish_reason: None,
+                tool_calls: Vec::new(),
             })
         };
 
@@ -454,6 +484,7 @@ mod tests {
                 model: "test".to_string(),
                 tokens_used: None,
                 finish_reason: None,
+                tool_calls: Vec::new(),
             })
         };
 
@@ -488,6 +519,7 @@ mod tests {
             model: "test".to_string(),
             tokens_used: None,
             finish_reason: None,
+            tool_calls: Vec::new(),
         }]);
 
         let result = engine.run(&mock_inference);
@@ -521,12 +553,14 @@ mod tests {
                 model: "test".to_string(),
                 tokens_used: None,
                 finish_reason: None,
+                tool_calls: Vec::new(),
             },
             InferenceResponse {
                 content: "<done>Complete</done>".to_string(),
                 model: "test".to_string(),
                 tokens_used: None,
                 finish_reason: Some("stop".to_string()),
+                tool_calls: Vec::new(),
             },
         ]);
 
@@ -543,4 +577,116 @@ mod tests {
         assert!(has_assistant, "History should contain assistant messages");
         assert!(has_tool, "History should contain tool messages");
     }
+
+    #[test]
+    fn test_run_structured_tool_calls() {
+        let ctx = create_test_context();
+        let mut registry = ToolRegistry::new();
+
+        let mock_tool = Box::new(MockTool::new("mock_tool"));
+        registry.register("mock_tool", mock_tool, create_mock_parser("mock_tool"));
+
+        let done_tool = Box::new(MockTool::new(control::DONE));
+        registry.register(control::DONE, done_tool, create_done_parser());
+
+        let config = AgentConfig::default();
+        let mut engine = AgentEngine::new(&ctx, registry, config).unwrap();
+
+        let mock_inference = create_mock_inference(vec![
+            InferenceResponse {
+                // Text invocations are ignored when structured calls are present
+                content: "<mock_tool>ignored</mock_tool>".to_string(),
+                model: "test".to_string(),
+                tokens_used: None,
+                finish_reason: Some("tool_calls".to_string()),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: "mock_tool".to_string(),
+                    arguments: r#"{"arg":"value"}"#.to_string(),
+                }],
+            },
+            InferenceResponse {
+                content: String::new(),
+                model: "test".to_string(),
+                tokens_used: None,
+                finish_reason: Some("tool_calls".to_string()),
+                tool_calls: vec![ToolCall {
+                    id: "call_2".to_string(),
+                    name: control::DONE.to_string(),
+                    arguments: r#"{"summary":"Structured done"}"#.to_string(),
+                }],
+            },
+        ]);
+
+        let result = engine.run(&mock_inference).unwrap();
+        assert_eq!(result, "Structured done");
+
+        let history = engine.history();
+        let assistant = &history[history.len() - 2];
+        assert_eq!(assistant.role, Role::Assistant);
+        assert_eq!(assistant.tool_calls.len(), 1);
+
+        let tool = &history[history.len() - 1];
+        assert_eq!(tool.role, Role::Tool);
+        assert_eq!(tool.tool_call_id(), Some("call_1"));
+        assert_eq!(tool.content, "mock_tool executed successfully");
+    }
+
+    #[test]
+    fn test_run_answers_failed_tool_calls() {
+        let ctx = create_test_context();
+        let mut registry = ToolRegistry::new();
+
+        let failing_tool = Box::new(MockTool::failing("failing_tool"));
+        registry.register(
+            "failing_tool",
+            failing_tool,
+            create_mock_parser("failing_tool"),
+        );
+
+        let config = AgentConfig::default();
+        let mut engine = AgentEngine::new(&ctx, registry, config).unwrap();
+
+        let call = |id: &str, name: &str, arguments: &str| ToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        };
+        let mock_inference = create_mock_inference(vec![
+            InferenceResponse {
+                content: String::new(),
+                model: "test".to_string(),
+                tokens_used: None,
+                finish_reason: Some("tool_calls".to_string()),
+                tool_calls: vec![
+                    call("call_1", "invented_tool", "{}"),
+                    call("call_2", "failing_tool", "not json"),
+                    call("call_3", "failing_tool", "{}"),
+                ],
+            },
+            InferenceResponse {
+                content: String::new(),
+                model: "test".to_string(),
+                tokens_used: None,
+                finish_reason: Some("tool_calls".to_string()),
+                tool_calls: vec![call("call_4", control::DONE, r#"{"summary":"Recovered"}"#)],
+            },
+        ]);
+
+        let result = engine.run(&mock_inference).unwrap();
+        assert_eq!(result, "Recovered");
+
+        let answers: Vec<(&str, &str)> = engine
+            .history()
+            .iter()
+            .filter_map(|m| Some((m.tool_call_id()?, m.content.as_str())))
+            .collect();
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers[0].0, "call_1");
+        assert!(answers[0].1.contains("not found"));
+        assert_eq!(answers[1].0, "call_2");
+        assert!(answers[1].1.contains("Invalid arguments"));
+        assert_eq!(answers[2].0, "call_3");
+        assert!(answers[2].1.contains("execution failed"));
+    }
 }
@@ -57,11 +57,12 @@ pub use engine::{AgentEngine, AgentEngineBuilder, InferenceFn};
 pub use error::{AgentError, FileSystemError, InferenceError, ResultExt, TaskError, ToolError};
 pub use prompt::PromptBuilder;
 pub use tools::{
-    SecureFilePath, Tool, ToolParser, ToolRegistry, Unvalidated, Validated, validate_file_size,
-    validate_path, validate_shell_command,
+    SecureFilePath, Tool, ToolParser, ToolRegistry, Unvalidated, Validated,
+    string_arguments_schema, validate_file_size, validate_path, validate_shell_command,
 };
 pub use types::{
-    ExecutionResult, InferenceResponse, Message, Role, TaskContext, ToolInvocation, ToolResult,
+    ExecutionResult, InferenceResponse, Message, RequestOptions, ResponseFormat, Role, TaskContext,
+    ToolCall, ToolDefinition, ToolInvocation, ToolResult,
 };
 
 /// Version of the agent SDK.
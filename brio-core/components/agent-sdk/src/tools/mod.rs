@@ -18,6 +18,16 @@ pub trait Tool: Send + Sync {
     /// Returns the description of the tool in XML format.
     fn description(&self) -> Cow<'static, str>;
 
+    /// Returns the JSON schema of the tool's arguments for structured tool calls.
+    ///
+    /// The default accepts any string-valued arguments.
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "additionalProperties": { "type": "string" }
+        })
+    }
+
     /// Executes the tool with the provided arguments.
     ///
     /// # Errors
@@ -26,6 +36,31 @@ pub trait Tool: Send + Sync {
     fn execute(&self, args: &HashMap<String, String>) -> Result<String, ToolError>;
 }
 
+/// Builds an object schema of string arguments.
+///
+/// `properties` pairs each argument name with its description.
+#[must_use]
+pub fn string_arguments_schema(
+    properties: &[(&str, &str)],
+    required: &[&str],
+) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = properties
+        .iter()
+        .map(|(name, description)| {
+            (
+                (*name).to_string(),
+                serde_json::json!({ "type": "string", "description": description }),
+            )
+        })
+        .collect();
+
+    serde_json::json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
 pub use parser::{ArgExtractor, ToolParser};
 pub use registry::ToolRegistry;
 pub use validation::{
@@ -4,7 +4,7 @@ use crate::error::ToolError;
 use crate::tools::Tool;
 use crate::tools::constants;
 use crate::tools::parser::ToolParser;
-use crate::types::{ExecutionResult, ToolInvocation, ToolResult};
+use crate::types::{ExecutionResult, ToolCall, ToolDefinition, ToolInvocation, ToolResult};
 use std::collections::HashMap;
 use std::fmt::Write;
 use std::sync::Arc;
@@ -63,17 +63,30 @@ impl ToolRegistry {
             .join("\n")
     }
 
+    /// Returns the definitions of all registered tools, sorted by name.
+    ///
+    /// These are offered to models that support native tool calling.
+    #[must_use]
+    pub fn definitions(&self) -> Vec<ToolDefinition> {
+        let mut definitions: Vec<ToolDefinition> = self
+            .tools
+            .iter()
+            .map(|(name, tool)| ToolDefinition {
+                name: name.clone(),
+                description: tool.description().into_owned(),
+                parameters: tool.parameters(),
+            })
+            .collect();
+        definitions.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions
+    }
+
     /// Executes all tool invocations found in the input.
     ///
     /// # Errors
     ///
     /// Returns an error if a tool is not found or if tool execution fails.
     pub fn execute_all(&self, input: &str) -> Result<ExecutionResult, ToolError> {
-        let mut collected_output = String::new();
-        let mut is_done = false;
-        let mut final_summary = None;
-        let mut tool_results = Vec::new();
-
         // Collect all invocations
         let mut invocations: Vec<ToolInvocation> = Vec::new();
         for (tool_name, parser) in &self.parsers {
@@ -87,6 +100,111 @@ impl ToolRegistry {
         // Sort by position
         invocations.sort_by_key(|inv| inv.position);
 
+        self.execute_invocations(invocations)
+    }
+
+    /// Executes structured tool calls returned by the model.
+    ///
+    /// Calls are executed in order, stopping at the first `done`. Argument
+    /// values that are not strings are passed to the tool as their JSON text.
+    ///
+    /// A call that names an unknown tool, has arguments that are not a JSON
+    /// object or fails does not stop the run: it yields an unsuccessful
+    /// result carrying the error, so every call can be answered and the model
+    /// can correct itself.
+    #[must_use]
+    pub fn execute_calls(&self, calls: &[ToolCall]) -> ExecutionResult {
+        let mut collected_output = String::new();
+        let mut is_done = false;
+        let mut final_summary = None;
+        let mut tool_results = Vec::new();
+
+        for (position, call) in calls.iter().enumerate() {
+            let invocation = match Self::call_arguments(call) {
+                Ok(args) => ToolInvocation {
+                    name: call.name.clone(),
+                    args,
+                    position,
+                },
+                Err(e) => {
+                    let _ = writeln!(collected_output, "✗ {} failed: {}", call.name, e);
+                    tool_results.push(ToolResult::failure(&e));
+                    continue;
+                }
+            };
+
+            if invocation.name == constants::control::DONE {
+                is_done = true;
+                final_summary = invocation.args.get("summary").cloned();
+                break;
+            }
+
+            let start = Instant::now();
+            match self.execute_single(&invocation) {
+                Ok(result) => {
+                    let _ = writeln!(
+                        collected_output,
+                        "✓ {}: {}",
+                        invocation.name,
+                        result.output.lines().next().unwrap_or(&result.output)
+                    );
+                    tool_results.push(result);
+                }
+                Err(e) => {
+                    let _ = writeln!(collected_output, "✗ {} failed: {}", invocation.name, e);
+                    tool_results.push(ToolResult {
+                        duration: start.elapsed(),
+                        ..ToolResult::failure(&e)
+                    });
+                }
+            }
+        }
+
+        ExecutionResult {
+            output: collected_output,
+            is_complete: is_done,
+            summary: final_summary,
+            tool_results,
+        }
+    }
+
+    /// Converts the JSON arguments of a tool call to string arguments.
+    fn call_arguments(call: &ToolCall) -> Result<HashMap<String, String>, ToolError> {
+        let invalid = |reason: String| ToolError::InvalidArguments {
+            tool: call.name.clone(),
+            reason,
+        };
+
+        if call.arguments.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let value: serde_json::Value =
+            serde_json::from_str(&call.arguments).map_err(|e| invalid(e.to_string()))?;
+        let serde_json::Value::Object(object) = value else {
+            return Err(invalid("arguments must be a JSON object".to_string()));
+        };
+
+        Ok(object
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => (key, s),
+                other => (key, other.to_string()),
+            })
+            .collect())
+    }
+
+    /// Executes invocations in order, stopping at the first `done`.
+    fn execute_invocations(
+        &self,
+        invocations: Vec<ToolInvocation>,
+    ) -> Result<ExecutionResult, ToolError> {
+        let mut collected_output = String::new();
+        let mut is_done = false;
+        let mut final_summary = None;
+        let mut tool_results = Vec::new();
+
         // Execute each invocation
         for invocation in invocations {
             if invocation.name == constants::control::DONE {
@@ -189,4 +307,86 @@ mod tests {
         registry.register("test", Box::new(TestTool), parser);
         assert_eq!(registry.available_tools(), vec!["test"]);
     }
+
+    #[test]
+    fn test_execute_calls() {
+        let mut registry = ToolRegistry::new();
+        let parser = Arc::new(
+            ToolParser::new(r"\u003ctest\s*/?\u003e", |_caps: &Captures| HashMap::new()).unwrap(),
+        );
+        registry.register("test", Box::new(TestTool), parser);
+
+        let calls = vec![
+            ToolCall {
+                id: "call_1".to_string(),
+                name: "test".to_string(),
+                arguments: r#"{"arg":"value","count":3}"#.to_string(),
+            },
+            ToolCall {
+                id: "call_2".to_string(),
+                name: "done".to_string(),
+                arguments: r#"{"summary":"finished"}"#.to_string(),
+            },
+        ];
+        let result = registry.execute_calls(&calls);
+        assert!(result.is_complete);
+        assert_eq!(result.summary.as_deref(), Some("finished"));
+        assert_eq!(result.tool_results.len(), 1);
+        assert_eq!(result.tool_results[0].output, "test result");
+    }
+
+    #[test]
+    fn test_execute_calls_reports_failures_as_results() {
+        let mut registry = ToolRegistry::new();
+        let parser = Arc::new(
+            ToolParser::new(r"\u003ctest\s*/?\u003e", |_caps: &Captures| HashMap::new()).unwrap(),
+        );
+        registry.register("test", Box::new(TestTool), parser);
+
+        let calls = vec![
+            ToolCall {
+                id: "call_1".to_string(),
+                name: "test".to_string(),
+                arguments: "[1, 2]".to_string(),
+            },
+            ToolCall {
+                id: "call_2".to_string(),
+                name: "missing".to_string(),
+                arguments: "{}".to_string(),
+            },
+            ToolCall {
+                id: "call_3".to_string(),
+                name: "test".to_string(),
+                arguments: "{".to_string(),
+            },
+            ToolCall {
+                id: "call_4".to_string(),
+                name: "test".to_string(),
+                arguments: "{}".to_string(),
+            },
+        ];
+        let result = registry.execute_calls(&calls);
+        assert!(!result.is_complete);
+        assert_eq!(result.tool_results.len(), 4);
+
+        let outcomes: Vec<bool> = result.tool_results.iter().map(|r| r.success).collect();
+        assert_eq!(outcomes, vec![false, false, false, true]);
+        assert!(result.tool_results[0].output.contains("JSON object"));
+        assert!(result.tool_results[1].output.contains("missing"));
+        assert_eq!(result.tool_results[3].output, "test result");
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut registry = ToolRegistry::new();
+        let parser = Arc::new(
+            ToolParser::new(r"\u003ctest\s*/?\u003e", |_caps: &Captures| HashMap::new()).unwrap(),
+        );
+        registry.register("test", Box::new(TestTool), parser);
+
+        let definitions = registry.definitions();
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].name, "test");
+        assert_eq!(definitions[0].parameters["type"], "object");
+    }
 }
@@ -13,8 +13,14 @@ pub struct Message {
     /// Optional metadata (e.g., tool call ID, timestamp).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
+    /// Structured tool calls made by the assistant in this message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
 }
 
+/// Metadata key holding the ID of the tool call a tool message answers.
+pub const TOOL_CALL_ID_KEY: &str = "tool_call_id";
+
 impl Message {
     /// Creates a new message.
     pub fn new(role: Role, content: impl Into<String>) -> Self {
@@ -22,6 +28,7 @@ impl Message {
             role,
             content: content.into(),
             metadata: None,
+            tool_calls: Vec::new(),
         }
     }
 
@@ -46,6 +53,30 @@ impl Message {
         Self::new(Role::Tool, content)
     }
 
+    /// Creates an assistant message that makes structured tool calls.
+    #[must_use]
+    pub fn assistant_tool_calls(content: impl Into<String>, tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            tool_calls,
+            ..Self::assistant(content)
+        }
+    }
+
+    /// Creates a tool message answering a structured tool call.
+    #[must_use]
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self::tool(content).with_metadata(TOOL_CALL_ID_KEY, tool_call_id)
+    }
+
+    /// Returns the ID of the tool call this message answers, if any.
+    #[must_use]
+    pub fn tool_call_id(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get(TOOL_CALL_ID_KEY))
+            .map(String::as_str)
+    }
+
     /// Adds metadata to the message.
     #[must_use]
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
@@ -92,6 +123,110 @@ pub struct InferenceResponse {
     pub tokens_used: Option<u32>,
     /// Completion reason (e.g., "stop", "length", "`tool_calls`").
     pub finish_reason: Option<String>,
+    /// Structured tool calls requested by the model.
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// A structured tool call requested by the model.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Provider-assigned identifier, echoed back with the result.
+    pub id: String,
+    /// Name of the tool to call.
+    pub name: String,
+    /// Arguments as a JSON object string.
+    pub arguments: String,
+}
+
+/// A tool offered to the model for structured calls.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    /// Name of the tool.
+    pub name: String,
+    /// What the tool does.
+    pub description: String,
+    /// JSON schema of the tool's arguments.
+    pub parameters: serde_json::Value,
+}
+
+/// Format the model must answer in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text.
+    #[default]
+    Text,
+    /// A single JSON object.
+    Json,
+}
+
+/// Sampling and output controls for inference requests.
+///
+/// Unset options use the provider's defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestOptions {
+    /// Sampling temperature; lower is more deterministic.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass.
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate.
+    pub max_tokens: Option<u32>,
+    /// Sequences that stop generation when produced.
+    pub stop: Vec<String>,
+    /// Seed for best-effort deterministic sampling.
+    pub seed: Option<u64>,
+    /// Format of the response.
+    pub response_format: ResponseFormat,
+}
+
+impl RequestOptions {
+    /// Creates options using the provider's defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sampling temperature.
+    #[must_use]
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the nucleus sampling probability mass.
+    #[must_use]
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Sets the maximum number of tokens to generate.
+    #[must_use]
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Sets the stop sequences.
+    #[must_use]
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Sets the sampling seed.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the response format.
+    #[must_use]
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
 }
 
 /// Task context containing task metadata and parameters.
@@ -173,6 +308,18 @@ pub struct ToolResult {
     pub duration: std::time::Duration,
 }
 
+impl ToolResult {
+    /// Creates an unsuccessful result whose output is the error message.
+    #[must_use]
+    pub fn failure(error: &impl std::fmt::Display) -> Self {
+        Self {
+            success: false,
+            output: error.to_string(),
+            duration: std::time::Duration::ZERO,
+        }
+    }
+}
+
 /// Execution result from processing all tools in a response.
 #[derive(Debug)]
 pub struct ExecutionResult {
@@ -244,6 +391,14 @@ mod tests {
         assert_eq!(meta.get("timestamp"), Some(&"2024-01-01".to_string()));
     }
 
+    #[test]
+    fn test_tool_result_message() {
+        let msg = Message::tool_result("call_1", "done");
+        assert_eq!(msg.role, Role::Tool);
+        assert_eq!(msg.tool_call_id(), Some("call_1"));
+        assert_eq!(Message::user("Hi").tool_call_id(), None);
+    }
+
     #[test]
     fn test_task_context_builder() {
         let ctx = TaskContext::new("task-1", "Do something")
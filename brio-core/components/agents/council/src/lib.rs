@@ -10,7 +10,10 @@ use agent_sdk::agent::{
 };
 use agent_sdk::error::AgentError;
 use agent_sdk::tools::ToolRegistry;
-use agent_sdk::types::{InferenceResponse, Message, Role, TaskContext};
+use agent_sdk::types::{
+    InferenceResponse, Message, RequestOptions, ResponseFormat, Role, TaskContext, ToolCall,
+    ToolDefinition,
+};
 use agent_sdk::{AgentConfig, PromptBuilder, Tool, ToolError};
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -69,26 +72,43 @@ impl StandardAgent for CouncilAgent {
         model: &str,
         history: &[Message],
     ) -> Result<InferenceResponse, AgentError> {
-        let wit_messages: Vec<brio::ai::inference::Message> = history
+        let wit_messages: Vec<brio::ai::inference::Message> =
+            history.iter().map(convert_message).collect();
+        let options = convert_options(self.request_options());
+
+        let response = brio::ai::inference::chat_with_options(model, &wit_messages, &options)
+            .map_err(|e| {
+                AgentError::Inference(agent_sdk::InferenceError::ApiError(format!("{e:?}")))
+            })?;
+
+        Ok(convert_response(model, response))
+    }
+
+    fn perform_inference_with_tools(
+        &self,
+        model: &str,
+        history: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Result<InferenceResponse, AgentError> {
+        let wit_messages: Vec<brio::ai::inference::Message> =
+            history.iter().map(convert_message).collect();
+        let options = convert_options(self.request_options());
+        let wit_tools: Vec<brio::ai::inference::ToolDefinition> = tools
             .iter()
-            .map(|msg| brio::ai::inference::Message {
-                role: convert_role(msg.role),
-                content: msg.content.clone(),
+            .map(|tool| brio::ai::inference::ToolDefinition {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.parameters.to_string(),
             })
             .collect();
 
-        let response = brio::ai::inference::chat(model, &wit_messages).map_err(|e| {
-            AgentError::Inference(agent_sdk::InferenceError::ApiError(format!("{e:?}")))
-        })?;
-
-        let tokens_used = response.usage.as_ref().map(|u| u.total_tokens);
+        let response =
+            brio::ai::inference::chat_with_tools(model, &wit_messages, &wit_tools, &options)
+                .map_err(|e| {
+                    AgentError::Inference(agent_sdk::InferenceError::ApiError(format!("{e:?}")))
+                })?;
 
-        Ok(InferenceResponse {
-            content: response.content,
-            model: model.to_string(),
-            tokens_used,
-            finish_reason: None,
-        })
+        Ok(convert_response(model, response))
     }
 }
 
@@ -125,6 +145,70 @@ fn convert_role(role: Role) -> brio::ai::inference::Role {
     }
 }
 
+/// Converts an SDK message to a WIT message.
+///
+/// Tool messages answering a structured tool call keep the tool role;
+/// other tool output is sent as assistant text.
+fn convert_message(msg: &Message) -> brio::ai::inference::Message {
+    let tool_call_id = msg.tool_call_id().map(str::to_string);
+    let role = match (msg.role, &tool_call_id) {
+        (Role::Tool, Some(_)) => brio::ai::inference::Role::Tool,
+        (role, _) => convert_role(role),
+    };
+
+    brio::ai::inference::Message {
+        role,
+        content: msg.content.clone(),
+        tool_calls: msg
+            .tool_calls
+            .iter()
+            .map(|call| brio::ai::inference::ToolCall {
+                id: call.id.clone(),
+                name: call.name.clone(),
+                arguments: call.arguments.clone(),
+            })
+            .collect(),
+        tool_call_id,
+    }
+}
+
+/// Converts a WIT completion response to an SDK inference response.
+fn convert_response(
+    model: &str,
+    response: brio::ai::inference::CompletionResponse,
+) -> InferenceResponse {
+    InferenceResponse {
+        content: response.content,
+        model: model.to_string(),
+        tokens_used: response.usage.as_ref().map(|u| u.total_tokens),
+        finish_reason: None,
+        tool_calls: response
+            .tool_calls
+            .into_iter()
+            .map(|call| ToolCall {
+                id: call.id,
+                name: call.name,
+                arguments: call.arguments,
+            })
+            .collect(),
+    }
+}
+
+/// Converts SDK request options to WIT request options.
+fn convert_options(options: RequestOptions) -> brio::ai::inference::RequestOptions {
+    brio::ai::inference::RequestOptions {
+        temperature: options.temperature,
+        top_p: options.top_p,
+        max_tokens: options.max_tokens,
+        stop: options.stop,
+        seed: options.seed,
+        response_format: match options.response_format {
+            ResponseFormat::Text => brio::ai::inference::ResponseFormat::Text,
+            ResponseFormat::Json => brio::ai::inference::ResponseFormat::Json,
+        },
+    }
+}
+
 // WriteFileTool implementation for council agent
 struct WriteFileTool;
 
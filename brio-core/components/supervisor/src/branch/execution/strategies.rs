@@ -133,7 +133,7 @@ pub async fn execute_agent_on_branch(
 
     // Dispatch to agent
     let dispatch_result = dispatcher
-        .dispatch(assignment.agent_id(), &task)
+        .dispatch(assignment.agent_id(), &task, &[])
         .map_err(ExecutionError::Dispatch)?;
 
     let duration = start_time.elapsed();
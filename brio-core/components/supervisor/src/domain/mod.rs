@@ -8,7 +8,7 @@
 //! - `ids`: Strongly-typed identifiers (`BranchId`, `TaskId`, `AgentId`, etc.)
 //! - `errors`: Error types for validation and parsing failures
 //! - `branch`: Branch entities, status, and configuration
-//! - `task`: Task entities, status, and branching strategy
+//! - `task`: Task entities, status, plans, and branching strategy
 //! - `merge`: Merge operations, conflicts, and results
 
 // Re-export all public items from submodules
@@ -24,7 +24,8 @@ pub use merge::{
     MergeRequest, MergeRequestStatus, MergeResult, MergeStatus, StagedChange,
 };
 pub use task::{
-    BranchSource, BranchingStrategy, Capability, Task, TaskStatus, should_use_branching,
+    BranchSource, BranchingStrategy, Capability, PlanError, PlannedSubtask, PredecessorOutput,
+    Task, TaskStatus, execution_order, should_use_branching,
 };
 
 // Declare submodules
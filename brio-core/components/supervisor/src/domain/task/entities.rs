@@ -138,6 +138,11 @@ pub struct Task {
     parent_id: Option<TaskId>,
     assigned_agent: Option<AgentId>,
     required_capabilities: HashSet<super::strategy::Capability>,
+    dependencies: Vec<TaskId>,
+    output: Option<String>,
+    session_id: Option<String>,
+    verification_rounds: u32,
+    feedback: Option<String>,
 }
 
 impl Task {
@@ -165,9 +170,43 @@ impl Task {
             parent_id,
             assigned_agent,
             required_capabilities,
+            dependencies: Vec::new(),
+            output: None,
+            session_id: None,
+            verification_rounds: 0,
+            feedback: None,
         })
     }
 
+    /// Sets the tasks that must complete before this one may start.
+    #[must_use]
+    pub fn with_dependencies(mut self, dependencies: Vec<TaskId>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// Sets the output recorded when the task completed.
+    #[must_use]
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Sets the VFS session the task works in.
+    #[must_use]
+    pub fn with_session(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Sets the number of failed verification rounds and the latest feedback.
+    #[must_use]
+    pub fn with_verification(mut self, rounds: u32, feedback: Option<String>) -> Self {
+        self.verification_rounds = rounds;
+        self.feedback = feedback;
+        self
+    }
+
     /// Returns the task ID.
     #[must_use]
     pub const fn id(&self) -> TaskId {
@@ -210,6 +249,36 @@ impl Task {
         &self.required_capabilities
     }
 
+    /// Returns the tasks that must complete before this one may start.
+    #[must_use]
+    pub fn dependencies(&self) -> &[TaskId] {
+        &self.dependencies
+    }
+
+    /// Returns the output recorded when the task completed, if any.
+    #[must_use]
+    pub fn output(&self) -> Option<&str> {
+        self.output.as_deref()
+    }
+
+    /// Returns the VFS session the task works in, if any.
+    #[must_use]
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Returns how many verification rounds the task has failed.
+    #[must_use]
+    pub const fn verification_rounds(&self) -> u32 {
+        self.verification_rounds
+    }
+
+    /// Returns the feedback of the latest failed verification, if any.
+    #[must_use]
+    pub fn feedback(&self) -> Option<&str> {
+        self.feedback.as_deref()
+    }
+
     /// Checks if this task is ready for dispatch (Pending).
     #[must_use]
     pub const fn is_pending(&self) -> bool {
@@ -1,10 +1,12 @@
 //! Task domain module
 //!
 //! This module defines task-related domain types including the Task entity,
-/// lifecycle status, and branching strategy detection.
+/// lifecycle status, dependency-aware plans, and branching strategy detection.
 pub mod entities;
+pub mod plan;
 pub mod strategy;
 
 // Re-export commonly used items
 pub use entities::{Task, TaskStatus};
+pub use plan::{PlanError, PlannedSubtask, PredecessorOutput, execution_order};
 pub use strategy::{BranchSource, BranchingStrategy, Capability, should_use_branching};
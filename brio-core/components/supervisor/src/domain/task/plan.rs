@@ -0,0 +1,231 @@
+//! Task domain - Dependency-aware plans
+//!
+//! This module defines the subtasks a planner produces, validates the
+//! dependency graph between them and describes the outputs handed from
+//! completed prerequisites to their successors.
+
+use super::strategy::Capability;
+use crate::domain::ids::TaskId;
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+/// A subtask proposed by a planner, linked to its prerequisites by plan-local ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedSubtask {
+    /// Identifier, unique within the plan.
+    pub id: String,
+    /// What the subtask should accomplish.
+    pub description: String,
+    /// Ids of the subtasks that must complete before this one starts.
+    pub depends_on: Vec<String>,
+    /// Artifacts the subtask is expected to produce for its successors.
+    pub outputs: Vec<String>,
+    /// Capability an agent needs to perform the subtask, if any.
+    pub capability: Option<Capability>,
+}
+
+impl PlannedSubtask {
+    /// Creates a subtask without prerequisites, outputs or capability.
+    #[must_use]
+    pub fn new(id: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            description: description.into(),
+            depends_on: Vec::new(),
+            outputs: Vec::new(),
+            capability: None,
+        }
+    }
+
+    /// Sets the ids of the subtasks that must complete first.
+    #[must_use]
+    pub fn with_dependencies<I, T>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.depends_on = ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the artifacts the subtask is expected to produce.
+    #[must_use]
+    pub fn with_outputs<I, T>(mut self, outputs: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.outputs = outputs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the capability an agent needs to perform the subtask.
+    #[must_use]
+    pub const fn with_capability(mut self, capability: Capability) -> Self {
+        self.capability = Some(capability);
+        self
+    }
+
+    /// Returns the content of the task created for this subtask.
+    #[must_use]
+    pub fn task_content(&self) -> String {
+        if self.outputs.is_empty() {
+            self.description.clone()
+        } else {
+            format!(
+                "{}\n\nExpected outputs: {}",
+                self.description,
+                self.outputs.join(", ")
+            )
+        }
+    }
+}
+
+/// Error type for plans whose dependency graph cannot be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    /// Two subtasks share the same id.
+    DuplicateSubtask(String),
+    /// A subtask depends on an id that is not part of the plan.
+    UnknownDependency {
+        /// Id of the dependent subtask.
+        subtask: String,
+        /// The unknown prerequisite id.
+        dependency: String,
+    },
+    /// The prerequisites form a cycle.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateSubtask(id) => write!(f, "Plan contains subtask '{id}' twice"),
+            Self::UnknownDependency {
+                subtask,
+                dependency,
+            } => write!(
+                f,
+                "Subtask '{subtask}' depends on unknown subtask '{dependency}'"
+            ),
+            Self::Cycle(ids) => write!(
+                f,
+                "Plan contains a dependency cycle between: {}",
+                ids.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+/// Returns the indices of `subtasks` in an order where every subtask follows
+/// its prerequisites.
+///
+/// Independent subtasks keep the order the planner listed them in.
+///
+/// # Errors
+/// Returns `PlanError` if ids are duplicated, a prerequisite is unknown or
+/// the prerequisites form a cycle.
+pub fn execution_order(subtasks: &[PlannedSubtask]) -> Result<Vec<usize>, PlanError> {
+    let mut index_of = HashMap::with_capacity(subtasks.len());
+    for (index, subtask) in subtasks.iter().enumerate() {
+        if index_of.insert(subtask.id.as_str(), index).is_some() {
+            return Err(PlanError::DuplicateSubtask(subtask.id.clone()));
+        }
+    }
+
+    let mut prerequisites = Vec::with_capacity(subtasks.len());
+    for subtask in subtasks {
+        let mut deps = HashSet::new();
+        for dependency in &subtask.depends_on {
+            let index =
+                index_of
+                    .get(dependency.as_str())
+                    .ok_or_else(|| PlanError::UnknownDependency {
+                        subtask: subtask.id.clone(),
+                        dependency: dependency.clone(),
+                    })?;
+            deps.insert(*index);
+        }
+        prerequisites.push(deps);
+    }
+
+    let mut order = Vec::with_capacity(subtasks.len());
+    let mut placed = vec![false; subtasks.len()];
+    while order.len() < subtasks.len() {
+        let next = (0..subtasks.len())
+            .find(|&i| !placed[i] && prerequisites[i].iter().all(|&dep| placed[dep]));
+        let Some(next) = next else {
+            let cycle = (0..subtasks.len())
+                .filter(|&i| !placed[i])
+                .map(|i| subtasks[i].id.clone())
+                .collect();
+            return Err(PlanError::Cycle(cycle));
+        };
+        placed[next] = true;
+        order.push(next);
+    }
+    Ok(order)
+}
+
+/// Output of a completed prerequisite, passed into its successor's context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredecessorOutput {
+    /// The completed prerequisite task.
+    pub task_id: TaskId,
+    /// Content of the prerequisite task.
+    pub description: String,
+    /// Output its agent returned.
+    pub output: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_subtasks_after_their_prerequisites() {
+        let plan = vec![
+            PlannedSubtask::new("test", "Test").with_dependencies(["build"]),
+            PlannedSubtask::new("design", "Design"),
+            PlannedSubtask::new("build", "Build").with_dependencies(["design"]),
+            PlannedSubtask::new("docs", "Docs"),
+        ];
+        assert_eq!(execution_order(&plan).unwrap(), vec![1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let plan = vec![
+            PlannedSubtask::new("a", "A").with_dependencies(["b"]),
+            PlannedSubtask::new("b", "B").with_dependencies(["a"]),
+            PlannedSubtask::new("c", "C"),
+        ];
+        assert_eq!(
+            execution_order(&plan).unwrap_err(),
+            PlanError::Cycle(vec!["a".to_string(), "b".to_string()])
+        );
+
+        let self_loop = vec![PlannedSubtask::new("a", "A").with_dependencies(["a"])];
+        assert!(matches!(
+            execution_order(&self_loop),
+            Err(PlanError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_and_duplicate_ids() {
+        let unknown = vec![PlannedSubtask::new("a", "A").with_dependencies(["ghost"])];
+        assert!(matches!(
+            execution_order(&unknown),
+            Err(PlanError::UnknownDependency { dependency, .. }) if dependency == "ghost"
+        ));
+
+        let duplicate = vec![PlannedSubtask::new("a", "A"), PlannedSubtask::new("a", "B")];
+        assert_eq!(
+            execution_order(&duplicate).unwrap_err(),
+            PlanError::DuplicateSubtask("a".to_string())
+        );
+    }
+}
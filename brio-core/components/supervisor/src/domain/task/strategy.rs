@@ -63,6 +63,29 @@ pub enum Capability {
     Reasoning,
 }
 
+impl Capability {
+    /// Parses a capability name, ignoring case.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "coding" => Some(Self::Coding),
+            "reviewing" => Some(Self::Reviewing),
+            "reasoning" => Some(Self::Reasoning),
+            _ => None,
+        }
+    }
+
+    /// Returns the database-compatible string representation.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Coding => "coding",
+            Self::Reviewing => "reviewing",
+            Self::Reasoning => "reasoning",
+        }
+    }
+}
+
 impl core::fmt::Display for Capability {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
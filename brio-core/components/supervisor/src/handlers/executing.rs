@@ -1,9 +1,13 @@
 //! Handler for the Executing task state.
 //!
-//! Dispatches tasks to agents for execution.
+//! Dispatches tasks to agents for execution, together with the outputs of
+//! their completed prerequisites. Tasks an agent accepted are polled until it
+//! completes them. Outputs are recorded so that successors can build on them,
+//! whether they are returned synchronously or later, and the task moves on to
+//! verification.
 
-use super::{SupervisorContext, TaskStateHandler};
-use crate::domain::Task;
+use super::{SupervisorContext, TaskStateHandler, prerequisites};
+use crate::domain::{PredecessorOutput, Task, TaskStatus};
 use crate::mesh_client::{AgentDispatcher, DispatchResult};
 use crate::orchestrator::{Planner, SupervisorError};
 use crate::repository::TaskRepository;
@@ -24,25 +28,60 @@ where
         ctx: &SupervisorContext<R, D, P, S>,
         task: &Task,
     ) -> Result<bool, SupervisorError> {
-        if task.assigned_agent().is_some() {
-            return Ok(false);
+        if let Some(agent) = task.assigned_agent() {
+            return match ctx.dispatcher.poll_completion(agent, task)? {
+                Some(output) => {
+                    complete(ctx, task, &output)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            };
         }
 
+        let predecessors: Vec<PredecessorOutput> = prerequisites(ctx.repository, task)?
+            .into_iter()
+            .filter_map(|t| {
+                t.output().map(|output| PredecessorOutput {
+                    task_id: t.id(),
+                    description: t.content().to_string(),
+                    output: output.to_string(),
+                })
+            })
+            .collect();
+
         let agent = ctx.selector.select(task);
-        match ctx.dispatcher.dispatch(&agent, task)? {
+        match ctx.dispatcher.dispatch(&agent, task, &predecessors)? {
             DispatchResult::Accepted => {
                 ctx.repository
                     .assign_agent(task.id(), &agent)
                     .map_err(SupervisorError::StatusUpdateFailure)?;
                 Ok(true)
             }
-            DispatchResult::Completed(_) => {
-                ctx.repository
-                    .mark_completed(task.id())
-                    .map_err(SupervisorError::StatusUpdateFailure)?;
+            DispatchResult::Completed(output) => {
+                complete(ctx, task, &output)?;
                 Ok(true)
             }
             DispatchResult::AgentBusy => Ok(false),
         }
     }
 }
+
+/// Records the output of a completed task and hands it to verification.
+fn complete<R, D, P, S>(
+    ctx: &SupervisorContext<R, D, P, S>,
+    task: &Task,
+    output: &str,
+) -> Result<(), SupervisorError>
+where
+    R: TaskRepository,
+    D: AgentDispatcher,
+    P: Planner,
+    S: AgentSelector,
+{
+    ctx.repository
+        .record_output(task.id(), output)
+        .map_err(SupervisorError::StatusUpdateFailure)?;
+    ctx.repository
+        .update_status(task.id(), TaskStatus::Verifying)
+        .map_err(SupervisorError::StatusUpdateFailure)
+}
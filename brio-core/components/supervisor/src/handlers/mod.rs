@@ -8,6 +8,8 @@ use crate::mesh_client::AgentDispatcher;
 use crate::orchestrator::{Planner, SupervisorError};
 use crate::repository::TaskRepository;
 use crate::selector::AgentSelector;
+use crate::verification::Verifier;
+use crate::workspace::Workspace;
 use std::sync::Arc;
 
 /// Context passed to state handlers containing all necessary dependencies.
@@ -22,6 +24,10 @@ pub struct SupervisorContext<'a, R, D, P, S> {
     pub selector: &'a S,
     /// Optional branch manager for branching task support.
     pub branch_manager: Option<Arc<dyn BranchManager>>,
+    /// Optional verifier checking results before tasks are completed.
+    pub verifier: Option<Arc<dyn Verifier>>,
+    /// Optional workspace root tasks open their sessions on.
+    pub workspace: Option<Arc<dyn Workspace>>,
 }
 
 /// Trait representing a handler for a specific task state.
@@ -45,6 +51,33 @@ where
     ) -> Result<bool, SupervisorError>;
 }
 
+/// Fetches the prerequisite tasks of `task` from its siblings.
+///
+/// Plans only link subtasks of the same parent, so tasks without a parent
+/// have no prerequisites to wait for.
+///
+/// # Errors
+/// Returns `SupervisorError` if the subtasks cannot be fetched.
+pub(crate) fn prerequisites<R: TaskRepository>(
+    repository: &R,
+    task: &Task,
+) -> Result<Vec<Task>, SupervisorError> {
+    let Some(parent_id) = task.parent_id() else {
+        return Ok(Vec::new());
+    };
+    if task.dependencies().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let siblings = repository
+        .fetch_subtasks(parent_id)
+        .map_err(SupervisorError::RepositoryFailure)?;
+    Ok(siblings
+        .into_iter()
+        .filter(|t| task.dependencies().contains(&t.id()))
+        .collect())
+}
+
 pub mod branching;
 pub mod coordinating;
 pub mod executing;
@@ -1,8 +1,12 @@
 //! Handler for the Pending task state.
 //!
-//! Transitions pending tasks to the planning state.
+//! Transitions pending tasks to the planning state once all of their
+//! prerequisites have completed. Root tasks are first given a session on the
+//! supervisor's workspace, which their subtasks inherit. Tasks that depend on
+//! a task which is not one of their siblings can never be released and fail
+//! instead.
 
-use super::{SupervisorContext, TaskStateHandler};
+use super::{SupervisorContext, TaskStateHandler, prerequisites};
 use crate::domain::{Task, TaskStatus};
 use crate::mesh_client::AgentDispatcher;
 use crate::orchestrator::{Planner, SupervisorError};
@@ -24,6 +28,43 @@ where
         ctx: &SupervisorContext<R, D, P, S>,
         task: &Task,
     ) -> Result<bool, SupervisorError> {
+        let needs_session = task.parent_id().is_none() && task.session_id().is_none();
+        if let Some(workspace) = ctx.workspace.as_ref().filter(|_| needs_session) {
+            let session_id = workspace.begin_session()?;
+            ctx.repository
+                .assign_session(task.id(), &session_id)
+                .map_err(SupervisorError::StatusUpdateFailure)?;
+        }
+
+        let prerequisites = prerequisites(ctx.repository, task)?;
+        if let Some(failed) = prerequisites
+            .iter()
+            .find(|t| matches!(t.status(), TaskStatus::Failed))
+        {
+            ctx.repository
+                .mark_failed(task.id(), &format!("Prerequisite {} failed", failed.id()))
+                .map_err(SupervisorError::StatusUpdateFailure)?;
+            return Ok(true);
+        }
+
+        if let Some(missing) = task
+            .dependencies()
+            .iter()
+            .find(|id| !prerequisites.iter().any(|t| t.id() == **id))
+        {
+            ctx.repository
+                .mark_failed(task.id(), &format!("Unknown prerequisite {missing}"))
+                .map_err(SupervisorError::StatusUpdateFailure)?;
+            return Ok(true);
+        }
+
+        if !prerequisites
+            .iter()
+            .all(|t| matches!(t.status(), TaskStatus::Completed))
+        {
+            return Ok(false);
+        }
+
         ctx.repository
             .update_status(task.id(), TaskStatus::Planning)
             .map_err(SupervisorError::StatusUpdateFailure)?;
@@ -1,13 +1,17 @@
 //! Handler for the Planning task state.
 //!
-//! Decomposes tasks into subtasks using the planner.
+//! Decomposes tasks into subtasks using the planner. Subtasks are created in
+//! dependency order so that each one records the task ids of its
+//! prerequisites, listed once each; plans whose dependencies cannot be
+//! executed are rejected before any subtask is created.
 
 use super::{SupervisorContext, TaskStateHandler};
-use crate::domain::{Priority, Task, TaskStatus};
+use crate::domain::{Priority, Task, TaskId, TaskStatus, execution_order};
 use crate::mesh_client::AgentDispatcher;
-use crate::orchestrator::{Planner, SupervisorError};
+use crate::orchestrator::{Planner, PlannerError, SupervisorError};
 use crate::repository::TaskRepository;
 use crate::selector::AgentSelector;
+use std::collections::{HashMap, HashSet};
 
 /// Handler for planning and decomposing tasks.
 pub struct PlanningHandler;
@@ -31,10 +35,32 @@ where
 
         match plan_result {
             Some(subtasks) if !subtasks.is_empty() => {
-                for sub_content in subtasks {
-                    ctx.repository
-                        .create_task(sub_content, Priority::DEFAULT, Some(task.id()))
+                let order = execution_order(&subtasks)
+                    .map_err(|e| SupervisorError::PlanningFailure(PlannerError(e.to_string())))?;
+
+                let mut created: HashMap<&str, TaskId> = HashMap::with_capacity(subtasks.len());
+                for index in order {
+                    let subtask = &subtasks[index];
+                    // Prerequisites precede their successors in `order`.
+                    let mut seen = HashSet::new();
+                    let depends_on: Vec<TaskId> = subtask
+                        .depends_on
+                        .iter()
+                        .map(|id| created[id.as_str()])
+                        .filter(|id| seen.insert(*id))
+                        .collect();
+
+                    let id = ctx
+                        .repository
+                        .create_dependent_task(
+                            subtask.task_content(),
+                            Priority::DEFAULT,
+                            task.id(),
+                            &depends_on,
+                            subtask.capability,
+                        )
                         .map_err(SupervisorError::RepositoryFailure)?;
+                    created.insert(&subtask.id, id);
                 }
 
                 ctx.repository
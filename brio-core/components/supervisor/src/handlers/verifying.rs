@@ -1,6 +1,8 @@
 //! Handler for the Verifying task state.
 //!
-//! Marks tasks as completed after verification.
+//! Runs the configured verifier on the task's result. Tasks that pass are
+//! completed; tasks that fail are sent back to execution with the feedback
+//! attached, until they have failed the maximum number of rounds.
 
 use super::{SupervisorContext, TaskStateHandler};
 use crate::domain::Task;
@@ -8,6 +10,7 @@ use crate::mesh_client::AgentDispatcher;
 use crate::orchestrator::{Planner, SupervisorError};
 use crate::repository::TaskRepository;
 use crate::selector::AgentSelector;
+use crate::verification::Verdict;
 
 /// Handler for verifying and completing tasks.
 pub struct VerifyingHandler;
@@ -24,9 +27,33 @@ where
         ctx: &SupervisorContext<R, D, P, S>,
         task: &Task,
     ) -> Result<bool, SupervisorError> {
-        ctx.repository
-            .mark_completed(task.id())
-            .map_err(SupervisorError::StatusUpdateFailure)?;
+        let verdict = match &ctx.verifier {
+            Some(verifier) => verifier.verify(task)?,
+            None => Verdict::Passed,
+        };
+
+        match verdict {
+            Verdict::Passed => ctx
+                .repository
+                .mark_completed(task.id())
+                .map_err(SupervisorError::StatusUpdateFailure)?,
+            Verdict::Failed(feedback) => {
+                let rounds = task.verification_rounds() + 1;
+                let max_rounds = ctx.verifier.as_ref().map_or(1, |v| v.max_rounds());
+                if rounds >= max_rounds {
+                    ctx.repository
+                        .mark_failed(
+                            task.id(),
+                            &format!("Verification failed after {rounds} rounds: {feedback}"),
+                        )
+                        .map_err(SupervisorError::StatusUpdateFailure)?;
+                } else {
+                    ctx.repository
+                        .return_for_rework(task.id(), &feedback)
+                        .map_err(SupervisorError::StatusUpdateFailure)?;
+                }
+            }
+        }
         Ok(true)
     }
 }
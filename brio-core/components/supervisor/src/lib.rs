@@ -19,7 +19,9 @@ pub mod orchestrator;
 pub mod planner;
 pub mod repository;
 pub mod selector;
+pub mod verification;
 pub mod wit_bindings;
+pub mod workspace;
 
 /// WIT bindings for the brio-host world.
 ///
@@ -41,6 +43,9 @@ use mesh_client::WitAgentDispatcher;
 use orchestrator::Supervisor;
 use planner::WitPlanner;
 use repository::WitTaskRepository;
+use std::sync::Arc;
+use verification::{MeshVerifier, VerificationPolicy};
+use workspace::WitWorkspace;
 
 /// Guest export: Run a single supervision cycle.
 ///
@@ -75,7 +80,15 @@ fn run_inner() -> Result<u32, orchestrator::SupervisorError> {
     let dispatcher = WitAgentDispatcher::new();
     let planner = WitPlanner::new();
     let selector = KeywordAgentSelector;
-    let supervisor = Supervisor::new(repository, dispatcher, planner, selector);
+    let policy = VerificationPolicy::from_host().unwrap_or_else(|e| {
+        eprintln!("[supervisor] Ignoring invalid verification policy: {e}");
+        VerificationPolicy::default()
+    });
+    let mut supervisor = Supervisor::new(repository, dispatcher, planner, selector)
+        .with_verifier(Arc::new(MeshVerifier::new(policy)));
+    if let Some(workspace) = WitWorkspace::from_host() {
+        supervisor = supervisor.with_workspace(Arc::new(workspace));
+    }
 
     supervisor.poll_tasks()
 }
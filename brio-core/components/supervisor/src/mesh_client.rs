@@ -3,8 +3,9 @@
 //! Abstracts agent communication via the WIT `service-mesh` interface.
 //! Follows Dependency Inversion: code depends on `AgentDispatcher` trait.
 
-use crate::domain::{AgentId, Task};
+use crate::domain::{AgentId, PredecessorOutput, Task};
 use crate::wit_bindings;
+use core::fmt::Write;
 
 /// Errors that can occur during mesh operations.
 #[derive(Debug)]
@@ -51,17 +52,68 @@ pub enum DispatchResult {
 pub trait AgentDispatcher: Send + Sync {
     /// Dispatches a task to the specified agent.
     ///
+    /// `predecessors` carries the outputs of the task's completed prerequisites
+    /// and is empty for tasks without any.
+    ///
     /// # Errors
     /// Returns `MeshError` if dispatch fails.
-    fn dispatch(&self, agent: &AgentId, task: &Task) -> Result<DispatchResult, MeshError>;
+    fn dispatch(
+        &self,
+        agent: &AgentId,
+        task: &Task,
+        predecessors: &[PredecessorOutput],
+    ) -> Result<DispatchResult, MeshError>;
+
+    /// Returns the output of a task the agent accepted earlier, or `None`
+    /// while the agent is still working on it.
+    ///
+    /// Dispatchers whose agents always complete synchronously never leave
+    /// accepted tasks behind, so the default reports them as still running.
+    ///
+    /// # Errors
+    /// Returns `MeshError` if the agent cannot be queried.
+    fn poll_completion(&self, agent: &AgentId, task: &Task) -> Result<Option<String>, MeshError> {
+        let _ = (agent, task);
+        Ok(None)
+    }
 }
 
 /// Payload sent to an agent for task execution.
 #[derive(Debug, serde::Serialize)]
-struct TaskContextDto {
+pub(crate) struct TaskContextDto {
     #[serde(rename = "task-id")]
-    task_id: String,
-    description: String,
+    pub(crate) task_id: String,
+    pub(crate) description: String,
+}
+
+impl TaskContextDto {
+    /// Builds the context for a task, appending the outputs of its
+    /// prerequisites and the feedback of a failed verification to the
+    /// description so any agent can use them.
+    fn new(task: &Task, predecessors: &[PredecessorOutput]) -> Self {
+        let mut description = task.content().to_string();
+        if let Some(feedback) = task.feedback() {
+            let _ = write!(
+                description,
+                "\n\nThe previous attempt failed verification:\n{feedback}"
+            );
+        }
+        if !predecessors.is_empty() {
+            description.push_str("\n\nOutputs of prerequisite tasks:");
+            for predecessor in predecessors {
+                let _ = write!(
+                    description,
+                    "\n\n### {} ({})\n{}",
+                    predecessor.description, predecessor.task_id, predecessor.output
+                );
+            }
+        }
+
+        Self {
+            task_id: task.id().to_string(),
+            description,
+        }
+    }
 }
 
 /// Dispatcher implementation using WIT `service-mesh` bindings.
@@ -82,11 +134,13 @@ impl Default for WitAgentDispatcher {
 }
 
 impl AgentDispatcher for WitAgentDispatcher {
-    fn dispatch(&self, agent: &AgentId, task: &Task) -> Result<DispatchResult, MeshError> {
-        let context = TaskContextDto {
-            task_id: task.id().to_string(),
-            description: task.content().to_string(),
-        };
+    fn dispatch(
+        &self,
+        agent: &AgentId,
+        task: &Task,
+        predecessors: &[PredecessorOutput],
+    ) -> Result<DispatchResult, MeshError> {
+        let context = TaskContextDto::new(task, predecessors);
 
         let payload_json = serde_json::to_string(&context)
             .map_err(|e| MeshError::SerializationError(e.to_string()))?;
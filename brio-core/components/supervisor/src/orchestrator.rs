@@ -7,20 +7,25 @@
 //!
 //! Dependencies are injected via traits (DIP), enabling testability.
 
-use crate::domain::{Task, TaskStatus};
+use crate::domain::{PlannedSubtask, Task, TaskStatus};
 use crate::mesh_client::{AgentDispatcher, MeshError};
 use crate::repository::{RepositoryError, TaskRepository};
 use crate::selector::AgentSelector;
+use crate::verification::Verifier;
+use crate::workspace::Workspace;
+use std::sync::Arc;
 
 use crate::handlers;
 
 /// Task decomposition capability.
 pub trait Planner {
-    /// Decomposes a task into subtasks or a plan.
+    /// Decomposes a task into subtasks linked by their prerequisites.
+    ///
+    /// Returns `None` if the task should be executed without decomposition.
     ///
     /// # Errors
     /// Returns error if planning fails.
-    fn plan(&self, objective: &str) -> Result<Option<Vec<String>>, PlannerError>;
+    fn plan(&self, objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError>;
 }
 
 /// Errors occurring during planning.
@@ -84,6 +89,8 @@ where
     pub(crate) dispatcher: D,
     pub(crate) planner: P,
     pub(crate) selector: S,
+    pub(crate) verifier: Option<Arc<dyn Verifier>>,
+    pub(crate) workspace: Option<Arc<dyn Workspace>>,
 }
 
 impl<R, D, P, S> Supervisor<R, D, P, S>
@@ -101,9 +108,30 @@ where
             dispatcher,
             planner,
             selector,
+            verifier: None,
+            workspace: None,
         }
     }
 
+    /// Checks task results with `verifier` before completing them.
+    ///
+    /// Without a verifier, tasks are completed as soon as they reach verification.
+    #[must_use]
+    pub fn with_verifier(mut self, verifier: Arc<dyn Verifier>) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// Gives every root task its own session on `workspace`.
+    ///
+    /// Subtasks share the session of their root task. Without a workspace,
+    /// tasks are not given a session.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: Arc<dyn Workspace>) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
     /// Executes a single poll cycle.
     ///
     /// Fetches all pending tasks and attempts to dispatch each one.
@@ -124,6 +152,8 @@ where
             planner: &self.planner,
             selector: &self.selector,
             branch_manager: None,
+            verifier: self.verifier.clone(),
+            workspace: self.workspace.clone(),
         };
 
         for task in active_tasks {
@@ -183,12 +213,15 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{AgentId, Priority, TaskId, TaskStatus};
+    use crate::domain::{AgentId, Capability, PredecessorOutput, Priority, TaskId, TaskStatus};
     use crate::mesh_client::{DispatchResult, MeshError};
     use crate::selector::KeywordAgentSelector;
+    use crate::verification::Verdict;
+    use crate::workspace::Workspace;
     use std::cell::RefCell;
     use std::collections::HashSet;
     use std::rc::Rc;
+    use std::sync::Mutex;
 
     /// Shared mock repository state.
     struct MockRepositoryInner {
@@ -233,6 +266,33 @@ mod tests {
         }
     }
 
+    /// Rebuilds a task with a new status and agent, keeping everything else.
+    fn rebuilt(
+        task: &Task,
+        status: TaskStatus,
+        agent: Option<AgentId>,
+    ) -> Result<Task, RepositoryError> {
+        Task::new(
+            task.id(),
+            task.content().to_string(),
+            task.priority(),
+            status,
+            task.parent_id(),
+            agent,
+            task.required_capabilities().clone(),
+        )
+        .map(|t| {
+            t.with_dependencies(task.dependencies().to_vec())
+                .with_output(task.output().map(str::to_string))
+                .with_session(task.session_id().map(str::to_string))
+                .with_verification(
+                    task.verification_rounds(),
+                    task.feedback().map(str::to_string),
+                )
+        })
+        .map_err(|e| RepositoryError::ParseError(e.to_string()))
+    }
+
     impl TaskRepository for MockRepository {
         fn fetch_active_tasks(&self) -> Result<Vec<Task>, RepositoryError> {
             Ok(self
@@ -252,16 +312,7 @@ mod tests {
         ) -> Result<(), RepositoryError> {
             let mut tasks = self.0.tasks.borrow_mut();
             if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
-                *t = Task::new(
-                    t.id(),
-                    t.content().to_string(),
-                    t.priority(),
-                    status,
-                    t.parent_id(),
-                    t.assigned_agent().cloned(),
-                    t.required_capabilities().clone(),
-                )
-                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+                *t = rebuilt(t, status, t.assigned_agent().cloned())?;
             }
             Ok(())
         }
@@ -271,16 +322,7 @@ mod tests {
 
             let mut tasks = self.0.tasks.borrow_mut();
             if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
-                *t = Task::new(
-                    t.id(),
-                    t.content().to_string(),
-                    t.priority(),
-                    t.status(),
-                    t.parent_id(),
-                    Some(agent.clone()),
-                    t.required_capabilities().clone(),
-                )
-                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+                *t = rebuilt(t, t.status(), Some(agent.clone()))?;
             }
             Ok(())
         }
@@ -290,16 +332,7 @@ mod tests {
 
             let mut tasks = self.0.tasks.borrow_mut();
             if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
-                *t = Task::new(
-                    t.id(),
-                    t.content().to_string(),
-                    t.priority(),
-                    TaskStatus::Assigned,
-                    t.parent_id(),
-                    Some(agent.clone()),
-                    t.required_capabilities().clone(),
-                )
-                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+                *t = rebuilt(t, TaskStatus::Assigned, Some(agent.clone()))?;
             }
             Ok(())
         }
@@ -309,16 +342,7 @@ mod tests {
 
             let mut tasks = self.0.tasks.borrow_mut();
             if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
-                *t = Task::new(
-                    t.id(),
-                    t.content().to_string(),
-                    t.priority(),
-                    TaskStatus::Completed,
-                    t.parent_id(),
-                    t.assigned_agent().cloned(),
-                    t.required_capabilities().clone(),
-                )
-                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+                *t = rebuilt(t, TaskStatus::Completed, t.assigned_agent().cloned())?;
             }
             Ok(())
         }
@@ -332,16 +356,7 @@ mod tests {
             // Also update status in main list so fetch_subtasks works correctly
             let mut tasks = self.0.tasks.borrow_mut();
             if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
-                *t = Task::new(
-                    t.id(),
-                    t.content().to_string(),
-                    t.priority(),
-                    TaskStatus::Failed,
-                    t.parent_id(),
-                    t.assigned_agent().cloned(),
-                    t.required_capabilities().clone(),
-                )
-                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+                *t = rebuilt(t, TaskStatus::Failed, t.assigned_agent().cloned())?;
             }
             Ok(())
         }
@@ -370,6 +385,68 @@ mod tests {
             Ok(new_id)
         }
 
+        fn create_dependent_task(
+            &self,
+            content: String,
+            priority: Priority,
+            parent_id: TaskId,
+            depends_on: &[TaskId],
+            capability: Option<Capability>,
+        ) -> Result<TaskId, RepositoryError> {
+            let mut tasks = self.0.tasks.borrow_mut();
+            let max_id = tasks.iter().map(|t| t.id().inner()).max().unwrap_or(0);
+            let new_id = TaskId::new(max_id + 1);
+            let task = Task::new(
+                new_id,
+                content,
+                priority,
+                TaskStatus::Pending,
+                Some(parent_id),
+                None,
+                capability.into_iter().collect(),
+            )
+            .map_err(|e| RepositoryError::ParseError(e.to_string()))?
+            .with_dependencies(depends_on.to_vec())
+            .with_session(
+                tasks
+                    .iter()
+                    .find(|t| t.id() == parent_id)
+                    .and_then(|t| t.session_id().map(str::to_string)),
+            );
+            tasks.push(task);
+            Ok(new_id)
+        }
+
+        fn assign_session(&self, task_id: TaskId, session_id: &str) -> Result<(), RepositoryError> {
+            let mut tasks = self.0.tasks.borrow_mut();
+            if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
+                *t = t.clone().with_session(Some(session_id.to_string()));
+            }
+            Ok(())
+        }
+
+        fn record_output(&self, task_id: TaskId, output: &str) -> Result<(), RepositoryError> {
+            let mut tasks = self.0.tasks.borrow_mut();
+            if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
+                *t = t.clone().with_output(Some(output.to_string()));
+            }
+            Ok(())
+        }
+
+        fn return_for_rework(
+            &self,
+            task_id: TaskId,
+            feedback: &str,
+        ) -> Result<(), RepositoryError> {
+            let mut tasks = self.0.tasks.borrow_mut();
+            if let Some(t) = tasks.iter_mut().find(|t| t.id() == task_id) {
+                let rounds = t.verification_rounds() + 1;
+                *t = rebuilt(t, TaskStatus::Executing, None)?
+                    .with_verification(rounds, Some(feedback.to_string()));
+            }
+            Ok(())
+        }
+
         fn fetch_subtasks(&self, parent_id: TaskId) -> Result<Vec<Task>, RepositoryError> {
             Ok(self
                 .0
@@ -384,7 +461,7 @@ mod tests {
 
     struct MockPlanner;
     impl Planner for MockPlanner {
-        fn plan(&self, _objective: &str) -> Result<Option<Vec<String>>, PlannerError> {
+        fn plan(&self, _objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError> {
             // Default: no decomposition
             Ok(None)
         }
@@ -396,7 +473,12 @@ mod tests {
     }
 
     impl AgentDispatcher for MockDispatcher {
-        fn dispatch(&self, _agent: &AgentId, _task: &Task) -> Result<DispatchResult, MeshError> {
+        fn dispatch(
+            &self,
+            _agent: &AgentId,
+            _task: &Task,
+            _predecessors: &[PredecessorOutput],
+        ) -> Result<DispatchResult, MeshError> {
             Ok(self.result.clone())
         }
     }
@@ -531,11 +613,11 @@ mod tests {
     }
 
     struct DecomposingPlanner {
-        subtasks: Vec<String>,
+        subtasks: Vec<PlannedSubtask>,
     }
 
     impl Planner for DecomposingPlanner {
-        fn plan(&self, _objective: &str) -> Result<Option<Vec<String>>, PlannerError> {
+        fn plan(&self, _objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError> {
             Ok(Some(self.subtasks.clone()))
         }
     }
@@ -555,7 +637,10 @@ mod tests {
         .expect("test task should be valid");
         let repo = MockRepository::new(vec![root_task]);
         let planner = DecomposingPlanner {
-            subtasks: vec!["Get Plans".to_string(), "Find Weakness".to_string()],
+            subtasks: vec![
+                PlannedSubtask::new("plans", "Get Plans"),
+                PlannedSubtask::new("weakness", "Find Weakness"),
+            ],
         };
         let dispatcher = MockDispatcher {
             result: DispatchResult::Accepted,
@@ -600,6 +685,479 @@ mod tests {
         Ok(())
     }
 
+    /// Workspace handing out numbered sessions.
+    #[derive(Default)]
+    struct CountingWorkspace {
+        opened: Mutex<u32>,
+    }
+
+    impl Workspace for CountingWorkspace {
+        fn begin_session(&self) -> Result<String, MeshError> {
+            let mut opened = self.opened.lock().expect("lock");
+            *opened += 1;
+            Ok(format!("session-{opened}"))
+        }
+    }
+
+    #[test]
+    fn root_tasks_open_a_session_their_subtasks_share() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![test_task(1, "Build Death Star")]);
+        let planner = DecomposingPlanner {
+            subtasks: vec![
+                PlannedSubtask::new("plans", "Get Plans"),
+                PlannedSubtask::new("weakness", "Find Weakness"),
+            ],
+        };
+        let workspace = Arc::new(CountingWorkspace::default());
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            MockDispatcher {
+                result: DispatchResult::Accepted,
+            },
+            planner,
+            KeywordAgentSelector,
+        )
+        .with_workspace(workspace.clone());
+
+        supervisor.poll_tasks()?;
+        let root = repo.task(TaskId::new(1)).expect("root task should exist");
+        assert_eq!(root.session_id(), Some("session-1"));
+
+        supervisor.poll_tasks()?;
+        supervisor.poll_tasks()?;
+        let subtasks = repo.fetch_subtasks(TaskId::new(1))?;
+        assert_eq!(subtasks.len(), 2);
+        assert!(subtasks.iter().all(|t| t.session_id() == Some("session-1")));
+        assert_eq!(*workspace.opened.lock().expect("lock"), 1);
+        Ok(())
+    }
+
+    /// Planner that only decomposes the root objective.
+    struct RootPlanner {
+        objective: &'static str,
+        subtasks: Vec<PlannedSubtask>,
+    }
+
+    impl Planner for RootPlanner {
+        fn plan(&self, objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError> {
+            Ok((objective == self.objective).then(|| self.subtasks.clone()))
+        }
+    }
+
+    /// Dispatcher completing every task and recording what it received.
+    #[derive(Default)]
+    struct RecordingDispatcher {
+        dispatched: Mutex<Vec<(String, AgentId, Vec<PredecessorOutput>)>>,
+    }
+
+    impl AgentDispatcher for RecordingDispatcher {
+        fn dispatch(
+            &self,
+            agent: &AgentId,
+            task: &Task,
+            predecessors: &[PredecessorOutput],
+        ) -> Result<DispatchResult, MeshError> {
+            self.dispatched.lock().expect("lock").push((
+                task.content().to_string(),
+                agent.clone(),
+                predecessors.to_vec(),
+            ));
+            Ok(DispatchResult::Completed(format!(
+                "done: {}",
+                task.content()
+            )))
+        }
+    }
+
+    fn planning_task(id: u64, content: &str) -> Task {
+        Task::new(
+            TaskId::new(id),
+            content.to_string(),
+            Priority::DEFAULT,
+            TaskStatus::Planning,
+            None,
+            None,
+            HashSet::new(),
+        )
+        .expect("test task should be valid")
+    }
+
+    #[test]
+    fn plan_subtasks_run_after_their_prerequisites() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![planning_task(1, "Ship feature")]);
+        let planner = RootPlanner {
+            objective: "Ship feature",
+            subtasks: vec![
+                PlannedSubtask::new("review", "Inspect the change")
+                    .with_dependencies(["build"])
+                    .with_capability(Capability::Reviewing),
+                PlannedSubtask::new("design", "Design the API").with_outputs(["api.md"]),
+                PlannedSubtask::new("build", "Build the API").with_dependencies(["design"]),
+            ],
+        };
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            planner,
+            KeywordAgentSelector,
+        );
+
+        // Prerequisites are created first so their ids can be recorded.
+        supervisor.poll_tasks()?;
+        let subtasks = repo.fetch_subtasks(TaskId::new(1))?;
+        let ids: Vec<_> = subtasks.iter().map(|t| t.id().inner()).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+        assert_eq!(
+            subtasks[0].content(),
+            "Design the API\n\nExpected outputs: api.md"
+        );
+        assert_eq!(subtasks[1].dependencies(), &[TaskId::new(2)]);
+        assert_eq!(subtasks[2].dependencies(), &[TaskId::new(3)]);
+
+        for _ in 0..16 {
+            supervisor.poll_tasks()?;
+        }
+        let root = repo.task(TaskId::new(1)).expect("root task should exist");
+        assert_eq!(root.status(), TaskStatus::Completed);
+
+        let dispatched = supervisor.dispatcher.dispatched.lock().expect("lock");
+        let order: Vec<_> = dispatched.iter().map(|(c, _, _)| c.as_str()).collect();
+        assert_eq!(
+            order,
+            vec![
+                "Design the API\n\nExpected outputs: api.md",
+                "Build the API",
+                "Inspect the change"
+            ]
+        );
+        assert!(dispatched[0].2.is_empty());
+        assert_eq!(
+            dispatched[1].2,
+            vec![PredecessorOutput {
+                task_id: TaskId::new(2),
+                description: "Design the API\n\nExpected outputs: api.md".to_string(),
+                output: "done: Design the API\n\nExpected outputs: api.md".to_string(),
+            }]
+        );
+        assert_eq!(dispatched[2].2[0].output, "done: Build the API");
+        assert_eq!(dispatched[2].1.as_str(), "agent_reviewer");
+        Ok(())
+    }
+
+    #[test]
+    fn cyclic_plan_fails_without_creating_subtasks() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![planning_task(1, "Ship feature")]);
+        let planner = RootPlanner {
+            objective: "Ship feature",
+            subtasks: vec![
+                PlannedSubtask::new("a", "First").with_dependencies(["b"]),
+                PlannedSubtask::new("b", "Second").with_dependencies(["a"]),
+            ],
+        };
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            planner,
+            KeywordAgentSelector,
+        );
+
+        supervisor.poll_tasks()?;
+
+        assert!(repo.fetch_subtasks(TaskId::new(1))?.is_empty());
+        let failed = repo.0.failed.borrow();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].1.contains("dependency cycle between: a, b"));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_prerequisite_fails_its_successors() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![planning_task(1, "Ship feature")]);
+        let planner = RootPlanner {
+            objective: "Ship feature",
+            subtasks: vec![
+                PlannedSubtask::new("build", "Build"),
+                PlannedSubtask::new("deploy", "Deploy").with_dependencies(["build"]),
+            ],
+        };
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            planner,
+            KeywordAgentSelector,
+        );
+
+        supervisor.poll_tasks()?;
+        repo.mark_failed(TaskId::new(2), "compiler crashed")?;
+        supervisor.poll_tasks()?;
+
+        let deploy = repo.task(TaskId::new(3)).expect("deploy task should exist");
+        assert_eq!(deploy.status(), TaskStatus::Failed);
+        assert!(
+            repo.0
+                .failed
+                .borrow()
+                .iter()
+                .any(|(id, reason)| *id == TaskId::new(3) && reason.contains("task_2"))
+        );
+        assert!(
+            supervisor
+                .dispatcher
+                .dispatched
+                .lock()
+                .expect("lock")
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_plan_dependencies_are_recorded_once() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![planning_task(1, "Ship feature")]);
+        let planner = RootPlanner {
+            objective: "Ship feature",
+            subtasks: vec![
+                PlannedSubtask::new("build", "Build"),
+                PlannedSubtask::new("deploy", "Deploy").with_dependencies(["build", "build"]),
+            ],
+        };
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            planner,
+            KeywordAgentSelector,
+        );
+
+        supervisor.poll_tasks()?;
+        let deploy = repo.task(TaskId::new(3)).expect("deploy task should exist");
+        assert_eq!(deploy.dependencies(), &[TaskId::new(2)]);
+
+        for _ in 0..16 {
+            supervisor.poll_tasks()?;
+        }
+        let root = repo.task(TaskId::new(1)).expect("root task should exist");
+        assert_eq!(root.status(), TaskStatus::Completed);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_prerequisite_fails_the_task() -> Result<(), SupervisorError> {
+        let parent = Task::new(
+            TaskId::new(1),
+            "Ship feature".to_string(),
+            Priority::DEFAULT,
+            TaskStatus::Coordinating,
+            None,
+            None,
+            HashSet::new(),
+        )
+        .expect("test task should be valid");
+        let child = Task::new(
+            TaskId::new(2),
+            "Deploy".to_string(),
+            Priority::DEFAULT,
+            TaskStatus::Pending,
+            Some(TaskId::new(1)),
+            None,
+            HashSet::new(),
+        )
+        .expect("test task should be valid")
+        .with_dependencies(vec![TaskId::new(99)]);
+        let repo = MockRepository::new(vec![parent, child]);
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            MockPlanner,
+            KeywordAgentSelector,
+        );
+
+        supervisor.poll_tasks()?;
+
+        let child = repo.task(TaskId::new(2)).expect("child task should exist");
+        assert_eq!(child.status(), TaskStatus::Failed);
+        assert!(
+            repo.0
+                .failed
+                .borrow()
+                .iter()
+                .any(|(id, reason)| *id == TaskId::new(2) && reason.contains("task_99"))
+        );
+        Ok(())
+    }
+
+    /// Dispatcher accepting every task and completing it once told to.
+    #[derive(Default)]
+    struct AsyncDispatcher {
+        finished: Mutex<bool>,
+    }
+
+    impl AgentDispatcher for AsyncDispatcher {
+        fn dispatch(
+            &self,
+            _agent: &AgentId,
+            _task: &Task,
+            _predecessors: &[PredecessorOutput],
+        ) -> Result<DispatchResult, MeshError> {
+            Ok(DispatchResult::Accepted)
+        }
+
+        fn poll_completion(
+            &self,
+            _agent: &AgentId,
+            task: &Task,
+        ) -> Result<Option<String>, MeshError> {
+            Ok((*self.finished.lock().expect("lock")).then(|| format!("done: {}", task.content())))
+        }
+    }
+
+    #[test]
+    fn accepted_tasks_record_their_output_on_completion() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![executing_task(1, "Add login")]);
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            AsyncDispatcher::default(),
+            MockPlanner,
+            KeywordAgentSelector,
+        );
+
+        supervisor.poll_tasks()?;
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Executing);
+        assert!(task.assigned_agent().is_some());
+        assert_eq!(task.output(), None);
+
+        *supervisor.dispatcher.finished.lock().expect("lock") = true;
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Verifying);
+        assert_eq!(task.output(), Some("done: Add login"));
+        Ok(())
+    }
+
+    /// Verifier returning scripted verdicts, then passing.
+    struct ScriptedVerifier {
+        verdicts: Mutex<Vec<Verdict>>,
+        max_rounds: u32,
+    }
+
+    impl ScriptedVerifier {
+        fn new(verdicts: Vec<Verdict>, max_rounds: u32) -> Arc<Self> {
+            Arc::new(Self {
+                verdicts: Mutex::new(verdicts),
+                max_rounds,
+            })
+        }
+    }
+
+    impl Verifier for ScriptedVerifier {
+        fn verify(&self, _task: &Task) -> Result<Verdict, MeshError> {
+            let mut verdicts = self.verdicts.lock().expect("lock");
+            Ok(if verdicts.is_empty() {
+                Verdict::Passed
+            } else {
+                verdicts.remove(0)
+            })
+        }
+
+        fn max_rounds(&self) -> u32 {
+            self.max_rounds
+        }
+    }
+
+    fn executing_task(id: u64, content: &str) -> Task {
+        Task::new(
+            TaskId::new(id),
+            content.to_string(),
+            Priority::DEFAULT,
+            TaskStatus::Executing,
+            None,
+            None,
+            HashSet::new(),
+        )
+        .expect("test task should be valid")
+    }
+
+    #[test]
+    fn verified_tasks_are_completed() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![executing_task(1, "Add login")]);
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            MockPlanner,
+            KeywordAgentSelector,
+        )
+        .with_verifier(ScriptedVerifier::new(vec![], 3));
+
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Verifying);
+        assert_eq!(task.output(), Some("done: Add login"));
+
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Completed);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_verification_returns_task_with_feedback() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![executing_task(1, "Add login")]);
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            MockPlanner,
+            KeywordAgentSelector,
+        )
+        .with_verifier(ScriptedVerifier::new(
+            vec![Verdict::Failed("`cargo test` failed".to_string())],
+            3,
+        ));
+
+        supervisor.poll_tasks()?;
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Executing);
+        assert_eq!(task.verification_rounds(), 1);
+        assert_eq!(task.feedback(), Some("`cargo test` failed"));
+        assert!(task.assigned_agent().is_none());
+
+        supervisor.poll_tasks()?;
+        supervisor.poll_tasks()?;
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Completed);
+        assert_eq!(
+            supervisor.dispatcher.dispatched.lock().expect("lock").len(),
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn task_fails_after_max_verification_rounds() -> Result<(), SupervisorError> {
+        let repo = MockRepository::new(vec![executing_task(1, "Add login")]);
+        let failure = || Verdict::Failed("Review by agent_reviewer: missing tests".to_string());
+        let supervisor = Supervisor::new(
+            repo.clone(),
+            RecordingDispatcher::default(),
+            MockPlanner,
+            KeywordAgentSelector,
+        )
+        .with_verifier(ScriptedVerifier::new(vec![failure(), failure()], 2));
+
+        for _ in 0..4 {
+            supervisor.poll_tasks()?;
+        }
+
+        let task = repo.task(TaskId::new(1)).expect("task should exist");
+        assert_eq!(task.status(), TaskStatus::Failed);
+        let failed = repo.0.failed.borrow();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].1.contains("Verification failed after 2 rounds"));
+        assert!(failed[0].1.contains("missing tests"));
+        Ok(())
+    }
+
     #[test]
     fn select_agent_reviewer_based_on_keyword() {
         let task = Task::new(
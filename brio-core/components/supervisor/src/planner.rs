@@ -2,6 +2,7 @@
 //!
 //! Abstracts the planner capability via the WIT `planner` interface.
 
+use crate::domain::{Capability, PlannedSubtask};
 use crate::orchestrator::{Planner, PlannerError};
 use crate::wit_bindings;
 
@@ -23,22 +24,25 @@ impl Default for WitPlanner {
 }
 
 impl Planner for WitPlanner {
-    fn plan(&self, objective: &str) -> Result<Option<Vec<String>>, PlannerError> {
-        // Call the WIT interface
-        // Note: The WIT definition returns a Plan struct with Subtasks.
-        // We map these to simple strings for this MVP step.
-
-        match wit_bindings::brio::core::planner::decompose(objective) {
-            Ok(plan) => {
-                if plan.steps.is_empty() {
-                    Ok(None)
-                } else {
-                    Ok(Some(
-                        plan.steps.into_iter().map(|s| s.description).collect(),
-                    ))
-                }
-            }
-            Err(e) => Err(PlannerError(e)),
+    fn plan(&self, objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError> {
+        let plan = wit_bindings::brio::core::planner::decompose(objective).map_err(PlannerError)?;
+        if plan.steps.is_empty() {
+            return Ok(None);
         }
+
+        // Capabilities the supervisor does not know are dropped, leaving the
+        // choice of agent to the selector.
+        Ok(Some(
+            plan.steps
+                .into_iter()
+                .map(|step| PlannedSubtask {
+                    id: step.id,
+                    description: step.description,
+                    depends_on: step.depends_on,
+                    outputs: step.outputs,
+                    capability: step.capability.as_deref().and_then(Capability::parse),
+                })
+                .collect(),
+        ))
     }
 }
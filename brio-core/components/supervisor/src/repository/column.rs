@@ -19,6 +19,18 @@ pub mod task_cols {
     pub const PARENT_ID: &str = "parent_id";
     /// Task `assigned_agent` column name
     pub const ASSIGNED_AGENT: &str = "assigned_agent";
+    /// Task `depends_on` column name (JSON array of task ids)
+    pub const DEPENDS_ON: &str = "depends_on";
+    /// Task `required_capability` column name
+    pub const REQUIRED_CAPABILITY: &str = "required_capability";
+    /// Task `output` column name
+    pub const OUTPUT: &str = "output";
+    /// Task `session_id` column name
+    pub const SESSION_ID: &str = "session_id";
+    /// Task `verification_rounds` column name
+    pub const VERIFICATION_ROUNDS: &str = "verification_rounds";
+    /// Task `feedback` column name
+    pub const FEEDBACK: &str = "feedback";
 }
 
 /// Column constants for branch table
@@ -2,7 +2,7 @@
 //!
 //! This module defines the `TaskRepository` trait.
 
-use crate::domain::{AgentId, Priority, Task, TaskId, TaskStatus};
+use crate::domain::{AgentId, Capability, Priority, Task, TaskId, TaskStatus};
 use crate::repository::column::RepositoryError;
 
 /// Contract for task state access.
@@ -58,6 +58,43 @@ pub trait TaskRepository {
         parent_id: Option<TaskId>,
     ) -> Result<TaskId, RepositoryError>;
 
+    /// Creates a pending subtask that may only start once `depends_on` have completed.
+    ///
+    /// The subtask works in the same session as its parent.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if the creation fails.
+    fn create_dependent_task(
+        &self,
+        content: String,
+        priority: Priority,
+        parent_id: TaskId,
+        depends_on: &[TaskId],
+        capability: Option<Capability>,
+    ) -> Result<TaskId, RepositoryError>;
+
+    /// Sets the session a task and the subtasks created for it work in.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if the update fails.
+    fn assign_session(&self, task_id: TaskId, session_id: &str) -> Result<(), RepositoryError>;
+
+    /// Records the output an agent returned for a task.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if the update fails.
+    fn record_output(&self, task_id: TaskId, output: &str) -> Result<(), RepositoryError>;
+
+    /// Sends a task that failed verification back to execution.
+    ///
+    /// Stores `feedback` for the next attempt, increments the task's
+    /// verification rounds and clears its assigned agent so it is dispatched
+    /// again.
+    ///
+    /// # Errors
+    /// Returns `RepositoryError` if the update fails.
+    fn return_for_rework(&self, task_id: TaskId, feedback: &str) -> Result<(), RepositoryError>;
+
     /// Fetches all subtasks for a given parent task.
     ///
     /// # Errors
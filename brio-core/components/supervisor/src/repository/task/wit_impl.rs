@@ -2,7 +2,7 @@
 //!
 //! This module provides the `WitTaskRepository` implementation.
 
-use crate::domain::{AgentId, Priority, Task, TaskId, TaskStatus};
+use crate::domain::{AgentId, Capability, Priority, Task, TaskId, TaskStatus};
 use crate::repository::column::{
     RepositoryError, expect_affected, extract_returned_id, get_column_value, task_cols,
 };
@@ -20,6 +20,25 @@ impl WitTaskRepository {
         Self
     }
 
+    /// Column list shared by every task query.
+    fn select_columns() -> String {
+        [
+            task_cols::ID,
+            task_cols::CONTENT,
+            task_cols::PRIORITY,
+            task_cols::STATUS,
+            task_cols::PARENT_ID,
+            task_cols::ASSIGNED_AGENT,
+            task_cols::DEPENDS_ON,
+            task_cols::REQUIRED_CAPABILITY,
+            task_cols::OUTPUT,
+            task_cols::SESSION_ID,
+            task_cols::VERIFICATION_ROUNDS,
+            task_cols::FEEDBACK,
+        ]
+        .join(", ")
+    }
+
     /// Parses a single row into a Task.
     fn parse_row(columns: &[String], values: &[String]) -> Result<Task, RepositoryError> {
         let id = get_column_value(columns, values, task_cols::ID)?
@@ -51,6 +70,39 @@ impl WitTaskRepository {
             _ => None,
         };
 
+        let dependencies = match get_column_value(columns, values, task_cols::DEPENDS_ON) {
+            Ok(v) if v != "NULL" && !v.is_empty() => serde_json::from_str::<Vec<u64>>(v)
+                .map_err(|e| RepositoryError::ParseError(format!("Invalid depends_on: {e}")))?
+                .into_iter()
+                .map(TaskId::new)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let required_capabilities =
+            get_column_value(columns, values, task_cols::REQUIRED_CAPABILITY)
+                .ok()
+                .and_then(|v| Capability::parse(v))
+                .into_iter()
+                .collect();
+
+        let optional = |name| {
+            get_column_value(columns, values, name)
+                .ok()
+                .filter(|v| *v != "NULL" && !v.is_empty())
+                .cloned()
+        };
+        let output = optional(task_cols::OUTPUT);
+        let session_id = optional(task_cols::SESSION_ID);
+        let feedback = optional(task_cols::FEEDBACK);
+
+        let verification_rounds = match optional(task_cols::VERIFICATION_ROUNDS) {
+            Some(v) => v.parse::<u32>().map_err(|e| {
+                RepositoryError::ParseError(format!("Invalid verification_rounds: {e}"))
+            })?,
+            None => 0,
+        };
+
         Task::new(
             TaskId::new(id),
             content,
@@ -58,8 +110,14 @@ impl WitTaskRepository {
             status,
             parent_id,
             assigned_agent,
-            std::collections::HashSet::new(),
+            required_capabilities,
         )
+        .map(|task| {
+            task.with_dependencies(dependencies)
+                .with_output(output)
+                .with_session(session_id)
+                .with_verification(verification_rounds, feedback)
+        })
         .map_err(|e| RepositoryError::ParseError(e.to_string()))
     }
 }
@@ -80,16 +138,11 @@ impl TaskRepository for WitTaskRepository {
             .join(", ");
 
         let sql = format!(
-            "SELECT {}, {}, {}, {}, {}, {} \
+            "SELECT {} \
              FROM tasks \
              WHERE status IN ({placeholders}) \
              ORDER BY priority DESC",
-            task_cols::ID,
-            task_cols::CONTENT,
-            task_cols::PRIORITY,
-            task_cols::STATUS,
-            task_cols::PARENT_ID,
-            task_cols::ASSIGNED_AGENT
+            Self::select_columns()
         );
 
         let params: Vec<String> = active_states
@@ -215,18 +268,95 @@ impl TaskRepository for WitTaskRepository {
         Ok(TaskId::new(id))
     }
 
+    fn create_dependent_task(
+        &self,
+        content: String,
+        priority: Priority,
+        parent_id: TaskId,
+        depends_on: &[TaskId],
+        capability: Option<Capability>,
+    ) -> Result<TaskId, RepositoryError> {
+        let sql = "INSERT INTO tasks \
+                   (content, priority, status, parent_id, depends_on, required_capability, session_id) \
+                   VALUES (?, ?, ?, ?, ?, ?, (SELECT session_id FROM tasks WHERE id = ?)) \
+                   RETURNING id";
+
+        let depends_on =
+            serde_json::to_string(&depends_on.iter().map(|id| id.inner()).collect::<Vec<_>>())
+                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+
+        let params = vec![
+            content,
+            priority.inner().to_string(),
+            TaskStatus::Pending
+                .as_str()
+                .expect("Pending is a simple variant")
+                .to_string(),
+            parent_id.inner().to_string(),
+            depends_on,
+            capability.map_or_else(|| "NULL".to_string(), |c| c.as_str().to_string()),
+            parent_id.inner().to_string(),
+        ];
+
+        let rows =
+            wit_bindings::sql_state::query(sql, &params).map_err(RepositoryError::SqlError)?;
+
+        let row = rows.first().ok_or_else(|| {
+            RepositoryError::SqlError("INSERT failed to return any rows".to_string())
+        })?;
+
+        let id = extract_returned_id(row, task_cols::ID)?;
+        Ok(TaskId::new(id))
+    }
+
+    fn assign_session(&self, task_id: TaskId, session_id: &str) -> Result<(), RepositoryError> {
+        let sql = "UPDATE tasks SET session_id = ? WHERE id = ?";
+        let params = vec![session_id.to_string(), task_id.inner().to_string()];
+
+        let affected =
+            wit_bindings::sql_state::execute(sql, &params).map_err(RepositoryError::SqlError)?;
+
+        expect_affected(task_id, affected)
+    }
+
+    fn record_output(&self, task_id: TaskId, output: &str) -> Result<(), RepositoryError> {
+        let sql = "UPDATE tasks SET output = ? WHERE id = ?";
+        let params = vec![output.to_string(), task_id.inner().to_string()];
+
+        let affected =
+            wit_bindings::sql_state::execute(sql, &params).map_err(RepositoryError::SqlError)?;
+
+        expect_affected(task_id, affected)
+    }
+
+    fn return_for_rework(&self, task_id: TaskId, feedback: &str) -> Result<(), RepositoryError> {
+        let sql = "UPDATE tasks \
+                   SET status = ?, assigned_agent = NULL, feedback = ?, \
+                       verification_rounds = verification_rounds + 1 \
+                   WHERE id = ?";
+
+        let params = vec![
+            TaskStatus::Executing
+                .as_str()
+                .expect("Executing is a simple variant")
+                .to_string(),
+            feedback.to_string(),
+            task_id.inner().to_string(),
+        ];
+
+        let affected =
+            wit_bindings::sql_state::execute(sql, &params).map_err(RepositoryError::SqlError)?;
+
+        expect_affected(task_id, affected)
+    }
+
     fn fetch_subtasks(&self, parent_id: TaskId) -> Result<Vec<Task>, RepositoryError> {
         let sql = format!(
-            "SELECT {}, {}, {}, {}, {}, {} \
+            "SELECT {} \
              FROM tasks \
              WHERE parent_id = ? \
              ORDER BY priority DESC",
-            task_cols::ID,
-            task_cols::CONTENT,
-            task_cols::PRIORITY,
-            task_cols::STATUS,
-            task_cols::PARENT_ID,
-            task_cols::ASSIGNED_AGENT
+            Self::select_columns()
         );
 
         let params = vec![parent_id.inner().to_string()];
@@ -2,7 +2,7 @@
 //!
 //! Provides algorithms for selecting the most appropriate agent for a given task.
 
-use crate::domain::{AgentId, Task};
+use crate::domain::{AgentId, Capability, Task};
 
 /// Strategy for selecting an agent for a task.
 pub trait AgentSelector {
@@ -11,6 +11,8 @@ pub trait AgentSelector {
 }
 
 /// Default implementation using keyword matching.
+///
+/// Capabilities a planner assigned to the task take precedence over keywords.
 pub struct KeywordAgentSelector;
 
 impl Default for KeywordAgentSelector {
@@ -21,6 +23,14 @@ impl Default for KeywordAgentSelector {
 
 impl AgentSelector for KeywordAgentSelector {
     fn select(&self, task: &Task) -> AgentId {
+        let capabilities = task.required_capabilities();
+        if capabilities.contains(&Capability::Reviewing) {
+            return AgentId::new("agent_reviewer").expect("static agent ID should be valid");
+        }
+        if capabilities.contains(&Capability::Coding) {
+            return AgentId::new("agent_coder").expect("static agent ID should be valid");
+        }
+
         // Use case-insensitive search without allocating a new String
         let content = task.content();
         if content.contains("review")
@@ -0,0 +1,518 @@
+//! Verification Layer - Checking Agent Results
+//!
+//! Abstracts how finished work is checked before a task is completed.
+//! `MeshVerifier` runs the configured steps via the WIT `service-mesh`
+//! interface: commands are executed by a tool component inside the task's
+//! session, and reviews are delegated to a reviewer agent along with the
+//! diff of the task's session.
+
+use crate::diff::{DiffAlgorithm, MyersDiff, format_unified_diff};
+use crate::domain::Task;
+use crate::mesh_client::{MeshError, TaskContextDto};
+use crate::wit_bindings;
+use crate::wit_bindings::session_fs::{ChangeKind, FileChange};
+use core::fmt::Write;
+use serde::{Deserialize, Serialize};
+
+/// Tool component that runs verification commands by default.
+pub const DEFAULT_COMMAND_TOOL: &str = "shell";
+
+/// Agent that reviews changes by default.
+pub const DEFAULT_REVIEWER: &str = "agent_reviewer";
+
+/// Number of failed verification rounds after which a task fails by default.
+pub const DEFAULT_MAX_ROUNDS: u32 = 3;
+
+/// Last line of a review reply that approves the changes.
+pub const APPROVAL_MARKER: &str = "VERDICT: APPROVED";
+
+/// Last line of a review reply that rejects the changes.
+pub const REJECTION_MARKER: &str = "VERDICT: REJECTED";
+
+/// Largest session diff, in bytes, sent to a reviewer; longer diffs are cut.
+pub const MAX_REVIEW_DIFF_BYTES: usize = 64 * 1024;
+
+/// A single check performed while verifying a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VerificationStep {
+    /// Runs a command, such as the test suite or a build, in the task's session.
+    ///
+    /// The step fails if the tool reports an error, e.g. a non-zero exit code.
+    Command {
+        /// Program followed by its arguments.
+        command: Vec<String>,
+        /// Tool component executing the command.
+        #[serde(default = "default_command_tool")]
+        tool: String,
+    },
+    /// Asks a reviewer agent to review the changes.
+    ///
+    /// The step fails unless the reply contains [`APPROVAL_MARKER`].
+    Review {
+        /// Agent performing the review.
+        #[serde(default = "default_reviewer")]
+        agent: String,
+    },
+}
+
+impl VerificationStep {
+    /// Creates a command step run by [`DEFAULT_COMMAND_TOOL`].
+    #[must_use]
+    pub fn command<I, T>(command: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self::Command {
+            command: command.into_iter().map(Into::into).collect(),
+            tool: default_command_tool(),
+        }
+    }
+
+    /// Creates a review step performed by [`DEFAULT_REVIEWER`].
+    #[must_use]
+    pub fn review() -> Self {
+        Self::Review {
+            agent: default_reviewer(),
+        }
+    }
+}
+
+fn default_command_tool() -> String {
+    DEFAULT_COMMAND_TOOL.to_string()
+}
+
+fn default_reviewer() -> String {
+    DEFAULT_REVIEWER.to_string()
+}
+
+const fn default_max_rounds() -> u32 {
+    DEFAULT_MAX_ROUNDS
+}
+
+/// Which checks verify a task and how often it may be retried.
+///
+/// Without steps, tasks are completed as soon as they reach verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationPolicy {
+    /// Checks run in order; all of them run even if an earlier one fails.
+    #[serde(default)]
+    pub steps: Vec<VerificationStep>,
+    /// Failed verification rounds after which the task fails.
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: u32,
+}
+
+impl VerificationPolicy {
+    /// Loads the policy from the kernel settings through the
+    /// `supervisor-config` interface.
+    ///
+    /// # Errors
+    /// Returns an error message if the host cannot provide the policy or it
+    /// is not valid.
+    pub fn from_host() -> Result<Self, String> {
+        let json = wit_bindings::supervisor_config::verification_policy()?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            max_rounds: DEFAULT_MAX_ROUNDS,
+        }
+    }
+}
+
+/// Outcome of verifying a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// All checks passed.
+    Passed,
+    /// At least one check failed; carries feedback for the next attempt.
+    Failed(String),
+}
+
+/// Contract for verifying finished tasks.
+pub trait Verifier: Send + Sync {
+    /// Checks the work done for a task.
+    ///
+    /// # Errors
+    /// Returns `MeshError` if a check could not be carried out.
+    fn verify(&self, task: &Task) -> Result<Verdict, MeshError>;
+
+    /// Returns the number of failed rounds after which the task fails.
+    fn max_rounds(&self) -> u32;
+}
+
+/// Mesh payload for invoking a tool component.
+#[derive(Debug, Serialize)]
+struct ToolCallDto {
+    params: String,
+    session_id: Option<String>,
+}
+
+/// Verifier implementation using WIT `service-mesh` bindings.
+pub struct MeshVerifier {
+    policy: VerificationPolicy,
+}
+
+impl MeshVerifier {
+    /// Creates a verifier running the steps of `policy`.
+    #[must_use]
+    pub const fn new(policy: VerificationPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Returns the policy this verifier applies.
+    #[must_use]
+    pub const fn policy(&self) -> &VerificationPolicy {
+        &self.policy
+    }
+
+    fn run_command(
+        task: &Task,
+        tool: &str,
+        command: &[String],
+    ) -> Result<Option<String>, MeshError> {
+        let call = ToolCallDto {
+            params: serde_json::to_string(command)
+                .map_err(|e| MeshError::SerializationError(e.to_string()))?,
+            session_id: task.session_id().map(str::to_string),
+        };
+        let payload = serde_json::to_string(&call)
+            .map_err(|e| MeshError::SerializationError(e.to_string()))?;
+
+        let result = wit_bindings::service_mesh::call(
+            tool,
+            "execute",
+            wit_bindings::service_mesh::Payload::Json(payload),
+        );
+        Ok(result
+            .err()
+            .map(|error| format!("`{}` failed: {error}", command.join(" "))))
+    }
+
+    fn run_review(task: &Task, agent: &str) -> Result<Option<String>, MeshError> {
+        let diff = task
+            .session_id()
+            .map(|session_id| {
+                wit_bindings::session_fs::changes(session_id)
+                    .map(|changes| render_changes(&changes))
+                    .map_err(MeshError::TransportError)
+            })
+            .transpose()?;
+        let payload = review_payload(task, diff.as_deref())?;
+
+        let response = wit_bindings::service_mesh::call(
+            agent,
+            "run",
+            wit_bindings::service_mesh::Payload::Json(payload),
+        )
+        .map_err(MeshError::AgentError)?;
+
+        match response {
+            wit_bindings::service_mesh::Payload::Json(review) if is_approved(&review) => Ok(None),
+            wit_bindings::service_mesh::Payload::Json(review) => {
+                Ok(Some(format!("Review by {agent}:\n{review}")))
+            }
+            wit_bindings::service_mesh::Payload::Binary(_) => Err(MeshError::SerializationError(
+                "Unexpected binary response".to_string(),
+            )),
+        }
+    }
+}
+
+impl Default for MeshVerifier {
+    fn default() -> Self {
+        Self::new(VerificationPolicy::default())
+    }
+}
+
+impl Verifier for MeshVerifier {
+    fn verify(&self, task: &Task) -> Result<Verdict, MeshError> {
+        let mut failures = Vec::new();
+        for step in &self.policy.steps {
+            let failure = match step {
+                VerificationStep::Command { command, tool } => {
+                    Self::run_command(task, tool, command)?
+                }
+                VerificationStep::Review { agent } => Self::run_review(task, agent)?,
+            };
+            failures.extend(failure);
+        }
+
+        if failures.is_empty() {
+            Ok(Verdict::Passed)
+        } else {
+            Ok(Verdict::Failed(failures.join("\n\n")))
+        }
+    }
+
+    fn max_rounds(&self) -> u32 {
+        self.policy.max_rounds
+    }
+}
+
+/// Serializes the task context sent to a reviewer agent.
+fn review_payload(task: &Task, diff: Option<&str>) -> Result<String, MeshError> {
+    let context = TaskContextDto {
+        task_id: task.id().to_string(),
+        description: review_request(task, diff),
+    };
+    serde_json::to_string(&context).map_err(|e| MeshError::SerializationError(e.to_string()))
+}
+
+/// Builds the request sent to a reviewer agent.
+///
+/// `diff` is the rendered diff of the task's session, if it has one.
+fn review_request(task: &Task, diff: Option<&str>) -> String {
+    let mut request = format!(
+        "Review the work done for the following task:\n{}\n\nResult reported by the agent:\n{}",
+        task.content(),
+        task.output().unwrap_or("(none)")
+    );
+    if let Some(session_id) = task.session_id() {
+        let _ = write!(
+            request,
+            "\n\nThe changes are in workspace session {session_id}."
+        );
+    }
+    match diff {
+        Some("") => request.push_str("\n\nThe session contains no changes."),
+        Some(diff) => {
+            let _ = write!(request, "\n\nDiff of the changes:\n```diff\n{diff}```");
+        }
+        None => {}
+    }
+    let _ = write!(
+        request,
+        "\n\nList any problems to fix, then end your reply with a line that is exactly \
+         \"{APPROVAL_MARKER}\" if the work is correct and complete, or exactly \
+         \"{REJECTION_MARKER}\" otherwise."
+    );
+    request
+}
+
+/// Renders session changes as a unified diff, cut at [`MAX_REVIEW_DIFF_BYTES`].
+///
+/// Files whose contents are not available as text are listed without
+/// their lines.
+fn render_changes(changes: &[FileChange]) -> String {
+    let mut diff = String::new();
+    for change in changes {
+        let old_path = match change.kind {
+            ChangeKind::Added => "/dev/null".to_string(),
+            ChangeKind::Modified | ChangeKind::Deleted => format!("a/{}", change.path),
+        };
+        let new_path = match change.kind {
+            ChangeKind::Deleted => "/dev/null".to_string(),
+            ChangeKind::Added | ChangeKind::Modified => format!("b/{}", change.path),
+        };
+        let before = match change.kind {
+            ChangeKind::Added => Some(""),
+            ChangeKind::Modified | ChangeKind::Deleted => change.before.as_deref(),
+        };
+        let after = match change.kind {
+            ChangeKind::Deleted => Some(""),
+            ChangeKind::Added | ChangeKind::Modified => change.after.as_deref(),
+        };
+
+        if let (Some(before), Some(after)) = (before, after) {
+            let old_lines: Vec<&str> = before.lines().collect();
+            let new_lines: Vec<&str> = after.lines().collect();
+            let ops = MyersDiff.diff(&old_lines, &new_lines);
+            diff.push_str(&format_unified_diff(
+                &old_path, &new_path, &old_lines, &new_lines, &ops,
+            ));
+        } else {
+            let _ = writeln!(
+                diff,
+                "--- {old_path}\n+++ {new_path}\n(binary or unreadable file, contents not shown)"
+            );
+        }
+    }
+
+    if diff.len() > MAX_REVIEW_DIFF_BYTES {
+        let mut end = MAX_REVIEW_DIFF_BYTES;
+        while !diff.is_char_boundary(end) {
+            end -= 1;
+        }
+        diff.truncate(end);
+        let _ = writeln!(diff, "\n(diff truncated to {MAX_REVIEW_DIFF_BYTES} bytes)");
+    }
+    diff
+}
+
+/// Returns true if a review reply approves the changes.
+///
+/// Only the last non-empty line counts, and it must be exactly
+/// [`APPROVAL_MARKER`]. Anything else, including [`REJECTION_MARKER`], a
+/// malformed verdict or a reply quoting the instructions, is a rejection.
+fn is_approved(review: &str) -> bool {
+    review.lines().map(str::trim).rfind(|line| !line.is_empty()) == Some(APPROVAL_MARKER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Priority, TaskId, TaskStatus};
+    use std::collections::HashSet;
+
+    fn task() -> Task {
+        Task::new(
+            TaskId::new(7),
+            "Add a login form".to_string(),
+            Priority::DEFAULT,
+            TaskStatus::Verifying,
+            None,
+            None,
+            HashSet::new(),
+        )
+        .expect("test task should be valid")
+        .with_output(Some("Added src/login.rs".to_string()))
+        .with_session(Some("session-1".to_string()))
+    }
+
+    #[test]
+    fn policy_deserializes_with_defaults() {
+        let policy: VerificationPolicy = serde_json::from_str(
+            r#"{"steps": [{"kind": "command", "command": ["cargo", "test"]}, {"kind": "review"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            policy,
+            VerificationPolicy {
+                steps: vec![
+                    VerificationStep::command(["cargo", "test"]),
+                    VerificationStep::review()
+                ],
+                max_rounds: DEFAULT_MAX_ROUNDS,
+            }
+        );
+    }
+
+    fn change(
+        kind: ChangeKind,
+        path: &str,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> FileChange {
+        FileChange {
+            path: path.to_string(),
+            kind,
+            before: before.map(str::to_string),
+            after: after.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn review_request_includes_output_and_session() {
+        let request = review_request(&task(), None);
+        assert!(request.contains("Add a login form"));
+        assert!(request.contains("Added src/login.rs"));
+        assert!(request.contains("session-1"));
+        assert!(request.contains(APPROVAL_MARKER));
+    }
+
+    #[test]
+    fn approval_requires_the_marker() {
+        assert!(is_approved("Looks good.\nVERDICT: APPROVED"));
+        assert!(is_approved("Looks good.\n  VERDICT: APPROVED  \n\n"));
+        assert!(!is_approved("- missing tests\nVERDICT: REJECTED"));
+    }
+
+    #[test]
+    fn approval_rejects_anything_but_an_exact_final_verdict() {
+        for review in [
+            "",
+            "Looks good.",
+            "NOT VERDICT: APPROVED",
+            "VERDICT: APPROVED?",
+            "verdict: approved",
+            "VERDICT: APPROVED\nVERDICT: REJECTED",
+            "VERDICT: APPROVED\nBut the tests are missing.",
+            &review_request(&task(), None),
+        ] {
+            assert!(!is_approved(review), "approved: {review:?}");
+        }
+    }
+
+    #[test]
+    fn changes_render_as_a_unified_diff() {
+        let diff = render_changes(&[
+            change(
+                ChangeKind::Added,
+                "src/login.rs",
+                None,
+                Some("fn login() {}\n"),
+            ),
+            change(
+                ChangeKind::Modified,
+                "src/lib.rs",
+                Some("mod app;\n"),
+                Some("mod app;\nmod login;\n"),
+            ),
+            change(
+                ChangeKind::Deleted,
+                "src/old.rs",
+                Some("fn old() {}\n"),
+                None,
+            ),
+            change(ChangeKind::Modified, "logo.png", None, None),
+        ]);
+
+        for expected in [
+            "--- /dev/null\n+++ b/src/login.rs\n",
+            "+fn login() {}\n",
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n",
+            "+mod login;\n",
+            "--- a/src/old.rs\n+++ /dev/null\n",
+            "-fn old() {}\n",
+            "+++ b/logo.png\n(binary or unreadable file, contents not shown)\n",
+        ] {
+            assert!(diff.contains(expected), "missing {expected:?} in:\n{diff}");
+        }
+    }
+
+    #[test]
+    fn long_diffs_are_truncated() {
+        let contents = "é\n".repeat(MAX_REVIEW_DIFF_BYTES);
+        let diff = render_changes(&[change(ChangeKind::Added, "big.txt", None, Some(&contents))]);
+        assert!(diff.len() < MAX_REVIEW_DIFF_BYTES + 100);
+        assert!(diff.ends_with(&format!(
+            "(diff truncated to {MAX_REVIEW_DIFF_BYTES} bytes)\n"
+        )));
+    }
+
+    #[test]
+    fn review_payload_carries_the_session_diff() {
+        let diff = render_changes(&[change(
+            ChangeKind::Added,
+            "src/login.rs",
+            None,
+            Some("fn login() {}\n"),
+        )]);
+        let payload = review_payload(&task(), Some(&diff)).unwrap();
+
+        let context: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        let description = context["description"].as_str().unwrap();
+        assert!(description.contains("```diff\n--- /dev/null\n+++ b/src/login.rs\n"));
+        assert!(description.contains("+fn login() {}\n```"));
+        assert!(description.contains(APPROVAL_MARKER));
+
+        let empty = review_request(&task(), Some(""));
+        assert!(empty.contains("The session contains no changes."));
+    }
+
+    #[test]
+    fn commands_pass_when_the_tool_succeeds() {
+        let verifier = MeshVerifier::new(VerificationPolicy {
+            steps: vec![VerificationStep::command(["cargo", "build"])],
+            ..VerificationPolicy::default()
+        });
+        assert_eq!(verifier.verify(&task()).unwrap(), Verdict::Passed);
+    }
+}
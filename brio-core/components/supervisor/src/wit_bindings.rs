@@ -107,6 +107,122 @@ pub mod service_mesh {
     }
 }
 
+/// Session filesystem interface bindings.
+pub mod session_fs {
+    /// How a file differs between a session and its base directory.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ChangeKind {
+        /// The file only exists in the session.
+        Added,
+        /// The file differs between the session and the base directory.
+        Modified,
+        /// The file was removed in the session.
+        Deleted,
+    }
+
+    /// A file changed in a session.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FileChange {
+        /// Path relative to the session root.
+        pub path: String,
+        /// Kind of change.
+        pub kind: ChangeKind,
+        /// Contents in the base directory, if present and UTF-8 text.
+        pub before: Option<String>,
+        /// Contents in the session, if present and UTF-8 text.
+        pub after: Option<String>,
+    }
+
+    /// Begins a session by copying `base_path` and returns the session id.
+    ///
+    /// # Errors
+    /// Returns error string if the session cannot be created.
+    pub fn begin_session(base_path: &str) -> Result<String, String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::brio_host::session_fs as wit;
+            wit::begin_session(base_path)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Stub for native testing
+            let _ = base_path;
+            Ok("native-session".to_string())
+        }
+    }
+
+    /// Lists the files that differ between a session and its base directory.
+    ///
+    /// # Errors
+    /// Returns error string if the session does not exist or cannot be diffed.
+    pub fn changes(session_id: &str) -> Result<Vec<FileChange>, String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::brio_host::session_fs as wit;
+
+            let changes = wit::changes(session_id)?;
+            Ok(changes
+                .into_iter()
+                .map(|c| FileChange {
+                    path: c.path,
+                    kind: match c.kind {
+                        wit::ChangeKind::Added => ChangeKind::Added,
+                        wit::ChangeKind::Modified => ChangeKind::Modified,
+                        wit::ChangeKind::Deleted => ChangeKind::Deleted,
+                    },
+                    before: c.before,
+                    after: c.after,
+                })
+                .collect())
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Stub for native testing
+            let _ = session_id;
+            Ok(vec![])
+        }
+    }
+}
+
+/// Supervisor configuration interface bindings.
+pub mod supervisor_config {
+    /// Returns the directory task sessions are begun on, if one is configured.
+    #[must_use]
+    pub fn workspace() -> Option<String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::brio_host::supervisor_config as wit;
+            wit::workspace()
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Stub for native testing
+            None
+        }
+    }
+
+    /// Returns the verification policy as JSON.
+    ///
+    /// # Errors
+    /// Returns error string if the host cannot provide the policy.
+    pub fn verification_policy() -> Result<String, String> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::brio_host::supervisor_config as wit;
+            wit::verification_policy()
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Stub for native testing
+            Ok("{}".to_string())
+        }
+    }
+}
+
 /// Brio Core bindings.
 pub mod brio {
     /// Core Brio services and interfaces.
@@ -120,12 +236,18 @@ pub mod brio {
                 pub id: String,
                 /// Description of what this subtask should accomplish.
                 pub description: String,
+                /// Ids of the subtasks that must complete before this one starts.
+                pub depends_on: Vec<String>,
+                /// Artifacts this subtask is expected to produce for its successors.
+                pub outputs: Vec<String>,
+                /// Capability an agent needs to perform this subtask, if any.
+                pub capability: Option<String>,
             }
 
-            /// A sequence of steps to achieve an objective.
+            /// A dependency graph of steps to achieve an objective.
             #[derive(Debug, Clone)]
             pub struct Plan {
-                /// Ordered list of subtasks to execute.
+                /// Subtasks in the order the planner listed them.
                 pub steps: Vec<Subtask>,
             }
 
@@ -147,6 +269,9 @@ pub mod brio {
                             .map(|s| Subtask {
                                 id: s.id,
                                 description: s.description,
+                                depends_on: s.depends_on,
+                                outputs: s.outputs,
+                                capability: s.capability,
                             })
                             .collect(),
                     })
@@ -160,6 +285,9 @@ pub mod brio {
                         steps: vec![Subtask {
                             id: "step1".to_string(),
                             description: "Mock step 1".to_string(),
+                            depends_on: Vec::new(),
+                            outputs: Vec::new(),
+                            capability: None,
                         }],
                     })
                 }
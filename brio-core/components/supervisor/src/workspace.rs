@@ -0,0 +1,51 @@
+//! Workspace Layer - Task Sessions
+//!
+//! Root tasks work in their own VFS session, copied from the workspace set in
+//! the kernel's `[supervisor]` settings. Subtasks share the session of their
+//! parent, so agents and verification commands all see the same changes.
+
+use crate::mesh_client::MeshError;
+use crate::wit_bindings;
+
+/// Contract for opening the session a root task works in.
+pub trait Workspace: Send + Sync {
+    /// Begins a new session on the workspace and returns its id.
+    ///
+    /// # Errors
+    /// Returns `MeshError` if the session cannot be created.
+    fn begin_session(&self) -> Result<String, MeshError>;
+}
+
+/// Workspace implementation using the WIT `session-fs` bindings.
+pub struct WitWorkspace {
+    base_path: String,
+}
+
+impl WitWorkspace {
+    /// Creates a workspace whose sessions are copied from `base_path`.
+    #[must_use]
+    pub fn new(base_path: impl Into<String>) -> Self {
+        Self {
+            base_path: base_path.into(),
+        }
+    }
+
+    /// Loads the workspace directory from the kernel settings through the
+    /// `supervisor-config` interface, returning `None` if none is configured.
+    #[must_use]
+    pub fn from_host() -> Option<Self> {
+        wit_bindings::supervisor_config::workspace().map(Self::new)
+    }
+
+    /// Returns the directory sessions are copied from.
+    #[must_use]
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+}
+
+impl Workspace for WitWorkspace {
+    fn begin_session(&self) -> Result<String, MeshError> {
+        wit_bindings::session_fs::begin_session(&self.base_path).map_err(MeshError::TransportError)
+    }
+}
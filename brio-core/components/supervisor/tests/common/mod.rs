@@ -29,6 +29,7 @@ impl AgentDispatcher for MockDispatcher {
         &self,
         _agent: &supervisor::domain::AgentId,
         _task: &Task,
+        _predecessors: &[supervisor::domain::PredecessorOutput],
     ) -> Result<DispatchResult, MeshError> {
         Ok(DispatchResult::Accepted)
     }
@@ -3,6 +3,9 @@
 //! This tool component allows agents to execute shell commands on the host system.
 //! Use with caution as this provides direct system access.
 //!
+//! The tool is session-aware: commands run with the working directory set to
+//! the caller's session, so builds and tests see the session's changes.
+//!
 //! # Security Considerations
 //!
 //! - Commands are validated against a denylist to prevent dangerous operations
@@ -19,13 +22,14 @@
 #![allow(missing_docs)]
 
 use std::fmt;
+use std::path::Path;
 use std::process::Command;
 use wit_bindgen::generate;
 
 // Generate WIT bindings
 generate!({
-    world: "standard-tool",
-    path: "../../../wit/tool.wit",
+    world: "session-aware-tool",
+    path: "../../../wit",
     export_macro_name: "export_shell_tool",
 });
 
@@ -38,6 +42,8 @@ pub enum ShellError {
     InvalidParams(String),
     /// No command was provided.
     NoCommand,
+    /// The session to run the command in could not be resolved.
+    Session(String),
     /// Command contains dangerous characters or patterns.
     DangerousCommand(String),
     /// Command execution failed.
@@ -60,6 +66,7 @@ impl fmt::Display for ShellError {
         match self {
             ShellError::InvalidParams(msg) => write!(f, "Invalid parameters: {msg}"),
             ShellError::NoCommand => write!(f, "No command provided"),
+            ShellError::Session(msg) => write!(f, "Session error: {msg}"),
             ShellError::DangerousCommand(cmd) => write!(f, "Dangerous command detected: {cmd}"),
             ShellError::ExecutionFailed(msg) => write!(f, "Execution failed: {msg}"),
             ShellError::CommandFailed { stderr, code, .. } => {
@@ -162,29 +169,12 @@ impl ShellTool {
             }
         }
     }
-}
 
-impl exports::brio::core::tool::Guest for ShellTool {
-    fn info() -> exports::brio::core::tool::ToolInfo {
-        exports::brio::core::tool::ToolInfo {
-            name: "shell".to_string(),
-            description: "Executes shell commands safely with input validation. Use with caution."
-                .to_string(),
-            version: "0.2.0".to_string(),
-            requires_session: false,
-        }
-    }
-
-    fn execute(params: String, _session_id: Option<String>) -> Result<String, String> {
-        let args: Vec<String> =
-            serde_json::from_str(&params).map_err(|e| ShellError::InvalidParams(e.to_string()))?;
-
-        if args.is_empty() {
-            return Err(ShellError::NoCommand.into());
-        }
-
-        let command = &args[0];
-        let command_args = &args[1..];
+    /// Runs a validated command with `dir` as its working directory.
+    fn run(args: &[String], dir: &Path) -> Result<String, ShellError> {
+        let Some((command, command_args)) = args.split_first() else {
+            return Err(ShellError::NoCommand);
+        };
 
         // Validate command and arguments for security
         Self::validate_command(command)?;
@@ -192,11 +182,12 @@ impl exports::brio::core::tool::Guest for ShellTool {
 
         let output = Command::new(command)
             .args(command_args)
+            .current_dir(dir)
             .output()
             .map_err(|e| ShellError::ExecutionFailed(e.to_string()))?;
 
         if output.status.success() {
-            Self::bytes_to_string(output.stdout).map_err(std::convert::Into::into)
+            Self::bytes_to_string(output.stdout)
         } else {
             let stderr = Self::bytes_to_string(output.stderr).unwrap_or_default();
             let code = output.status.code().unwrap_or(-1);
@@ -204,12 +195,35 @@ impl exports::brio::core::tool::Guest for ShellTool {
                 stdout: Self::bytes_to_string(output.stdout).unwrap_or_default(),
                 stderr,
                 code,
-            }
-            .into())
+            })
         }
     }
 }
 
+impl exports::brio::core::tool::Guest for ShellTool {
+    fn info() -> exports::brio::core::tool::ToolInfo {
+        exports::brio::core::tool::ToolInfo {
+            name: "shell".to_string(),
+            description: "Executes shell commands safely with input validation. Use with caution."
+                .to_string(),
+            version: "0.2.0".to_string(),
+            requires_session: true,
+        }
+    }
+
+    fn execute(params: String, session_id: Option<String>) -> Result<String, String> {
+        let args: Vec<String> =
+            serde_json::from_str(&params).map_err(|e| ShellError::InvalidParams(e.to_string()))?;
+
+        let session_id =
+            session_id.ok_or_else(|| ShellError::Session("No session provided".to_string()))?;
+        let dir =
+            brio::core::session_fs::get_session_path(&session_id).map_err(ShellError::Session)?;
+
+        Self::run(&args, Path::new(&dir)).map_err(Into::into)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +280,25 @@ mod tests {
         assert!(result.unwrap().contains("INVALID_UTF8_TRUNCATED"));
     }
 
+    #[test]
+    fn test_run_uses_session_directory() {
+        let dir = std::env::temp_dir().join(format!("shell-tool-session-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("session-marker.txt"), "").unwrap();
+
+        let output = ShellTool::run(&["ls".to_string()], &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(output.unwrap().contains("session-marker.txt"));
+    }
+
+    #[test]
+    fn test_run_requires_command() {
+        assert!(matches!(
+            ShellTool::run(&[], Path::new(".")),
+            Err(ShellError::NoCommand)
+        ));
+    }
+
     #[test]
     fn test_shell_error_display() {
         let err = ShellError::NoCommand;
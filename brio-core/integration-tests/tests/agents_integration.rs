@@ -241,6 +241,7 @@ pub fn create_mock_inference(
             model: "mock-model".to_string(),
             tokens_used: Some(100),
             finish_reason: Some("stop".to_string()),
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -256,6 +257,7 @@ pub fn create_static_inference(
             model: "mock-model".to_string(),
             tokens_used: Some(100),
             finish_reason: Some("stop".to_string()),
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -278,6 +280,7 @@ pub fn create_sequential_inference(
             model: "mock-model".to_string(),
             tokens_used: Some(100),
             finish_reason: Some("stop".to_string()),
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -1060,6 +1063,7 @@ mod error_scenarios {
                 model: "mock".to_string(),
                 tokens_used: None,
                 finish_reason: None,
+                tool_calls: Vec::new(),
             })
         };
 
@@ -1126,6 +1130,7 @@ mod error_scenarios {
                 model: "mock".to_string(),
                 tokens_used: None,
                 finish_reason: None,
+                tool_calls: Vec::new(),
             })
         };
 
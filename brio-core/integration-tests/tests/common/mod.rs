@@ -96,6 +96,7 @@ impl LLMProvider for MockProvider {
         Ok(ChatResponse {
             content: "Mock response".to_string(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
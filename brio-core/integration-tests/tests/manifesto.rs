@@ -14,12 +14,16 @@
 
 use anyhow::Result;
 use brio_kernel::host::{BrioHostState, MeshHandler};
-use brio_kernel::inference::{ChatRequest, ChatResponse, InferenceError, LLMProvider};
+use brio_kernel::inference::{
+    ChatRequest, ChatResponse, InferenceError, LLMProvider, RequestOptions,
+};
 use brio_kernel::mesh::{MeshMessage, Payload};
 use sqlx::Row;
 use std::collections::HashSet;
 use std::sync::Arc;
-use supervisor::domain::{AgentId, Priority, Task, TaskId, TaskStatus};
+use supervisor::domain::{
+    AgentId, Capability, PlannedSubtask, PredecessorOutput, Priority, Task, TaskId, TaskStatus,
+};
 use supervisor::mesh_client::{AgentDispatcher, DispatchResult, MeshError};
 use supervisor::orchestrator::{Planner, PlannerError, Supervisor};
 use supervisor::repository::{RepositoryError, TaskRepository};
@@ -45,38 +49,18 @@ impl TestEnvironment {
 
         let host = Arc::new(BrioHostState::with_provider("sqlite::memory:", provider).await?);
 
-        let env = Self {
+        // The tasks table is created by the kernel's schema migrations.
+        Ok(Self {
             host,
             root,
             agent_msg_rx: None,
-        };
-        env.init_db().await?;
-        Ok(env)
+        })
     }
 
     async fn setup() -> Result<Self> {
         Self::setup_with_provider(Box::new(MockProvider)).await
     }
 
-    async fn init_db(&self) -> Result<()> {
-        sqlx::query(
-            r"
-            CREATE TABLE tasks (
-                id INTEGER PRIMARY KEY,
-                content TEXT NOT NULL,
-                priority INTEGER NOT NULL,
-                status TEXT NOT NULL,
-                assigned_agent TEXT,
-                failure_reason TEXT,
-                parent_id INTEGER
-            );
-            ",
-        )
-        .execute(self.host.db())
-        .await?;
-        Ok(())
-    }
-
     fn register_agent(&mut self, id: &str) {
         let (tx, rx) = mpsc::channel(10);
         self.host.register_component(id.to_string(), tx);
@@ -95,13 +79,14 @@ impl LLMProvider for MockProvider {
         Ok(ChatResponse {
             content: "Mock".to_string(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
 
 struct MockPlanner;
 impl Planner for MockPlanner {
-    fn plan(&self, _objective: &str) -> Result<Option<Vec<String>>, PlannerError> {
+    fn plan(&self, _objective: &str) -> Result<Option<Vec<PlannedSubtask>>, PlannerError> {
         Ok(None)
     }
 }
@@ -244,6 +229,85 @@ impl TaskRepository for TestTaskRepository {
         })
     }
 
+    fn create_dependent_task(
+        &self,
+        content: String,
+        priority: Priority,
+        parent_id: TaskId,
+        depends_on: &[TaskId],
+        capability: Option<Capability>,
+    ) -> Result<TaskId, RepositoryError> {
+        let depends_on =
+            serde_json::to_string(&depends_on.iter().map(|id| id.inner()).collect::<Vec<_>>())
+                .map_err(|e| RepositoryError::ParseError(e.to_string()))?;
+
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                let id = sqlx::query(
+                    "INSERT INTO tasks (content, priority, status, parent_id, depends_on, required_capability, session_id) VALUES (?, ?, ?, ?, ?, ?, (SELECT session_id FROM tasks WHERE id = ?)) RETURNING id",
+                )
+                .bind(content)
+                .bind(i64::from(priority.inner()))
+                .bind(TaskStatus::Pending.as_str())
+                .bind(parent_id.inner() as i64)
+                .bind(depends_on)
+                .bind(capability.map(|c| c.as_str()))
+                .bind(parent_id.inner() as i64)
+                .fetch_one(self.host.db())
+                .await
+                .map_err(|e| RepositoryError::SqlError(e.to_string()))?
+                .get::<i64, _>("id");
+
+                Ok(TaskId::new(id as u64))
+            })
+        })
+    }
+
+    fn assign_session(&self, task_id: TaskId, session_id: &str) -> Result<(), RepositoryError> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                sqlx::query("UPDATE tasks SET session_id = ? WHERE id = ?")
+                    .bind(session_id)
+                    .bind(task_id.inner() as i64)
+                    .execute(self.host.db())
+                    .await
+                    .map_err(|e| RepositoryError::SqlError(e.to_string()))?;
+                Ok(())
+            })
+        })
+    }
+
+    fn record_output(&self, task_id: TaskId, output: &str) -> Result<(), RepositoryError> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                sqlx::query("UPDATE tasks SET output = ? WHERE id = ?")
+                    .bind(output)
+                    .bind(task_id.inner() as i64)
+                    .execute(self.host.db())
+                    .await
+                    .map_err(|e| RepositoryError::SqlError(e.to_string()))?;
+                Ok(())
+            })
+        })
+    }
+
+    fn return_for_rework(&self, task_id: TaskId, feedback: &str) -> Result<(), RepositoryError> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async {
+                sqlx::query(
+                    "UPDATE tasks SET status = 'executing', assigned_agent = NULL, feedback = ?, \
+                     verification_rounds = verification_rounds + 1 WHERE id = ?",
+                )
+                .bind(feedback)
+                .bind(task_id.inner() as i64)
+                .execute(self.host.db())
+                .await
+                .map_err(|e| RepositoryError::SqlError(e.to_string()))?;
+                Ok(())
+            })
+        })
+    }
+
     fn fetch_subtasks(&self, parent_id: TaskId) -> Result<Vec<Task>, RepositoryError> {
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
@@ -303,7 +367,12 @@ struct TestDispatcher {
 }
 
 impl AgentDispatcher for TestDispatcher {
-    fn dispatch(&self, agent: &AgentId, task: &Task) -> Result<DispatchResult, MeshError> {
+    fn dispatch(
+        &self,
+        agent: &AgentId,
+        task: &Task,
+        _predecessors: &[PredecessorOutput],
+    ) -> Result<DispatchResult, MeshError> {
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 MeshHandler::mesh_call(
@@ -420,12 +489,18 @@ async fn smart_agent_logic(
                     Message {
                         role: Role::System,
                         content: "You are a precise code editor.".into(),
+                        tool_calls: Vec::new(),
+                        tool_call_id: None,
                     },
                     Message {
                         role: Role::User,
                         content: prompt,
+                        tool_calls: Vec::new(),
+                        tool_call_id: None,
                     },
                 ],
+                tools: Vec::new(),
+                options: RequestOptions::default(),
             };
 
             let response = host
@@ -0,0 +1,140 @@
+//! Integration tests for recording and replaying inference traffic.
+//!
+//! Records an agent conversation against a scripted provider, then replays
+//! it through a host configured from kernel settings, without network access.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use brio_kernel::host::BrioHostState;
+use brio_kernel::inference::{
+    Cassette, ChatRequest, ChatResponse, InferenceError, LLMProvider, Message, ProviderRegistry,
+    RecordingProvider, Role,
+};
+use brio_kernel::infrastructure::config::{
+    CassetteMatching, CassetteMode, CassetteSettings, InferenceSettings, SandboxSettings,
+};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Answers each call with a numbered step, like a live model would vary.
+struct ScriptedProvider {
+    calls: AtomicU32,
+}
+
+#[async_trait]
+impl LLMProvider for ScriptedProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        let step = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let last = request.messages.last().map_or("", |m| m.content.as_str());
+        Ok(ChatResponse::new(format!("step {step}: {last}")))
+    }
+}
+
+fn conversation() -> Vec<ChatRequest> {
+    let first = ChatRequest::new(
+        "gpt-4",
+        vec![
+            Message::new(Role::System, "You are a coder."),
+            Message::new(Role::User, "Fix the bug"),
+        ],
+    );
+    let second = first
+        .clone()
+        .add_message(Role::Assistant, "step 1: Fix the bug")
+        .add_message(Role::User, "Run the tests");
+    vec![first, second]
+}
+
+async fn record(path: &Path) -> Result<Vec<String>> {
+    let recorder = RecordingProvider::new(
+        Arc::new(ScriptedProvider {
+            calls: AtomicU32::new(0),
+        }),
+        Arc::new(Cassette::new(path)),
+    );
+
+    let mut answers = Vec::new();
+    for request in conversation() {
+        answers.push(recorder.chat(request).await?.content);
+    }
+    Ok(answers)
+}
+
+async fn replaying_host(path: &Path, matching: CassetteMatching) -> Result<BrioHostState> {
+    let settings = InferenceSettings {
+        cassette: Some(CassetteSettings {
+            mode: CassetteMode::Replay,
+            path: path.to_path_buf(),
+            matching,
+        }),
+        ..InferenceSettings::default()
+    };
+    let registry = ProviderRegistry::from_settings(&settings)?;
+    BrioHostState::new(
+        "sqlite::memory:",
+        registry,
+        None,
+        SandboxSettings::default(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_replayed_run_matches_recording() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("coder.json");
+    let recorded = record(&path).await?;
+
+    let host = replaying_host(&path, CassetteMatching::Strict).await?;
+    let mut replayed = Vec::new();
+    for request in conversation() {
+        let provider = host
+            .inference_for_model(&request.model)
+            .expect("replay provider registered");
+        replayed.push(provider.chat(request).await?.content);
+    }
+
+    assert_eq!(replayed, recorded);
+    assert_eq!(replayed[1], "step 2: Run the tests");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_diverging_run_fails_on_cassette_miss() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("coder.json");
+    record(&path).await?;
+
+    let host = replaying_host(&path, CassetteMatching::Strict).await?;
+    let provider = host.inference().expect("replay provider registered");
+    let result = provider
+        .chat(ChatRequest::with_message("gpt-4", "Delete everything"))
+        .await;
+
+    assert!(
+        matches!(&result, Err(InferenceError::ProviderError(msg)) if msg.contains("Cassette miss")),
+        "Expected cassette miss, got {result:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fuzzy_replay_ignores_whitespace() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("coder.json");
+    let recorded = record(&path).await?;
+
+    let host = replaying_host(&path, CassetteMatching::Fuzzy).await?;
+    let reformatted = ChatRequest::new(
+        "gpt-4",
+        vec![
+            Message::new(Role::System, "You are a coder.\n"),
+            Message::new(Role::User, "  Fix the bug"),
+        ],
+    );
+    let provider = host.inference().expect("replay provider registered");
+
+    assert_eq!(provider.chat(reformatted).await?.content, recorded[0]);
+    Ok(())
+}
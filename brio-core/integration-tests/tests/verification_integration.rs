@@ -0,0 +1,94 @@
+//! Integration tests for the supervisor configuration.
+//!
+//! Checks that the workspace and verification policy set in the kernel's
+//! `[supervisor]` settings reach the supervisor through the
+//! `supervisor-config` host interface, and that the configured steps run.
+
+use anyhow::Result;
+use brio_kernel::engine::brio::core::supervisor_config::Host;
+use brio_kernel::infrastructure::config::SupervisorSettings;
+use serde_json::json;
+use std::collections::HashSet;
+use supervisor::domain::{Priority, Task, TaskId, TaskStatus};
+use supervisor::verification::{
+    MeshVerifier, Verdict, VerificationPolicy, VerificationStep, Verifier,
+};
+
+mod common;
+
+fn verifying_task() -> Task {
+    Task::new(
+        TaskId::new(1),
+        "Add a login form".to_string(),
+        Priority::DEFAULT,
+        TaskStatus::Verifying,
+        None,
+        None,
+        HashSet::new(),
+    )
+    .expect("test task should be valid")
+    .with_output(Some("Added src/login.rs".to_string()))
+}
+
+/// Test that configured verification steps are served to the supervisor and run.
+#[tokio::test]
+async fn test_configured_verification_steps_run() -> Result<()> {
+    let ctx = common::IntegrationTestContext::new().await?;
+    let settings: SupervisorSettings = serde_json::from_value(json!({
+        "workspace": "/srv/project",
+        "verification": {
+            "steps": [
+                { "kind": "command", "command": ["cargo", "test"] },
+                { "kind": "review" }
+            ],
+            "max_rounds": 2
+        }
+    }))?;
+    let mut host = (*ctx.host).clone().with_supervisor_settings(settings);
+
+    assert_eq!(host.workspace().as_deref(), Some("/srv/project"));
+    let json = host.verification_policy().map_err(anyhow::Error::msg)?;
+    let policy: VerificationPolicy = serde_json::from_str(&json)?;
+    assert_eq!(
+        policy,
+        VerificationPolicy {
+            steps: vec![
+                VerificationStep::command(["cargo", "test"]),
+                VerificationStep::review(),
+            ],
+            max_rounds: 2,
+        }
+    );
+
+    // The native mesh stub replies without a verdict, so the review step
+    // rejects the task; with no steps it would pass.
+    let verdict = MeshVerifier::new(policy)
+        .verify(&verifying_task())
+        .expect("verification should run");
+    assert!(
+        matches!(&verdict, Verdict::Failed(feedback) if feedback.starts_with("Review by agent_reviewer")),
+        "unexpected verdict: {verdict:?}"
+    );
+
+    Ok(())
+}
+
+/// Test that the supervisor falls back to its defaults without settings.
+#[tokio::test]
+async fn test_unconfigured_supervisor_uses_defaults() -> Result<()> {
+    let ctx = common::IntegrationTestContext::new().await?;
+    let mut host = (*ctx.host).clone();
+
+    assert_eq!(host.workspace(), None);
+    let json = host.verification_policy().map_err(anyhow::Error::msg)?;
+    let policy: VerificationPolicy = serde_json::from_str(&json)?;
+    assert_eq!(policy, VerificationPolicy::default());
+    assert_eq!(
+        MeshVerifier::new(policy)
+            .verify(&verifying_task())
+            .expect("verification should run"),
+        Verdict::Passed
+    );
+
+    Ok(())
+}
@@ -9,15 +9,18 @@ use axum::{
 };
 use serde_json::json;
 use std::sync::Arc;
+use tracing::warn;
 
 use crate::api::branches::types::{
-    BranchNodeResponse, BranchResponse, BranchTreeResponse, CreateBranchRequest,
-    ExecuteBranchRequest, ListBranchesQuery, MergeRequest, MergeResponse, branch_to_response,
+    BranchNodeResponse, BranchResponse, BranchSourceRequest, BranchTreeResponse,
+    CreateBranchRequest, ExecuteBranchRequest, ListBranchesQuery, MergeRequest, MergeResponse,
+    branch_to_response,
 };
 use crate::branch_manager::{
-    AgentAssignment, BranchError, BranchId, BranchManager, ExecutionStrategy, MergeRequestId,
+    AgentAssignment, BranchError, BranchId, BranchManager, BranchSource, ExecutionStrategy,
+    MergeRequestId,
 };
-use crate::host::BrioHostState;
+use crate::host::{BranchExecutor, BrioHostState};
 
 /// API errors for branch operations.
 #[derive(Debug, thiserror::Error)]
@@ -135,14 +138,23 @@ pub async fn create_branch(
         }
     };
 
+    let source = match req.source {
+        BranchSourceRequest::Base { path } => BranchSource::Base(path),
+        BranchSourceRequest::Branch { branch_id } => BranchSource::Branch(
+            BranchId::new(branch_id.clone()).map_err(|_| ApiError::InvalidBranchId(branch_id))?,
+        ),
+    };
+
     let branch = manager
-        .create_branch(
+        .create_branch_from(
+            source,
             req.config.name,
             agents,
             execution_strategy,
             req.config.auto_merge,
             req.config.merge_strategy,
         )
+        .await
         .map_err(ApiError::Branch)?;
 
     Ok(Json(branch_to_response(&branch)))
@@ -171,6 +183,7 @@ pub async fn list_branches(
 
     let branches = manager
         .list_branches(query.status.as_deref(), parent_id.as_ref())
+        .await
         .map_err(ApiError::Branch)?;
 
     Ok(Json(
@@ -198,7 +211,10 @@ pub async fn get_branch(
     let manager = get_branch_manager(&state);
     let branch_id = BranchId::new(id.clone()).map_err(|_| ApiError::InvalidBranchId(id))?;
 
-    let branch = manager.get_branch(&branch_id).map_err(ApiError::Branch)?;
+    let branch = manager
+        .get_branch(&branch_id)
+        .await
+        .map_err(ApiError::Branch)?;
 
     Ok(Json(branch_to_response(&branch)))
 }
@@ -220,16 +236,23 @@ pub async fn delete_branch(
     let manager = get_branch_manager(&state);
     let branch_id = BranchId::new(id.clone()).map_err(|_| ApiError::InvalidBranchId(id))?;
 
+    let branch = manager
+        .get_branch(&branch_id)
+        .await
+        .map_err(ApiError::Branch)?;
     manager
         .delete_branch(&branch_id)
+        .await
         .map_err(ApiError::Branch)?;
+    state.discard_branch_session(&branch);
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// POST /api/v1/branches/{id}/execute
 ///
-/// Execute a branch.
+/// Start executing a branch. Agents run in the background; progress is
+/// reported as branch events over the WebSocket.
 ///
 /// # Errors
 ///
@@ -242,11 +265,11 @@ pub async fn execute_branch(
     Path(id): Path<String>,
     Json(req): Json<ExecuteBranchRequest>,
 ) -> Result<Json<BranchResponse>, ApiError> {
-    let manager = get_branch_manager(&state);
     let branch_id = BranchId::new(id.clone()).map_err(|_| ApiError::InvalidBranchId(id))?;
 
-    let branch = manager
-        .execute_branch(&branch_id, Some(req.agents), req.task_description)
+    let branch = state
+        .execute_branch(&branch_id, &req.agents, req.task_description)
+        .await
         .map_err(ApiError::Branch)?;
 
     Ok(Json(branch_to_response(&branch)))
@@ -272,6 +295,7 @@ pub async fn request_merge(
 
     let merge_request = manager
         .request_merge(&branch_id, req.strategy, req.requires_approval)
+        .await
         .map_err(ApiError::Branch)?;
 
     Ok(Json(MergeResponse {
@@ -301,6 +325,7 @@ pub async fn get_branch_tree(
 
     let branch = manager
         .get_branch_tree(&branch_id)
+        .await
         .map_err(ApiError::Branch)?;
 
     Ok(Json(BranchTreeResponse {
@@ -324,7 +349,8 @@ pub async fn get_branch_tree(
 
 /// POST /api/v1/branches/{id}/abort
 ///
-/// Abort a branch.
+/// Abort a branch, cancelling the agents still running in it and discarding
+/// its changes.
 ///
 /// # Errors
 ///
@@ -336,23 +362,33 @@ pub async fn abort_branch(
     State(state): State<Arc<BrioHostState>>,
     Path(id): Path<String>,
 ) -> Result<Json<BranchResponse>, ApiError> {
-    let manager = get_branch_manager(&state);
     let branch_id = BranchId::new(id.clone()).map_err(|_| ApiError::InvalidBranchId(id))?;
 
-    let branch = manager.abort_branch(&branch_id).map_err(ApiError::Branch)?;
+    let branch = state
+        .abort_branch(&branch_id)
+        .await
+        .map_err(ApiError::Branch)?;
 
     Ok(Json(branch_to_response(&branch)))
 }
 
 /// POST /api/v1/merge-requests/{id}/approve
 ///
-/// Approve a merge request.
+/// Approve a merge request, committing the branch's changes to its base
+/// directory.
+///
+/// The merge request is approved before anything is committed, so a
+/// request that is no longer pending, or whose branch has not completed, is
+/// refused without touching the base directory. If the changes cannot be
+/// applied, the merge request is marked as conflicting.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The branch manager is not initialized
 /// - The merge request ID is invalid
+/// - The merge request is not pending or its branch has not completed
+/// - The base directory changed since the branch started
 /// - The approve operation fails
 pub async fn approve_merge(
     State(state): State<Arc<BrioHostState>>,
@@ -364,7 +400,18 @@ pub async fn approve_merge(
 
     let merge_request = manager
         .approve_merge(&merge_request_id, "system".to_string())
+        .await
         .map_err(ApiError::Branch)?;
+    let branch = manager
+        .get_branch(&merge_request.branch_id)
+        .await
+        .map_err(ApiError::Branch)?;
+    if let Err(e) = state.commit_branch_session(&branch) {
+        if let Err(mark) = manager.mark_merge_conflict(&merge_request_id).await {
+            warn!(merge_request_id = %merge_request_id, "Failed to mark merge as conflicting: {mark}");
+        }
+        return Err(ApiError::Branch(e));
+    }
 
     Ok(Json(MergeResponse {
         merge_request_id: merge_request.id.to_string(),
@@ -376,13 +423,14 @@ pub async fn approve_merge(
 
 /// POST /api/v1/merge-requests/{id}/reject
 ///
-/// Reject a merge request.
+/// Reject a merge request, discarding the branch's changes.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The branch manager is not initialized
 /// - The merge request ID is invalid
+/// - The merge request is not pending
 /// - The reject operation fails
 pub async fn reject_merge(
     State(state): State<Arc<BrioHostState>>,
@@ -392,9 +440,13 @@ pub async fn reject_merge(
     let merge_request_id =
         MergeRequestId::new(id.clone()).map_err(|_| ApiError::InvalidMergeRequestId(id))?;
 
-    manager
+    let merge_request = manager
         .reject_merge(&merge_request_id)
+        .await
         .map_err(ApiError::Branch)?;
+    if let Ok(branch) = manager.get_branch(&merge_request.branch_id).await {
+        state.discard_branch_session(&branch);
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
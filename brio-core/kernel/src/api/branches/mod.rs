@@ -21,10 +21,37 @@ pub use types::{
 mod tests {
     use super::*;
     use crate::branch_manager::{
-        BranchError, BranchId, BranchManager, BranchStatus, ExecutionStrategy, MergeRequestId,
-        MergeRequestStatus,
+        Branch, BranchError, BranchId, BranchManager, BranchResult, BranchStatus,
+        ExecutionStrategy, MergeRequestId, MergeRequestStatus,
     };
 
+    /// Creates a branch and runs it to completion without any agents.
+    async fn completed_branch(manager: &BranchManager, name: &str) -> Branch {
+        let branch = manager
+            .create_branch(
+                name.to_string(),
+                vec![],
+                ExecutionStrategy::Sequential,
+                false,
+                "union".to_string(),
+            )
+            .await
+            .unwrap();
+        manager
+            .start_execution(&branch.id, "session".to_string())
+            .await
+            .unwrap();
+        manager
+            .finish_execution(BranchResult {
+                branch_id: branch.id.clone(),
+                file_changes: vec![],
+                agent_results: vec![],
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap()
+    }
+
     // Test request/response serialization
     #[test]
     fn test_create_branch_request_deserialization() {
@@ -198,13 +225,14 @@ mod tests {
                 config.auto_merge,
                 config.merge_strategy.clone(),
             )
+            .await
             .unwrap();
 
         assert_eq!(branch.name, "test-branch");
         assert!(matches!(branch.status, BranchStatus::Pending));
 
         // Get the branch
-        let retrieved = manager.get_branch(&branch.id).unwrap();
+        let retrieved = manager.get_branch(&branch.id).await.unwrap();
         assert_eq!(retrieved.name, branch.name);
     }
 
@@ -228,9 +256,10 @@ mod tests {
                 config.auto_merge,
                 config.merge_strategy.clone(),
             )
+            .await
             .unwrap();
 
-        let branches = manager.list_branches(None, None).unwrap();
+        let branches = manager.list_branches(None, None).await.unwrap();
         assert_eq!(branches.len(), 1);
         assert_eq!(branches[0].name, "branch-1");
     }
@@ -255,35 +284,19 @@ mod tests {
                 config.auto_merge,
                 config.merge_strategy.clone(),
             )
+            .await
             .unwrap();
 
-        manager.delete_branch(&branch.id).unwrap();
+        manager.delete_branch(&branch.id).await.unwrap();
 
-        let result = manager.get_branch(&branch.id);
+        let result = manager.get_branch(&branch.id).await;
         assert!(matches!(result, Err(BranchError::BranchNotFound(_))));
     }
 
     #[tokio::test]
     async fn test_merge_request_workflow() {
         let manager = BranchManager::new();
-
-        let config = types::BranchConfigRequest {
-            name: "merge-test".to_string(),
-            agents: vec![],
-            execution_strategy: types::ExecutionStrategyRequest::Sequential,
-            auto_merge: false,
-            merge_strategy: "union".to_string(),
-        };
-
-        let branch = manager
-            .create_branch(
-                config.name.clone(),
-                vec![],
-                ExecutionStrategy::Sequential,
-                config.auto_merge,
-                config.merge_strategy.clone(),
-            )
-            .unwrap();
+        let branch = completed_branch(&manager, "merge-test").await;
 
         let merge_req = types::MergeRequest {
             strategy: "union".to_string(),
@@ -296,6 +309,7 @@ mod tests {
                 merge_req.strategy.clone(),
                 merge_req.requires_approval,
             )
+            .await
             .unwrap();
         assert_eq!(mr.status, MergeRequestStatus::Pending);
         assert!(mr.requires_approval);
@@ -303,10 +317,77 @@ mod tests {
         // Approve
         let approved = manager
             .approve_merge(&mr.id, "test-approver".to_string())
+            .await
             .unwrap();
         assert_eq!(approved.status, MergeRequestStatus::Approved);
     }
 
+    #[tokio::test]
+    async fn test_approve_merge_requires_a_completed_branch() {
+        let manager = BranchManager::new();
+        let branch = manager
+            .create_branch(
+                "still-running".to_string(),
+                vec![],
+                ExecutionStrategy::Sequential,
+                false,
+                "union".to_string(),
+            )
+            .await
+            .unwrap();
+        manager
+            .start_execution(&branch.id, "session".to_string())
+            .await
+            .unwrap();
+        let mr = manager
+            .request_merge(&branch.id, "union".to_string(), true)
+            .await
+            .unwrap();
+
+        let result = manager.approve_merge(&mr.id, "approver".to_string()).await;
+        assert!(matches!(
+            result,
+            Err(BranchError::InvalidStateTransition { from, .. }) if from == "running"
+        ));
+        let stored = manager.get_merge_request(&mr.id).await.unwrap();
+        assert_eq!(stored.status, MergeRequestStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_approve_merge_requires_a_pending_merge_request() {
+        let manager = BranchManager::new();
+        let branch = completed_branch(&manager, "decided").await;
+
+        let approved = manager
+            .request_merge(&branch.id, "union".to_string(), true)
+            .await
+            .unwrap();
+        manager
+            .approve_merge(&approved.id, "approver".to_string())
+            .await
+            .unwrap();
+        let again = manager
+            .approve_merge(&approved.id, "approver".to_string())
+            .await;
+        assert!(matches!(
+            again,
+            Err(BranchError::InvalidStateTransition { from, .. }) if from == "approved"
+        ));
+
+        let rejected = manager
+            .request_merge(&branch.id, "union".to_string(), true)
+            .await
+            .unwrap();
+        manager.reject_merge(&rejected.id).await.unwrap();
+        let after_reject = manager
+            .approve_merge(&rejected.id, "approver".to_string())
+            .await;
+        assert!(matches!(
+            after_reject,
+            Err(BranchError::InvalidStateTransition { from, .. }) if from == "rejected"
+        ));
+    }
+
     // Test error conversions
     #[test]
     fn test_api_error_into_response() {
@@ -0,0 +1,121 @@
+//! API Handler implementations for the pub/sub event log.
+
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::events::types::{
+    DeadLettersResponse, ReplayRequest, SubscribersResponse, TopicsResponse,
+};
+use crate::events::{DeadLetterFilter, SubscriberOffset};
+use crate::host::BrioHostState;
+
+/// API errors for event log operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The event log could not be read or written.
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// No plugin subscribes under this ID.
+    #[error("Subscriber not found: {0}")]
+    SubscriberNotFound(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::Database(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to access event log: {e}"),
+            ),
+            ApiError::SubscriberNotFound(id) => {
+                (StatusCode::NOT_FOUND, format!("Subscriber not found: {id}"))
+            }
+        };
+
+        let body = Json(json!({
+            "error": message,
+            "error_type": format!("{:?}", std::mem::discriminant(&self))
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+/// GET /api/v1/events/topics
+///
+/// Lists the topics in the event log with their event counts and sequences.
+///
+/// # Errors
+///
+/// Returns an error if the event log cannot be read.
+pub async fn list_topics(
+    State(state): State<Arc<BrioHostState>>,
+) -> Result<Json<TopicsResponse>, ApiError> {
+    let topics = state.event_delivery().log().topics().await?;
+    Ok(Json(TopicsResponse { topics }))
+}
+
+/// GET /api/v1/events/subscribers
+///
+/// Lists the subscribers with their patterns, committed offsets and lag.
+///
+/// # Errors
+///
+/// Returns an error if the event log cannot be read.
+pub async fn list_subscribers(
+    State(state): State<Arc<BrioHostState>>,
+) -> Result<Json<SubscribersResponse>, ApiError> {
+    let subscribers = state.event_delivery().log().offsets().await?;
+    Ok(Json(SubscribersResponse { subscribers }))
+}
+
+/// GET /api/v1/events/dead-letters
+///
+/// Lists dead letters newest first, optionally filtered by `subscriber` and
+/// `topic` and capped by `limit` query parameters.
+///
+/// # Errors
+///
+/// Returns an error if the event log cannot be read.
+pub async fn list_dead_letters(
+    State(state): State<Arc<BrioHostState>>,
+    Query(filter): Query<DeadLetterFilter>,
+) -> Result<Json<DeadLettersResponse>, ApiError> {
+    let dead_letters = state.event_delivery().log().dead_letters(&filter).await?;
+    Ok(Json(DeadLettersResponse { dead_letters }))
+}
+
+/// POST /api/v1/events/subscribers/{id}/replay
+///
+/// Moves a subscriber's offset so delivery continues from `from_sequence`,
+/// redelivering events it already handled or skipping ahead.
+///
+/// # Errors
+///
+/// Returns an error if the subscriber is unknown or the event log cannot be
+/// written.
+pub async fn replay_subscriber(
+    State(state): State<Arc<BrioHostState>>,
+    Path(id): Path<String>,
+    Json(request): Json<ReplayRequest>,
+) -> Result<Json<SubscriberOffset>, ApiError> {
+    let delivery = state.event_delivery();
+    if !delivery.log().replay(&id, request.from_sequence).await? {
+        return Err(ApiError::SubscriberNotFound(id));
+    }
+    delivery.notify();
+
+    let offset = delivery
+        .log()
+        .offsets()
+        .await?
+        .into_iter()
+        .find(|offset| offset.subscriber == id)
+        .ok_or(ApiError::SubscriberNotFound(id))?;
+    Ok(Json(offset))
+}
@@ -0,0 +1,48 @@
+//! REST API endpoints for the pub/sub event log.
+//!
+//! This module exposes the topics in the durable event log, how far each
+//! subscriber got through it and the events moved to dead letters, and lets
+//! operators replay events to a subscriber from a given sequence.
+
+pub mod handlers;
+pub mod routes;
+pub mod types;
+
+pub use handlers::ApiError;
+pub use routes::routes;
+pub use types::{DeadLettersResponse, ReplayRequest, SubscribersResponse, TopicsResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{DeadLetterFilter, SubscriberOffset};
+
+    #[test]
+    fn test_replay_request_deserialization() {
+        let request: ReplayRequest = serde_json::from_str(r#"{"from_sequence": 42}"#).unwrap();
+        assert_eq!(request.from_sequence, 42);
+
+        let filter: DeadLetterFilter =
+            serde_json::from_str(r#"{"subscriber": "foreman"}"#).unwrap();
+        assert_eq!(filter.subscriber.as_deref(), Some("foreman"));
+        assert!(filter.limit.is_none());
+    }
+
+    #[test]
+    fn test_subscribers_serialization() {
+        let response = SubscribersResponse {
+            subscribers: vec![SubscriberOffset {
+                subscriber: "foreman".to_string(),
+                patterns: vec!["proposal:*".to_string()],
+                committed: 7,
+                attempts: 2,
+                lag: 3,
+            }],
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["subscribers"][0]["subscriber"], "foreman");
+        assert_eq!(json["subscribers"][0]["patterns"][0], "proposal:*");
+        assert_eq!(json["subscribers"][0]["lag"], 3);
+    }
+}
@@ -0,0 +1,26 @@
+//! REST API routes for the pub/sub event log.
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::api::events::handlers::{
+    list_dead_letters, list_subscribers, list_topics, replay_subscriber,
+};
+use crate::host::BrioHostState;
+
+/// API routes for the pub/sub event log.
+///
+/// Creates a router with all event endpoints mounted at `/api/v1/events`.
+pub fn routes() -> Router<Arc<BrioHostState>> {
+    Router::new()
+        .route("/api/v1/events/topics", get(list_topics))
+        .route("/api/v1/events/subscribers", get(list_subscribers))
+        .route(
+            "/api/v1/events/subscribers/{id}/replay",
+            post(replay_subscriber),
+        )
+        .route("/api/v1/events/dead-letters", get(list_dead_letters))
+}
@@ -0,0 +1,35 @@
+//! Request/Response Types for Events API
+//!
+//! This module provides DTOs for inspecting and replaying the event log.
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{DeadLetter, SubscriberOffset, TopicSummary};
+
+/// Topics in the event log.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicsResponse {
+    /// Topics with their event counts, sorted by name.
+    pub topics: Vec<TopicSummary>,
+}
+
+/// Subscribers of the event log.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscribersResponse {
+    /// Subscribers with their offsets and lag, sorted by ID.
+    pub subscribers: Vec<SubscriberOffset>,
+}
+
+/// Events subscribers failed to handle.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLettersResponse {
+    /// Dead letters, newest first.
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+/// Request to replay events to a subscriber.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayRequest {
+    /// Sequence of the first event to deliver again.
+    pub from_sequence: u64,
+}
@@ -0,0 +1,203 @@
+//! API Handler implementations for inference administration.
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::inference::types::{
+    CacheClearedResponse, CacheStatsResponse, ProviderStatus, ProvidersResponse,
+};
+use crate::host::BrioHostState;
+use crate::inference::{InferenceError, ProviderHealth, ProviderRegistry, ResponseCache};
+
+/// API errors for inference operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// Domain-level inference error.
+    #[error("Inference error: {0}")]
+    Inference(#[from] InferenceError),
+    /// Response caching is not configured.
+    #[error("Response cache not enabled")]
+    CacheDisabled,
+    /// No provider is registered under the name.
+    #[error("Provider not found: {0}")]
+    ProviderNotFound(String),
+    /// The provider has no circuit breaker to trip or reset.
+    #[error("Provider has no circuit breaker: {0}")]
+    NoCircuitBreaker(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::Inference(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            ApiError::CacheDisabled => (
+                StatusCode::NOT_FOUND,
+                "Response cache not enabled".to_string(),
+            ),
+            ApiError::ProviderNotFound(name) => {
+                (StatusCode::NOT_FOUND, format!("Provider not found: {name}"))
+            }
+            ApiError::NoCircuitBreaker(name) => (
+                StatusCode::CONFLICT,
+                format!("Provider has no circuit breaker: {name}"),
+            ),
+        };
+
+        let body = Json(json!({
+            "error": message,
+            "error_type": format!("{:?}", std::mem::discriminant(&self))
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+fn response_cache(state: &BrioHostState) -> Result<Arc<ResponseCache>, ApiError> {
+    state
+        .registry()
+        .response_cache()
+        .ok_or(ApiError::CacheDisabled)
+}
+
+/// GET /api/v1/inference/cache
+///
+/// Returns the size and hit/miss counts of the response cache.
+///
+/// # Errors
+///
+/// Returns an error if response caching is not enabled.
+pub async fn get_cache_stats(
+    State(state): State<Arc<BrioHostState>>,
+) -> Result<Json<CacheStatsResponse>, ApiError> {
+    let cache = response_cache(&state)?;
+    let path = cache.dir().display().to_string();
+    // The entry count is read from disk the first time
+    let cache_stats = tokio::task::spawn_blocking(move || cache.stats())
+        .await
+        .map_err(|e| InferenceError::ConfigError(format!("Cannot read response cache: {e}")))?;
+    Ok(Json(CacheStatsResponse {
+        path,
+        stats: cache_stats,
+    }))
+}
+
+/// DELETE /api/v1/inference/cache
+///
+/// Removes every cached response.
+///
+/// # Errors
+///
+/// Returns an error if response caching is not enabled or the cache cannot
+/// be cleared.
+pub async fn clear_cache(
+    State(state): State<Arc<BrioHostState>>,
+) -> Result<Json<CacheClearedResponse>, ApiError> {
+    let cache = response_cache(&state)?;
+    let cleared = tokio::task::spawn_blocking(move || cache.clear())
+        .await
+        .map_err(|e| InferenceError::ConfigError(format!("Cannot clear response cache: {e}")))??;
+    tracing::info!(cleared, "Cleared inference response cache");
+    Ok(Json(CacheClearedResponse { cleared }))
+}
+
+async fn provider_status(registry: &ProviderRegistry, name: &str) -> ProviderStatus {
+    let health = registry.health(name);
+    let circuit = match &health {
+        Some(health) => health.circuit().await.map(Into::into),
+        None => None,
+    };
+    ProviderStatus {
+        name: name.to_string(),
+        is_default: registry.default_name().as_deref() == Some(name),
+        circuit,
+        recent: health.map(|health| health.recent()).unwrap_or_default(),
+        queued_requests: registry.rate_limiter(name).map(|limiter| limiter.queued()),
+    }
+}
+
+fn provider_health(state: &BrioHostState, name: &str) -> Result<Arc<ProviderHealth>, ApiError> {
+    state
+        .registry()
+        .health(name)
+        .ok_or_else(|| ApiError::ProviderNotFound(name.to_string()))
+}
+
+/// GET /api/v1/inference/providers
+///
+/// Lists the registered providers with their circuit breaker state, recent
+/// latency and error rate.
+pub async fn list_providers(State(state): State<Arc<BrioHostState>>) -> Json<ProvidersResponse> {
+    let registry = state.registry();
+    let mut names = registry.list_providers();
+    names.sort();
+
+    let mut providers = Vec::with_capacity(names.len());
+    for name in &names {
+        providers.push(provider_status(&registry, name).await);
+    }
+    Json(ProvidersResponse {
+        default_provider: registry.default_name(),
+        providers,
+    })
+}
+
+/// POST /api/v1/inference/providers/{name}/trip
+///
+/// Opens the provider's circuit breaker, failing its requests fast.
+///
+/// # Errors
+///
+/// Returns an error if the provider is unknown or has no circuit breaker.
+pub async fn trip_circuit(
+    State(state): State<Arc<BrioHostState>>,
+    Path(name): Path<String>,
+) -> Result<Json<ProviderStatus>, ApiError> {
+    if !provider_health(&state, &name)?.trip().await {
+        return Err(ApiError::NoCircuitBreaker(name));
+    }
+    tracing::warn!(provider = %name, "Circuit breaker tripped through the API");
+    Ok(Json(provider_status(&state.registry(), &name).await))
+}
+
+/// POST /api/v1/inference/providers/{name}/reset
+///
+/// Closes the provider's circuit breaker, letting its requests through.
+///
+/// # Errors
+///
+/// Returns an error if the provider is unknown or has no circuit breaker.
+pub async fn reset_circuit(
+    State(state): State<Arc<BrioHostState>>,
+    Path(name): Path<String>,
+) -> Result<Json<ProviderStatus>, ApiError> {
+    if !provider_health(&state, &name)?.reset().await {
+        return Err(ApiError::NoCircuitBreaker(name));
+    }
+    tracing::info!(provider = %name, "Circuit breaker reset through the API");
+    Ok(Json(provider_status(&state.registry(), &name).await))
+}
+
+/// POST /api/v1/inference/providers/{name}/default
+///
+/// Makes the provider serve models that match no route.
+///
+/// # Errors
+///
+/// Returns an error if the provider is unknown.
+pub async fn set_default_provider(
+    State(state): State<Arc<BrioHostState>>,
+    Path(name): Path<String>,
+) -> Result<Json<ProviderStatus>, ApiError> {
+    let registry = state.registry();
+    if registry.get(&name).is_none() {
+        return Err(ApiError::ProviderNotFound(name));
+    }
+    registry.set_default(name.clone());
+    tracing::info!(provider = %name, "Default inference provider changed through the API");
+    Ok(Json(provider_status(&registry, &name).await))
+}
@@ -0,0 +1,74 @@
+//! REST API endpoints for inference administration.
+//!
+//! This module provides HTTP endpoints for inspecting and clearing the
+//! inference response cache, and for inspecting provider health, tripping
+//! and resetting circuit breakers and changing the default provider.
+
+pub mod handlers;
+pub mod routes;
+pub mod types;
+
+pub use handlers::ApiError;
+pub use routes::routes;
+pub use types::{
+    CacheClearedResponse, CacheStatsResponse, CircuitStatus, ProviderStatus, ProvidersResponse,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::cache::CacheStats;
+
+    #[test]
+    fn test_cache_stats_response_serialization() {
+        let response = CacheStatsResponse {
+            path: "/var/cache/brio".to_string(),
+            stats: CacheStats {
+                entries: 3,
+                hits: 10,
+                misses: 4,
+            },
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["path"], "/var/cache/brio");
+        assert_eq!(json["entries"], 3);
+        assert_eq!(json["hits"], 10);
+    }
+
+    #[test]
+    fn test_provider_status_serialization() {
+        use crate::inference::health::RecentStats;
+        use crate::inference::types::CircuitBreakerState;
+
+        let status = ProviderStatus {
+            name: "anthropic".to_string(),
+            is_default: true,
+            circuit: Some(CircuitStatus {
+                state: CircuitBreakerState::HalfOpen,
+                consecutive_failures: 0,
+                total_successes: 12,
+                total_failures: 5,
+            }),
+            recent: RecentStats {
+                requests: 4,
+                errors: 1,
+                error_rate: 0.25,
+                average_latency_ms: Some(800),
+                p95_latency_ms: Some(1500),
+            },
+            queued_requests: Some(2),
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["circuit"]["state"], "half_open");
+        assert_eq!(json["recent"]["error_rate"], 0.25);
+        assert_eq!(json["queued_requests"], 2);
+    }
+
+    #[test]
+    fn test_cache_cleared_response_serialization() {
+        let json = serde_json::to_string(&CacheClearedResponse { cleared: 3 }).unwrap();
+        assert_eq!(json, r#"{"cleared":3}"#);
+    }
+}
@@ -0,0 +1,36 @@
+//! REST API routes for inference administration.
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::api::inference::handlers::{
+    clear_cache, get_cache_stats, list_providers, reset_circuit, set_default_provider, trip_circuit,
+};
+use crate::host::BrioHostState;
+
+/// API routes for inference administration.
+///
+/// Creates a router with all inference endpoints mounted at `/api/v1/inference`.
+pub fn routes() -> Router<Arc<BrioHostState>> {
+    Router::new()
+        .route(
+            "/api/v1/inference/cache",
+            get(get_cache_stats).delete(clear_cache),
+        )
+        .route("/api/v1/inference/providers", get(list_providers))
+        .route(
+            "/api/v1/inference/providers/{name}/trip",
+            post(trip_circuit),
+        )
+        .route(
+            "/api/v1/inference/providers/{name}/reset",
+            post(reset_circuit),
+        )
+        .route(
+            "/api/v1/inference/providers/{name}/default",
+            post(set_default_provider),
+        )
+}
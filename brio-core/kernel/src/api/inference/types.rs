@@ -0,0 +1,75 @@
+//! Request/Response Types for Inference API
+//!
+//! This module provides DTOs for inference administration.
+
+use serde::Serialize;
+
+use crate::inference::cache::CacheStats;
+use crate::inference::health::RecentStats;
+use crate::inference::types::{CircuitBreakerState, CircuitBreakerStats};
+
+/// Response cache statistics payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatsResponse {
+    /// Directory the cache is stored in.
+    pub path: String,
+    /// Entry count and hit/miss counters.
+    #[serde(flatten)]
+    pub stats: CacheStats,
+}
+
+/// Response cache clear payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheClearedResponse {
+    /// Number of cached responses removed.
+    pub cleared: usize,
+}
+
+/// Registered providers payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvidersResponse {
+    /// Provider used for unrouted models, if one was set.
+    pub default_provider: Option<String>,
+    /// Registered providers, sorted by name.
+    pub providers: Vec<ProviderStatus>,
+}
+
+/// Health of a registered provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderStatus {
+    /// Name the provider is registered under.
+    pub name: String,
+    /// Whether the provider serves unrouted models.
+    pub is_default: bool,
+    /// Circuit breaker state; `None` for providers without one, such as
+    /// fallback chains.
+    pub circuit: Option<CircuitStatus>,
+    /// Latency and error rate of recent requests.
+    pub recent: RecentStats,
+    /// Requests waiting for the rate limiter; `None` if it has none.
+    pub queued_requests: Option<usize>,
+}
+
+/// Circuit breaker state payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitStatus {
+    /// Current state.
+    pub state: CircuitBreakerState,
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+    /// Successful requests since startup.
+    pub total_successes: u64,
+    /// Failed requests since startup.
+    pub total_failures: u64,
+}
+
+impl From<CircuitBreakerStats> for CircuitStatus {
+    fn from(stats: CircuitBreakerStats) -> Self {
+        Self {
+            state: stats.state,
+            consecutive_failures: stats.consecutive_failures,
+            total_successes: stats.total_successes,
+            total_failures: stats.total_failures,
+        }
+    }
+}
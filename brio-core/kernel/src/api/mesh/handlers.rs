@@ -0,0 +1,85 @@
+//! API Handler implementations for the mesh membership.
+
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::mesh::types::{NodeStatus, NodesResponse};
+use crate::host::BrioHostState;
+use crate::mesh::types::NodeId;
+
+/// API errors for mesh operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The kernel runs in standalone mode.
+    #[error("Mesh networking not enabled")]
+    MeshDisabled,
+    /// No node with this ID is known.
+    #[error("Node not found: {0}")]
+    NodeNotFound(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::MeshDisabled => (
+                StatusCode::NOT_FOUND,
+                "Mesh networking not enabled".to_string(),
+            ),
+            ApiError::NodeNotFound(id) => (StatusCode::NOT_FOUND, format!("Node not found: {id}")),
+        };
+
+        let body = Json(json!({
+            "error": message,
+            "error_type": format!("{:?}", std::mem::discriminant(&self))
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+/// GET /api/v1/mesh/nodes
+///
+/// Lists the remote nodes this node knows about with their liveness.
+///
+/// # Errors
+///
+/// Returns an error if the kernel runs in standalone mode.
+pub async fn list_nodes(
+    State(state): State<Arc<BrioHostState>>,
+) -> Result<Json<NodesResponse>, ApiError> {
+    let router = state.remote_router().ok_or(ApiError::MeshDisabled)?;
+    Ok(Json(NodesResponse {
+        node_id: router.local_node().map(ToString::to_string),
+        nodes: router
+            .members()
+            .iter()
+            .map(|(info, node_state)| NodeStatus::new(info, *node_state))
+            .collect(),
+    }))
+}
+
+/// DELETE /api/v1/mesh/nodes/{id}
+///
+/// Removes a node from the membership, dropping the events still queued
+/// for it. The node is added again if it rejoins or another node lists it.
+///
+/// # Errors
+///
+/// Returns an error if the kernel runs in standalone mode or the node is
+/// unknown.
+pub async fn remove_node(
+    State(state): State<Arc<BrioHostState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    let router = state.remote_router().ok_or(ApiError::MeshDisabled)?;
+    let node_id = NodeId::try_from_str(&id).map_err(|_| ApiError::NodeNotFound(id.clone()))?;
+    if !router.remove_node(&node_id) {
+        return Err(ApiError::NodeNotFound(id));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
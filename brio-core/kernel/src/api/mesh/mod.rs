@@ -0,0 +1,40 @@
+//! REST API endpoints for the mesh membership.
+//!
+//! This module provides HTTP endpoints for inspecting which remote nodes
+//! this node knows about and whether they are alive.
+
+pub mod handlers;
+pub mod routes;
+pub mod types;
+
+pub use handlers::ApiError;
+pub use routes::routes;
+pub use types::{NodeStatus, NodesResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::NodeState;
+    use crate::mesh::types::{NodeAddress, NodeId, NodeInfo};
+
+    #[test]
+    fn test_nodes_response_serialization() {
+        let info = NodeInfo::new(
+            NodeId::try_from_str("node-b").unwrap(),
+            NodeAddress::new("10.0.0.2:50051").unwrap(),
+            vec!["gpu".to_string()],
+            1_700_000_000,
+        )
+        .unwrap();
+        let response = NodesResponse {
+            node_id: Some("node-a".to_string()),
+            nodes: vec![NodeStatus::new(&info, NodeState::Suspect)],
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["node_id"], "node-a");
+        assert_eq!(json["nodes"][0]["address"], "10.0.0.2:50051");
+        assert_eq!(json["nodes"][0]["state"], "suspect");
+        assert_eq!(json["nodes"][0]["last_seen"], 1_700_000_000);
+    }
+}
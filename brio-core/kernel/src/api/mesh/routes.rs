@@ -0,0 +1,19 @@
+//! REST API routes for the mesh membership.
+
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use std::sync::Arc;
+
+use crate::api::mesh::handlers::{list_nodes, remove_node};
+use crate::host::BrioHostState;
+
+/// API routes for the mesh membership.
+///
+/// Creates a router with all mesh endpoints mounted at `/api/v1/mesh`.
+pub fn routes() -> Router<Arc<BrioHostState>> {
+    Router::new()
+        .route("/api/v1/mesh/nodes", get(list_nodes))
+        .route("/api/v1/mesh/nodes/{id}", delete(remove_node))
+}
@@ -0,0 +1,46 @@
+//! Request/Response Types for Mesh API
+//!
+//! This module provides DTOs for inspecting the mesh membership.
+
+use serde::Serialize;
+
+use crate::mesh::NodeState;
+use crate::mesh::types::NodeInfo;
+
+/// Mesh membership payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodesResponse {
+    /// ID of the node answering, if configured.
+    pub node_id: Option<String>,
+    /// Known remote nodes, sorted by ID.
+    pub nodes: Vec<NodeStatus>,
+}
+
+/// A known remote node.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeStatus {
+    /// Unique identifier of the node.
+    pub id: String,
+    /// Address of the node's mesh server.
+    pub address: String,
+    /// Capabilities advertised by the node.
+    pub capabilities: Vec<String>,
+    /// Unix timestamp the node last answered this node.
+    pub last_seen: u64,
+    /// Liveness as observed by this node.
+    pub state: NodeState,
+}
+
+impl NodeStatus {
+    /// Creates the payload of a node in `state`.
+    #[must_use]
+    pub fn new(info: &NodeInfo, state: NodeState) -> Self {
+        Self {
+            id: info.id().to_string(),
+            address: info.address().to_string(),
+            capabilities: info.capabilities().to_vec(),
+            last_seen: info.last_seen(),
+            state,
+        }
+    }
+}
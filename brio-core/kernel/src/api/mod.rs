@@ -1,11 +1,36 @@
 //! REST API for the Brio kernel.
 //!
 //! This module provides HTTP endpoints for managing branches, sessions,
-//! agents, and other kernel operations.
+//! agents, inference usage, mesh membership, the event log and other kernel
+//! operations.
 
 pub mod branches;
+pub mod events;
+pub mod inference;
+pub mod mesh;
 pub mod sessions;
+pub mod usage;
+
+use axum::Router;
+use std::sync::Arc;
+
+use crate::host::BrioHostState;
 
 pub use branches::ApiError;
 pub use branches::routes as branch_routes;
+pub use events::routes as event_routes;
+pub use inference::routes as inference_routes;
+pub use mesh::routes as mesh_routes;
 pub use sessions::routes as session_routes;
+pub use usage::routes as usage_routes;
+
+/// All REST API routes served on the control plane.
+pub fn api_router() -> Router<Arc<BrioHostState>> {
+    Router::new()
+        .merge(session_routes())
+        .merge(branch_routes())
+        .merge(usage_routes())
+        .merge(inference_routes())
+        .merge(mesh_routes())
+        .merge(event_routes())
+}
@@ -76,6 +76,21 @@ impl IntoResponse for ApiError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Failed to read directory: {msg}"),
             ),
+            ApiError::Session(SessionError::FileOperationFailed { path, source }) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("File operation on '{}' failed: {source}", path.display()),
+            ),
+            ApiError::Session(SessionError::FileTooLarge { path, size, limit }) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "File '{}' is {size} bytes, larger than the {limit} byte limit",
+                    path.display()
+                ),
+            ),
+            ApiError::Session(SessionError::InvalidLineRange { start, end }) => (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid line range {start}..={end}"),
+            ),
             ApiError::InvalidSessionId(id) => {
                 (StatusCode::BAD_REQUEST, format!("Invalid session ID: {id}"))
             }
@@ -0,0 +1,82 @@
+//! API Handler implementations for inference usage.
+
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::usage::types::{BudgetResetResponse, UsageReportResponse};
+use crate::host::BrioHostState;
+use crate::usage::UsageFilter;
+
+/// API errors for usage operations.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The usage ledger could not be read or written.
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match &self {
+            ApiError::Database(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to access usage: {e}"),
+            ),
+        };
+
+        let body = Json(json!({
+            "error": message,
+            "error_type": format!("{:?}", std::mem::discriminant(&self))
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+/// GET /api/v1/usage
+///
+/// Returns recorded token usage and estimated cost, optionally filtered by
+/// `plugin_id`, `task_id` and `model` query parameters.
+///
+/// # Errors
+///
+/// Returns an error if the usage ledger cannot be read.
+pub async fn get_usage(
+    State(state): State<Arc<BrioHostState>>,
+    Query(filter): Query<UsageFilter>,
+) -> Result<Json<UsageReportResponse>, ApiError> {
+    let ledger = state.usage_ledger();
+    let entries = ledger.entries(&filter).await?;
+    let totals = ledger.totals(&filter).await?;
+
+    Ok(Json(UsageReportResponse {
+        totals,
+        entries,
+        budgets: state.usage_tracker().budgets().into(),
+    }))
+}
+
+/// POST /api/v1/usage/plugins/{id}/reset
+///
+/// Resets a plugin's token budget, so only tokens it uses from now on count
+/// against `max_tokens_per_plugin`. Recorded usage is kept.
+///
+/// # Errors
+///
+/// Returns an error if the usage ledger cannot be written.
+pub async fn reset_plugin_budget(
+    State(state): State<Arc<BrioHostState>>,
+    Path(plugin_id): Path<String>,
+) -> Result<Json<BudgetResetResponse>, ApiError> {
+    let tokens_used = state.usage_ledger().reset_plugin_budget(&plugin_id).await?;
+    tracing::info!(plugin = %plugin_id, tokens_used, "Reset plugin token budget");
+    Ok(Json(BudgetResetResponse {
+        plugin_id,
+        tokens_used,
+    }))
+}
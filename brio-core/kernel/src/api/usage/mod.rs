@@ -0,0 +1,57 @@
+//! REST API endpoints for inference usage.
+//!
+//! This module exposes the token usage and estimated cost recorded for guest
+//! inference calls, together with the budgets they are held to.
+
+pub mod handlers;
+pub mod routes;
+pub mod types;
+
+pub use handlers::ApiError;
+pub use routes::routes;
+pub use types::{BudgetResetResponse, BudgetsResponse, UsageReportResponse};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usage::{UsageEntry, UsageFilter, UsageTotals};
+
+    #[test]
+    fn test_usage_query_deserialization() {
+        let filter: UsageFilter = serde_json::from_str(r#"{"task_id": "42"}"#).unwrap();
+        assert_eq!(filter, UsageFilter::task("42"));
+    }
+
+    #[test]
+    fn test_usage_report_serialization() {
+        let response = UsageReportResponse {
+            totals: UsageTotals {
+                requests: 1,
+                prompt_tokens: 100,
+                completion_tokens: 20,
+                total_tokens: 120,
+                cost_usd: 0.0005,
+            },
+            entries: vec![UsageEntry {
+                plugin_id: "coder".to_string(),
+                task_id: None,
+                model: "gpt-4o".to_string(),
+                requests: 1,
+                prompt_tokens: 100,
+                completion_tokens: 20,
+                total_tokens: 120,
+                cost_usd: 0.0005,
+            }],
+            budgets: BudgetsResponse {
+                max_tokens_per_task: Some(10_000),
+                max_tokens_per_plugin: None,
+            },
+        };
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["totals"]["total_tokens"], 120);
+        assert_eq!(json["entries"][0]["plugin_id"], "coder");
+        assert!(json["entries"][0]["task_id"].is_null());
+        assert_eq!(json["budgets"]["max_tokens_per_task"], 10_000);
+    }
+}
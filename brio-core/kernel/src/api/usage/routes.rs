@@ -0,0 +1,21 @@
+//! REST API routes for inference usage.
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::api::usage::handlers::{get_usage, reset_plugin_budget};
+use crate::host::BrioHostState;
+
+/// API routes for inference usage.
+///
+/// Creates a router with the usage report and budget resets mounted at
+/// `/api/v1/usage`.
+pub fn routes() -> Router<Arc<BrioHostState>> {
+    Router::new().route("/api/v1/usage", get(get_usage)).route(
+        "/api/v1/usage/plugins/{id}/reset",
+        post(reset_plugin_budget),
+    )
+}
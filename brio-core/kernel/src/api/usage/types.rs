@@ -0,0 +1,45 @@
+//! Request/Response Types for Usage API
+//!
+//! This module provides DTOs for inference usage reports.
+
+use serde::Serialize;
+
+use crate::usage::{TokenBudgets, UsageEntry, UsageTotals};
+
+/// Usage report payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReportResponse {
+    /// Usage summed over all selected entries.
+    pub totals: UsageTotals,
+    /// Usage per plugin, task and model.
+    pub entries: Vec<UsageEntry>,
+    /// Token budgets enforced on inference calls.
+    pub budgets: BudgetsResponse,
+}
+
+/// Budget reset payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetResetResponse {
+    /// Plugin whose budget was reset.
+    pub plugin_id: String,
+    /// Tokens the plugin had used since its previous reset.
+    pub tokens_used: u64,
+}
+
+/// Token budgets payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetsResponse {
+    /// Maximum tokens per task, `null` if unlimited.
+    pub max_tokens_per_task: Option<u64>,
+    /// Maximum tokens per plugin, `null` if unlimited.
+    pub max_tokens_per_plugin: Option<u64>,
+}
+
+impl From<TokenBudgets> for BudgetsResponse {
+    fn from(budgets: TokenBudgets) -> Self {
+        Self {
+            max_tokens_per_task: budgets.per_task,
+            max_tokens_per_plugin: budgets.per_plugin,
+        }
+    }
+}
@@ -3,72 +3,132 @@
 //! This module provides the `BranchManager` which orchestrates branch operations.
 
 use chrono::Utc;
+use std::sync::Arc;
 
-use super::storage::BranchStorage;
+use super::storage::{BranchStorage, BranchStore};
 use super::types::{
-    AgentAssignment, Branch, BranchConfig, BranchError, BranchId, BranchStatus, ExecutionStrategy,
-    MergeRequestId, MergeRequestModel, MergeRequestStatus,
+    AgentAssignment, Branch, BranchConfig, BranchError, BranchId, BranchResult, BranchSource,
+    BranchStatus, ExecutionStrategy, MergeRequestId, MergeRequestModel, MergeRequestStatus,
 };
 
 /// Manager for branch operations.
-#[derive(Debug, Default)]
 pub struct BranchManager {
-    /// In-memory storage for branches and merge requests.
-    storage: BranchStorage,
+    /// Storage backend for branches and merge requests.
+    storage: Arc<dyn BranchStore>,
+}
+
+impl std::fmt::Debug for BranchManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BranchManager").finish_non_exhaustive()
+    }
+}
+
+impl Default for BranchManager {
+    fn default() -> Self {
+        Self::with_storage(BranchStorage::new())
+    }
 }
 
 impl BranchManager {
-    /// Create a new branch manager.
+    /// Create a new branch manager backed by in-memory storage.
     #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Create a new branch with explicit storage (dependency injection).
+    /// Create a new branch manager with explicit storage (dependency injection).
     #[must_use]
-    pub fn with_storage(storage: BranchStorage) -> Self {
-        Self { storage }
+    pub fn with_storage(storage: impl BranchStore + 'static) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
     }
 
-    /// Create a new branch.
+    /// Create a new branch without a base directory.
+    ///
+    /// Such a branch can be tracked and merged but not executed.
     ///
     /// # Errors
     ///
     /// Returns an error if the branch cannot be created.
-    pub fn create_branch(
+    pub async fn create_branch(
+        &self,
+        name: String,
+        agents: Vec<AgentAssignment>,
+        execution_strategy: ExecutionStrategy,
+        auto_merge: bool,
+        merge_strategy: String,
+    ) -> Result<Branch, BranchError> {
+        let config = BranchConfig {
+            base_path: None,
+            agents,
+            execution_strategy,
+            auto_merge,
+            merge_strategy,
+        };
+        self.insert_new_branch(None, name, config).await
+    }
+
+    /// Create a new branch from a base directory or an existing branch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent branch is not found or the branch cannot be created.
+    pub async fn create_branch_from(
         &self,
+        source: BranchSource,
         name: String,
         agents: Vec<AgentAssignment>,
         execution_strategy: ExecutionStrategy,
         auto_merge: bool,
         merge_strategy: String,
+    ) -> Result<Branch, BranchError> {
+        let (parent_id, base_path) = match source {
+            BranchSource::Base(path) => (None, Some(path)),
+            BranchSource::Branch(parent_id) => {
+                let parent = self.get_branch(&parent_id).await?;
+                (Some(parent_id), parent.config.base_path)
+            }
+        };
+
+        let config = BranchConfig {
+            base_path,
+            agents,
+            execution_strategy,
+            auto_merge,
+            merge_strategy,
+        };
+        self.insert_new_branch(parent_id, name, config).await
+    }
+
+    /// Persist a new pending branch.
+    async fn insert_new_branch(
+        &self,
+        parent_id: Option<BranchId>,
+        name: String,
+        config: BranchConfig,
     ) -> Result<Branch, BranchError> {
         let id = BranchId::new(uuid::Uuid::new_v4().to_string())
             .map_err(|e| BranchError::Internal(e.to_string()))?;
 
         // Check for duplicate name
-        if self.storage.branch_name_exists(&name) {
+        if self.storage.branch_name_exists(&name).await? {
             return Err(BranchError::BranchAlreadyExists(name.clone()));
         }
 
         let branch = Branch {
             id,
-            parent_id: None, // TODO: Set based on source
+            parent_id,
             name,
             status: BranchStatus::Pending,
             session_id: uuid::Uuid::new_v4().to_string(),
             created_at: Utc::now(),
             completed_at: None,
             children: Vec::new(),
-            config: BranchConfig {
-                agents,
-                execution_strategy,
-                auto_merge,
-                merge_strategy,
-            },
+            config,
         };
 
-        self.storage.insert_branch(branch.clone());
+        self.storage.insert_branch(branch.clone()).await?;
 
         Ok(branch)
     }
@@ -78,9 +138,10 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the branch is not found.
-    pub fn get_branch(&self, id: &BranchId) -> Result<Branch, BranchError> {
+    pub async fn get_branch(&self, id: &BranchId) -> Result<Branch, BranchError> {
         self.storage
             .get_branch(id)
+            .await?
             .ok_or_else(|| BranchError::BranchNotFound(id.to_string()))
     }
 
@@ -89,7 +150,7 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the database query fails.
-    pub fn list_branches(
+    pub async fn list_branches(
         &self,
         status_filter: Option<&str>,
         parent_id_filter: Option<&BranchId>,
@@ -97,6 +158,7 @@ impl BranchManager {
         let mut result: Vec<Branch> = self
             .storage
             .get_all_branches()
+            .await?
             .into_iter()
             .filter(|b| {
                 if let Some(status) = status_filter {
@@ -126,43 +188,70 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the branch is not found or cannot be deleted.
-    pub fn delete_branch(&self, id: &BranchId) -> Result<(), BranchError> {
-        if !self.storage.contains_branch(id) {
+    pub async fn delete_branch(&self, id: &BranchId) -> Result<(), BranchError> {
+        if !self.storage.remove_branch(id).await? {
             return Err(BranchError::BranchNotFound(id.to_string()));
         }
-        self.storage.remove_branch(id);
         Ok(())
     }
 
-    /// Execute a branch.
+    /// Mark a branch as running in the given VFS session.
+    ///
+    /// Only pending or failed branches can be started.
     ///
     /// # Errors
     ///
-    /// Returns an error if the branch is not found or execution fails.
-    pub fn execute_branch(
+    /// Returns an error if the branch is not found or cannot be started.
+    pub async fn start_execution(
         &self,
         id: &BranchId,
-        _agent_filter: Option<Vec<String>>,
-        _task_override: Option<String>,
+        session_id: String,
     ) -> Result<Branch, BranchError> {
-        let mut branch = self
-            .storage
-            .get_branch_mut(id)
-            .ok_or_else(|| BranchError::BranchNotFound(id.to_string()))?;
-
-        if branch.status != BranchStatus::Pending && branch.status != BranchStatus::Failed {
-            return Err(BranchError::InvalidStateTransition {
-                from: branch.status.to_string(),
-                to: "running".to_string(),
-            });
-        }
+        let mut branch = self.get_branch(id).await?;
 
         branch.status = BranchStatus::Running;
-        // TODO: Actually execute the branch
-        branch.status = BranchStatus::Completed;
+        branch.session_id = session_id;
+        branch.completed_at = None;
+        self.transition_branch(&branch, &[BranchStatus::Pending, BranchStatus::Failed])
+            .await?;
+
+        Ok(branch)
+    }
+
+    /// Record the result of a running branch and mark it completed or failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the branch is not found or is no longer running
+    /// (e.g. it was aborted while its agents were executing).
+    pub async fn finish_execution(&self, result: BranchResult) -> Result<Branch, BranchError> {
+        let mut branch = self.get_branch(&result.branch_id).await?;
+
+        let status = if result.succeeded() {
+            BranchStatus::Completed
+        } else {
+            BranchStatus::Failed
+        };
+
+        branch.status = status;
         branch.completed_at = Some(Utc::now());
+        self.transition_branch(&branch, &[BranchStatus::Running])
+            .await?;
+        self.storage.save_result(result).await?;
 
-        Ok(branch.clone())
+        Ok(branch)
+    }
+
+    /// Get the recorded execution result of a branch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage query fails.
+    pub async fn get_branch_result(
+        &self,
+        id: &BranchId,
+    ) -> Result<Option<BranchResult>, BranchError> {
+        self.storage.get_result(id).await
     }
 
     /// Abort a branch.
@@ -170,23 +259,15 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the branch is not found or cannot be aborted.
-    pub fn abort_branch(&self, id: &BranchId) -> Result<Branch, BranchError> {
-        let mut branch = self
-            .storage
-            .get_branch_mut(id)
-            .ok_or_else(|| BranchError::BranchNotFound(id.to_string()))?;
-
-        if branch.status != BranchStatus::Running && branch.status != BranchStatus::Pending {
-            return Err(BranchError::InvalidStateTransition {
-                from: branch.status.to_string(),
-                to: "aborted".to_string(),
-            });
-        }
+    pub async fn abort_branch(&self, id: &BranchId) -> Result<Branch, BranchError> {
+        let mut branch = self.get_branch(id).await?;
 
         branch.status = BranchStatus::Aborted;
         branch.completed_at = Some(Utc::now());
+        self.transition_branch(&branch, &[BranchStatus::Running, BranchStatus::Pending])
+            .await?;
 
-        Ok(branch.clone())
+        Ok(branch)
     }
 
     /// Request a merge for a branch.
@@ -194,13 +275,13 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the branch is not found or cannot be merged.
-    pub fn request_merge(
+    pub async fn request_merge(
         &self,
         branch_id: &BranchId,
         strategy: String,
         requires_approval: bool,
     ) -> Result<MergeRequestModel, BranchError> {
-        if self.storage.get_branch(branch_id).is_none() {
+        if self.storage.get_branch(branch_id).await?.is_none() {
             return Err(BranchError::BranchNotFound(branch_id.to_string()));
         }
 
@@ -217,50 +298,83 @@ impl BranchManager {
             approved_at: None,
         };
 
-        self.storage.insert_merge_request(merge_request.clone());
+        self.storage
+            .insert_merge_request(merge_request.clone())
+            .await?;
 
         Ok(merge_request)
     }
 
-    /// Approve a merge request.
+    /// Approve a pending merge request of a completed branch.
+    ///
+    /// The status check and the write happen in a single storage update, so
+    /// a merge request is approved at most once and an approval cannot
+    /// overtake a concurrent rejection.
     ///
     /// # Errors
     ///
-    /// Returns an error if the merge request is not found.
-    pub fn approve_merge(
+    /// Returns an error if the merge request or its branch is not found, the
+    /// merge request is no longer pending, or the branch has not completed
+    /// (e.g. its agents are still running).
+    pub async fn approve_merge(
         &self,
         merge_request_id: &MergeRequestId,
         approver: String,
     ) -> Result<MergeRequestModel, BranchError> {
-        let mut merge_request = self
-            .storage
-            .get_merge_request_mut(merge_request_id)
-            .ok_or_else(|| BranchError::BranchNotFound(merge_request_id.to_string()))?;
+        let mut merge_request = self.get_merge_request(merge_request_id).await?;
+        let branch = self.get_branch(&merge_request.branch_id).await?;
+        if branch.status != BranchStatus::Completed {
+            return Err(BranchError::InvalidStateTransition {
+                from: branch.status.to_string(),
+                to: BranchStatus::Merging.to_string(),
+            });
+        }
 
         merge_request.status = MergeRequestStatus::Approved;
         merge_request.approved_by = Some(approver);
         merge_request.approved_at = Some(Utc::now());
+        self.transition_merge_request(&merge_request, &[MergeRequestStatus::Pending])
+            .await?;
 
-        Ok(merge_request.clone())
+        Ok(merge_request)
     }
 
-    /// Reject a merge request.
+    /// Reject a pending merge request.
     ///
     /// # Errors
     ///
-    /// Returns an error if the merge request is not found.
-    pub fn reject_merge(
+    /// Returns an error if the merge request is not found or is no longer
+    /// pending.
+    pub async fn reject_merge(
         &self,
         merge_request_id: &MergeRequestId,
     ) -> Result<MergeRequestModel, BranchError> {
-        let mut merge_request = self
-            .storage
-            .get_merge_request_mut(merge_request_id)
-            .ok_or_else(|| BranchError::BranchNotFound(merge_request_id.to_string()))?;
+        let mut merge_request = self.get_merge_request(merge_request_id).await?;
 
         merge_request.status = MergeRequestStatus::Rejected;
+        self.transition_merge_request(&merge_request, &[MergeRequestStatus::Pending])
+            .await?;
 
-        Ok(merge_request.clone())
+        Ok(merge_request)
+    }
+
+    /// Mark an approved merge request whose changes could not be applied as
+    /// conflicting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the merge request is not found or is not approved.
+    pub async fn mark_merge_conflict(
+        &self,
+        merge_request_id: &MergeRequestId,
+    ) -> Result<MergeRequestModel, BranchError> {
+        let mut merge_request = self.get_merge_request(merge_request_id).await?;
+
+        merge_request.status = MergeRequestStatus::Conflict;
+        self.transition_merge_request(&merge_request, &[MergeRequestStatus::Approved])
+            .await?;
+
+        Ok(merge_request)
     }
 
     /// Get the merge request for a branch.
@@ -268,12 +382,13 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the merge request is not found.
-    pub fn get_merge_request(
+    pub async fn get_merge_request(
         &self,
         merge_request_id: &MergeRequestId,
     ) -> Result<MergeRequestModel, BranchError> {
         self.storage
             .get_merge_request(merge_request_id)
+            .await?
             .ok_or_else(|| BranchError::BranchNotFound(merge_request_id.to_string()))
     }
 
@@ -282,11 +397,11 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the operation fails.
-    pub fn get_branch_merge_requests(
+    pub async fn get_branch_merge_requests(
         &self,
         branch_id: &BranchId,
     ) -> Result<Vec<MergeRequestModel>, BranchError> {
-        Ok(self.storage.get_merge_requests_for_branch(branch_id))
+        self.storage.get_merge_requests_for_branch(branch_id).await
     }
 
     /// Get the branch tree.
@@ -294,7 +409,48 @@ impl BranchManager {
     /// # Errors
     ///
     /// Returns an error if the branch is not found.
-    pub fn get_branch_tree(&self, id: &BranchId) -> Result<Branch, BranchError> {
-        self.get_branch(id)
+    pub async fn get_branch_tree(&self, id: &BranchId) -> Result<Branch, BranchError> {
+        self.get_branch(id).await
+    }
+
+    /// Persist a status change of a branch if its stored status is one of
+    /// `from`.
+    ///
+    /// The check and the write happen in a single storage update, so of two
+    /// concurrent transitions out of the same state only one succeeds.
+    async fn transition_branch(
+        &self,
+        branch: &Branch,
+        from: &[BranchStatus],
+    ) -> Result<(), BranchError> {
+        if self.storage.update_branch_from(branch, from).await? {
+            return Ok(());
+        }
+        let current = self.get_branch(&branch.id).await?;
+        Err(BranchError::InvalidStateTransition {
+            from: current.status.to_string(),
+            to: branch.status.to_string(),
+        })
+    }
+
+    /// Persist a status change of a merge request if its stored status is
+    /// one of `from`.
+    async fn transition_merge_request(
+        &self,
+        merge_request: &MergeRequestModel,
+        from: &[MergeRequestStatus],
+    ) -> Result<(), BranchError> {
+        if self
+            .storage
+            .update_merge_request_from(merge_request, from)
+            .await?
+        {
+            return Ok(());
+        }
+        let current = self.get_merge_request(&merge_request.id).await?;
+        Err(BranchError::InvalidStateTransition {
+            from: current.status.to_string(),
+            to: merge_request.status.to_string(),
+        })
     }
 }
@@ -5,13 +5,16 @@
 //! to avoid circular dependencies.
 
 pub mod core;
+pub mod sqlite;
 pub mod storage;
 pub mod types;
 
 // Re-export primary types for convenience
 pub use core::BranchManager;
-pub use storage::{BranchStorage, BranchStoragePort, MergeRequestStoragePort};
+pub use sqlite::SqliteBranchStorage;
+pub use storage::{BranchStorage, BranchStoragePort, BranchStore, MergeRequestStoragePort};
 pub use types::{
-    AgentAssignment, Branch, BranchConfig, BranchError, BranchId, BranchStatus, ExecutionStrategy,
-    MergeRequestId, MergeRequestModel, MergeRequestStatus,
+    AgentAssignment, AgentResult, Branch, BranchConfig, BranchError, BranchId, BranchResult,
+    BranchSource, BranchStatus, ExecutionStrategy, MergeRequestId, MergeRequestModel,
+    MergeRequestStatus,
 };
@@ -0,0 +1,583 @@
+//! SQLite-backed branch storage for the Brio kernel.
+//!
+//! This module persists branches and merge requests in the `branches` and
+//! `merge_queue` tables so they survive kernel restarts.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+
+use super::storage::{BranchStoragePort, MergeRequestStoragePort};
+use super::types::{
+    Branch, BranchError, BranchId, BranchResult, BranchStatus, MergeRequestId, MergeRequestModel,
+    MergeRequestStatus,
+};
+
+const BRANCH_COLUMNS: &str =
+    "id, parent_id, session_id, name, status_json, config_json, created_at, completed_at";
+
+const MERGE_REQUEST_COLUMNS: &str =
+    "id, branch_id, strategy, status, requires_approval, approved_by, approved_at";
+
+/// Branch storage backed by the kernel `SQLite` database.
+///
+/// Requires the schema created by the kernel migrations.
+#[derive(Debug, Clone)]
+pub struct SqliteBranchStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteBranchStorage {
+    /// Creates a storage backend over an existing connection pool.
+    #[must_use]
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Loads the IDs of the direct children of a branch.
+    async fn children_of(&self, id: &BranchId) -> Result<Vec<BranchId>, BranchError> {
+        let ids: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM branches WHERE parent_id = ? ORDER BY created_at")
+                .bind(id.as_str())
+                .fetch_all(&self.pool)
+                .await?;
+
+        ids.into_iter().map(BranchId::new).collect()
+    }
+}
+
+#[async_trait]
+impl BranchStoragePort for SqliteBranchStorage {
+    async fn insert_branch(&self, branch: Branch) -> Result<(), BranchError> {
+        sqlx::query(&format!(
+            "INSERT INTO branches ({BRANCH_COLUMNS}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        ))
+        .bind(branch.id.as_str())
+        .bind(branch.parent_id.as_ref().map(BranchId::as_str))
+        .bind(&branch.session_id)
+        .bind(&branch.name)
+        .bind(to_json(&branch.status)?)
+        .bind(to_json(&branch.config)?)
+        .bind(branch.created_at.to_rfc3339())
+        .bind(branch.completed_at.map(|t| t.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_branch(&self, id: &BranchId) -> Result<Option<Branch>, BranchError> {
+        let row = sqlx::query(&format!(
+            "SELECT {BRANCH_COLUMNS} FROM branches WHERE id = ?"
+        ))
+        .bind(id.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let mut branch = branch_from_row(&row)?;
+        branch.children = self.children_of(id).await?;
+        Ok(Some(branch))
+    }
+
+    async fn get_all_branches(&self) -> Result<Vec<Branch>, BranchError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {BRANCH_COLUMNS} FROM branches ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut branches = rows
+            .iter()
+            .map(branch_from_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Children are derived from parent links rather than stored separately
+        let links: Vec<(BranchId, BranchId)> = branches
+            .iter()
+            .filter_map(|b| b.parent_id.clone().map(|parent| (parent, b.id.clone())))
+            .collect();
+        for (parent, child) in links {
+            if let Some(branch) = branches.iter_mut().find(|b| b.id == parent) {
+                branch.children.push(child);
+            }
+        }
+
+        Ok(branches)
+    }
+
+    async fn update_branch(&self, branch: &Branch) -> Result<bool, BranchError> {
+        let result = sqlx::query(
+            "UPDATE branches SET parent_id = ?, session_id = ?, name = ?, status_json = ?, \
+             config_json = ?, completed_at = ? WHERE id = ?",
+        )
+        .bind(branch.parent_id.as_ref().map(BranchId::as_str))
+        .bind(&branch.session_id)
+        .bind(&branch.name)
+        .bind(to_json(&branch.status)?)
+        .bind(to_json(&branch.config)?)
+        .bind(branch.completed_at.map(|t| t.to_rfc3339()))
+        .bind(branch.id.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_branch_from(
+        &self,
+        branch: &Branch,
+        from: &[BranchStatus],
+    ) -> Result<bool, BranchError> {
+        if from.is_empty() {
+            return Ok(false);
+        }
+        let placeholders = vec!["?"; from.len()].join(", ");
+        let sql = format!(
+            "UPDATE branches SET parent_id = ?, session_id = ?, name = ?, status_json = ?, \
+             config_json = ?, completed_at = ? WHERE id = ? AND status_json IN ({placeholders})"
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(branch.parent_id.as_ref().map(BranchId::as_str))
+            .bind(&branch.session_id)
+            .bind(&branch.name)
+            .bind(to_json(&branch.status)?)
+            .bind(to_json(&branch.config)?)
+            .bind(branch.completed_at.map(|t| t.to_rfc3339()))
+            .bind(branch.id.as_str());
+        for status in from {
+            query = query.bind(to_json(status)?);
+        }
+        let result = query.execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_branch(&self, id: &BranchId) -> Result<bool, BranchError> {
+        let result = sqlx::query("DELETE FROM branches WHERE id = ?")
+            .bind(id.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn branch_name_exists(&self, name: &str) -> Result<bool, BranchError> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM branches WHERE name = ?)")
+                .bind(name)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(exists)
+    }
+
+    async fn save_result(&self, result: BranchResult) -> Result<(), BranchError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO branch_results \
+             (branch_id, file_changes_json, agent_results_json, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(result.branch_id.as_str())
+        .bind(to_json(&result.file_changes)?)
+        .bind(to_json(&result.agent_results)?)
+        .bind(result.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_result(&self, id: &BranchId) -> Result<Option<BranchResult>, BranchError> {
+        let row = sqlx::query(
+            "SELECT file_changes_json, agent_results_json, created_at \
+             FROM branch_results WHERE branch_id = ?",
+        )
+        .bind(id.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        Ok(Some(BranchResult {
+            branch_id: id.clone(),
+            file_changes: from_json(row.try_get("file_changes_json")?)?,
+            agent_results: from_json(row.try_get("agent_results_json")?)?,
+            created_at: parse_timestamp(row.try_get("created_at")?)?,
+        }))
+    }
+}
+
+#[async_trait]
+impl MergeRequestStoragePort for SqliteBranchStorage {
+    async fn insert_merge_request(
+        &self,
+        merge_request: MergeRequestModel,
+    ) -> Result<(), BranchError> {
+        sqlx::query(&format!(
+            "INSERT INTO merge_queue ({MERGE_REQUEST_COLUMNS}, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        ))
+        .bind(merge_request.id.as_str())
+        .bind(merge_request.branch_id.as_str())
+        .bind(&merge_request.strategy)
+        .bind(merge_request.status.to_string())
+        .bind(merge_request.requires_approval)
+        .bind(&merge_request.approved_by)
+        .bind(merge_request.approved_at.map(|t| t.to_rfc3339()))
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_merge_request(
+        &self,
+        id: &MergeRequestId,
+    ) -> Result<Option<MergeRequestModel>, BranchError> {
+        sqlx::query(&format!(
+            "SELECT {MERGE_REQUEST_COLUMNS} FROM merge_queue WHERE id = ?"
+        ))
+        .bind(id.as_str())
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(merge_request_from_row)
+        .transpose()
+    }
+
+    async fn update_merge_request(
+        &self,
+        merge_request: &MergeRequestModel,
+    ) -> Result<bool, BranchError> {
+        let result = sqlx::query(
+            "UPDATE merge_queue SET strategy = ?, status = ?, requires_approval = ?, \
+             approved_by = ?, approved_at = ? WHERE id = ?",
+        )
+        .bind(&merge_request.strategy)
+        .bind(merge_request.status.to_string())
+        .bind(merge_request.requires_approval)
+        .bind(&merge_request.approved_by)
+        .bind(merge_request.approved_at.map(|t| t.to_rfc3339()))
+        .bind(merge_request.id.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_merge_request_from(
+        &self,
+        merge_request: &MergeRequestModel,
+        from: &[MergeRequestStatus],
+    ) -> Result<bool, BranchError> {
+        if from.is_empty() {
+            return Ok(false);
+        }
+        let placeholders = vec!["?"; from.len()].join(", ");
+        let sql = format!(
+            "UPDATE merge_queue SET strategy = ?, status = ?, requires_approval = ?, \
+             approved_by = ?, approved_at = ? WHERE id = ? AND status IN ({placeholders})"
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(&merge_request.strategy)
+            .bind(merge_request.status.to_string())
+            .bind(merge_request.requires_approval)
+            .bind(&merge_request.approved_by)
+            .bind(merge_request.approved_at.map(|t| t.to_rfc3339()))
+            .bind(merge_request.id.as_str());
+        for status in from {
+            query = query.bind(status.to_string());
+        }
+        let result = query.execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_merge_requests_for_branch(
+        &self,
+        branch_id: &BranchId,
+    ) -> Result<Vec<MergeRequestModel>, BranchError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {MERGE_REQUEST_COLUMNS} FROM merge_queue WHERE branch_id = ? ORDER BY created_at"
+        ))
+        .bind(branch_id.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(merge_request_from_row).collect()
+    }
+}
+
+fn branch_from_row(row: &SqliteRow) -> Result<Branch, BranchError> {
+    let parent_id: Option<String> = row.try_get("parent_id")?;
+    let completed_at: Option<String> = row.try_get("completed_at")?;
+
+    Ok(Branch {
+        id: BranchId::new(row.try_get("id")?)?,
+        parent_id: parent_id.map(BranchId::new).transpose()?,
+        name: row.try_get("name")?,
+        status: from_json(row.try_get("status_json")?)?,
+        session_id: row.try_get("session_id")?,
+        created_at: parse_timestamp(row.try_get("created_at")?)?,
+        completed_at: completed_at.as_deref().map(parse_timestamp).transpose()?,
+        children: Vec::new(),
+        config: from_json(row.try_get("config_json")?)?,
+    })
+}
+
+fn merge_request_from_row(row: &SqliteRow) -> Result<MergeRequestModel, BranchError> {
+    let approved_at: Option<String> = row.try_get("approved_at")?;
+
+    Ok(MergeRequestModel {
+        id: MergeRequestId::new(row.try_get("id")?)?,
+        branch_id: BranchId::new(row.try_get("branch_id")?)?,
+        strategy: row.try_get("strategy")?,
+        status: parse_merge_status(row.try_get("status")?)?,
+        requires_approval: row.try_get("requires_approval")?,
+        approved_by: row.try_get("approved_by")?,
+        approved_at: approved_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+fn parse_merge_status(status: &str) -> Result<MergeRequestStatus, BranchError> {
+    match status {
+        "pending" => Ok(MergeRequestStatus::Pending),
+        "approved" => Ok(MergeRequestStatus::Approved),
+        "rejected" => Ok(MergeRequestStatus::Rejected),
+        "merged" => Ok(MergeRequestStatus::Merged),
+        "conflict" => Ok(MergeRequestStatus::Conflict),
+        other => Err(BranchError::Internal(format!(
+            "unknown merge request status: {other}"
+        ))),
+    }
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, BranchError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| BranchError::Internal(format!("invalid timestamp '{value}': {e}")))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, BranchError> {
+    serde_json::to_string(value).map_err(|e| BranchError::Internal(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, BranchError> {
+    serde_json::from_str(value).map_err(|e| BranchError::Internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::branch_manager::{
+        AgentAssignment, AgentResult, BranchConfig, BranchStatus, ExecutionStrategy,
+    };
+    use crate::store::Migrator;
+    use crate::ws::FileChangeSummary;
+    use crate::ws::types::ChangeType;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn storage() -> SqliteBranchStorage {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory database");
+        Migrator::new().run(&pool).await.expect("migrations");
+        SqliteBranchStorage::new(pool)
+    }
+
+    fn branch(name: &str, parent: Option<&Branch>) -> Branch {
+        Branch {
+            id: BranchId::new(uuid::Uuid::new_v4().to_string()).unwrap(),
+            parent_id: parent.map(|p| p.id.clone()),
+            name: name.to_string(),
+            status: BranchStatus::Pending,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            completed_at: None,
+            children: Vec::new(),
+            config: BranchConfig {
+                base_path: Some("/tmp/base".to_string()),
+                agents: vec![AgentAssignment {
+                    agent_id: "coder".to_string(),
+                    task_override: Some("write tests".to_string()),
+                    priority: 3,
+                }],
+                execution_strategy: ExecutionStrategy::Parallel {
+                    max_concurrent: Some(2),
+                },
+                auto_merge: false,
+                merge_strategy: "union".to_string(),
+            },
+        }
+    }
+
+    fn merge_request(branch: &Branch) -> MergeRequestModel {
+        MergeRequestModel {
+            id: MergeRequestId::new(uuid::Uuid::new_v4().to_string()).unwrap(),
+            branch_id: branch.id.clone(),
+            strategy: "union".to_string(),
+            status: MergeRequestStatus::Pending,
+            requires_approval: true,
+            approved_by: None,
+            approved_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn branch_round_trip() {
+        let storage = storage().await;
+        let root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+
+        let loaded = storage.get_branch(&root.id).await.unwrap().unwrap();
+        assert_eq!(loaded.name, "root");
+        assert_eq!(loaded.status, BranchStatus::Pending);
+        assert_eq!(loaded.session_id, root.session_id);
+        assert_eq!(loaded.config.agents[0].agent_id, "coder");
+        assert!(matches!(
+            loaded.config.execution_strategy,
+            ExecutionStrategy::Parallel {
+                max_concurrent: Some(2)
+            }
+        ));
+        assert_eq!(loaded.config.base_path.as_deref(), Some("/tmp/base"));
+        assert!(storage.branch_name_exists("root").await.unwrap());
+        assert!(!storage.branch_name_exists("other").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn children_are_derived_from_parent_links() {
+        let storage = storage().await;
+        let root = branch("root", None);
+        let child = branch("child", Some(&root));
+        storage.insert_branch(root.clone()).await.unwrap();
+        storage.insert_branch(child.clone()).await.unwrap();
+
+        let loaded = storage.get_branch(&root.id).await.unwrap().unwrap();
+        assert_eq!(loaded.children, vec![child.id.clone()]);
+
+        let all = storage.get_all_branches().await.unwrap();
+        let root_from_all = all.iter().find(|b| b.id == root.id).unwrap();
+        assert_eq!(root_from_all.children, vec![child.id]);
+    }
+
+    #[tokio::test]
+    async fn update_and_remove_branch() {
+        let storage = storage().await;
+        let mut root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+
+        root.status = BranchStatus::Completed;
+        root.completed_at = Some(Utc::now());
+        assert!(storage.update_branch(&root).await.unwrap());
+
+        let loaded = storage.get_branch(&root.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, BranchStatus::Completed);
+        assert!(loaded.completed_at.is_some());
+
+        assert!(storage.remove_branch(&root.id).await.unwrap());
+        assert!(!storage.remove_branch(&root.id).await.unwrap());
+        assert!(storage.get_branch(&root.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn update_branch_from_checks_the_stored_status() {
+        let storage = storage().await;
+        let mut root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+
+        root.status = BranchStatus::Running;
+        let startable = [BranchStatus::Pending, BranchStatus::Failed];
+        assert!(storage.update_branch_from(&root, &startable).await.unwrap());
+        assert!(!storage.update_branch_from(&root, &startable).await.unwrap());
+
+        let loaded = storage.get_branch(&root.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, BranchStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn result_round_trip() {
+        let storage = storage().await;
+        let root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+        assert!(storage.get_result(&root.id).await.unwrap().is_none());
+
+        let result = BranchResult {
+            branch_id: root.id.clone(),
+            file_changes: vec![FileChangeSummary::new(
+                "src/lib.rs".to_string(),
+                ChangeType::Modified,
+                None,
+            )],
+            agent_results: vec![AgentResult {
+                agent_id: "coder".to_string(),
+                success: true,
+                output: "done".to_string(),
+            }],
+            created_at: Utc::now(),
+        };
+        storage.save_result(result.clone()).await.unwrap();
+        storage.save_result(result).await.unwrap();
+
+        let loaded = storage.get_result(&root.id).await.unwrap().unwrap();
+        assert!(loaded.succeeded());
+        assert_eq!(loaded.file_changes[0].path(), "src/lib.rs");
+        assert_eq!(loaded.agent_results[0].output, "done");
+    }
+
+    #[tokio::test]
+    async fn merge_request_round_trip() {
+        let storage = storage().await;
+        let root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+
+        let mut mr = merge_request(&root);
+        storage.insert_merge_request(mr.clone()).await.unwrap();
+        storage
+            .insert_merge_request(merge_request(&root))
+            .await
+            .unwrap();
+
+        mr.status = MergeRequestStatus::Approved;
+        mr.approved_by = Some("reviewer".to_string());
+        mr.approved_at = Some(Utc::now());
+        assert!(storage.update_merge_request(&mr).await.unwrap());
+
+        let loaded = storage.get_merge_request(&mr.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, MergeRequestStatus::Approved);
+        assert_eq!(loaded.approved_by.as_deref(), Some("reviewer"));
+        assert!(loaded.requires_approval);
+
+        let for_branch = storage
+            .get_merge_requests_for_branch(&root.id)
+            .await
+            .unwrap();
+        assert_eq!(for_branch.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn update_merge_request_from_checks_the_stored_status() {
+        let storage = storage().await;
+        let root = branch("root", None);
+        storage.insert_branch(root.clone()).await.unwrap();
+        let mut mr = merge_request(&root);
+        storage.insert_merge_request(mr.clone()).await.unwrap();
+
+        mr.status = MergeRequestStatus::Approved;
+        let pending = [MergeRequestStatus::Pending];
+        assert!(
+            storage
+                .update_merge_request_from(&mr, &pending)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !storage
+                .update_merge_request_from(&mr, &pending)
+                .await
+                .unwrap()
+        );
+
+        let loaded = storage.get_merge_request(&mr.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, MergeRequestStatus::Approved);
+    }
+}
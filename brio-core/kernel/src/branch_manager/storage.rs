@@ -1,19 +1,29 @@
 //! Branch storage for the Brio kernel.
 //!
-//! This module provides in-memory storage for branches and merge requests.
+//! This module defines the storage ports used by the `BranchManager` and
+//! provides an in-memory implementation of them.
 
+use async_trait::async_trait;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 
-use super::types::{Branch, BranchId, MergeRequestId, MergeRequestModel};
+use super::types::{
+    Branch, BranchError, BranchId, BranchResult, BranchStatus, MergeRequestId, MergeRequestModel,
+    MergeRequestStatus,
+};
 
-/// Storage for branches and merge requests.
+/// In-memory storage for branches and merge requests.
+///
+/// State is lost when the process exits; see
+/// [`SqliteBranchStorage`](super::SqliteBranchStorage) for a persistent backend.
 #[derive(Debug, Default)]
 pub struct BranchStorage {
-    /// In-memory storage for branches (temporary - will use database).
+    /// Branches keyed by ID.
     branches: RwLock<HashMap<String, Branch>>,
-    /// In-memory storage for merge requests (temporary - will use database).
+    /// Merge requests keyed by ID.
     merge_requests: RwLock<HashMap<String, MergeRequestModel>>,
+    /// Execution results keyed by branch ID.
+    results: RwLock<HashMap<String, BranchResult>>,
 }
 
 impl BranchStorage {
@@ -50,9 +60,45 @@ impl BranchStorage {
         self.branches.read().contains_key(id.as_str())
     }
 
-    /// Remove a branch from storage.
-    pub fn remove_branch(&self, id: &BranchId) {
-        self.branches.write().remove(id.as_str());
+    /// Replace a stored branch, returning `false` if it does not exist.
+    pub fn update_branch(&self, branch: &Branch) -> bool {
+        match self.branches.write().get_mut(branch.id.as_str()) {
+            Some(stored) => {
+                *stored = branch.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace a stored branch if its status is one of `from`, returning
+    /// `false` if it does not exist or is in another state.
+    pub fn update_branch_from(&self, branch: &Branch, from: &[BranchStatus]) -> bool {
+        match self.branches.write().get_mut(branch.id.as_str()) {
+            Some(stored) if from.contains(&stored.status) => {
+                *stored = branch.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Remove a branch from storage, returning `false` if it did not exist.
+    pub fn remove_branch(&self, id: &BranchId) -> bool {
+        self.results.write().remove(id.as_str());
+        self.branches.write().remove(id.as_str()).is_some()
+    }
+
+    /// Store the execution result of a branch, replacing any previous one.
+    pub fn save_result(&self, result: BranchResult) {
+        self.results
+            .write()
+            .insert(result.branch_id.as_str().to_string(), result);
+    }
+
+    /// Get the execution result of a branch.
+    pub fn get_result(&self, id: &BranchId) -> Option<BranchResult> {
+        self.results.read().get(id.as_str()).cloned()
     }
 
     /// Get all branches.
@@ -82,6 +128,41 @@ impl BranchStorage {
         RwLockWriteGuard::try_map(merge_requests, |mrs| mrs.get_mut(id.as_str())).ok()
     }
 
+    /// Replace a stored merge request, returning `false` if it does not exist.
+    pub fn update_merge_request(&self, merge_request: &MergeRequestModel) -> bool {
+        match self
+            .merge_requests
+            .write()
+            .get_mut(merge_request.id.as_str())
+        {
+            Some(stored) => {
+                *stored = merge_request.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace a stored merge request if its status is one of `from`,
+    /// returning `false` if it does not exist or is in another state.
+    pub fn update_merge_request_from(
+        &self,
+        merge_request: &MergeRequestModel,
+        from: &[MergeRequestStatus],
+    ) -> bool {
+        match self
+            .merge_requests
+            .write()
+            .get_mut(merge_request.id.as_str())
+        {
+            Some(stored) if from.contains(&stored.status) => {
+                *stored = merge_request.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Get all merge requests for a specific branch.
     pub fn get_merge_requests_for_branch(&self, branch_id: &BranchId) -> Vec<MergeRequestModel> {
         self.merge_requests
@@ -99,61 +180,151 @@ impl BranchStorage {
 }
 
 /// Trait for branch storage operations.
+#[async_trait]
 pub trait BranchStoragePort: Send + Sync {
     /// Insert a branch.
-    fn insert_branch(&self, branch: Branch);
+    async fn insert_branch(&self, branch: Branch) -> Result<(), BranchError>;
     /// Get a branch by ID.
-    fn get_branch(&self, id: &BranchId) -> Option<Branch>;
+    async fn get_branch(&self, id: &BranchId) -> Result<Option<Branch>, BranchError>;
     /// Get all branches.
-    fn get_all_branches(&self) -> Vec<Branch>;
-    /// Remove a branch.
-    fn remove_branch(&self, id: &BranchId);
+    async fn get_all_branches(&self) -> Result<Vec<Branch>, BranchError>;
+    /// Replace an existing branch, returning `false` if it does not exist.
+    async fn update_branch(&self, branch: &Branch) -> Result<bool, BranchError>;
+    /// Replace an existing branch if its stored status is one of `from`,
+    /// returning `false` if it does not exist or is in another state.
+    async fn update_branch_from(
+        &self,
+        branch: &Branch,
+        from: &[BranchStatus],
+    ) -> Result<bool, BranchError>;
+    /// Remove a branch, returning `false` if it does not exist.
+    async fn remove_branch(&self, id: &BranchId) -> Result<bool, BranchError>;
     /// Check if branch name exists.
-    fn branch_name_exists(&self, name: &str) -> bool;
+    async fn branch_name_exists(&self, name: &str) -> Result<bool, BranchError>;
+    /// Store the execution result of a branch, replacing any previous one.
+    async fn save_result(&self, result: BranchResult) -> Result<(), BranchError>;
+    /// Get the execution result of a branch.
+    async fn get_result(&self, id: &BranchId) -> Result<Option<BranchResult>, BranchError>;
 }
 
 /// Trait for merge request storage operations.
+#[async_trait]
 pub trait MergeRequestStoragePort: Send + Sync {
     /// Insert a merge request.
-    fn insert_merge_request(&self, merge_request: MergeRequestModel);
+    async fn insert_merge_request(
+        &self,
+        merge_request: MergeRequestModel,
+    ) -> Result<(), BranchError>;
     /// Get a merge request by ID.
-    fn get_merge_request(&self, id: &MergeRequestId) -> Option<MergeRequestModel>;
+    async fn get_merge_request(
+        &self,
+        id: &MergeRequestId,
+    ) -> Result<Option<MergeRequestModel>, BranchError>;
+    /// Replace an existing merge request, returning `false` if it does not exist.
+    async fn update_merge_request(
+        &self,
+        merge_request: &MergeRequestModel,
+    ) -> Result<bool, BranchError>;
+    /// Replace an existing merge request if its stored status is one of
+    /// `from`, returning `false` if it does not exist or is in another state.
+    async fn update_merge_request_from(
+        &self,
+        merge_request: &MergeRequestModel,
+        from: &[MergeRequestStatus],
+    ) -> Result<bool, BranchError>;
     /// Get all merge requests for a branch.
-    fn get_merge_requests_for_branch(&self, branch_id: &BranchId) -> Vec<MergeRequestModel>;
+    async fn get_merge_requests_for_branch(
+        &self,
+        branch_id: &BranchId,
+    ) -> Result<Vec<MergeRequestModel>, BranchError>;
 }
 
+/// Combined storage backend for the `BranchManager`.
+pub trait BranchStore: BranchStoragePort + MergeRequestStoragePort {}
+
+impl<T: BranchStoragePort + MergeRequestStoragePort> BranchStore for T {}
+
+#[async_trait]
 impl BranchStoragePort for BranchStorage {
-    fn insert_branch(&self, branch: Branch) {
+    async fn insert_branch(&self, branch: Branch) -> Result<(), BranchError> {
         self.insert_branch(branch);
+        Ok(())
+    }
+
+    async fn get_branch(&self, id: &BranchId) -> Result<Option<Branch>, BranchError> {
+        Ok(self.get_branch(id))
+    }
+
+    async fn get_all_branches(&self) -> Result<Vec<Branch>, BranchError> {
+        Ok(self.get_all_branches())
+    }
+
+    async fn update_branch(&self, branch: &Branch) -> Result<bool, BranchError> {
+        Ok(self.update_branch(branch))
+    }
+
+    async fn update_branch_from(
+        &self,
+        branch: &Branch,
+        from: &[BranchStatus],
+    ) -> Result<bool, BranchError> {
+        Ok(self.update_branch_from(branch, from))
     }
 
-    fn get_branch(&self, id: &BranchId) -> Option<Branch> {
-        self.get_branch(id)
+    async fn remove_branch(&self, id: &BranchId) -> Result<bool, BranchError> {
+        Ok(self.remove_branch(id))
     }
 
-    fn get_all_branches(&self) -> Vec<Branch> {
-        self.get_all_branches()
+    async fn branch_name_exists(&self, name: &str) -> Result<bool, BranchError> {
+        Ok(self.branch_name_exists(name))
     }
 
-    fn remove_branch(&self, id: &BranchId) {
-        self.remove_branch(id);
+    async fn save_result(&self, result: BranchResult) -> Result<(), BranchError> {
+        self.save_result(result);
+        Ok(())
     }
 
-    fn branch_name_exists(&self, name: &str) -> bool {
-        self.branch_name_exists(name)
+    async fn get_result(&self, id: &BranchId) -> Result<Option<BranchResult>, BranchError> {
+        Ok(self.get_result(id))
     }
 }
 
+#[async_trait]
 impl MergeRequestStoragePort for BranchStorage {
-    fn insert_merge_request(&self, merge_request: MergeRequestModel) {
+    async fn insert_merge_request(
+        &self,
+        merge_request: MergeRequestModel,
+    ) -> Result<(), BranchError> {
         self.insert_merge_request(merge_request);
+        Ok(())
     }
 
-    fn get_merge_request(&self, id: &MergeRequestId) -> Option<MergeRequestModel> {
-        self.get_merge_request(id)
+    async fn get_merge_request(
+        &self,
+        id: &MergeRequestId,
+    ) -> Result<Option<MergeRequestModel>, BranchError> {
+        Ok(self.get_merge_request(id))
     }
 
-    fn get_merge_requests_for_branch(&self, branch_id: &BranchId) -> Vec<MergeRequestModel> {
-        self.get_merge_requests_for_branch(branch_id)
+    async fn update_merge_request(
+        &self,
+        merge_request: &MergeRequestModel,
+    ) -> Result<bool, BranchError> {
+        Ok(self.update_merge_request(merge_request))
+    }
+
+    async fn update_merge_request_from(
+        &self,
+        merge_request: &MergeRequestModel,
+        from: &[MergeRequestStatus],
+    ) -> Result<bool, BranchError> {
+        Ok(self.update_merge_request_from(merge_request, from))
+    }
+
+    async fn get_merge_requests_for_branch(
+        &self,
+        branch_id: &BranchId,
+    ) -> Result<Vec<MergeRequestModel>, BranchError> {
+        Ok(self.get_merge_requests_for_branch(branch_id))
     }
 }
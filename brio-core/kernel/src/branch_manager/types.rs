@@ -3,6 +3,9 @@
 //! This module provides domain types for managing branches in the Brio system.
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ws::FileChangeSummary;
 
 /// Branch identifier.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -121,7 +124,8 @@ pub enum BranchError {
 }
 
 /// Branch status.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BranchStatus {
     /// Branch created but not yet executed.
     #[default]
@@ -175,8 +179,11 @@ pub struct Branch {
 }
 
 /// Branch configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BranchConfig {
+    /// Base directory the branch VFS session is created from.
+    #[serde(default)]
+    pub base_path: Option<String>,
     /// Agent assignments.
     pub agents: Vec<AgentAssignment>,
     /// Execution strategy.
@@ -188,7 +195,8 @@ pub struct BranchConfig {
 }
 
 /// Execution strategy.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
 pub enum ExecutionStrategy {
     /// Sequential execution.
     Sequential,
@@ -200,7 +208,7 @@ pub enum ExecutionStrategy {
 }
 
 /// Agent assignment.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentAssignment {
     /// Agent ID.
     pub agent_id: String,
@@ -210,6 +218,47 @@ pub struct AgentAssignment {
     pub priority: u8,
 }
 
+/// Where a new branch takes its files from.
+#[derive(Debug, Clone)]
+pub enum BranchSource {
+    /// A base directory on the host filesystem.
+    Base(String),
+    /// An existing branch, whose base directory is inherited.
+    Branch(BranchId),
+}
+
+/// Outcome of a single agent run on a branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentResult {
+    /// Agent ID.
+    pub agent_id: String,
+    /// Whether the agent completed successfully.
+    pub success: bool,
+    /// Agent output, or the error message on failure.
+    pub output: String,
+}
+
+/// Recorded results of a branch execution.
+#[derive(Debug, Clone)]
+pub struct BranchResult {
+    /// Branch ID.
+    pub branch_id: BranchId,
+    /// Files changed in the branch session relative to its base directory.
+    pub file_changes: Vec<FileChangeSummary>,
+    /// Per-agent outcomes, in completion order.
+    pub agent_results: Vec<AgentResult>,
+    /// Timestamp the result was recorded.
+    pub created_at: DateTime<Utc>,
+}
+
+impl BranchResult {
+    /// Returns true if every agent completed successfully.
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.agent_results.iter().all(|r| r.success)
+    }
+}
+
 /// Merge request domain model.
 #[derive(Debug, Clone)]
 pub struct MergeRequestModel {
@@ -0,0 +1,338 @@
+//! Resource limits for guest components.
+//!
+//! Every store created for a guest is metered with fuel and capped by a
+//! [`GuestLimiter`]. Fuel doubles as a cooperative yield point: guests yield
+//! back to the async runtime every [`FUEL_YIELD_INTERVAL`] units, so a looping
+//! component cannot pin a tokio worker and wall-clock timeouts can fire.
+//! Limit violations and traps are classified into [`GuestError`].
+
+use crate::host::BrioHostState;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use wasmtime::{Engine, ResourceLimiter, Store, Trap};
+
+/// Fuel consumed between two cooperative yields to the async runtime.
+pub const FUEL_YIELD_INTERVAL: u64 = 1_000_000;
+
+/// Per-invocation resource budget for a guest component.
+///
+/// `None` leaves the resource unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Fuel budget, roughly the number of executed WebAssembly instructions.
+    pub fuel: Option<u64>,
+    /// Maximum size of a linear memory in bytes.
+    pub max_memory_bytes: Option<usize>,
+    /// Maximum number of elements in a table.
+    pub max_table_elements: Option<usize>,
+    /// Wall-clock timeout in milliseconds.
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for ResourceLimits {
+    /// Kernel defaults: 512 MiB of memory, 100k table elements, a five
+    /// minute timeout and no fuel budget.
+    fn default() -> Self {
+        Self {
+            fuel: None,
+            max_memory_bytes: Some(512 * 1024 * 1024),
+            max_table_elements: Some(100_000),
+            timeout_ms: Some(300_000),
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// Limits that leave every resource unbounded.
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            fuel: None,
+            max_memory_bytes: None,
+            max_table_elements: None,
+            timeout_ms: None,
+        }
+    }
+
+    /// Returns these limits, tightened so none exceeds `ceiling`.
+    #[must_use]
+    pub fn capped_by(self, ceiling: &Self) -> Self {
+        fn min<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        Self {
+            fuel: min(self.fuel, ceiling.fuel),
+            max_memory_bytes: min(self.max_memory_bytes, ceiling.max_memory_bytes),
+            max_table_elements: min(self.max_table_elements, ceiling.max_table_elements),
+            timeout_ms: min(self.timeout_ms, ceiling.timeout_ms),
+        }
+    }
+
+    /// Returns the wall-clock timeout, if any.
+    #[must_use]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+}
+
+/// Applies fuel metering and memory/table caps to a store.
+///
+/// Stores of engines built without fuel consumption are not metered, so
+/// they only accept limits without a fuel budget.
+///
+/// # Errors
+///
+/// Returns an error if `limits` has a fuel budget but the engine does not
+/// consume fuel, or if the engine consumes fuel but has no async support.
+pub fn apply_limits(
+    store: &mut Store<BrioHostState>,
+    limits: &ResourceLimits,
+) -> anyhow::Result<()> {
+    store.data_mut().limiter = GuestLimiter::new(limits);
+    store.limiter(|state| &mut state.limiter);
+
+    // `get_fuel` only fails when the engine does not consume fuel
+    if store.get_fuel().is_err() {
+        return match limits.fuel {
+            Some(fuel) => Err(anyhow::anyhow!(
+                "Fuel budget of {fuel} requires an engine with fuel consumption enabled"
+            )),
+            None => Ok(()),
+        };
+    }
+    store.set_fuel(limits.fuel.unwrap_or(u64::MAX))?;
+    store.fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL))?;
+    Ok(())
+}
+
+/// Creates a store for `host_state` with `limits` applied.
+pub(crate) fn limited_store(
+    engine: &Engine,
+    host_state: BrioHostState,
+    limits: &ResourceLimits,
+) -> anyhow::Result<Store<BrioHostState>> {
+    let mut store = Store::new(engine, host_state);
+    apply_limits(&mut store, limits)?;
+    Ok(store)
+}
+
+/// Runs a guest invocation under the wall-clock timeout of `limits`,
+/// classifying and counting any trap.
+pub(crate) async fn run_bounded<T>(
+    limits: &ResourceLimits,
+    plugin_id: &str,
+    run: impl Future<Output = anyhow::Result<T>>,
+) -> Result<T, GuestError> {
+    let result = match limits.timeout() {
+        Some(timeout) => tokio::time::timeout(timeout, run)
+            .await
+            .unwrap_or_else(|_| Err(GuestError::Timeout(timeout).into())),
+        None => run.await,
+    };
+
+    result.map_err(|e| {
+        let error = GuestError::from_wasmtime(e);
+        if error.is_trap() {
+            metrics::counter!(
+                "brio_guest_traps_total",
+                "plugin" => plugin_id.to_string(),
+                "reason" => error.kind(),
+            )
+            .increment(1);
+        }
+        error
+    })
+}
+
+/// Returns the plugin id used to label metrics.
+pub(crate) fn plugin_label(host_state: &BrioHostState) -> String {
+    host_state
+        .current_plugin_id()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Resource limiter that fails growth beyond the configured caps with a
+/// [`GuestError`] instead of letting the guest observe a failed grow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GuestLimiter {
+    max_memory_bytes: Option<usize>,
+    max_table_elements: Option<usize>,
+}
+
+impl GuestLimiter {
+    /// Creates a limiter enforcing the memory and table caps of `limits`.
+    #[must_use]
+    pub fn new(limits: &ResourceLimits) -> Self {
+        Self {
+            max_memory_bytes: limits.max_memory_bytes,
+            max_table_elements: limits.max_table_elements,
+        }
+    }
+}
+
+impl ResourceLimiter for GuestLimiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        match self.max_memory_bytes {
+            Some(limit) if desired > limit => Err(GuestError::MemoryLimitExceeded { limit }.into()),
+            _ => Ok(true),
+        }
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        match self.max_table_elements {
+            Some(limit) if desired > limit => Err(GuestError::TableLimitExceeded { limit }.into()),
+            _ => Ok(true),
+        }
+    }
+}
+
+/// Errors raised while running a guest component.
+#[derive(Debug, thiserror::Error)]
+pub enum GuestError {
+    /// The guest consumed its whole fuel budget.
+    #[error("Guest exhausted its fuel budget")]
+    OutOfFuel,
+    /// The guest tried to grow a memory beyond the limit.
+    #[error("Guest exceeded the memory limit of {limit} bytes")]
+    MemoryLimitExceeded {
+        /// Configured memory limit in bytes.
+        limit: usize,
+    },
+    /// The guest tried to grow a table beyond the limit.
+    #[error("Guest exceeded the table limit of {limit} elements")]
+    TableLimitExceeded {
+        /// Configured table limit in elements.
+        limit: usize,
+    },
+    /// The guest did not finish within its wall-clock timeout.
+    #[error("Guest timed out after {0:?}")]
+    Timeout(Duration),
+    /// The guest trapped for another reason.
+    #[error("Guest trapped: {0}")]
+    Trap(Trap),
+    /// The guest ran to completion but reported a failure.
+    #[error("Guest execution failed: {0}")]
+    Failed(String),
+    /// The component could not be instantiated or called.
+    #[error(transparent)]
+    Runtime(anyhow::Error),
+}
+
+impl GuestError {
+    /// Classifies an error returned by wasmtime.
+    #[must_use]
+    pub fn from_wasmtime(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<Self>() {
+            Ok(guest) => return guest,
+            Err(error) => error,
+        };
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => Self::OutOfFuel,
+            Some(trap) => Self::Trap(*trap),
+            None => Self::Runtime(error),
+        }
+    }
+
+    /// Returns a stable label for metrics.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::OutOfFuel => "out_of_fuel",
+            Self::MemoryLimitExceeded { .. } => "memory_limit",
+            Self::TableLimitExceeded { .. } => "table_limit",
+            Self::Timeout(_) => "timeout",
+            Self::Trap(_) => "trap",
+            Self::Failed(_) => "failed",
+            Self::Runtime(_) => "runtime",
+        }
+    }
+
+    /// Returns true if the guest was stopped by the runtime rather than
+    /// failing on its own terms.
+    #[must_use]
+    pub fn is_trap(&self) -> bool {
+        !matches!(self, Self::Failed(_) | Self::Runtime(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capped_by_takes_the_tighter_limit() {
+        let plugin = ResourceLimits {
+            fuel: Some(10),
+            max_memory_bytes: Some(4096),
+            max_table_elements: None,
+            timeout_ms: Some(60_000),
+        };
+        let ceiling = ResourceLimits {
+            fuel: None,
+            max_memory_bytes: Some(1024),
+            max_table_elements: Some(50),
+            timeout_ms: Some(120_000),
+        };
+
+        let effective = plugin.capped_by(&ceiling);
+        assert_eq!(effective.fuel, Some(10));
+        assert_eq!(effective.max_memory_bytes, Some(1024));
+        assert_eq!(effective.max_table_elements, Some(50));
+        assert_eq!(effective.timeout(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn missing_fields_use_kernel_defaults() {
+        let limits: ResourceLimits = serde_json::from_str(r#"{"fuel": 5}"#).unwrap();
+        assert_eq!(limits.fuel, Some(5));
+        assert_eq!(
+            limits.max_memory_bytes,
+            ResourceLimits::default().max_memory_bytes
+        );
+    }
+
+    #[test]
+    fn limiter_rejects_growth_beyond_caps() {
+        let mut limiter = GuestLimiter::new(&ResourceLimits {
+            max_memory_bytes: Some(65_536),
+            max_table_elements: Some(10),
+            ..ResourceLimits::unlimited()
+        });
+
+        assert!(limiter.memory_growing(0, 65_536, None).unwrap());
+        let error = limiter.memory_growing(65_536, 131_072, None).unwrap_err();
+        assert!(matches!(
+            GuestError::from_wasmtime(error),
+            GuestError::MemoryLimitExceeded { limit: 65_536 }
+        ));
+        assert!(limiter.table_growing(0, 11, None).is_err());
+    }
+
+    #[test]
+    fn classifies_traps() {
+        let fuel = GuestError::from_wasmtime(anyhow::Error::new(Trap::OutOfFuel));
+        assert_eq!(fuel.kind(), "out_of_fuel");
+        assert!(fuel.is_trap());
+
+        let other = GuestError::from_wasmtime(anyhow::anyhow!("link error"));
+        assert_eq!(other.kind(), "runtime");
+        assert!(!other.is_trap());
+    }
+}
@@ -10,7 +10,7 @@ use crate::host::mesh::MeshHandler;
 use crate::host::permissions::PermissionChecker;
 use crate::mesh::Payload;
 use anyhow::Result;
-use wasmtime::component::{HasSelf, Linker};
+use wasmtime::component::{HasSelf, Linker, Resource};
 use wasmtime::{Config, Engine};
 
 impl brio::core::service_mesh::Host for BrioHostState {
@@ -97,6 +97,98 @@ impl brio::core::session_fs::Host for BrioHostState {
     fn commit_session(&mut self, session_id: String) -> Result<(), String> {
         BrioHostState::commit_session(self, &session_id).map_err(|e| e.to_string())
     }
+
+    fn get_session_path(&mut self, session_id: String) -> Result<String, String> {
+        self.check_permission("fs:read")?;
+        let files = self.session_files(&session_id).map_err(|e| e.to_string())?;
+        Ok(files.root().to_string_lossy().into_owned())
+    }
+
+    fn rollback_session(&mut self, session_id: String) -> Result<(), String> {
+        self.check_permission("fs:write")?;
+        BrioHostState::rollback_session(self, &session_id).map_err(|e| e.to_string())
+    }
+
+    fn changes(
+        &mut self,
+        session_id: String,
+    ) -> Result<Vec<brio::core::session_fs::FileChange>, String> {
+        use crate::vfs::diff::FileChange as Change;
+        use brio::core::session_fs::{ChangeKind, FileChange};
+
+        self.check_permission("fs:read")?;
+        let changes = self.session_diff(&session_id).map_err(|e| e.to_string())?;
+        Ok(changes
+            .into_iter()
+            .map(|change| {
+                let (path, kind) = match change.change {
+                    Change::Added(path) => (path, ChangeKind::Added),
+                    Change::Modified(path) => (path, ChangeKind::Modified),
+                    Change::Deleted(path) => (path, ChangeKind::Deleted),
+                };
+                FileChange {
+                    path: path.to_string_lossy().into_owned(),
+                    kind,
+                    before: change.before,
+                    after: change.after,
+                }
+            })
+            .collect())
+    }
+}
+
+impl brio::core::session_fs_ops::Host for BrioHostState {
+    fn read_file(&mut self, session_id: String, path: String) -> Result<String, String> {
+        self.check_permission("fs:read")?;
+        self.session_files(&session_id)
+            .and_then(|files| files.read_file(&path))
+            .map_err(|e| e.to_string())
+    }
+
+    fn read_file_range(
+        &mut self,
+        session_id: String,
+        path: String,
+        start_line: u32,
+        end_line: u32,
+    ) -> Result<String, String> {
+        self.check_permission("fs:read")?;
+        self.session_files(&session_id)
+            .and_then(|files| files.read_file_range(&path, start_line, end_line))
+            .map_err(|e| e.to_string())
+    }
+
+    fn write_file(
+        &mut self,
+        session_id: String,
+        path: String,
+        content: String,
+    ) -> Result<(), String> {
+        self.check_permission("fs:write")?;
+        self.session_files(&session_id)
+            .and_then(|files| files.write_file(&path, &content))
+            .map_err(|e| e.to_string())
+    }
+
+    fn list_directory(
+        &mut self,
+        session_id: String,
+        path: String,
+    ) -> Result<Vec<brio::core::session_fs_ops::DirectoryEntry>, String> {
+        self.check_permission("fs:read")?;
+        let entries = self
+            .session_files(&session_id)
+            .and_then(|files| files.list_directory(&path))
+            .map_err(|e| e.to_string())?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| brio::core::session_fs_ops::DirectoryEntry {
+                name: entry.name,
+                is_directory: entry.is_directory,
+                size: entry.size,
+            })
+            .collect())
+    }
 }
 
 impl brio::core::pub_sub::Host for BrioHostState {
@@ -106,63 +198,186 @@ impl brio::core::pub_sub::Host for BrioHostState {
             .ok_or_else(|| "Only plugins can subscribe to events".to_string())?
             .to_string();
 
-        self.event_bus().subscribe(topic, plugin_id);
-        Ok(())
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(async { self.subscribe_plugin(&plugin_id, &topic).await })
+        })
+        .map_err(|e| e.to_string())
     }
 
     fn publish(&mut self, topic: String, data: brio::core::pub_sub::Payload) -> Result<(), String> {
-        let subscribers = self.event_bus().subscribers(&topic);
-        if subscribers.is_empty() {
-            return Ok(());
+        let payload = match data {
+            brio::core::pub_sub::Payload::Json(s) => crate::mesh::Payload::Json(Box::new(s)),
+            brio::core::pub_sub::Payload::Binary(b) => crate::mesh::Payload::Binary(Box::new(b)),
+        };
+
+        // The event is durably logged before the guest continues
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(async { self.publish_event(topic, payload).await })
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Converts guest messages into an inference request.
+fn to_chat_request(
+    model: String,
+    messages: Vec<brio::core::inference::Message>,
+) -> crate::inference::ChatRequest {
+    use crate::inference::{ChatRequest, Message, Role, ToolCall};
+
+    let messages = messages
+        .into_iter()
+        .map(|m| Message {
+            role: match m.role {
+                brio::core::inference::Role::System => Role::System,
+                brio::core::inference::Role::User => Role::User,
+                brio::core::inference::Role::Assistant => Role::Assistant,
+                brio::core::inference::Role::Tool => Role::Tool,
+            },
+            content: m.content,
+            tool_calls: m
+                .tool_calls
+                .into_iter()
+                .map(|c| ToolCall::new(c.id, c.name, c.arguments))
+                .collect(),
+            tool_call_id: m.tool_call_id,
+        })
+        .collect();
+
+    ChatRequest::new(model, messages)
+}
+
+/// Converts guest request options.
+fn to_request_options(
+    options: brio::core::inference::RequestOptions,
+) -> crate::inference::RequestOptions {
+    crate::inference::RequestOptions {
+        temperature: options.temperature,
+        top_p: options.top_p,
+        max_tokens: options.max_tokens,
+        stop: options.stop,
+        seed: options.seed,
+        response_format: match options.response_format {
+            brio::core::inference::ResponseFormat::Text => crate::inference::ResponseFormat::Text,
+            brio::core::inference::ResponseFormat::Json => crate::inference::ResponseFormat::Json,
+        },
+    }
+}
+
+/// Converts guest tool definitions, parsing their JSON schemas.
+fn to_tool_definitions(
+    tools: Vec<brio::core::inference::ToolDefinition>,
+) -> Result<Vec<crate::inference::ToolDefinition>, brio::core::inference::InferenceError> {
+    tools
+        .into_iter()
+        .map(|t| {
+            let parameters = serde_json::from_str(&t.parameters).map_err(|e| {
+                brio::core::inference::InferenceError::ProviderError(format!(
+                    "Invalid parameters schema for tool '{}': {e}",
+                    t.name
+                ))
+            })?;
+            Ok(crate::inference::ToolDefinition::new(
+                t.name,
+                t.description,
+                parameters,
+            ))
+        })
+        .collect()
+}
+
+fn to_wit_response(
+    response: crate::inference::ChatResponse,
+) -> brio::core::inference::CompletionResponse {
+    brio::core::inference::CompletionResponse {
+        content: response.content,
+        usage: response.usage.as_ref().map(to_wit_usage),
+        tool_calls: response
+            .tool_calls
+            .into_iter()
+            .map(|c| brio::core::inference::ToolCall {
+                id: c.id,
+                name: c.name,
+                arguments: c.arguments,
+            })
+            .collect(),
+    }
+}
+
+fn to_wit_usage(usage: &crate::inference::Usage) -> brio::core::inference::Usage {
+    brio::core::inference::Usage {
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+    }
+}
+
+fn to_wit_error(error: crate::inference::InferenceError) -> brio::core::inference::InferenceError {
+    match error {
+        crate::inference::InferenceError::RateLimit => {
+            brio::core::inference::InferenceError::RateLimit
+        }
+        crate::inference::InferenceError::ContextLengthExceeded => {
+            brio::core::inference::InferenceError::ContextLengthExceeded
         }
+        crate::inference::InferenceError::BudgetExceeded(message) => {
+            brio::core::inference::InferenceError::BudgetExceeded(message)
+        }
+        other => brio::core::inference::InferenceError::ProviderError(other.to_string()),
+    }
+}
 
-        let state = self.clone();
+impl BrioHostState {
+    /// Runs a chat completion for a guest on the provider its model is routed
+    /// to, within the token budgets of its task and plugin.
+    fn complete(
+        &self,
+        request: crate::inference::ChatRequest,
+    ) -> Result<brio::core::inference::CompletionResponse, brio::core::inference::InferenceError>
+    {
+        if let Err(e) = self.check_permission("ai:inference") {
+            return Err(brio::core::inference::InferenceError::ProviderError(e));
+        }
 
-        let payload_enum = match data {
-            brio::core::pub_sub::Payload::Json(s) => crate::engine::runner::EventPayload::Json(s),
-            brio::core::pub_sub::Payload::Binary(b) => {
-                crate::engine::runner::EventPayload::Binary(b)
-            }
+        let Some(inference_provider) = self.inference_for_model(&request.model) else {
+            return Err(brio::core::inference::InferenceError::ProviderError(
+                format!(
+                    "No inference provider configured for model '{}'",
+                    request.model
+                ),
+            ));
         };
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(async move {
+                self.chat_within_budget(inference_provider.as_ref(), request)
+                    .await
+            })
+        });
 
-        tokio::spawn(async move {
-            if let Some(registry) = state.plugin_registry() {
-                let engine = registry.engine();
-                let runner = crate::engine::runner::AgentRunner::new(engine.clone());
-
-                for agent_id in subscribers {
-                    if let Some(metadata) = registry.get(&agent_id) {
-                        let payload_clone = match &payload_enum {
-                            crate::engine::runner::EventPayload::Json(s) => {
-                                crate::engine::runner::EventPayload::Json(s.clone())
-                            }
-                            crate::engine::runner::EventPayload::Binary(b) => {
-                                crate::engine::runner::EventPayload::Binary(b.clone())
-                            }
-                        };
-
-                        if let Err(e) = runner
-                            .run_event_handler(
-                                &metadata.path,
-                                state.clone(),
-                                topic.clone(),
-                                payload_clone,
-                            )
-                            .await
-                        {
-                            tracing::error!(
-                                "Failed to deliver event '{}' to agent '{}': {}",
-                                topic,
-                                agent_id,
-                                e
-                            );
-                        }
-                    }
-                }
-            }
+        result.map(to_wit_response).map_err(to_wit_error)
+    }
+
+    /// Opens a streaming completion for a guest, within the token budgets of
+    /// its task and plugin.
+    fn open_stream(
+        &mut self,
+        request: crate::inference::ChatRequest,
+    ) -> Result<
+        Resource<brio::core::inference::CompletionStream>,
+        brio::core::inference::InferenceError,
+    > {
+        if let Err(e) = self.check_permission("ai:inference") {
+            return Err(brio::core::inference::InferenceError::ProviderError(e));
+        }
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.open_inference_stream(request))
         });
 
-        Ok(())
+        result.map(Resource::new_own).map_err(to_wit_error)
     }
 }
 
@@ -173,58 +388,120 @@ impl brio::core::inference::Host for BrioHostState {
         messages: Vec<brio::core::inference::Message>,
     ) -> Result<brio::core::inference::CompletionResponse, brio::core::inference::InferenceError>
     {
-        use crate::inference::{ChatRequest, Message, Role};
+        self.complete(to_chat_request(model, messages))
+    }
 
-        if let Err(e) = self.check_permission("ai:inference") {
-            return Err(brio::core::inference::InferenceError::ProviderError(e));
-        }
+    fn chat_with_options(
+        &mut self,
+        model: String,
+        messages: Vec<brio::core::inference::Message>,
+        options: brio::core::inference::RequestOptions,
+    ) -> Result<brio::core::inference::CompletionResponse, brio::core::inference::InferenceError>
+    {
+        self.complete(to_chat_request(model, messages).with_options(to_request_options(options)))
+    }
 
-        // Convert WASM messages to internal messages
-        let internal_messages: Vec<Message> = messages
-            .into_iter()
-            .map(|m| Message {
-                role: match m.role {
-                    brio::core::inference::Role::System => Role::System,
-                    brio::core::inference::Role::User => Role::User,
-                    brio::core::inference::Role::Assistant => Role::Assistant,
-                },
-                content: m.content,
-            })
-            .collect();
+    fn chat_with_tools(
+        &mut self,
+        model: String,
+        messages: Vec<brio::core::inference::Message>,
+        tools: Vec<brio::core::inference::ToolDefinition>,
+        options: brio::core::inference::RequestOptions,
+    ) -> Result<brio::core::inference::CompletionResponse, brio::core::inference::InferenceError>
+    {
+        let tools = to_tool_definitions(tools)?;
+        self.complete(
+            to_chat_request(model, messages)
+                .with_tools(tools)
+                .with_options(to_request_options(options)),
+        )
+    }
 
-        let request = ChatRequest {
-            model,
-            messages: internal_messages,
-        };
+    fn chat_stream(
+        &mut self,
+        model: String,
+        messages: Vec<brio::core::inference::Message>,
+    ) -> Result<
+        Resource<brio::core::inference::CompletionStream>,
+        brio::core::inference::InferenceError,
+    > {
+        self.open_stream(to_chat_request(model, messages))
+    }
 
-        let Some(inference_provider) = self.inference() else {
-            return Err(brio::core::inference::InferenceError::ProviderError(
-                "No default inference provider configured".to_string(),
-            ));
-        };
+    fn chat_stream_with_options(
+        &mut self,
+        model: String,
+        messages: Vec<brio::core::inference::Message>,
+        options: brio::core::inference::RequestOptions,
+    ) -> Result<
+        Resource<brio::core::inference::CompletionStream>,
+        brio::core::inference::InferenceError,
+    > {
+        self.open_stream(to_chat_request(model, messages).with_options(to_request_options(options)))
+    }
+}
+
+impl brio::core::inference::HostCompletionStream for BrioHostState {
+    fn next(
+        &mut self,
+        stream: Resource<brio::core::inference::CompletionStream>,
+    ) -> Result<Option<brio::core::inference::StreamChunk>, brio::core::inference::InferenceError>
+    {
+        let id = stream.rep();
         let result = tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(async move { inference_provider.chat(request).await })
+            tokio::runtime::Handle::current().block_on(self.next_inference_chunk(id))
         });
 
         result
-            .map(|response| brio::core::inference::CompletionResponse {
-                content: response.content,
-                usage: response.usage.map(|u| brio::core::inference::Usage {
-                    prompt_tokens: u.prompt_tokens,
-                    completion_tokens: u.completion_tokens,
-                    total_tokens: u.total_tokens,
-                }),
+            .map(|chunk| {
+                chunk.map(|chunk| brio::core::inference::StreamChunk {
+                    delta: chunk.delta,
+                    usage: chunk.usage.as_ref().map(to_wit_usage),
+                    finish_reason: chunk.finish_reason,
+                })
             })
-            .map_err(|e| match e {
-                crate::inference::InferenceError::RateLimit => {
-                    brio::core::inference::InferenceError::RateLimit
-                }
-                crate::inference::InferenceError::ContextLengthExceeded => {
-                    brio::core::inference::InferenceError::ContextLengthExceeded
-                }
-                other => brio::core::inference::InferenceError::ProviderError(other.to_string()),
+            .map_err(to_wit_error)
+    }
+
+    fn drop(&mut self, stream: Resource<brio::core::inference::CompletionStream>) -> Result<()> {
+        self.close_inference_stream(stream.rep());
+        Ok(())
+    }
+}
+
+impl brio::core::supervisor_config::Host for BrioHostState {
+    fn workspace(&mut self) -> Option<String> {
+        self.supervisor_settings().workspace.clone()
+    }
+
+    fn verification_policy(&mut self) -> Result<String, String> {
+        serde_json::to_string(&self.supervisor_settings().verification).map_err(|e| e.to_string())
+    }
+}
+
+impl brio::core::planner::Host for BrioHostState {
+    fn decompose(&mut self, objective: String) -> Result<brio::core::planner::Plan, String> {
+        self.check_permission("ai:inference")?;
+
+        let result = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.plan(&objective))
+        });
+
+        result
+            .map(|plan| brio::core::planner::Plan {
+                steps: plan
+                    .steps
+                    .into_iter()
+                    .map(|step| brio::core::planner::Subtask {
+                        id: step.id,
+                        description: step.description,
+                        depends_on: step.depends_on,
+                        outputs: step.outputs,
+                        capability: step.capability,
+                    })
+                    .collect(),
             })
+            .map_err(|e| e.to_string())
     }
 }
 
@@ -273,6 +550,7 @@ pub fn create_linker(engine: &Engine) -> Result<Linker<BrioHostState>> {
 /// - Maximum Wasm stack: 8 MiB
 /// - Async stack size: 8 MiB
 /// - Memory reservation: 4 GiB
+/// - Fuel metering, budgeted per store by [`crate::engine::limits::apply_limits`]
 #[must_use]
 pub fn create_engine_config() -> Config {
     let mut config = Config::new();
@@ -284,6 +562,9 @@ pub fn create_engine_config() -> Config {
     config.async_stack_size(8 * 1024 * 1024); // 8 MiB
     config.memory_reservation(4 * 1024 * 1024 * 1024); // 4 GiB static memory limits
 
+    // CPU budgets and cooperative yielding, see `engine::limits`
+    config.consume_fuel(true);
+
     config
 }
 
@@ -293,9 +574,12 @@ fn register_host_interfaces(linker: &mut Linker<BrioHostState>) -> Result<()> {
     brio::core::service_mesh::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
     brio::core::sql_state::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
     brio::core::session_fs::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
+    brio::core::session_fs_ops::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
     brio::core::inference::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
     brio::core::logging::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
+    brio::core::planner::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
     brio::core::pub_sub::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
+    brio::core::supervisor_config::add_to_linker::<BrioHostState, State>(linker, |s| s)?;
 
     Ok(())
 }
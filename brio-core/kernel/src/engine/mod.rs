@@ -1,12 +1,15 @@
 //! WebAssembly engine components for the Brio kernel.
 //!
 //! This module provides the core WASM runtime functionality including
-//! the component linker, runner, and runtime management.
+//! the component linker, agent and tool runners, and runtime management.
 
+pub mod limits;
 pub mod linker;
 pub mod runner;
 pub mod runtime;
+pub mod tools;
 
+pub use limits::{GuestError, ResourceLimits};
 pub use linker::{create_engine_config, create_linker};
 pub use runtime::WasmEngine;
 
@@ -35,17 +38,55 @@ mod wit_bindings {
             }
 
             interface session-fs {
+                enum change-kind {
+                    added,
+                    modified,
+                    deleted,
+                }
+                record file-change {
+                    path: string,
+                    kind: change-kind,
+                    before: option<string>,
+                    after: option<string>,
+                }
                 begin-session: func(base-path: string) -> result<string, string>;
                 commit-session: func(session-id: string) -> result<tuple<>, string>;
+                get-session-path: func(session-id: string) -> result<string, string>;
+                rollback-session: func(session-id: string) -> result<tuple<>, string>;
+                changes: func(session-id: string) -> result<list<file-change>, string>;
+            }
+
+            interface session-fs-ops {
+                record directory-entry {
+                    name: string,
+                    is-directory: bool,
+                    size: u64,
+                }
+                read-file: func(session-id: string, path: string) -> result<string, string>;
+                read-file-range: func(session-id: string, path: string, start-line: u32, end-line: u32) -> result<string, string>;
+                write-file: func(session-id: string, path: string, content: string) -> result<tuple<>, string>;
+                list-directory: func(session-id: string, path: string) -> result<list<directory-entry>, string>;
             }
 
             interface inference {
-                 variant role { system, user, assistant }
-                 record message { role: role, content: string }
+                 variant role { system, user, assistant, tool }
+                 record tool-call { id: string, name: string, arguments: string }
+                 record message { role: role, content: string, tool-calls: list<tool-call>, tool-call-id: option<string> }
+                 record tool-definition { name: string, description: string, parameters: string }
                  record usage { prompt-tokens: u32, completion-tokens: u32, total-tokens: u32 }
-                 record completion-response { content: string, usage: option<usage> }
-                 variant inference-error { provider-error(string), rate-limit, context-length-exceeded }
+                 record completion-response { content: string, usage: option<usage>, tool-calls: list<tool-call> }
+                 enum response-format { text, json }
+                 record request-options { temperature: option<f32>, top-p: option<f32>, max-tokens: option<u32>, stop: list<string>, seed: option<u64>, response-format: response-format }
+                 variant inference-error { provider-error(string), rate-limit, context-length-exceeded, budget-exceeded(string) }
+                 record stream-chunk { delta: string, usage: option<usage>, finish-reason: option<string> }
+                 resource completion-stream {
+                     next: func() -> result<option<stream-chunk>, inference-error>;
+                 }
                  chat: func(model: string, messages: list<message>) -> result<completion-response, inference-error>;
+                 chat-with-options: func(model: string, messages: list<message>, options: request-options) -> result<completion-response, inference-error>;
+                 chat-with-tools: func(model: string, messages: list<message>, tools: list<tool-definition>, options: request-options) -> result<completion-response, inference-error>;
+                 chat-stream: func(model: string, messages: list<message>) -> result<completion-stream, inference-error>;
+                 chat-stream-with-options: func(model: string, messages: list<message>, options: request-options) -> result<completion-stream, inference-error>;
             }
 
             interface logging {
@@ -53,19 +94,39 @@ mod wit_bindings {
                 log: func(level: level, context: string, message: string);
             }
 
+            interface planner {
+                record subtask {
+                    id: string,
+                    description: string,
+                    depends-on: list<string>,
+                    outputs: list<string>,
+                    capability: option<string>,
+                }
+                record plan { steps: list<subtask> }
+                decompose: func(objective: string) -> result<plan, string>;
+            }
+
             interface pub-sub {
                  use service-mesh.{payload};
                  subscribe: func(topic: string) -> result<tuple<>, string>;
                  publish: func(topic: string, data: payload) -> result<tuple<>, string>;
             }
 
+            interface supervisor-config {
+                workspace: func() -> option<string>;
+                verification-policy: func() -> result<string, string>;
+            }
+
             world brio-host {
                 import service-mesh;
                 import sql-state;
                 import session-fs;
+                import session-fs-ops;
                 import inference;
                 import logging;
+                import planner;
                 import pub-sub;
+                import supervisor-config;
             }
         "#,
     });
@@ -2,11 +2,12 @@
 //!
 //! This module provides the runtime execution environment for smart agents,
 //! handling task execution and event processing through WASM component instantiation.
+//! Each invocation runs in a fresh store bounded by the runner's [`ResourceLimits`].
 
+use crate::engine::limits::{GuestError, ResourceLimits, limited_store, plugin_label, run_bounded};
 use crate::host::BrioHostState;
-use anyhow::{Context, Result};
-use wasmtime::component::Component;
-use wasmtime::{Engine, Store};
+use wasmtime::Engine;
+use wasmtime::component::InstancePre;
 
 // WIT bindings module - generated code allows missing docs
 #[allow(missing_docs)]
@@ -40,6 +41,7 @@ mod wit_bindings {
             }
         "#,
         world: "smart-agent",
+        exports: { default: async },
         additional_derives: [serde::Deserialize, serde::Serialize],
     });
 }
@@ -55,66 +57,84 @@ pub use exports::brio::core::event_handler::Payload as EventPayload;
 pub struct AgentRunner {
     /// The WASM engine used for component instantiation.
     engine: Engine,
+    /// Budget applied to every invocation.
+    limits: ResourceLimits,
 }
 
 impl AgentRunner {
-    /// Creates a new agent runner with the given engine.
+    /// Creates a new agent runner with the given engine and no resource limits.
     #[must_use]
     pub fn new(engine: Engine) -> Self {
-        Self { engine }
+        Self {
+            engine,
+            limits: ResourceLimits::unlimited(),
+        }
     }
 
-    /// Instantiates an agent component and runs it.
+    /// Sets the resource budget applied to every invocation.
+    #[must_use]
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Instantiates a prepared agent component and runs it on behalf of the
+    /// task in `context`.
     ///
     /// # Errors
     ///
-    /// Returns an error if the component fails to load, instantiate, or execute.
+    /// Returns an error if the component does not export the agent interfaces,
+    /// fails to instantiate, exceeds its resource limits or reports a failure.
     pub async fn run_agent(
         &self,
-        component_path: &std::path::Path,
+        component: &InstancePre<BrioHostState>,
         host_state: BrioHostState,
         context: exports::brio::core::agent_runner::TaskContext,
-    ) -> Result<String> {
-        let component = Component::from_file(&self.engine, component_path)
-            .context("Failed to load component")?;
-
-        let linker = crate::engine::linker::create_linker(&self.engine)?;
-
-        let mut store = Store::new(&self.engine, host_state);
-
-        let agent = SmartAgent::instantiate_async(&mut store, &component, &linker).await?;
-
-        let result = agent
-            .brio_core_agent_runner()
-            .call_run(&mut store, &context)?;
-
-        result.map_err(|e| anyhow::anyhow!("Agent execution failed: {e}"))
+    ) -> Result<String, GuestError> {
+        let plugin_id = plugin_label(&host_state);
+        let host_state = host_state.with_task_context(context.task_id.clone());
+        let run = async {
+            let mut store = limited_store(&self.engine, host_state, &self.limits)?;
+            let agent = SmartAgentPre::new(component.clone())?
+                .instantiate_async(&mut store)
+                .await?;
+            agent
+                .brio_core_agent_runner()
+                .call_run(&mut store, &context)
+                .await
+        };
+
+        match run_bounded(&self.limits, &plugin_id, run).await? {
+            Ok(output) => Ok(output),
+            Err(e) => Err(GuestError::Failed(e)),
+        }
     }
 
     /// Runs an event handler for a given component.
     ///
     /// # Errors
     ///
-    /// Returns an error if the component fails to load, instantiate, or handle the event.
+    /// Returns an error if the component fails to instantiate, exceeds its
+    /// resource limits or traps while handling the event.
     pub async fn run_event_handler(
         &self,
-        component_path: &std::path::Path,
+        component: &InstancePre<BrioHostState>,
         host_state: BrioHostState,
         topic: String,
         payload: exports::brio::core::event_handler::Payload,
-    ) -> Result<()> {
-        let component = Component::from_file(&self.engine, component_path)
-            .context("Failed to load component")?;
-
-        let linker = crate::engine::linker::create_linker(&self.engine)?;
-        let mut store = Store::new(&self.engine, host_state);
-
-        let agent = SmartAgent::instantiate_async(&mut store, &component, &linker).await?;
-
-        agent
-            .brio_core_event_handler()
-            .call_handle_event(&mut store, &topic, &payload)?;
-
-        Ok(())
+    ) -> Result<(), GuestError> {
+        let plugin_id = plugin_label(&host_state);
+        let run = async {
+            let mut store = limited_store(&self.engine, host_state, &self.limits)?;
+            let agent = SmartAgentPre::new(component.clone())?
+                .instantiate_async(&mut store)
+                .await?;
+            agent
+                .brio_core_event_handler()
+                .call_handle_event(&mut store, &topic, &payload)
+                .await
+        };
+
+        run_bounded(&self.limits, &plugin_id, run).await
     }
 }
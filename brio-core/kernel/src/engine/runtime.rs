@@ -3,6 +3,7 @@
 //! This module provides a high-level wrapper around wasmtime for loading
 //! and executing WASM components with the Brio host state.
 
+use crate::engine::limits::{ResourceLimits, apply_limits};
 use crate::host::BrioHostState;
 use anyhow::{Context, Result};
 use wasmtime::component::{Component, Linker};
@@ -43,10 +44,16 @@ impl WasmEngine {
     ///
     /// # Returns
     ///
-    /// A new `Store` initialized with the engine and host state.
-    #[must_use]
-    pub fn prepare_store(&self, state: BrioHostState) -> Store<BrioHostState> {
-        Store::new(&self.engine, state)
+    /// A new `Store` initialized with the engine and host state and no
+    /// resource limits. Fuel is metered if the engine consumes fuel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the engine consumes fuel but has no async support.
+    pub fn prepare_store(&self, state: BrioHostState) -> Result<Store<BrioHostState>> {
+        let mut store = Store::new(&self.engine, state);
+        apply_limits(&mut store, &ResourceLimits::unlimited())?;
+        Ok(store)
     }
 
     /// Returns a reference to the linker.
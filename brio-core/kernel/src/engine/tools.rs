@@ -0,0 +1,183 @@
+//! Tool runner for executing WASM tool components.
+//!
+//! Tools implement the `standard-tool` or `session-aware-tool` world and
+//! export the `tool` interface. The runner asks a tool for its [`ToolInfo`]
+//! before every call and only hands it the caller's session id when the tool
+//! declares `requires-session`. Each invocation runs in a fresh store bounded
+//! by the runner's [`ResourceLimits`].
+
+use crate::engine::limits::{GuestError, ResourceLimits, limited_store, plugin_label, run_bounded};
+use crate::host::BrioHostState;
+use serde::{Deserialize, Serialize};
+use wasmtime::Engine;
+use wasmtime::component::InstancePre;
+use wasmtime::component::types::ComponentItem;
+
+/// Name of the interface exported by tool components.
+pub const TOOL_INTERFACE: &str = "brio:core/tool";
+
+// WIT bindings module - generated code allows missing docs
+#[allow(missing_docs)]
+mod wit_bindings {
+    wasmtime::component::bindgen!({
+        inline: r#"
+            package brio:core;
+
+            interface tool {
+                record tool-info {
+                    name: string,
+                    description: string,
+                    version: string,
+                    requires-session: bool,
+                }
+
+                info: func() -> tool-info;
+                execute: func(params: string, session-id: option<string>) -> result<string, string>;
+            }
+
+            world standard-tool {
+                export tool;
+            }
+        "#,
+        world: "standard-tool",
+        exports: { default: async },
+    });
+}
+
+use wit_bindings::StandardToolPre;
+pub use wit_bindings::exports::brio::core::tool::ToolInfo;
+
+/// Mesh payload for invoking a tool component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Tool-specific parameters, usually JSON.
+    pub params: String,
+    /// Session the caller is working in, passed on to session-aware tools.
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// Errors raised while invoking a tool.
+#[derive(Debug, thiserror::Error)]
+pub enum ToolError {
+    /// No tool is registered under the given id.
+    #[error("Tool not found: {0}")]
+    NotFound(String),
+    /// The tool requires a session but the caller did not provide one.
+    #[error("Tool '{tool}' requires a session but none was provided")]
+    SessionRequired {
+        /// Name the tool reported in its info.
+        tool: String,
+    },
+    /// The tool failed to run or reported a failure.
+    #[error(transparent)]
+    Guest(#[from] GuestError),
+}
+
+/// Returns true if a prepared component exports the tool interface.
+#[must_use]
+pub fn is_tool(component: &InstancePre<BrioHostState>) -> bool {
+    let engine = component.engine();
+    component
+        .component()
+        .component_type()
+        .exports(engine)
+        .any(|(name, item)| {
+            matches!(item, ComponentItem::ComponentInstance(_))
+                && name.split('@').next() == Some(TOOL_INTERFACE)
+        })
+}
+
+/// Runner for executing WASM tool components.
+pub struct ToolRunner {
+    /// The WASM engine used for component instantiation.
+    engine: Engine,
+    /// Budget applied to every invocation.
+    limits: ResourceLimits,
+}
+
+impl ToolRunner {
+    /// Creates a new tool runner with the given engine and no resource limits.
+    #[must_use]
+    pub fn new(engine: Engine) -> Self {
+        Self {
+            engine,
+            limits: ResourceLimits::unlimited(),
+        }
+    }
+
+    /// Sets the resource budget applied to every invocation.
+    #[must_use]
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Instantiates a prepared tool component and returns its info.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component does not export the tool interface,
+    /// fails to instantiate or exceeds its resource limits.
+    pub async fn info(
+        &self,
+        component: &InstancePre<BrioHostState>,
+        host_state: BrioHostState,
+    ) -> Result<ToolInfo, GuestError> {
+        let plugin_id = plugin_label(&host_state);
+        let run = async {
+            let mut store = limited_store(&self.engine, host_state, &self.limits)?;
+            let tool = StandardToolPre::new(component.clone())?
+                .instantiate_async(&mut store)
+                .await?;
+            tool.brio_core_tool().call_info(&mut store).await
+        };
+
+        run_bounded(&self.limits, &plugin_id, run).await
+    }
+
+    /// Instantiates a prepared tool component and executes it.
+    ///
+    /// The session id is only passed to tools whose info sets
+    /// `requires-session`; other tools always receive `none`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tool requires a session and `session_id` is
+    /// `None`, or if the component fails to instantiate, exceeds its resource
+    /// limits or reports a failure.
+    pub async fn execute(
+        &self,
+        component: &InstancePre<BrioHostState>,
+        host_state: BrioHostState,
+        params: &str,
+        session_id: Option<&str>,
+    ) -> Result<String, ToolError> {
+        let plugin_id = plugin_label(&host_state);
+        let run = async {
+            let mut store = limited_store(&self.engine, host_state, &self.limits)?;
+            let tool = StandardToolPre::new(component.clone())?
+                .instantiate_async(&mut store)
+                .await?;
+            let api = tool.brio_core_tool();
+
+            let info = api.call_info(&mut store).await?;
+            let session_id = match (info.requires_session, session_id) {
+                (true, None) => {
+                    return Ok(Err(ToolError::SessionRequired { tool: info.name }));
+                }
+                (true, session_id) => session_id,
+                (false, _) => None,
+            };
+
+            let output = api.call_execute(&mut store, params, session_id).await?;
+            Ok(Ok(output))
+        };
+
+        match run_bounded(&self.limits, &plugin_id, run).await? {
+            Ok(Ok(output)) => Ok(output),
+            Ok(Err(e)) => Err(GuestError::Failed(e).into()),
+            Err(e) => Err(e),
+        }
+    }
+}
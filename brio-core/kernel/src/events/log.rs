@@ -0,0 +1,574 @@
+//! `SQLite` log of published events and subscriber offsets.
+//!
+//! Events are appended to the `event_log` table, where `SQLite` assigns
+//! each one an increasing sequence number. Each subscriber has a row in
+//! `event_offsets` holding the last sequence it handled; offsets only move
+//! forward from the value they were read at, so a replay requested while an
+//! event is being handled is not overwritten.
+//!
+//! Events every subscriber has handled are pruned once they are older than
+//! the retention period, together with dead letters older than it. Events
+//! are deduplicated by ID only while they are in the log, so an event
+//! redelivered after it was pruned is appended and delivered again.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+
+use crate::mesh::Payload;
+use crate::mesh::events::MeshEvent;
+
+/// Prefix of the topics events are moved to after too many failed attempts.
+pub const DEAD_LETTER_PREFIX: &str = "dead-letter:";
+
+/// Shared filter of the dead letter queries; `NULL` parameters match any value.
+const DEAD_LETTER_FILTER: &str = "(?1 IS NULL OR subscriber = ?1) AND (?2 IS NULL OR topic = ?2)";
+
+/// Default number of dead letters listed at once.
+const DEFAULT_DEAD_LETTER_LIMIT: u32 = 100;
+
+/// An event in the log.
+#[derive(Debug, Clone)]
+pub struct LoggedEvent {
+    /// Position of the event in the log
+    pub sequence: u64,
+    /// The event
+    pub event: MeshEvent,
+    /// When the event was appended, as an ISO8601 timestamp
+    pub published_at: String,
+}
+
+/// Events a subscriber has yet to handle.
+#[derive(Debug, Clone)]
+pub struct PendingEvents {
+    /// Last sequence the subscriber handled
+    pub committed: u64,
+    /// Failed attempts at handling the first pending event
+    pub attempts: u32,
+    /// Pending events matching the subscriber's patterns, oldest first
+    pub events: Vec<LoggedEvent>,
+}
+
+/// Events published to one topic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicSummary {
+    /// The topic
+    pub topic: String,
+    /// Number of events in the log
+    pub events: u64,
+    /// Sequence of the oldest event
+    pub first_sequence: u64,
+    /// Sequence of the newest event
+    pub last_sequence: u64,
+    /// When the newest event was appended
+    pub last_published_at: String,
+}
+
+/// How far a subscriber got through the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubscriberOffset {
+    /// Plugin subscribed to the topics
+    pub subscriber: String,
+    /// Topic patterns the plugin subscribes to
+    pub patterns: Vec<String>,
+    /// Last sequence the subscriber handled
+    pub committed: u64,
+    /// Failed attempts at handling the next event
+    pub attempts: u32,
+    /// Matching events the subscriber has yet to handle
+    pub lag: u64,
+}
+
+/// An event a subscriber failed to handle too many times.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeadLetter {
+    /// ID of the dead letter
+    pub id: u64,
+    /// Sequence of the event in the log
+    pub sequence: u64,
+    /// Plugin that failed to handle the event
+    pub subscriber: String,
+    /// Topic of the event
+    pub topic: String,
+    /// Error of the last attempt
+    pub error: String,
+    /// Number of attempts made
+    pub attempts: u32,
+    /// When the event was given up on
+    pub failed_at: String,
+}
+
+/// Selects dead letters; unset fields match every dead letter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DeadLetterFilter {
+    /// Only dead letters of this subscriber
+    #[serde(default)]
+    pub subscriber: Option<String>,
+    /// Only dead letters of events published to this topic
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// Maximum number of dead letters to list, newest first (default: 100)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Converts a topic pattern, where `*` matches any run of characters, into
+/// an `SQLite` GLOB expression.
+#[must_use]
+pub fn topic_glob(pattern: &str) -> String {
+    let mut glob = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        match c {
+            '?' => glob.push_str("[?]"),
+            '[' => glob.push_str("[[]"),
+            c => glob.push(c),
+        }
+    }
+    glob
+}
+
+/// Event log backed by the kernel database.
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    pool: SqlitePool,
+}
+
+impl EventLog {
+    /// Creates an event log on a migrated kernel database
+    #[must_use]
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Appends an event to the log. Returns its sequence, or `None` if an
+    /// event with the same ID is already in the log. Pruned events no longer
+    /// count, so duplicates are only dropped within the retention period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn append(&self, event: &MeshEvent) -> Result<Option<u64>, sqlx::Error> {
+        let (kind, payload) = match &event.payload {
+            Payload::Json(s) => ("json", s.as_bytes()),
+            Payload::Binary(b) => ("binary", b.as_slice()),
+        };
+        let result = sqlx::query(
+            "INSERT INTO event_log (event_id, topic, payload_kind, payload, published_at) \
+             VALUES (?, ?, ?, ?, ?) ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(&event.id)
+        .bind(&event.topic)
+        .bind(kind)
+        .bind(payload)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        Ok(u64::try_from(result.last_insert_rowid()).ok())
+    }
+
+    /// Returns the sequence of the newest event, 0 if the log is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn head(&self) -> Result<u64, sqlx::Error> {
+        let row = sqlx::query("SELECT COALESCE(MAX(sequence), 0) AS head FROM event_log")
+            .fetch_one(&self.pool)
+            .await?;
+        unsigned(&row, "head")
+    }
+
+    /// Subscribes a plugin to a topic pattern. A plugin subscribing for the
+    /// first time starts after the newest event in the log. Returns false if
+    /// it was already subscribed to the pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn subscribe(&self, subscriber: &str, pattern: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let added = sqlx::query(
+            "INSERT INTO event_subscriptions (subscriber, pattern, topic_glob) VALUES (?, ?, ?) \
+             ON CONFLICT (subscriber, pattern) DO NOTHING",
+        )
+        .bind(subscriber)
+        .bind(pattern)
+        .bind(topic_glob(pattern))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        sqlx::query(
+            "INSERT INTO event_offsets (subscriber, committed, attempts, updated_at) \
+             SELECT ?, COALESCE(MAX(sequence), 0), 0, ? FROM event_log \
+             WHERE true ON CONFLICT (subscriber) DO NOTHING",
+        )
+        .bind(subscriber)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(added)
+    }
+
+    /// Returns every subscription as `(subscriber, pattern)` pairs, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn subscriptions(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT subscriber, pattern FROM event_subscriptions ORDER BY subscriber, pattern",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| Ok((row.try_get("subscriber")?, row.try_get("pattern")?)))
+            .collect()
+    }
+
+    /// Returns up to `limit` events the subscriber has yet to handle, or
+    /// `None` if it has no subscriptions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn pending(
+        &self,
+        subscriber: &str,
+        limit: u32,
+    ) -> Result<Option<PendingEvents>, sqlx::Error> {
+        let Some(offset) =
+            sqlx::query("SELECT committed, attempts FROM event_offsets WHERE subscriber = ?")
+                .bind(subscriber)
+                .fetch_optional(&self.pool)
+                .await?
+        else {
+            return Ok(None);
+        };
+        let committed = unsigned(&offset, "committed")?;
+        let attempts = attempts(&offset)?;
+
+        let rows = sqlx::query(
+            "SELECT e.sequence, e.event_id, e.topic, e.payload_kind, e.payload, e.published_at \
+             FROM event_log e WHERE e.sequence > ?1 AND EXISTS ( \
+                 SELECT 1 FROM event_subscriptions s \
+                 WHERE s.subscriber = ?2 AND e.topic GLOB s.topic_glob) \
+             ORDER BY e.sequence LIMIT ?3",
+        )
+        .bind(signed(committed))
+        .bind(subscriber)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        let events = rows.iter().map(logged_event).collect::<Result<_, _>>()?;
+        Ok(Some(PendingEvents {
+            committed,
+            attempts,
+            events,
+        }))
+    }
+
+    /// Moves a subscriber's offset from `from` to `to`, clearing its failed
+    /// attempts. Returns false if the offset is no longer at `from`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn commit(&self, subscriber: &str, from: u64, to: u64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE event_offsets SET committed = ?, attempts = 0, updated_at = ? \
+             WHERE subscriber = ? AND committed = ?",
+        )
+        .bind(signed(to))
+        .bind(Utc::now().to_rfc3339())
+        .bind(subscriber)
+        .bind(signed(from))
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Counts a failed attempt at handling the event after `committed`.
+    /// Returns the number of attempts so far, or `None` if the offset is no
+    /// longer at `committed`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn record_failure(
+        &self,
+        subscriber: &str,
+        committed: u64,
+    ) -> Result<Option<u32>, sqlx::Error> {
+        let row = sqlx::query(
+            "UPDATE event_offsets SET attempts = attempts + 1, updated_at = ? \
+             WHERE subscriber = ? AND committed = ? RETURNING attempts",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(subscriber)
+        .bind(signed(committed))
+        .fetch_optional(&self.pool)
+        .await?;
+        row.as_ref().map(attempts).transpose()
+    }
+
+    /// Gives up on an event the subscriber failed to handle: records a dead
+    /// letter, appends a copy of the event to the dead letter topic and
+    /// moves the offset past it. Returns false if the offset is no longer at
+    /// `committed`.
+    ///
+    /// Events of dead letter topics are not copied again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn dead_letter(
+        &self,
+        subscriber: &str,
+        committed: u64,
+        logged: &LoggedEvent,
+        error: &str,
+        attempts: u32,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        let moved = sqlx::query(
+            "UPDATE event_offsets SET committed = ?, attempts = 0, updated_at = ? \
+             WHERE subscriber = ? AND committed = ?",
+        )
+        .bind(signed(logged.sequence))
+        .bind(&now)
+        .bind(subscriber)
+        .bind(signed(committed))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !moved {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "INSERT INTO event_dead_letters \
+             (event_sequence, subscriber, topic, error, attempts, failed_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(signed(logged.sequence))
+        .bind(subscriber)
+        .bind(&logged.event.topic)
+        .bind(error)
+        .bind(attempts)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        if !logged.event.topic.starts_with(DEAD_LETTER_PREFIX) {
+            sqlx::query(
+                "INSERT INTO event_log (event_id, topic, payload_kind, payload, published_at) \
+                 SELECT ?, ?, payload_kind, payload, ? FROM event_log WHERE sequence = ?",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(format!("{DEAD_LETTER_PREFIX}{}", logged.event.topic))
+            .bind(&now)
+            .bind(signed(logged.sequence))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Rewinds or fast-forwards a subscriber so the next event it handles is
+    /// the first matching event at or after `from_sequence`. Returns false
+    /// if the subscriber is unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn replay(&self, subscriber: &str, from_sequence: u64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE event_offsets SET committed = ?, attempts = 0, updated_at = ? \
+             WHERE subscriber = ?",
+        )
+        .bind(signed(from_sequence.saturating_sub(1)))
+        .bind(Utc::now().to_rfc3339())
+        .bind(subscriber)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes dead letters recorded before `before`, then the events
+    /// published before it that every subscriber has handled and no dead
+    /// letter refers to. Returns the number of events deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let before = before.to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM event_dead_letters WHERE failed_at < ?")
+            .bind(&before)
+            .execute(&mut *tx)
+            .await?;
+        let pruned = sqlx::query(
+            "DELETE FROM event_log WHERE published_at < ?1 AND sequence <= COALESCE( \
+                 (SELECT MIN(committed) FROM event_offsets), \
+                 (SELECT MAX(sequence) FROM event_log)) \
+             AND sequence NOT IN (SELECT event_sequence FROM event_dead_letters)",
+        )
+        .bind(&before)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(pruned)
+    }
+
+    /// Returns the topics in the log with their event counts, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn topics(&self) -> Result<Vec<TopicSummary>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT topic, COUNT(*) AS events, MIN(sequence) AS first_sequence, \
+             MAX(sequence) AS last_sequence, MAX(published_at) AS last_published_at \
+             FROM event_log GROUP BY topic ORDER BY topic",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(TopicSummary {
+                    topic: row.try_get("topic")?,
+                    events: unsigned(row, "events")?,
+                    first_sequence: unsigned(row, "first_sequence")?,
+                    last_sequence: unsigned(row, "last_sequence")?,
+                    last_published_at: row.try_get("last_published_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the offset and lag of every subscriber, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn offsets(&self) -> Result<Vec<SubscriberOffset>, sqlx::Error> {
+        let subscriptions = self.subscriptions().await?;
+        let rows = sqlx::query(
+            "SELECT o.subscriber, o.committed, o.attempts, ( \
+                 SELECT COUNT(*) FROM event_log e WHERE e.sequence > o.committed AND EXISTS ( \
+                     SELECT 1 FROM event_subscriptions s \
+                     WHERE s.subscriber = o.subscriber AND e.topic GLOB s.topic_glob) \
+             ) AS lag FROM event_offsets o ORDER BY o.subscriber",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                let subscriber: String = row.try_get("subscriber")?;
+                let patterns = subscriptions
+                    .iter()
+                    .filter(|(s, _)| *s == subscriber)
+                    .map(|(_, pattern)| pattern.clone())
+                    .collect();
+                Ok(SubscriberOffset {
+                    subscriber,
+                    patterns,
+                    committed: unsigned(row, "committed")?,
+                    attempts: attempts(row)?,
+                    lag: unsigned(row, "lag")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the dead letters selected by `filter`, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn dead_letters(
+        &self,
+        filter: &DeadLetterFilter,
+    ) -> Result<Vec<DeadLetter>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT id, event_sequence, subscriber, topic, error, attempts, failed_at \
+             FROM event_dead_letters WHERE {DEAD_LETTER_FILTER} ORDER BY id DESC LIMIT ?3"
+        ))
+        .bind(&filter.subscriber)
+        .bind(&filter.topic)
+        .bind(filter.limit.unwrap_or(DEFAULT_DEAD_LETTER_LIMIT))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(DeadLetter {
+                    id: unsigned(row, "id")?,
+                    sequence: unsigned(row, "event_sequence")?,
+                    subscriber: row.try_get("subscriber")?,
+                    topic: row.try_get("topic")?,
+                    error: row.try_get("error")?,
+                    attempts: attempts(row)?,
+                    failed_at: row.try_get("failed_at")?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reads an event row.
+fn logged_event(row: &SqliteRow) -> Result<LoggedEvent, sqlx::Error> {
+    let kind: String = row.try_get("payload_kind")?;
+    let bytes: Vec<u8> = row.try_get("payload")?;
+    let payload = if kind == "json" {
+        let json = String::from_utf8(bytes).map_err(|e| sqlx::Error::ColumnDecode {
+            index: "payload".to_string(),
+            source: Box::new(e),
+        })?;
+        Payload::Json(Box::new(json))
+    } else {
+        Payload::Binary(Box::new(bytes))
+    };
+    Ok(LoggedEvent {
+        sequence: unsigned(row, "sequence")?,
+        event: MeshEvent {
+            id: row.try_get("event_id")?,
+            topic: row.try_get("topic")?,
+            payload,
+        },
+        published_at: row.try_get("published_at")?,
+    })
+}
+
+/// Reads a non-negative integer column.
+fn unsigned(row: &SqliteRow, column: &str) -> Result<u64, sqlx::Error> {
+    let value: i64 = row.try_get(column)?;
+    u64::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
+
+/// Reads the `attempts` column.
+fn attempts(row: &SqliteRow) -> Result<u32, sqlx::Error> {
+    let value: i64 = row.try_get("attempts")?;
+    u32::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: "attempts".to_string(),
+        source: Box::new(e),
+    })
+}
+
+/// Converts a sequence for binding; sequences never exceed `i64::MAX`.
+fn signed(sequence: u64) -> i64 {
+    i64::try_from(sequence).unwrap_or(i64::MAX)
+}
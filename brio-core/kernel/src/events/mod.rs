@@ -0,0 +1,313 @@
+//! Durable pub/sub event delivery.
+//!
+//! Published events are appended to an [`EventLog`] in the kernel database
+//! before `publish` returns. Every subscribing plugin consumes the log in
+//! sequence order from its own committed offset, so events are delivered at
+//! least once and survive handler traps and kernel restarts. An event whose
+//! handler keeps failing is retried with exponential backoff and, after
+//! [`RetryPolicy::max_attempts`] attempts, moved to the dead letter topic.
+//! Handled events are pruned from the log after the configured retention.
+
+pub mod log;
+
+pub use log::{
+    DEAD_LETTER_PREFIX, DeadLetter, DeadLetterFilter, EventLog, LoggedEvent, PendingEvents,
+    SubscriberOffset, TopicSummary,
+};
+
+use parking_lot::Mutex;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::infrastructure::config::EventSettings;
+
+/// How failed deliveries are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts at handling an event before it is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Longest delay between retries
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates the policy from configuration
+    #[must_use]
+    pub fn from_settings(settings: &EventSettings) -> Self {
+        Self {
+            max_attempts: settings.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(settings.initial_backoff_ms),
+            max_backoff: Duration::from_millis(settings.max_backoff_ms),
+        }
+    }
+
+    /// Returns the delay after the given number of failed attempts
+    #[must_use]
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let doublings = attempts.saturating_sub(1).min(31);
+        self.initial_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_settings(&EventSettings::default())
+    }
+}
+
+/// The event log together with the state shared by the delivery workers.
+#[derive(Debug)]
+pub struct EventDelivery {
+    log: EventLog,
+    policy: RetryPolicy,
+    appended: watch::Sender<u64>,
+    workers: Mutex<HashSet<String>>,
+}
+
+impl EventDelivery {
+    /// Creates the delivery of events logged in the kernel database
+    #[must_use]
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            log: EventLog::new(pool),
+            policy: RetryPolicy::default(),
+            appended: watch::channel(0).0,
+            workers: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Replaces the retry policy
+    #[must_use]
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the event log
+    #[must_use]
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+
+    /// Returns the retry policy
+    #[must_use]
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Wakes the workers after events were appended or offsets moved
+    pub fn notify(&self) {
+        self.appended
+            .send_modify(|count| *count = count.wrapping_add(1));
+    }
+
+    /// Returns a receiver that changes whenever the workers are woken
+    #[must_use]
+    pub fn watch(&self) -> watch::Receiver<u64> {
+        self.appended.subscribe()
+    }
+
+    /// Records that a worker runs for the subscriber. Returns false if one
+    /// already does.
+    #[must_use]
+    pub fn claim_worker(&self, subscriber: &str) -> bool {
+        self.workers.lock().insert(subscriber.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::Payload;
+    use crate::mesh::events::MeshEvent;
+    use crate::store::{Migrator, open_pool};
+    use chrono::Utc;
+
+    async fn event_log() -> EventLog {
+        let pool = open_pool("sqlite::memory:").await.unwrap();
+        Migrator::new().run(&pool).await.unwrap();
+        EventLog::new(pool)
+    }
+
+    fn event(topic: &str) -> MeshEvent {
+        MeshEvent::new(
+            topic,
+            Payload::Json(Box::new(format!("{{\"topic\":\"{topic}\"}}"))),
+        )
+    }
+
+    fn sequences(pending: &PendingEvents) -> Vec<u64> {
+        pending.events.iter().map(|e| e.sequence).collect()
+    }
+
+    #[tokio::test]
+    async fn test_events_are_sequenced_and_deduplicated() {
+        let log = event_log().await;
+        let first = event("branch:created");
+
+        assert_eq!(log.append(&first).await.unwrap(), Some(1));
+        assert_eq!(log.append(&event("branch:merged")).await.unwrap(), Some(2));
+        assert_eq!(log.append(&first).await.unwrap(), None);
+        assert_eq!(log.head().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_consume_matching_events_from_their_offset() {
+        let log = event_log().await;
+        log.append(&event("branch:created")).await.unwrap();
+        assert!(log.subscribe("foreman", "branch:*").await.unwrap());
+        assert!(!log.subscribe("foreman", "branch:*").await.unwrap());
+        assert!(log.pending("reviewer", 10).await.unwrap().is_none());
+
+        log.append(&event("branch:merged")).await.unwrap();
+        log.append(&event("proposal:milestones")).await.unwrap();
+        log.append(&event("branch:deleted")).await.unwrap();
+
+        // Events published before the subscription are skipped
+        let pending = log.pending("foreman", 10).await.unwrap().unwrap();
+        assert_eq!(pending.committed, 1);
+        assert_eq!(sequences(&pending), [2, 4]);
+        assert_eq!(pending.events[0].event.topic, "branch:merged");
+        assert!(
+            matches!(&pending.events[0].event.payload, Payload::Json(s) if s.contains("merged"))
+        );
+
+        assert!(log.commit("foreman", 1, 2).await.unwrap());
+        assert!(!log.commit("foreman", 1, 4).await.unwrap());
+        let offsets = log.offsets().await.unwrap();
+        assert_eq!(
+            offsets,
+            [SubscriberOffset {
+                subscriber: "foreman".to_string(),
+                patterns: vec!["branch:*".to_string()],
+                committed: 2,
+                attempts: 0,
+                lag: 1,
+            }]
+        );
+
+        assert!(log.replay("foreman", 1).await.unwrap());
+        assert!(!log.replay("reviewer", 1).await.unwrap());
+        let pending = log.pending("foreman", 10).await.unwrap().unwrap();
+        assert_eq!(sequences(&pending), [1, 2, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_events_are_dead_lettered() {
+        let log = event_log().await;
+        log.subscribe("foreman", "*").await.unwrap();
+        log.append(&event("branch:merged")).await.unwrap();
+
+        assert_eq!(log.record_failure("foreman", 0).await.unwrap(), Some(1));
+        assert_eq!(log.record_failure("foreman", 0).await.unwrap(), Some(2));
+        assert_eq!(log.record_failure("foreman", 5).await.unwrap(), None);
+
+        let pending = log.pending("foreman", 10).await.unwrap().unwrap();
+        assert_eq!(pending.attempts, 2);
+        let failed = pending.events[0].clone();
+        assert!(
+            log.dead_letter("foreman", 0, &failed, "trap", 2)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !log.dead_letter("foreman", 0, &failed, "trap", 2)
+                .await
+                .unwrap()
+        );
+
+        let dead = log
+            .dead_letters(&DeadLetterFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].sequence, 1);
+        assert_eq!(dead[0].topic, "branch:merged");
+        assert_eq!(dead[0].error, "trap");
+
+        // The copy on the dead letter topic is itself never dead-lettered again
+        let pending = log.pending("foreman", 10).await.unwrap().unwrap();
+        assert_eq!(pending.attempts, 0);
+        let copy = pending.events[0].clone();
+        assert_eq!(copy.event.topic, "dead-letter:branch:merged");
+        assert!(
+            log.dead_letter("foreman", 1, &copy, "trap", 2)
+                .await
+                .unwrap()
+        );
+        let topics: Vec<String> = log
+            .topics()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.topic)
+            .collect();
+        assert_eq!(topics, ["branch:merged", "dead-letter:branch:merged"]);
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_events_a_subscriber_still_needs() {
+        let log = event_log().await;
+        log.subscribe("foreman", "branch:*").await.unwrap();
+        for topic in ["branch:created", "branch:merged", "branch:deleted"] {
+            log.append(&event(topic)).await.unwrap();
+        }
+        log.commit("foreman", 0, 2).await.unwrap();
+        let pending = log.pending("foreman", 10).await.unwrap().unwrap();
+        let failed = pending.events[0].clone();
+        log.dead_letter("foreman", 2, &failed, "trap", 5)
+            .await
+            .unwrap();
+
+        // Nothing is old enough yet
+        let past = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(log.prune(past).await.unwrap(), 0);
+
+        // Handled events and old dead letters go; the copy on the dead
+        // letter topic is after the subscriber's offset and stays
+        let future = Utc::now() + chrono::Duration::hours(1);
+        assert_eq!(log.prune(future).await.unwrap(), 3);
+        let topics: Vec<String> = log
+            .topics()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.topic)
+            .collect();
+        assert_eq!(topics, ["dead-letter:branch:deleted"]);
+        let dead = log
+            .dead_letters(&DeadLetterFilter::default())
+            .await
+            .unwrap();
+        assert!(dead.is_empty());
+
+        // Sequences are never reused after a prune
+        assert_eq!(log.append(&event("branch:created")).await.unwrap(), Some(5));
+    }
+
+    #[test]
+    fn test_topic_globs_only_expand_stars() {
+        assert_eq!(log::topic_glob("branch:*"), "branch:*");
+        assert_eq!(log::topic_glob("what?[x]"), "what[?][[]x]");
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(3));
+        assert_eq!(policy.backoff(40), Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,392 @@
+//! Branch execution for the Brio kernel.
+//!
+//! This module runs the agents assigned to a branch inside the branch's VFS
+//! session and reports progress as `BranchEvent`s over the WebSocket.
+//!
+//! The session of a failed or aborted branch is rolled back as soon as its
+//! agents finish. A completed branch keeps its session until its merge
+//! request is approved, which commits the changes to the base directory, or
+//! until the merge is rejected or the branch deleted.
+//!
+//! Aborting a running branch cancels its run before the branch is marked
+//! aborted: agent calls still waiting for a reply are dropped and the session
+//! is rolled back, so agents that are still busy can no longer write to it.
+
+use chrono::Utc;
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+use crate::branch_manager::{
+    AgentAssignment, AgentResult, Branch, BranchError, BranchId, BranchResult, BranchStatus,
+    ExecutionStrategy,
+};
+use crate::mesh::Payload;
+use crate::vfs::SessionError;
+use crate::vfs::diff::{FileChange, compute_diff};
+use crate::ws::types::{ChangeType, EventMetadata, ExecutionStrategy as WsExecutionStrategy};
+use crate::ws::{BranchEvent, BranchResultSummary, FileChangeSummary, WsMessage};
+
+use super::mesh::MeshHandler;
+use super::state::BrioHostState;
+
+/// Mesh method invoked on each agent assigned to a branch.
+pub const BRANCH_AGENT_METHOD: &str = "run";
+
+/// Background runs of the branches that are executing, keyed by branch ID.
+#[derive(Debug, Default)]
+pub(crate) struct BranchRuns(Mutex<HashMap<BranchId, JoinHandle<()>>>);
+
+impl BranchRuns {
+    /// Removes and returns the run of a branch, if it is still tracked.
+    fn take(&self, id: &BranchId) -> Option<JoinHandle<()>> {
+        self.0.lock().remove(id)
+    }
+
+    /// Forgets the run of a branch once it finished, unless a newer run of
+    /// the branch has replaced it.
+    fn finished(&self, id: &BranchId, task: tokio::task::Id) {
+        let mut runs = self.0.lock();
+        if runs.get(id).is_some_and(|run| run.id() == task) {
+            runs.remove(id);
+        }
+    }
+}
+
+/// Trait for branch execution functionality.
+pub trait BranchExecutor: Send + Sync {
+    /// Starts executing a branch.
+    ///
+    /// Begins a VFS session on the branch's base directory, marks the branch
+    /// as running and dispatches its agents in the background. Agents listed in
+    /// `agent_filter` are run (all agents if empty); `task_override` replaces the
+    /// task description of every agent. Progress is broadcast as `BranchEvent`s
+    /// and the outcome is recorded with the branch manager.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The branch is not found or is not in a startable state
+    /// - The branch has no base directory or no agents to run
+    /// - The VFS session cannot be created
+    fn execute_branch(
+        &self,
+        id: &BranchId,
+        agent_filter: &[String],
+        task_override: Option<String>,
+    ) -> impl std::future::Future<Output = Result<Branch, BranchError>> + Send;
+}
+
+impl BranchExecutor for BrioHostState {
+    async fn execute_branch(
+        &self,
+        id: &BranchId,
+        agent_filter: &[String],
+        task_override: Option<String>,
+    ) -> Result<Branch, BranchError> {
+        let manager = self.branch_manager();
+        let branch = manager.get_branch(id).await?;
+
+        let base_path = branch.config.base_path.clone().ok_or_else(|| {
+            BranchError::ExecutionFailed(format!("Branch {id} has no base directory"))
+        })?;
+
+        let mut agents: Vec<AgentAssignment> = branch
+            .config
+            .agents
+            .iter()
+            .filter(|a| agent_filter.is_empty() || agent_filter.contains(&a.agent_id))
+            .cloned()
+            .collect();
+        if agents.is_empty() {
+            return Err(BranchError::ExecutionFailed(format!(
+                "Branch {id} has no agents to execute"
+            )));
+        }
+        // Higher priority agents run first
+        agents.sort_by_key(|a| std::cmp::Reverse(a.priority));
+        if let Some(task) = &task_override {
+            for agent in &mut agents {
+                agent.task_override = Some(task.clone());
+            }
+        }
+
+        let session_id = self
+            .begin_session(&base_path)
+            .map_err(|e| BranchError::ExecutionFailed(e.to_string()))?;
+
+        let branch = match manager.start_execution(id, session_id.clone()).await {
+            Ok(branch) => branch,
+            Err(e) => {
+                if let Err(rollback) = self.rollback_session(&session_id) {
+                    warn!(session_id, "Failed to roll back branch session: {rollback}");
+                }
+                return Err(e);
+            }
+        };
+
+        let host = self.clone();
+        let running = branch.clone();
+        // Hold the lock while spawning so the run cannot finish and forget
+        // itself before it is tracked.
+        let mut runs = self.inner.branch_runs.0.lock();
+        let run = tokio::spawn(async move {
+            let id = running.id.clone();
+            host.run_branch(running, agents, base_path).await;
+            host.inner.branch_runs.finished(&id, tokio::task::id());
+        });
+        runs.insert(branch.id.clone(), run);
+
+        Ok(branch)
+    }
+}
+
+impl BrioHostState {
+    /// Aborts a pending or running branch.
+    ///
+    /// The run of a running branch is cancelled and awaited first, so none of
+    /// its agents is still dispatched when the branch is marked aborted, and
+    /// its session is then rolled back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the branch is not found or cannot be aborted.
+    pub async fn abort_branch(&self, id: &BranchId) -> Result<Branch, BranchError> {
+        let manager = self.branch_manager();
+        if manager.get_branch(id).await?.status == BranchStatus::Running {
+            if let Some(run) = self.inner.branch_runs.take(id) {
+                run.abort();
+                // The run either stopped or had already finished.
+                let _ = run.await;
+            }
+        }
+
+        let branch = manager.abort_branch(id).await?;
+        self.discard_branch_session(&branch);
+        Ok(branch)
+    }
+
+    /// Runs the agents of a started branch and records the result.
+    async fn run_branch(&self, branch: Branch, agents: Vec<AgentAssignment>, base_path: String) {
+        let started = Instant::now();
+        let ws_branch_id = crate::ws::types::BranchId::new(branch.id.to_string());
+        let total_agents = agents.len();
+
+        self.emit_branch_event(BranchEvent::ExecutionStarted {
+            branch_id: ws_branch_id.clone(),
+            agents: agents.iter().map(|a| a.agent_id.clone()).collect(),
+            execution_strategy: match branch.config.execution_strategy {
+                ExecutionStrategy::Sequential => WsExecutionStrategy::Sequential,
+                ExecutionStrategy::Parallel { .. } => WsExecutionStrategy::Parallel,
+            },
+            metadata: EventMetadata::new(),
+        });
+
+        let mut agent_results = Vec::with_capacity(total_agents);
+        let record = |result: AgentResult, results: &mut Vec<AgentResult>| {
+            if result.success {
+                self.emit_branch_event(BranchEvent::AgentCompleted {
+                    branch_id: ws_branch_id.clone(),
+                    agent_id: result.agent_id.clone(),
+                    result_summary: summarize(&result.output),
+                    metadata: EventMetadata::new(),
+                });
+            }
+            results.push(result);
+            self.emit_branch_event(BranchEvent::ExecutionProgress {
+                branch_id: ws_branch_id.clone(),
+                total_agents,
+                completed_agents: results.len(),
+                current_agent: None,
+                metadata: EventMetadata::new(),
+            });
+        };
+
+        match branch.config.execution_strategy {
+            ExecutionStrategy::Sequential => {
+                for agent in &agents {
+                    let result = self.run_branch_agent(&branch, agent).await;
+                    let failed = !result.success;
+                    record(result, &mut agent_results);
+                    // Later agents build on earlier ones, so stop at the first failure
+                    if failed {
+                        break;
+                    }
+                }
+            }
+            ExecutionStrategy::Parallel { max_concurrent } => {
+                let limit = max_concurrent.unwrap_or(total_agents).max(1);
+                let runs: Vec<_> = agents
+                    .iter()
+                    .map(|agent| self.run_branch_agent(&branch, agent))
+                    .collect();
+                let mut runs = futures_util::stream::iter(runs).buffer_unordered(limit);
+                while let Some(result) = runs.next().await {
+                    record(result, &mut agent_results);
+                }
+            }
+        }
+
+        let file_changes = self.session_changes(&branch.session_id, &base_path);
+        let result = BranchResult {
+            branch_id: branch.id.clone(),
+            file_changes,
+            agent_results,
+            created_at: Utc::now(),
+        };
+
+        let event = if let Some(failed) = result.agent_results.iter().find(|r| !r.success) {
+            BranchEvent::ExecutionFailed {
+                branch_id: ws_branch_id,
+                error: failed.output.clone(),
+                failed_agent: Some(failed.agent_id.clone()),
+                metadata: EventMetadata::new(),
+            }
+        } else {
+            BranchEvent::ExecutionCompleted {
+                branch_id: ws_branch_id,
+                file_changes_count: result.file_changes.len(),
+                result: BranchResultSummary::new(
+                    result.file_changes.clone(),
+                    result.agent_results.len(),
+                    started.elapsed().as_secs(),
+                ),
+                metadata: EventMetadata::new(),
+            }
+        };
+
+        match self.branch_manager().finish_execution(result).await {
+            Ok(finished) => {
+                if finished.status != BranchStatus::Completed {
+                    self.discard_branch_session(&branch);
+                }
+            }
+            Err(e) => {
+                error!(branch_id = %branch.id, "Failed to record branch result: {e}");
+                self.discard_branch_session(&branch);
+                return;
+            }
+        }
+        self.emit_branch_event(event);
+    }
+
+    /// Commits the changes a completed branch made in its session to the
+    /// base directory.
+    ///
+    /// A branch whose session no longer exists, e.g. after a kernel restart,
+    /// has nothing left to commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MergeConflict` if the base directory changed since the
+    /// branch started, or `ExecutionFailed` if the changes cannot be applied.
+    pub fn commit_branch_session(&self, branch: &Branch) -> Result<(), BranchError> {
+        match self.commit_session(&branch.session_id) {
+            Ok(()) => Ok(()),
+            Err(SessionError::SessionNotFound(_)) => {
+                warn!(branch_id = %branch.id, "Branch session no longer exists, nothing to commit");
+                Ok(())
+            }
+            Err(SessionError::Conflict { path, .. }) => Err(BranchError::MergeConflict {
+                file_path: path.to_string_lossy().into_owned(),
+                description: "base directory was modified since the branch started".to_string(),
+            }),
+            Err(e) => Err(BranchError::ExecutionFailed(e.to_string())),
+        }
+    }
+
+    /// Rolls back the session of a branch if it still exists.
+    pub fn discard_branch_session(&self, branch: &Branch) {
+        match self.rollback_session(&branch.session_id) {
+            Ok(()) | Err(SessionError::SessionNotFound(_)) => {}
+            Err(e) => {
+                warn!(branch_id = %branch.id, "Failed to roll back branch session: {e}");
+            }
+        }
+    }
+
+    /// Dispatches a single agent assignment through the mesh.
+    async fn run_branch_agent(&self, branch: &Branch, agent: &AgentAssignment) -> AgentResult {
+        let context = serde_json::json!({
+            "task_id": format!("{}/{}", branch.id, agent.agent_id),
+            "description": agent.task_override.clone().unwrap_or_else(|| branch.name.clone()),
+            "input_files": Vec::<String>::new(),
+            "branch_id": branch.id.to_string(),
+            "session_id": branch.session_id,
+        });
+
+        let outcome = self
+            .mesh_call(
+                &agent.agent_id,
+                BRANCH_AGENT_METHOD,
+                Payload::Json(Box::new(context.to_string())),
+            )
+            .await;
+
+        match outcome {
+            Ok(Payload::Json(output)) => AgentResult {
+                agent_id: agent.agent_id.clone(),
+                success: true,
+                output: *output,
+            },
+            Ok(Payload::Binary(bytes)) => AgentResult {
+                agent_id: agent.agent_id.clone(),
+                success: true,
+                output: String::from_utf8_lossy(&bytes).into_owned(),
+            },
+            Err(e) => AgentResult {
+                agent_id: agent.agent_id.clone(),
+                success: false,
+                output: e.to_string(),
+            },
+        }
+    }
+
+    /// Lists files changed in a session relative to its base directory.
+    fn session_changes(&self, session_id: &str, base_path: &str) -> Vec<FileChangeSummary> {
+        let Some(session_path) = self.session_manager().lock().session_path(session_id) else {
+            return Vec::new();
+        };
+
+        match compute_diff(&session_path, Path::new(base_path)) {
+            Ok(changes) => changes
+                .into_iter()
+                .map(|change| {
+                    let (path, change_type) = match change {
+                        FileChange::Added(p) => (p, ChangeType::Added),
+                        FileChange::Modified(p) => (p, ChangeType::Modified),
+                        FileChange::Deleted(p) => (p, ChangeType::Deleted),
+                    };
+                    FileChangeSummary::new(path.to_string_lossy().into_owned(), change_type, None)
+                })
+                .collect(),
+            Err(e) => {
+                warn!(session_id, "Failed to diff branch session: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Broadcasts a branch event to WebSocket clients.
+    fn emit_branch_event(&self, event: BranchEvent) {
+        if let Err(e) = self
+            .broadcaster()
+            .broadcast_message(WsMessage::BranchEvent(event))
+        {
+            warn!("Failed to broadcast branch event: {e}");
+        }
+    }
+}
+
+/// Shortens agent output for event summaries.
+fn summarize(output: &str) -> String {
+    const MAX_SUMMARY_CHARS: usize = 200;
+    match output.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((idx, _)) => format!("{}...", &output[..idx]),
+        None => output.to_string(),
+    }
+}
@@ -0,0 +1,263 @@
+//! Pub/sub event delivery for the Brio kernel.
+//!
+//! Published events are appended to the durable event log and forwarded to
+//! other mesh nodes with matching subscriptions. One worker per subscribing
+//! plugin runs the plugin's event handler for each matching event in log
+//! order, committing its offset after every success. Failed events are
+//! retried with backoff and dead-lettered once they run out of attempts.
+//! Events every subscriber has handled are pruned once they outlive the
+//! retention period.
+
+use anyhow::{Result, anyhow};
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::engine::runner::{AgentRunner, EventPayload};
+use crate::events::{LoggedEvent, PendingEvents};
+use crate::mesh::Payload;
+use crate::mesh::events::MeshEvent;
+
+use super::state::BrioHostState;
+
+/// Number of pending events a worker reads from the log at once.
+const BATCH_SIZE: u32 = 64;
+
+/// Interval at which idle workers check the log for events appended by
+/// another process sharing the database.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest interval between two prunes of the event log.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+impl BrioHostState {
+    /// Subscribes a plugin to a topic pattern and starts delivering
+    /// matching events to it.
+    ///
+    /// The subscription is stored in the event log, so delivery resumes
+    /// from the plugin's committed offset after a restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription cannot be stored.
+    pub async fn subscribe_plugin(&self, plugin_id: &str, pattern: &str) -> Result<()> {
+        self.event_delivery()
+            .log()
+            .subscribe(plugin_id, pattern)
+            .await?;
+        self.event_bus()
+            .subscribe(pattern.to_string(), plugin_id.to_string());
+        self.spawn_event_worker(plugin_id);
+        Ok(())
+    }
+
+    /// Publishes an event to its subscribers on this node and on every
+    /// remote node subscribed to its topic.
+    ///
+    /// The event is in the event log when this returns; local handlers run
+    /// in the background and remote nodes are sent the event through their
+    /// outbox.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be appended to the log.
+    pub async fn publish_event(
+        &self,
+        topic: impl Into<String>,
+        payload: Payload,
+    ) -> Result<MeshEvent> {
+        let event = MeshEvent::new(topic, payload);
+        self.record_event(&event).await?;
+
+        if let Some(router) = self.remote_router() {
+            let nodes = router.publish(&event);
+            if nodes > 0 {
+                debug!(topic = %event.topic, event = %event.id, nodes, "Forwarding event to remote subscribers");
+            }
+        }
+        Ok(event)
+    }
+
+    /// Appends an event to the log and wakes the delivery workers. Returns
+    /// false if the event was already in the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn record_event(&self, event: &MeshEvent) -> Result<bool, sqlx::Error> {
+        let delivery = self.event_delivery();
+        let appended = delivery.log().append(event).await?.is_some();
+        if appended {
+            delivery.notify();
+        }
+        Ok(appended)
+    }
+
+    /// Restores the subscriptions stored in the event log and starts a
+    /// delivery worker for every subscriber. Returns the number of
+    /// subscribers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscriptions cannot be read.
+    pub async fn resume_event_delivery(&self) -> Result<usize> {
+        let subscriptions = self.event_delivery().log().subscriptions().await?;
+        let mut subscribers = BTreeSet::new();
+        for (subscriber, pattern) in subscriptions {
+            self.event_bus().subscribe(pattern, subscriber.clone());
+            subscribers.insert(subscriber);
+        }
+        for subscriber in &subscribers {
+            self.spawn_event_worker(subscriber);
+        }
+        Ok(subscribers.len())
+    }
+
+    /// Starts a task pruning events and dead letters older than `retention`
+    /// from the event log.
+    pub fn spawn_event_pruning(&self, retention: Duration) -> tokio::task::JoinHandle<()> {
+        let state = self.clone();
+        let age = chrono::Duration::from_std(retention).unwrap_or(chrono::Duration::MAX);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(retention.min(PRUNE_INTERVAL));
+            loop {
+                interval.tick().await;
+                let Some(before) = chrono::Utc::now().checked_sub_signed(age) else {
+                    continue;
+                };
+                match state.event_delivery().log().prune(before).await {
+                    Ok(0) => {}
+                    Ok(pruned) => info!(pruned, "Pruned handled events from the event log"),
+                    Err(e) => error!("Failed to prune the event log: {e}"),
+                }
+            }
+        })
+    }
+
+    /// Starts the delivery worker of a subscriber unless it already runs.
+    fn spawn_event_worker(&self, subscriber: &str) {
+        if !self.event_delivery().claim_worker(subscriber) {
+            return;
+        }
+        tokio::spawn(self.clone().consume_events(subscriber.to_string()));
+    }
+
+    /// Delivers the subscriber's pending events, waiting for new ones when
+    /// it has caught up.
+    async fn consume_events(self, subscriber: String) {
+        let mut woken = self.event_delivery().watch();
+        loop {
+            match self
+                .event_delivery()
+                .log()
+                .pending(&subscriber, BATCH_SIZE)
+                .await
+            {
+                Ok(Some(pending)) if !pending.events.is_empty() => {
+                    self.handle_pending(&subscriber, pending).await;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => error!(subscriber = %subscriber, "Failed to read event log: {e}"),
+            }
+            let _ = tokio::time::timeout(POLL_INTERVAL, woken.changed()).await;
+        }
+    }
+
+    /// Handles pending events in order, stopping at the first failure or
+    /// when the offset was moved by a replay.
+    async fn handle_pending(&self, subscriber: &str, pending: PendingEvents) {
+        let log = self.event_delivery().log();
+        let mut committed = pending.committed;
+        for logged in pending.events {
+            if let Err(e) = self.handle_event(subscriber, &logged.event).await {
+                self.handle_failure(subscriber, committed, &logged, &e.to_string())
+                    .await;
+                return;
+            }
+            match log.commit(subscriber, committed, logged.sequence).await {
+                Ok(true) => committed = logged.sequence,
+                Ok(false) => return,
+                Err(e) => {
+                    error!(subscriber = %subscriber, sequence = logged.sequence, "Failed to commit event offset: {e}");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Counts a failed attempt at handling an event, then waits before the
+    /// next attempt or dead-letters the event if it ran out of attempts.
+    async fn handle_failure(
+        &self,
+        subscriber: &str,
+        committed: u64,
+        logged: &LoggedEvent,
+        error: &str,
+    ) {
+        let delivery = self.event_delivery();
+        let policy = delivery.policy();
+        let attempts = match delivery.log().record_failure(subscriber, committed).await {
+            Ok(Some(attempts)) => attempts,
+            Ok(None) => return,
+            Err(e) => {
+                error!(subscriber = %subscriber, "Failed to record event failure: {e}");
+                tokio::time::sleep(policy.max_backoff).await;
+                return;
+            }
+        };
+        warn!(
+            subscriber = %subscriber,
+            topic = %logged.event.topic,
+            sequence = logged.sequence,
+            attempts,
+            "Failed to deliver event: {error}"
+        );
+        if attempts < policy.max_attempts {
+            tokio::time::sleep(policy.backoff(attempts)).await;
+            return;
+        }
+
+        match delivery
+            .log()
+            .dead_letter(subscriber, committed, logged, error, attempts)
+            .await
+        {
+            Ok(true) => {
+                metrics::counter!(
+                    "brio_events_dead_lettered_total",
+                    "subscriber" => subscriber.to_string(),
+                )
+                .increment(1);
+                warn!(subscriber = %subscriber, topic = %logged.event.topic, sequence = logged.sequence, "Moved event to dead letters");
+                delivery.notify();
+            }
+            Ok(false) => {}
+            Err(e) => error!(subscriber = %subscriber, "Failed to dead-letter event: {e}"),
+        }
+    }
+
+    /// Runs the event handler of a plugin for one event.
+    async fn handle_event(&self, plugin_id: &str, event: &MeshEvent) -> Result<()> {
+        let (registry, metadata) = self
+            .plugin_registry()
+            .and_then(|registry| registry.get(plugin_id).map(|metadata| (registry, metadata)))
+            .ok_or_else(|| anyhow!("Plugin '{plugin_id}' is not loaded"))?;
+        let runner = AgentRunner::new(registry.engine().clone()).with_limits(metadata.limits);
+        let payload = match &event.payload {
+            Payload::Json(s) => EventPayload::Json(s.to_string()),
+            Payload::Binary(b) => EventPayload::Binary(b.to_vec()),
+        };
+
+        let component = registry.prepare(&metadata.id)?;
+        runner
+            .run_event_handler(
+                &component,
+                self.with_plugin_context(metadata.id.clone(), metadata.permissions.clone()),
+                event.topic.clone(),
+                payload,
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+//! Inference support for guest components.
+//!
+//! Guest completions, including the ones made by the planner on a guest's
+//! behalf, are checked against the token budgets of the calling task and
+//! plugin, and their usage is recorded once they finish. They are
+//! queued by provider rate limiters as requests of the calling plugin, in
+//! the background when they are part of a task.
+//!
+//! Streams opened by guests are kept in a table of the store's host state,
+//! keyed by the resource handle handed to the guest, so they never outlive
+//! the store. Every chunk read through the host is also broadcast to
+//! WebSocket clients so they can follow agents as they think. Broadcasts
+//! carry an ID that is unique within the kernel, since handles are only
+//! unique within their store, and end with a `done` delta even when the
+//! guest drops a stream early.
+
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::warn;
+
+use crate::engine::limits::plugin_label;
+use crate::inference::{
+    ChatChunk, ChatRequest, ChatResponse, ChatStream, InferenceError, LLMProvider, Priority,
+    RequestContext, Usage,
+};
+use crate::planner::{Plan, PlannerError};
+use crate::ws::{InferenceDelta, WsMessage};
+
+use super::BrioHostState;
+
+/// Source of the stream IDs broadcast to WebSocket clients.
+static NEXT_BROADCAST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An open stream and the model it was requested from.
+#[derive(Debug)]
+struct OpenStream {
+    model: String,
+    stream: ChatStream,
+    /// ID of the stream in broadcast deltas, unique within the kernel.
+    broadcast_id: u64,
+    /// Whether a `done` delta has been broadcast for the stream.
+    finished: bool,
+}
+
+/// Inference streams opened by the guest of one store, keyed by stream ID.
+///
+/// The table is part of the store's host state rather than the kernel's, so
+/// its streams are closed with the store when the guest traps, runs out of
+/// fuel or times out without dropping them. A cloned host state therefore
+/// starts without any open streams.
+#[derive(Debug, Default)]
+pub struct InferenceStreams {
+    next_id: u32,
+    streams: Mutex<HashMap<u32, OpenStream>>,
+}
+
+impl Clone for InferenceStreams {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl InferenceStreams {
+    /// Stores a stream of a model and returns its ID.
+    pub fn insert(&mut self, model: impl Into<String>, stream: ChatStream) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let open = OpenStream {
+            model: model.into(),
+            stream,
+            broadcast_id: NEXT_BROADCAST_ID.fetch_add(1, Ordering::Relaxed),
+            finished: false,
+        };
+        self.streams.get_mut().insert(id, open);
+        id
+    }
+
+    /// Removes a stream, cancelling its request. Returns false if it was unknown.
+    pub fn remove(&mut self, id: u32) -> bool {
+        self.take(id).is_some()
+    }
+
+    /// Returns the ID broadcast with the deltas of a stream, if it is open.
+    #[must_use]
+    pub fn broadcast_id(&self, id: u32) -> Option<u64> {
+        self.streams.lock().get(&id).map(|open| open.broadcast_id)
+    }
+
+    /// Returns the number of open streams.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.streams.lock().len()
+    }
+
+    /// Returns true if no streams are open.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.streams.lock().is_empty()
+    }
+
+    fn take(&mut self, id: u32) -> Option<OpenStream> {
+        self.streams.get_mut().remove(&id)
+    }
+
+    fn restore(&mut self, id: u32, stream: OpenStream) {
+        self.streams.get_mut().insert(id, stream);
+    }
+}
+
+impl BrioHostState {
+    /// Runs a chat completion on `provider` within the token budgets of the
+    /// current task and plugin, recording the usage it reports.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::BudgetExceeded` if a budget is used up, or
+    /// the provider's error if the completion fails.
+    pub async fn chat_within_budget(
+        &self,
+        provider: &dyn LLMProvider,
+        request: ChatRequest,
+    ) -> Result<ChatResponse, InferenceError> {
+        self.check_token_budgets().await?;
+        let model = request.model.clone();
+        let response = self.request_context().scope(provider.chat(request)).await?;
+        if let Some(usage) = &response.usage {
+            self.record_usage(&model, usage).await;
+        }
+        Ok(response)
+    }
+
+    /// Decomposes `objective` into a plan with the configured planner, as a
+    /// completion of the current task and plugin.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the objective is empty, no provider is configured
+    /// for planning, a token budget is used up or the completion fails.
+    pub async fn plan(&self, objective: &str) -> Result<Plan, PlannerError> {
+        let planner = self.planner();
+        let request = planner.plan_request(objective)?;
+        let provider = match planner.provider() {
+            Some(name) => self.inference_by_name(name),
+            None => self.inference_for_model(planner.model()),
+        }
+        .ok_or_else(|| {
+            InferenceError::ProviderError(
+                "No inference provider configured for planning".to_string(),
+            )
+        })?;
+        let response = self.chat_within_budget(provider.as_ref(), request).await?;
+        Ok(planner.parse_plan(&response.content, objective.trim()))
+    }
+
+    /// Returns the context rate limiters queue requests of the current
+    /// plugin under: background priority while running a task.
+    #[must_use]
+    pub fn request_context(&self) -> RequestContext {
+        let priority = if self.current_task_id().is_some() {
+            Priority::Background
+        } else {
+            Priority::Interactive
+        };
+        RequestContext::new(plugin_label(self), priority)
+    }
+
+    /// Checks that the current task and plugin are within their token budgets.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::BudgetExceeded` if a budget is used up.
+    pub async fn check_token_budgets(&self) -> Result<(), InferenceError> {
+        self.usage_tracker()
+            .check_budgets(
+                &self.usage_ledger(),
+                &plugin_label(self),
+                self.current_task_id(),
+            )
+            .await
+    }
+
+    /// Records usage of a model against the current task and plugin.
+    ///
+    /// Failures are logged rather than failing the completion they belong to.
+    pub async fn record_usage(&self, model: &str, usage: &Usage) {
+        if let Err(e) = self
+            .usage_tracker()
+            .record(
+                &self.usage_ledger(),
+                &plugin_label(self),
+                self.current_task_id(),
+                model,
+                usage,
+            )
+            .await
+        {
+            warn!("Failed to record inference usage for model '{model}': {e}");
+        }
+    }
+
+    /// Opens a streaming completion on the provider the request's model is
+    /// routed to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no provider serves the model, a token budget is
+    /// used up or the stream cannot be opened.
+    pub async fn open_inference_stream(
+        &mut self,
+        request: ChatRequest,
+    ) -> Result<u32, InferenceError> {
+        let provider = self.inference_for_model(&request.model).ok_or_else(|| {
+            InferenceError::ProviderError(format!(
+                "No inference provider configured for model '{}'",
+                request.model
+            ))
+        })?;
+        self.check_token_budgets().await?;
+        let model = request.model.clone();
+        let stream = self
+            .request_context()
+            .scope(provider.chat_stream(request))
+            .await?;
+        Ok(self.streams.insert(model, stream))
+    }
+
+    /// Reads the next chunk of an open stream and broadcasts it.
+    ///
+    /// Returns `None` once the stream has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stream is unknown or the provider fails, in
+    /// which case the stream is closed.
+    pub async fn next_inference_chunk(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<ChatChunk>, InferenceError> {
+        let mut open = self
+            .streams
+            .take(id)
+            .ok_or_else(|| InferenceError::ProviderError(format!("Unknown stream {id}")))?;
+
+        match open.stream.next().await {
+            Some(Ok(chunk)) => {
+                if let Some(usage) = &chunk.usage {
+                    self.record_usage(&open.model, usage).await;
+                }
+                open.finished = chunk.is_final();
+                self.emit_inference_delta(
+                    open.broadcast_id,
+                    chunk.delta.clone(),
+                    chunk.usage.clone(),
+                    open.finished,
+                );
+                self.streams.restore(id, open);
+                Ok(Some(chunk))
+            }
+            Some(Err(e)) => {
+                self.emit_inference_delta(open.broadcast_id, String::new(), None, true);
+                Err(e)
+            }
+            None => {
+                if !open.finished {
+                    self.emit_inference_delta(open.broadcast_id, String::new(), None, true);
+                    open.finished = true;
+                }
+                self.streams.restore(id, open);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Closes a stream, cancelling its request if it is still running.
+    ///
+    /// Clients are told the stream is done if it had not finished yet.
+    pub fn close_inference_stream(&mut self, id: u32) {
+        if let Some(open) = self.streams.take(id) {
+            if !open.finished {
+                self.emit_inference_delta(open.broadcast_id, String::new(), None, true);
+            }
+        }
+    }
+
+    /// Returns the streams opened through this host state.
+    #[must_use]
+    pub fn inference_streams(&self) -> &InferenceStreams {
+        &self.streams
+    }
+
+    /// Broadcasts a streamed delta to WebSocket clients.
+    fn emit_inference_delta(
+        &self,
+        id: u64,
+        delta: String,
+        usage: Option<crate::inference::Usage>,
+        done: bool,
+    ) {
+        let event =
+            InferenceDelta::new(id, self.inner.current_plugin_id.clone(), delta, usage, done);
+        if let Err(e) = self
+            .broadcaster()
+            .broadcast_message(WsMessage::InferenceDelta(event))
+        {
+            warn!("Failed to broadcast inference delta: {e}");
+        }
+    }
+}
@@ -7,6 +7,7 @@ use std::sync::Arc;
 use tokio::sync::oneshot;
 
 use crate::engine::runner::{AgentRunner, TaskContext};
+use crate::engine::tools::{ToolCall, ToolRunner, is_tool};
 use crate::mesh::types::NodeId;
 use crate::mesh::{MeshMessage, Payload};
 use crate::registry::PluginRegistry;
@@ -17,14 +18,17 @@ use super::state::BrioHostState;
 pub trait MeshHandler: Send + Sync {
     /// Calls a target component through the mesh network.
     ///
-    /// Attempts local routing first, then remote routing, and finally on-demand plugin execution.
+    /// Attempts local components and plugins first, then a `node_id/component`
+    /// target on that node, and finally any live remote node advertising the
+    /// target, preferring the least busy one.
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The target component is not found locally or remotely
+    /// - Every remote node tried fails
     /// - The message send operation fails
-    /// - Plugin execution fails (for agent targets)
+    /// - Plugin execution fails (for agent and tool targets)
     fn mesh_call(
         &self,
         target: &str,
@@ -35,7 +39,87 @@ pub trait MeshHandler: Send + Sync {
 
 impl MeshHandler for BrioHostState {
     async fn mesh_call(&self, target: &str, method: &str, payload: Payload) -> Result<Payload> {
-        // 1. Try local routing first
+        // 1. Try local components and plugins first
+        if self.hosts_locally(target) {
+            return self.local_mesh_call(target, method, payload).await;
+        }
+
+        let Some(router) = &self.inner.remote_router else {
+            return Err(not_found(target));
+        };
+
+        // 2. Explicit remote addressing: "node_id/component_id"
+        if let Some((node_id_str, component)) = target.split_once('/') {
+            let node_id = NodeId::try_from_str(node_id_str).expect("valid node id");
+            let message = MeshMessage {
+                target: component.to_string(),
+                method: method.to_string(),
+                payload,
+                reply_tx: oneshot::channel().0, // Reply handling is managed by RemoteRouter's request/response flow
+            };
+
+            return router.send(&node_id, message).await;
+        }
+
+        // 3. Any live remote node advertising the target
+        if router.candidates(target).is_empty() {
+            return Err(not_found(target));
+        }
+        router.route(target, method, payload).await
+    }
+}
+
+fn not_found(target: &str) -> anyhow::Error {
+    anyhow!(
+        "Target component '{target}' not found locally or on any live node. Ensure format is 'component' or 'node_id/component' (remote)."
+    )
+}
+
+impl BrioHostState {
+    /// Returns the components and plugins this node hosts, which it
+    /// advertises to other nodes as its capabilities.
+    #[must_use]
+    pub fn local_capabilities(&self) -> Vec<String> {
+        let mut capabilities: Vec<String> = self.inner.mesh_router.read().keys().cloned().collect();
+        if let Some(registry) = &self.inner.plugin_registry {
+            capabilities.extend(
+                registry
+                    .list_plugins()
+                    .into_iter()
+                    .map(|metadata| metadata.id),
+            );
+        }
+        capabilities.sort();
+        capabilities.dedup();
+        capabilities
+    }
+
+    /// Returns true if `target` is a component or plugin on this node.
+    fn hosts_locally(&self, target: &str) -> bool {
+        self.inner.mesh_router.read().contains_key(target)
+            || self
+                .inner
+                .plugin_registry
+                .as_ref()
+                .is_some_and(|registry| registry.get(target).is_some())
+    }
+
+    /// Calls a component or plugin on this node, without routing to other
+    /// nodes.
+    ///
+    /// Used for calls arriving from other nodes, so a call is never passed
+    /// on between nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target is not hosted on this node, the
+    /// message send operation fails or plugin execution fails.
+    pub async fn local_mesh_call(
+        &self,
+        target: &str,
+        method: &str,
+        payload: Payload,
+    ) -> Result<Payload> {
         let sender = {
             let router = self.inner.mesh_router.read();
             router.get(target).cloned()
@@ -60,47 +144,45 @@ impl MeshHandler for BrioHostState {
             return response.map_err(|e| anyhow!("Target '{target}' returned error: {e}"));
         }
 
-        // 2. Try remote routing if enabled and target is formatted as "node_id/component"
-        // Explicit remote addressing: "node_id/component_id"
-        if let (Some(router), Some((node_id_str, component))) =
-            (&self.inner.remote_router, target.split_once('/'))
-        {
-            let node_id = NodeId::try_from_str(node_id_str).expect("valid node id");
-
-            // If the target is a different node, route via gRPC
-            let message = MeshMessage {
-                target: component.to_string(),
-                method: method.to_string(),
-                payload,
-                reply_tx: oneshot::channel().0, // Reply handling is managed by RemoteRouter's request/response flow
-            };
-
-            return router.send(&node_id, message).await;
-        }
-
-        // 3. Try on-demand plugin execution
-        // We expect this lint because collapsing the nested if would require duplicating the
-        // `registry` variable binding or restructuring the logic significantly, reducing readability.
-        #[expect(clippy::collapsible_if)]
-        if let Some(registry) = &self.inner.plugin_registry {
-            if let Some(metadata) = registry.get(target) {
-                let context: TaskContext = match payload {
-                    Payload::Json(s) => serde_json::from_str(&s)
-                        .map_err(|e| anyhow!("Invalid task context: {e}"))?,
-                    Payload::Binary(_) => return Err(anyhow!("Agents only support JSON payload")),
-                };
-
-                let runner = AgentRunner::new(registry.engine().clone());
-                let result = runner
-                    .run_agent(&metadata.path, self.clone(), context)
-                    .await?;
-                return Ok(Payload::Json(Box::new(result)));
-            }
-        }
+        let metadata = self
+            .inner
+            .plugin_registry
+            .as_ref()
+            .and_then(|registry| registry.get(target).map(|metadata| (registry, metadata)));
+        let Some((registry, metadata)) = metadata else {
+            return Err(anyhow!(
+                "Target component '{target}' not found on this node"
+            ));
+        };
+        let Payload::Json(json) = payload else {
+            return Err(anyhow!("Plugins only support JSON payload"));
+        };
 
-        Err(anyhow!(
-            "Target component '{target}' not found. Ensure format is 'component' (local) or 'node_id/component' (remote)."
-        ))
+        let component = registry.prepare(&metadata.id)?;
+        let plugin_state =
+            self.with_plugin_context(metadata.id.clone(), metadata.permissions.clone());
+
+        let result = if is_tool(&component) {
+            let call: ToolCall =
+                serde_json::from_str(&json).map_err(|e| anyhow!("Invalid tool call: {e}"))?;
+            ToolRunner::new(registry.engine().clone())
+                .with_limits(metadata.limits)
+                .execute(
+                    &component,
+                    plugin_state,
+                    &call.params,
+                    call.session_id.as_deref(),
+                )
+                .await?
+        } else {
+            let context: TaskContext =
+                serde_json::from_str(&json).map_err(|e| anyhow!("Invalid task context: {e}"))?;
+            AgentRunner::new(registry.engine().clone())
+                .with_limits(metadata.limits)
+                .run_agent(&component, plugin_state, context)
+                .await?
+        };
+        Ok(Payload::Json(Box::new(result)))
     }
 }
 
@@ -1,15 +1,22 @@
 //! Host state and WIT interface implementations.
 //!
 //! This module provides the core host state management, permission checking,
-//! and mesh networking functionality for the Brio kernel.
+//! mesh networking, pub/sub events and tool invocation for the Brio kernel.
 
+pub mod branch;
+pub mod events;
+pub mod inference;
 pub mod mesh;
 pub mod permissions;
 pub mod state;
+pub mod tools;
 
 // Re-export primary types for convenience
+pub use branch::{BRANCH_AGENT_METHOD, BranchExecutor};
+pub use inference::InferenceStreams;
 pub use mesh::{MeshHandler, MeshRoute, RouteType};
 pub use permissions::{
     AllowAllPermissions, PermissionChecker, PermissionError, RestrictedPermissions,
 };
 pub use state::BrioHostState;
+pub use tools::ToolInvoker;
@@ -5,26 +5,34 @@
 
 use anyhow::{Context, Result, anyhow};
 use parking_lot::{Mutex, RwLock};
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 
-use crate::branch_manager::BranchManager;
+use crate::branch_manager::{BranchManager, SqliteBranchStorage};
+use crate::engine::limits::GuestLimiter;
+use crate::events::{EventDelivery, RetryPolicy};
 use crate::inference::{LLMProvider, ProviderRegistry};
-use crate::infrastructure::config::SandboxSettings;
+use crate::infrastructure::config::{SandboxSettings, SupervisorSettings};
 use crate::mesh::MeshMessage;
 use crate::mesh::events::EventBus;
 use crate::mesh::remote::RemoteRouter;
+use crate::mesh::tls::MeshTls;
 use crate::mesh::types::{NodeId, NodeInfo};
+use crate::planner::Planner;
 use crate::registry::PluginRegistry;
-use crate::store::{PrefixPolicy, SqlStore};
+use crate::store::{Migrator, PrefixPolicy, SqlStore, open_pool};
+use crate::usage::{UsageLedger, UsageTracker};
 use crate::vfs::manager::SessionManager;
 use crate::ws::Broadcaster;
 
+use super::branch::BranchRuns;
+use super::inference::InferenceStreams;
 use super::permissions::PermissionChecker;
 
 /// Inner state that can be cheaply cloned via Arc.
+#[derive(Clone)]
 pub(crate) struct BrioHostStateInner {
     pub(crate) mesh_router: Arc<RwLock<HashMap<String, Sender<MeshMessage>>>>,
     pub(crate) remote_router: Option<RemoteRouter>,
@@ -35,8 +43,14 @@ pub(crate) struct BrioHostStateInner {
     pub(crate) permissions: Arc<std::collections::HashSet<String>>,
     pub(crate) plugin_registry: Option<Arc<PluginRegistry>>,
     pub(crate) event_bus: Arc<EventBus>,
+    pub(crate) events: Arc<EventDelivery>,
     pub(crate) current_plugin_id: Option<String>,
+    pub(crate) current_task_id: Option<String>,
     pub(crate) branch_manager: Arc<BranchManager>,
+    pub(crate) branch_runs: Arc<BranchRuns>,
+    pub(crate) planner: Arc<Planner>,
+    pub(crate) supervisor: Arc<SupervisorSettings>,
+    pub(crate) usage: Arc<UsageTracker>,
 }
 
 /// The main host state for the Brio kernel.
@@ -47,12 +61,17 @@ pub(crate) struct BrioHostStateInner {
 #[derive(Clone)]
 pub struct BrioHostState {
     pub(crate) inner: Arc<BrioHostStateInner>,
+    /// Memory and table caps of the guest store this state belongs to.
+    pub(crate) limiter: GuestLimiter,
+    /// Inference streams opened by the guest of the store this state belongs to.
+    pub(crate) streams: InferenceStreams,
 }
 
 impl std::fmt::Debug for BrioHostState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BrioHostState")
             .field("current_plugin_id", &self.inner.current_plugin_id)
+            .field("current_task_id", &self.inner.current_task_id)
             .finish_non_exhaustive()
     }
 }
@@ -62,15 +81,17 @@ impl BrioHostState {
     ///
     /// # Errors
     ///
-    /// Returns an error if the database connection fails or if the session manager
-    /// cannot be initialized.
+    /// Returns an error if the database connection fails, if schema migrations
+    /// cannot be applied, or if the session manager cannot be initialized.
     pub async fn new(
         db_url: &str,
         registry: ProviderRegistry,
         plugin_registry: Option<Arc<PluginRegistry>>,
         sandbox: SandboxSettings,
     ) -> Result<Self> {
-        let pool = SqlitePoolOptions::new().connect(db_url).await?;
+        let pool = open_database(db_url).await?;
+        let branch_manager = BranchManager::with_storage(SqliteBranchStorage::new(pool.clone()));
+        let events = Arc::new(EventDelivery::new(pool.clone()));
 
         Ok(Self {
             inner: Arc::new(BrioHostStateInner {
@@ -86,9 +107,17 @@ impl BrioHostState {
                 permissions: Arc::new(std::collections::HashSet::new()),
                 plugin_registry,
                 event_bus: Arc::new(EventBus::new()),
+                events,
                 current_plugin_id: None,
-                branch_manager: Arc::new(BranchManager::new()),
+                current_task_id: None,
+                branch_manager: Arc::new(branch_manager),
+                branch_runs: Arc::new(BranchRuns::default()),
+                planner: Arc::new(Planner::default()),
+                supervisor: Arc::new(SupervisorSettings::default()),
+                usage: Arc::new(UsageTracker::default()),
             }),
+            limiter: GuestLimiter::default(),
+            streams: InferenceStreams::default(),
         })
     }
 
@@ -96,17 +125,19 @@ impl BrioHostState {
     ///
     /// # Errors
     ///
-    /// Returns an error if the database connection fails or if the session manager
-    /// cannot be initialized.
+    /// Returns an error if the database connection fails, if schema migrations
+    /// cannot be applied, or if the session manager cannot be initialized.
     pub async fn new_distributed(
         db_url: &str,
         registry: ProviderRegistry,
         plugin_registry: Option<Arc<PluginRegistry>>,
-        _node_id: NodeId,
+        node_id: NodeId,
         sandbox: SandboxSettings,
     ) -> Result<Self> {
-        let pool = SqlitePoolOptions::new().connect(db_url).await?;
-        let remote_router = RemoteRouter::new();
+        let pool = open_database(db_url).await?;
+        let branch_manager = BranchManager::with_storage(SqliteBranchStorage::new(pool.clone()));
+        let events = Arc::new(EventDelivery::new(pool.clone()));
+        let remote_router = RemoteRouter::new().with_local_node(node_id);
 
         Ok(Self {
             inner: Arc::new(BrioHostStateInner {
@@ -122,9 +153,17 @@ impl BrioHostState {
                 permissions: Arc::new(std::collections::HashSet::new()),
                 plugin_registry,
                 event_bus: Arc::new(EventBus::new()),
+                events,
                 current_plugin_id: None,
-                branch_manager: Arc::new(BranchManager::new()),
+                current_task_id: None,
+                branch_manager: Arc::new(branch_manager),
+                branch_runs: Arc::new(BranchRuns::default()),
+                planner: Arc::new(Planner::default()),
+                supervisor: Arc::new(SupervisorSettings::default()),
+                usage: Arc::new(UsageTracker::default()),
             }),
+            limiter: GuestLimiter::default(),
+            streams: InferenceStreams::default(),
         })
     }
 
@@ -207,6 +246,30 @@ impl BrioHostState {
         manager.commit_session(session_id)
     }
 
+    /// Returns a handle for file operations inside a VFS session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist.
+    pub fn session_files(
+        &self,
+        session_id: &str,
+    ) -> Result<crate::vfs::manager::SessionFiles, crate::vfs::SessionError> {
+        self.inner.session_manager.lock().files(session_id)
+    }
+
+    /// Lists the files changed in a VFS session, ordered by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the changes cannot be computed (see [`SessionManager::changes`]).
+    pub fn session_diff(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<crate::vfs::manager::SessionChange>, crate::vfs::SessionError> {
+        self.inner.session_manager.lock().changes(session_id)
+    }
+
     /// Rolls back a session, discarding all changes.
     ///
     /// # Arguments
@@ -248,6 +311,14 @@ impl BrioHostState {
         self.inner.provider_registry.default_provider()
     }
 
+    /// Returns the LLM provider a model is routed to.
+    ///
+    /// Falls back to the default provider if no routing rule matches the model.
+    #[must_use]
+    pub fn inference_for_model(&self, model: &str) -> Option<Arc<dyn LLMProvider>> {
+        self.inner.provider_registry.provider_for_model(model)
+    }
+
     /// Creates a new view of the host state with restricted permissions and plugin context.
     #[must_use]
     pub fn with_plugin_context(&self, plugin_id: String, permissions: Vec<String>) -> Self {
@@ -261,20 +332,105 @@ impl BrioHostState {
             permissions: Arc::new(permissions.into_iter().collect()),
             plugin_registry: self.inner.plugin_registry.clone(),
             event_bus: Arc::clone(&self.inner.event_bus),
+            events: Arc::clone(&self.inner.events),
             current_plugin_id: Some(plugin_id),
+            current_task_id: self.inner.current_task_id.clone(),
             branch_manager: Arc::clone(&self.inner.branch_manager),
+            branch_runs: Arc::clone(&self.inner.branch_runs),
+            planner: Arc::clone(&self.inner.planner),
+            supervisor: Arc::clone(&self.inner.supervisor),
+            usage: Arc::clone(&self.inner.usage),
         };
         Self {
             inner: Arc::new(inner),
+            limiter: self.limiter,
+            streams: InferenceStreams::default(),
         }
     }
 
+    /// Creates a new view of the host state running on behalf of a task.
+    ///
+    /// Inference usage of the view is accounted to the task. An empty task
+    /// id clears the task context.
+    #[must_use]
+    pub fn with_task_context(&self, task_id: impl Into<String>) -> Self {
+        let task_id = task_id.into();
+        let mut state = self.clone();
+        Arc::make_mut(&mut state.inner).current_task_id = Some(task_id).filter(|id| !id.is_empty());
+        state
+    }
+
+    /// Returns the ID of the task the state runs on behalf of, if any.
+    #[must_use]
+    pub fn current_task_id(&self) -> Option<&str> {
+        self.inner.current_task_id.as_deref()
+    }
+
+    /// Replaces the prices and token budgets applied to inference calls.
+    #[must_use]
+    pub fn with_usage_tracker(mut self, tracker: UsageTracker) -> Self {
+        Arc::make_mut(&mut self.inner).usage = Arc::new(tracker);
+        self
+    }
+
+    /// Returns the prices and token budgets applied to inference calls.
+    #[must_use]
+    pub fn usage_tracker(&self) -> &UsageTracker {
+        &self.inner.usage
+    }
+
+    /// Returns the ledger of inference token usage.
+    #[must_use]
+    pub fn usage_ledger(&self) -> UsageLedger {
+        UsageLedger::new(self.inner.db_pool.clone())
+    }
+
+    /// Replaces the planner used by the `planner` host interface.
+    #[must_use]
+    pub fn with_planner(mut self, planner: Planner) -> Self {
+        Arc::make_mut(&mut self.inner).planner = Arc::new(planner);
+        self
+    }
+
+    /// Returns the planner used by the `planner` host interface.
+    #[must_use]
+    pub fn planner(&self) -> &Planner {
+        &self.inner.planner
+    }
+
+    /// Replaces the settings served by the `supervisor-config` host interface.
+    #[must_use]
+    pub fn with_supervisor_settings(mut self, settings: SupervisorSettings) -> Self {
+        Arc::make_mut(&mut self.inner).supervisor = Arc::new(settings);
+        self
+    }
+
+    /// Returns the settings served by the `supervisor-config` host interface.
+    #[must_use]
+    pub fn supervisor_settings(&self) -> &SupervisorSettings {
+        &self.inner.supervisor
+    }
+
     /// Returns a reference to the event bus for mesh communication.
     #[must_use]
     pub fn event_bus(&self) -> &EventBus {
         &self.inner.event_bus
     }
 
+    /// Replaces how failed event deliveries are retried.
+    #[must_use]
+    pub fn with_event_retry(mut self, policy: RetryPolicy) -> Self {
+        let inner = Arc::make_mut(&mut self.inner);
+        inner.events = Arc::new(EventDelivery::new(inner.db_pool.clone()).with_policy(policy));
+        self
+    }
+
+    /// Returns the durable event log and the state of its delivery workers.
+    #[must_use]
+    pub fn event_delivery(&self) -> &EventDelivery {
+        &self.inner.events
+    }
+
     /// Returns the ID of the currently executing plugin, if any.
     #[must_use]
     pub fn current_plugin_id(&self) -> Option<&str> {
@@ -299,9 +455,21 @@ impl BrioHostState {
         &self.inner.mesh_router
     }
 
-    /// Get a reference to the remote router (internal use).
-    #[allow(dead_code)]
-    pub(crate) fn remote_router(&self) -> Option<&RemoteRouter> {
+    /// Connects to other nodes over mutual TLS. Has no effect in standalone
+    /// mode.
+    #[must_use]
+    pub fn with_mesh_tls(mut self, tls: MeshTls) -> Self {
+        let inner = Arc::make_mut(&mut self.inner);
+        inner.remote_router = inner
+            .remote_router
+            .take()
+            .map(|router| router.with_tls(tls));
+        self
+    }
+
+    /// Returns the router tracking remote nodes, or `None` in standalone mode.
+    #[must_use]
+    pub fn remote_router(&self) -> Option<&RemoteRouter> {
         self.inner.remote_router.as_ref()
     }
 
@@ -317,6 +485,16 @@ impl BrioHostState {
     }
 }
 
+/// Opens the kernel database and brings its schema up to date.
+async fn open_database(db_url: &str) -> Result<SqlitePool> {
+    let pool = open_pool(db_url).await.context("Failed to open database")?;
+    Migrator::new()
+        .run(&pool)
+        .await
+        .context("Failed to apply database migrations")?;
+    Ok(pool)
+}
+
 impl PermissionChecker for BrioHostState {
     fn check_permission(&self, permission: &str) -> Result<(), String> {
         if self.inner.permissions.contains(permission) {
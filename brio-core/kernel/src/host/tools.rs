@@ -0,0 +1,86 @@
+//! Tool invocation for the Brio kernel.
+//!
+//! This module runs tool plugins from the plugin registry under the tool's
+//! own permissions and resource limits.
+
+use crate::engine::GuestError;
+use crate::engine::tools::{ToolError, ToolInfo, ToolRunner};
+
+use super::state::BrioHostState;
+
+/// Trait for tool invocation functionality.
+pub trait ToolInvoker: Send + Sync {
+    /// Returns the info a registered tool reports about itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tool is not registered or fails to run.
+    fn tool_info(
+        &self,
+        tool_id: &str,
+    ) -> impl std::future::Future<Output = Result<ToolInfo, ToolError>> + Send;
+
+    /// Executes a registered tool.
+    ///
+    /// `session_id` is passed to the tool only if it declares
+    /// `requires-session`, in which case it is mandatory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The tool is not registered
+    /// - The tool requires a session and none was given
+    /// - The tool fails to run or reports a failure
+    fn call_tool(
+        &self,
+        tool_id: &str,
+        params: &str,
+        session_id: Option<&str>,
+    ) -> impl std::future::Future<Output = Result<String, ToolError>> + Send;
+}
+
+impl ToolInvoker for BrioHostState {
+    async fn tool_info(&self, tool_id: &str) -> Result<ToolInfo, ToolError> {
+        let (runner, component, state) = self.prepare_tool(tool_id)?;
+        Ok(runner.info(&component, state).await?)
+    }
+
+    async fn call_tool(
+        &self,
+        tool_id: &str,
+        params: &str,
+        session_id: Option<&str>,
+    ) -> Result<String, ToolError> {
+        let (runner, component, state) = self.prepare_tool(tool_id)?;
+        runner.execute(&component, state, params, session_id).await
+    }
+}
+
+impl BrioHostState {
+    /// Looks up a tool plugin and prepares a runner and host state for it.
+    fn prepare_tool(
+        &self,
+        tool_id: &str,
+    ) -> Result<
+        (
+            ToolRunner,
+            wasmtime::component::InstancePre<BrioHostState>,
+            BrioHostState,
+        ),
+        ToolError,
+    > {
+        let registry = self
+            .plugin_registry()
+            .ok_or_else(|| ToolError::NotFound(tool_id.to_string()))?;
+        let metadata = registry
+            .get(tool_id)
+            .ok_or_else(|| ToolError::NotFound(tool_id.to_string()))?;
+        let component = registry
+            .prepare(&metadata.id)
+            .map_err(GuestError::Runtime)?;
+
+        let runner = ToolRunner::new(registry.engine().clone()).with_limits(metadata.limits);
+        let state = self.with_plugin_context(metadata.id.clone(), metadata.permissions.clone());
+        Ok((runner, component, state))
+    }
+}
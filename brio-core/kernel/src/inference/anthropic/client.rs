@@ -2,16 +2,23 @@
 //!
 //! This module provides the HTTP client for communicating with Anthropic's API.
 
-use crate::inference::anthropic::mapping::{AnthropicChatRequest, map_response, prepare_messages};
+use crate::inference::anthropic::mapping::{
+    AnthropicChatRequest, apply_response_format, map_response, prepare_messages, validate_options,
+};
 use crate::inference::anthropic::retry::{DEFAULT_MAX_RETRIES, RetryConfig};
+use crate::inference::anthropic::streaming::AnthropicStreamParser;
 use crate::inference::provider::LLMProvider;
+use crate::inference::ratelimit::RateLimiter;
+use crate::inference::sse::{DEFAULT_CHUNK_TIMEOUT_MS, event_stream};
 use crate::inference::types::{
-    ChatRequest, ChatResponse, CircuitBreaker, CircuitBreakerConfig, InferenceError,
+    ChatRequest, ChatResponse, ChatStream, CircuitBreaker, CircuitBreakerConfig, InferenceError,
 };
 use async_trait::async_trait;
 use reqwest::{Client, StatusCode, Url};
 use secrecy::{ExposeSecret, SecretString};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
@@ -30,10 +37,15 @@ pub struct AnthropicConfig {
     pub base_delay_ms: Option<u64>,
     /// API version header value
     pub api_version: Option<String>,
-    /// Maximum tokens to generate (required by Anthropic API)
+    /// Default maximum tokens to generate (required by Anthropic API),
+    /// used when a request does not set its own limit
     pub max_tokens: Option<u32>,
     /// Circuit breaker configuration for resilience
     pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Maximum time to wait for the next chunk of a stream, in milliseconds
+    pub chunk_timeout_ms: Option<u64>,
+    /// Limiter told about the rate limit headers of each response
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl AnthropicConfig {
@@ -48,6 +60,8 @@ impl AnthropicConfig {
             api_version: None,
             max_tokens: None,
             circuit_breaker: None,
+            chunk_timeout_ms: None,
+            rate_limiter: None,
         }
     }
 
@@ -92,12 +106,26 @@ impl AnthropicConfig {
         self
     }
 
-    /// Sets the maximum tokens to generate
+    /// Sets the default maximum tokens to generate
     #[must_use]
     pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
         self.max_tokens = Some(max_tokens);
         self
     }
+
+    /// Sets the maximum time to wait for the next chunk of a stream
+    #[must_use]
+    pub fn with_chunk_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.chunk_timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Sets the limiter told about the rate limit headers of each response
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
 }
 
 /// Provider implementation for Anthropic's Claude API.
@@ -107,6 +135,7 @@ pub struct AnthropicProvider {
     retry_config: RetryConfig,
     api_version: String,
     max_tokens: u32,
+    chunk_timeout: Duration,
     circuit_breaker: Arc<RwLock<CircuitBreaker>>,
 }
 
@@ -122,6 +151,8 @@ impl AnthropicProvider {
             .unwrap_or_else(|| DEFAULT_API_VERSION.to_string());
         let max_tokens = config.max_tokens.unwrap_or(4096);
         let cb_config = config.circuit_breaker.unwrap_or_default();
+        let chunk_timeout =
+            Duration::from_millis(config.chunk_timeout_ms.unwrap_or(DEFAULT_CHUNK_TIMEOUT_MS));
 
         Self {
             client: Client::new(),
@@ -130,11 +161,18 @@ impl AnthropicProvider {
                 .with_base_delay_ms(base_delay_ms),
             api_version,
             max_tokens,
+            chunk_timeout,
             config,
             circuit_breaker: Arc::new(RwLock::new(CircuitBreaker::new(cb_config))),
         }
     }
 
+    /// Returns the circuit breaker guarding requests, shared with monitoring
+    #[must_use]
+    pub fn circuit_breaker(&self) -> Arc<RwLock<CircuitBreaker>> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
     /// Makes a single request attempt
     async fn make_request(
         &self,
@@ -150,10 +188,107 @@ impl AnthropicProvider {
                 true, // Retry network errors
             )
         })?;
+        self.observe_limits(&res);
 
         self.map_api_response(res).await
     }
 
+    /// Starts a streaming request, returning the response once its status is OK
+    async fn open_stream(
+        &self,
+        provider_req: &AnthropicChatRequest,
+    ) -> Result<reqwest::Response, (InferenceError, bool)> {
+        let request = self
+            .build_api_request(provider_req)
+            .map_err(|e| (e, false))?;
+
+        let res = request
+            .send()
+            .await
+            .map_err(|e| (InferenceError::NetworkError(e.to_string()), true))?;
+        self.observe_limits(&res);
+
+        if res.status() == StatusCode::OK {
+            Ok(res)
+        } else {
+            Err(Self::map_error_response(res).await)
+        }
+    }
+
+    /// Reports the rate limit headers of a response to the limiter
+    fn observe_limits(&self, res: &reqwest::Response) {
+        if let Some(limiter) = &self.config.rate_limiter {
+            limiter.observe(res.headers());
+        }
+    }
+
+    /// Runs `attempt_fn` with retries, guarded by the circuit breaker
+    async fn with_retries<T, F, Fut>(&self, attempt_fn: F) -> Result<T, InferenceError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, (InferenceError, bool)>>,
+    {
+        // Check circuit breaker first
+        let can_execute = {
+            let mut cb = self.circuit_breaker.write().await;
+            cb.try_acquire()
+        };
+
+        if !can_execute {
+            return Err(InferenceError::CircuitBreakerOpen(
+                "Anthropic circuit is open".to_string(),
+            ));
+        }
+
+        let mut last_error = InferenceError::NetworkError("No attempts made".to_string());
+
+        for attempt in 0..=self.retry_config.max_retries {
+            match attempt_fn().await {
+                Ok(response) => {
+                    // Record success on circuit breaker
+                    let mut cb = self.circuit_breaker.write().await;
+                    cb.record_success();
+                    return Ok(response);
+                }
+                Err((error, should_retry)) => {
+                    last_error = error;
+
+                    if !should_retry || attempt >= self.retry_config.max_retries {
+                        break;
+                    }
+
+                    // Wait at least as long as the provider asked us to
+                    let backoff = self.retry_config.calculate_backoff_delay(attempt);
+                    let delay = self
+                        .config
+                        .rate_limiter
+                        .as_ref()
+                        .and_then(|limiter| limiter.retry_delay())
+                        .map_or(backoff, |asked| asked.max(backoff));
+                    let delay_ms: u64 = delay.as_millis().try_into().unwrap_or(u64::MAX);
+                    warn!(
+                        attempt = attempt + 1,
+                        max_retries = self.retry_config.max_retries,
+                        delay_ms = delay_ms,
+                        error = %last_error,
+                        "Anthropic request failed, retrying after backoff"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        // Record failure on circuit breaker
+        let mut cb = self.circuit_breaker.write().await;
+        cb.record_failure();
+
+        debug!(
+            attempts = self.retry_config.max_retries + 1,
+            "All Anthropic retry attempts exhausted"
+        );
+        Err(last_error)
+    }
+
     fn build_api_request(
         &self,
         provider_req: &AnthropicChatRequest,
@@ -188,18 +323,25 @@ impl AnthropicProvider {
 
                 Ok(map_response(body))
             }
+            _ => Err(Self::map_error_response(res).await),
+        }
+    }
+
+    /// Maps an unsuccessful response to an error and whether to retry it
+    async fn map_error_response(res: reqwest::Response) -> (InferenceError, bool) {
+        match res.status() {
             // Anthropic uses 529 for overloaded, 429 for rate limit
-            StatusCode::TOO_MANY_REQUESTS => Err((InferenceError::RateLimit, true)),
-            status if status.as_u16() == 529 => Err((InferenceError::RateLimit, true)),
+            StatusCode::TOO_MANY_REQUESTS => (InferenceError::RateLimit, true),
+            status if status.as_u16() == 529 => (InferenceError::RateLimit, true),
             StatusCode::BAD_REQUEST => {
                 let text = res.text().await.unwrap_or_default();
                 if text.contains("context_length") || text.contains("max_tokens") {
-                    Err((InferenceError::ContextLengthExceeded, false))
+                    (InferenceError::ContextLengthExceeded, false)
                 } else {
-                    Err((
+                    (
                         InferenceError::ProviderError(format!("Bad Request: {text}")),
                         false,
-                    ))
+                    )
                 }
             }
             StatusCode::INTERNAL_SERVER_ERROR
@@ -208,87 +350,61 @@ impl AnthropicProvider {
             | StatusCode::GATEWAY_TIMEOUT => {
                 let status = res.status();
                 let text = res.text().await.unwrap_or_default();
-                Err((
+                (
                     InferenceError::ProviderError(format!("HTTP {status}: {text}")),
                     true,
-                ))
+                )
             }
             _ => {
                 let status = res.status();
                 let text = res.text().await.unwrap_or_default();
-                Err((
+                (
                     InferenceError::ProviderError(format!("HTTP {status}: {text}")),
                     false,
-                ))
+                )
             }
         }
     }
 }
 
-#[async_trait]
-impl LLMProvider for AnthropicProvider {
-    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
-        // Check circuit breaker first
-        let can_execute = {
-            let mut cb = self.circuit_breaker.write().await;
-            cb.try_acquire()
-        };
-
-        if !can_execute {
-            return Err(InferenceError::CircuitBreakerOpen(
-                "Anthropic circuit is open".to_string(),
-            ));
-        }
-
+impl AnthropicProvider {
+    fn create_request(&self, request: ChatRequest, stream: bool) -> AnthropicChatRequest {
         let (system, messages) = prepare_messages(&request.messages);
+        let options = request.options;
 
-        let provider_req = AnthropicChatRequest {
+        AnthropicChatRequest {
             model: request.model,
-            max_tokens: self.max_tokens,
+            max_tokens: options.max_tokens.unwrap_or(self.max_tokens),
             messages,
-            system,
-        };
-
-        let mut last_error = InferenceError::NetworkError("No attempts made".to_string());
-
-        for attempt in 0..=self.retry_config.max_retries {
-            match self.make_request(&provider_req).await {
-                Ok(response) => {
-                    // Record success on circuit breaker
-                    let mut cb = self.circuit_breaker.write().await;
-                    cb.record_success();
-                    return Ok(response);
-                }
-                Err((error, should_retry)) => {
-                    last_error = error;
-
-                    if !should_retry || attempt >= self.retry_config.max_retries {
-                        break;
-                    }
-
-                    let delay = self.retry_config.calculate_backoff_delay(attempt);
-                    let delay_ms: u64 = delay.as_millis().try_into().unwrap_or(u64::MAX);
-                    warn!(
-                        attempt = attempt + 1,
-                        max_retries = self.retry_config.max_retries,
-                        delay_ms = delay_ms,
-                        error = %last_error,
-                        "Anthropic request failed, retrying after backoff"
-                    );
-                    tokio::time::sleep(delay).await;
-                }
-            }
+            system: apply_response_format(system, options.response_format),
+            tools: request.tools.into_iter().map(Into::into).collect(),
+            temperature: options.temperature,
+            top_p: options.top_p,
+            stop_sequences: options.stop,
+            stream,
         }
+    }
+}
 
-        // Record failure on circuit breaker
-        let mut cb = self.circuit_breaker.write().await;
-        cb.record_failure();
+#[async_trait]
+impl LLMProvider for AnthropicProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        validate_options(&request.options)?;
+        let provider_req = self.create_request(request, false);
+        self.with_retries(|| self.make_request(&provider_req)).await
+    }
 
-        debug!(
-            attempts = self.retry_config.max_retries + 1,
-            "All Anthropic retry attempts exhausted"
-        );
-        Err(last_error)
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        validate_options(&request.options)?;
+        let provider_req = self.create_request(request, true);
+        let response = self
+            .with_retries(|| self.open_stream(&provider_req))
+            .await?;
+
+        let mut parser = AnthropicStreamParser::new();
+        Ok(event_stream(response, self.chunk_timeout, move |event| {
+            parser.handle(&event)
+        }))
     }
 }
 
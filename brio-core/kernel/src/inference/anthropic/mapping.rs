@@ -2,8 +2,20 @@
 //!
 //! This module provides types for mapping between internal and Anthropic API formats.
 
-use crate::inference::types::{ChatResponse, Message, Role, Usage};
+use crate::inference::types::{
+    ChatResponse, InferenceError, Message, RequestOptions, ResponseFormat, Role, ToolCall,
+    ToolDefinition, Usage,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Highest sampling temperature accepted by the Anthropic API
+pub const MAX_TEMPERATURE: f32 = 1.0;
+
+/// System prompt instruction used to emulate JSON mode, which the Messages
+/// API does not support natively
+pub const JSON_INSTRUCTION: &str =
+    "Respond only with a single valid JSON object and no other text.";
 
 /// Anthropic API message format
 #[derive(Serialize)]
@@ -11,7 +23,68 @@ pub struct AnthropicMessage {
     /// The role of the message sender (user or assistant)
     pub role: String,
     /// The content of the message
-    pub content: String,
+    pub content: AnthropicMessageContent,
+}
+
+/// Content of an Anthropic API message
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum AnthropicMessageContent {
+    /// Plain text
+    Text(String),
+    /// Content blocks, used for tool calls and results
+    Blocks(Vec<AnthropicContentBlock>),
+}
+
+/// Anthropic API content block
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicContentBlock {
+    /// Generated or supplied text
+    Text {
+        /// The text content
+        text: String,
+    },
+    /// A tool call made by the model
+    ToolUse {
+        /// Identifier of the call
+        id: String,
+        /// Name of the tool
+        name: String,
+        /// Arguments of the call
+        input: Value,
+    },
+    /// The result of a tool call
+    ToolResult {
+        /// The call this result answers
+        tool_use_id: String,
+        /// Output of the tool
+        content: String,
+    },
+    /// Block types this client does not handle
+    #[serde(other)]
+    Unknown,
+}
+
+/// Anthropic API tool definition format
+#[derive(Serialize)]
+pub struct AnthropicTool {
+    /// Name of the tool
+    pub name: String,
+    /// What the tool does
+    pub description: String,
+    /// JSON schema of the tool's input
+    pub input_schema: Value,
+}
+
+impl From<ToolDefinition> for AnthropicTool {
+    fn from(tool: ToolDefinition) -> Self {
+        Self {
+            name: tool.name,
+            description: tool.description,
+            input_schema: tool.parameters,
+        }
+    }
 }
 
 /// Anthropic API chat request format
@@ -26,13 +99,21 @@ pub struct AnthropicChatRequest {
     /// Optional system prompt
     #[serde(skip_serializing_if = "Option::is_none")]
     pub system: Option<String>,
-}
-
-/// Anthropic API content block
-#[derive(Deserialize)]
-pub struct AnthropicContent {
-    /// The text content of the response
-    pub text: String,
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<AnthropicTool>,
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Sequences that stop generation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// Whether to stream the response as server-sent events
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 /// Anthropic API usage information
@@ -48,16 +129,19 @@ pub struct AnthropicUsage {
 #[derive(Deserialize)]
 pub struct AnthropicChatResponse {
     /// The generated content blocks
-    pub content: Vec<AnthropicContent>,
+    pub content: Vec<AnthropicContentBlock>,
     /// Token usage information if available
     pub usage: Option<AnthropicUsage>,
 }
 
-/// Converts internal Message type to Anthropic format, extracting system message
+/// Converts internal Message type to Anthropic format, extracting system message.
+///
+/// Tool results are sent as `tool_result` blocks in user messages, merging
+/// consecutive results into one message as the API requires.
 #[must_use]
 pub fn prepare_messages(messages: &[Message]) -> (Option<String>, Vec<AnthropicMessage>) {
     let mut system_message = None;
-    let mut anthropic_messages = Vec::with_capacity(messages.len());
+    let mut anthropic_messages: Vec<AnthropicMessage> = Vec::with_capacity(messages.len());
 
     for msg in messages {
         match msg.role {
@@ -68,15 +152,52 @@ pub fn prepare_messages(messages: &[Message]) -> (Option<String>, Vec<AnthropicM
             Role::User => {
                 anthropic_messages.push(AnthropicMessage {
                     role: "user".to_string(),
-                    content: msg.content.clone(),
+                    content: AnthropicMessageContent::Text(msg.content.clone()),
+                });
+            }
+            Role::Assistant if msg.tool_calls.is_empty() => {
+                anthropic_messages.push(AnthropicMessage {
+                    role: "assistant".to_string(),
+                    content: AnthropicMessageContent::Text(msg.content.clone()),
                 });
             }
             Role::Assistant => {
+                let text = (!msg.content.is_empty()).then(|| AnthropicContentBlock::Text {
+                    text: msg.content.clone(),
+                });
+                let calls = msg
+                    .tool_calls
+                    .iter()
+                    .map(|call| AnthropicContentBlock::ToolUse {
+                        id: call.id.clone(),
+                        name: call.name.clone(),
+                        input: call
+                            .parsed_arguments()
+                            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+                    });
                 anthropic_messages.push(AnthropicMessage {
                     role: "assistant".to_string(),
-                    content: msg.content.clone(),
+                    content: AnthropicMessageContent::Blocks(
+                        text.into_iter().chain(calls).collect(),
+                    ),
                 });
             }
+            Role::Tool => {
+                let block = AnthropicContentBlock::ToolResult {
+                    tool_use_id: msg.tool_call_id.clone().unwrap_or_default(),
+                    content: msg.content.clone(),
+                };
+                match anthropic_messages.last_mut() {
+                    Some(AnthropicMessage {
+                        content: AnthropicMessageContent::Blocks(blocks),
+                        role,
+                    }) if role == "user" => blocks.push(block),
+                    _ => anthropic_messages.push(AnthropicMessage {
+                        role: "user".to_string(),
+                        content: AnthropicMessageContent::Blocks(vec![block]),
+                    }),
+                }
+            }
         }
     }
 
@@ -86,11 +207,18 @@ pub fn prepare_messages(messages: &[Message]) -> (Option<String>, Vec<AnthropicM
 /// Maps Anthropic API response to internal `ChatResponse`
 #[must_use]
 pub fn map_response(body: AnthropicChatResponse) -> ChatResponse {
-    let content = body
-        .content
-        .first()
-        .map(|c| c.text.clone())
-        .unwrap_or_default();
+    let mut content = String::new();
+    let mut tool_calls = Vec::new();
+
+    for block in body.content {
+        match block {
+            AnthropicContentBlock::Text { text } => content.push_str(&text),
+            AnthropicContentBlock::ToolUse { id, name, input } => {
+                tool_calls.push(ToolCall::new(id, name, input.to_string()));
+            }
+            AnthropicContentBlock::ToolResult { .. } | AnthropicContentBlock::Unknown => {}
+        }
+    }
 
     ChatResponse {
         content,
@@ -99,6 +227,34 @@ pub fn map_response(body: AnthropicChatResponse) -> ChatResponse {
             completion_tokens: u.output_tokens,
             total_tokens: u.input_tokens + u.output_tokens,
         }),
+        tool_calls,
+    }
+}
+
+/// Checks request options against the Anthropic API limits
+///
+/// # Errors
+///
+/// Returns `InferenceError::InvalidRequest` if an option is out of range or
+/// not supported by the API.
+pub fn validate_options(options: &RequestOptions) -> Result<(), InferenceError> {
+    options.validate(MAX_TEMPERATURE)?;
+    if options.seed.is_some() {
+        return Err(InferenceError::InvalidRequest(
+            "seed is not supported by the Anthropic API".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Adds the instructions needed for the requested response format to the
+/// system prompt
+#[must_use]
+pub fn apply_response_format(system: Option<String>, format: ResponseFormat) -> Option<String> {
+    match (format, system) {
+        (ResponseFormat::Text, system) => system,
+        (ResponseFormat::Json, Some(system)) => Some(format!("{system}\n\n{JSON_INSTRUCTION}")),
+        (ResponseFormat::Json, None) => Some(JSON_INSTRUCTION.to_string()),
     }
 }
 
@@ -109,34 +265,25 @@ mod tests {
     #[test]
     fn test_prepare_messages_extracts_system() {
         let messages = vec![
-            Message {
-                role: Role::System,
-                content: "You are helpful.".to_string(),
-            },
-            Message {
-                role: Role::User,
-                content: "Hello!".to_string(),
-            },
+            Message::new(Role::System, "You are helpful."),
+            Message::new(Role::User, "Hello!"),
         ];
 
         let (system, msgs) = prepare_messages(&messages);
         assert_eq!(system, Some("You are helpful.".to_string()));
         assert_eq!(msgs.len(), 1);
         assert_eq!(msgs[0].role, "user");
-        assert_eq!(msgs[0].content, "Hello!");
+        assert_eq!(
+            msgs[0].content,
+            AnthropicMessageContent::Text("Hello!".to_string())
+        );
     }
 
     #[test]
     fn test_prepare_messages_no_system() {
         let messages = vec![
-            Message {
-                role: Role::User,
-                content: "Hello!".to_string(),
-            },
-            Message {
-                role: Role::Assistant,
-                content: "Hi there!".to_string(),
-            },
+            Message::new(Role::User, "Hello!"),
+            Message::new(Role::Assistant, "Hi there!"),
         ];
 
         let (system, msgs) = prepare_messages(&messages);
@@ -144,10 +291,61 @@ mod tests {
         assert_eq!(msgs.len(), 2);
     }
 
+    #[test]
+    fn test_prepare_messages_maps_tool_calls_and_results() {
+        let messages = vec![
+            Message::new(Role::User, "Read both files"),
+            Message::tool_calls(
+                "Reading",
+                vec![
+                    ToolCall::new("toolu_1", "read_file", r#"{"path":"a.rs"}"#),
+                    ToolCall::new("toolu_2", "read_file", r#"{"path":"b.rs"}"#),
+                ],
+            ),
+            Message::tool_result("toolu_1", "a"),
+            Message::tool_result("toolu_2", "b"),
+        ];
+
+        let (_, msgs) = prepare_messages(&messages);
+        assert_eq!(msgs.len(), 3);
+
+        let json = serde_json::to_value(&msgs).unwrap();
+        assert_eq!(json[1]["role"], "assistant");
+        assert_eq!(json[1]["content"][0]["type"], "text");
+        assert_eq!(json[1]["content"][1]["type"], "tool_use");
+        assert_eq!(json[1]["content"][1]["input"]["path"], "a.rs");
+
+        assert_eq!(json[2]["role"], "user");
+        assert_eq!(json[2]["content"][0]["type"], "tool_result");
+        assert_eq!(json[2]["content"][0]["tool_use_id"], "toolu_1");
+        assert_eq!(json[2]["content"][1]["tool_use_id"], "toolu_2");
+    }
+
+    #[test]
+    fn test_map_response_with_tool_use() {
+        let body: AnthropicChatResponse = serde_json::from_str(
+            r#"{
+                "content": [
+                    {"type": "text", "text": "Let me look."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "ls", "input": {"path": "."}}
+                ],
+                "usage": {"input_tokens": 3, "output_tokens": 4}
+            }"#,
+        )
+        .unwrap();
+
+        let response = map_response(body);
+        assert_eq!(response.content, "Let me look.");
+        assert_eq!(
+            response.tool_calls,
+            vec![ToolCall::new("toolu_1", "ls", r#"{"path":"."}"#)]
+        );
+    }
+
     #[test]
     fn test_map_response() {
         let body = AnthropicChatResponse {
-            content: vec![AnthropicContent {
+            content: vec![AnthropicContentBlock::Text {
                 text: "Test response".to_string(),
             }],
             usage: Some(AnthropicUsage {
@@ -168,7 +366,7 @@ mod tests {
     #[test]
     fn test_map_response_no_usage() {
         let body = AnthropicChatResponse {
-            content: vec![AnthropicContent {
+            content: vec![AnthropicContentBlock::Text {
                 text: "No usage".to_string(),
             }],
             usage: None,
@@ -189,4 +387,30 @@ mod tests {
         let response = map_response(body);
         assert_eq!(response.content, "");
     }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(validate_options(&RequestOptions::new().with_temperature(0.0)).is_ok());
+        assert!(validate_options(&RequestOptions::new().with_temperature(1.5)).is_err());
+        assert!(matches!(
+            validate_options(&RequestOptions::new().with_seed(42)),
+            Err(InferenceError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_response_format() {
+        assert_eq!(
+            apply_response_format(Some("Review".to_string()), ResponseFormat::Text),
+            Some("Review".to_string())
+        );
+        assert_eq!(
+            apply_response_format(None, ResponseFormat::Json),
+            Some(JSON_INSTRUCTION.to_string())
+        );
+        let system =
+            apply_response_format(Some("Review".to_string()), ResponseFormat::Json).unwrap();
+        assert!(system.starts_with("Review"));
+        assert!(system.ends_with(JSON_INSTRUCTION));
+    }
 }
@@ -5,12 +5,15 @@
 pub mod client;
 pub mod mapping;
 pub mod retry;
+pub mod streaming;
 
 pub use client::{AnthropicConfig, AnthropicProvider};
 pub use mapping::{
-    AnthropicChatRequest, AnthropicChatResponse, AnthropicContent, AnthropicMessage,
-    AnthropicUsage, prepare_messages,
+    AnthropicChatRequest, AnthropicChatResponse, AnthropicContentBlock, AnthropicMessage,
+    AnthropicMessageContent, AnthropicTool, AnthropicUsage, JSON_INSTRUCTION, MAX_TEMPERATURE,
+    apply_response_format, prepare_messages, validate_options,
 };
 pub use retry::{
     DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_RETRIES, MAX_DELAY_MS, RetryConfig, rand_jitter_factor,
 };
+pub use streaming::{AnthropicStreamEvent, AnthropicStreamParser};
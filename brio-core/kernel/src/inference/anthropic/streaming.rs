@@ -0,0 +1,283 @@
+//! Anthropic API streaming support.
+//!
+//! This module parses the SSE (Server-Sent Events) emitted by the Messages
+//! API when `stream` is enabled.
+
+use crate::inference::anthropic::mapping::AnthropicContentBlock;
+use crate::inference::sse::{SseEvent, StreamStep};
+use crate::inference::types::{ChatChunk, InferenceError, ToolCall, Usage};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Usage reported in `message_start` and `message_delta` events
+#[derive(Deserialize, Default)]
+pub struct AnthropicStreamUsage {
+    /// Number of input tokens (prompt)
+    #[serde(default)]
+    pub input_tokens: Option<u32>,
+    /// Number of output tokens generated so far
+    #[serde(default)]
+    pub output_tokens: Option<u32>,
+}
+
+/// Message summary sent in the `message_start` event
+#[derive(Deserialize)]
+pub struct AnthropicStreamMessage {
+    /// Usage at the start of the message
+    #[serde(default)]
+    pub usage: AnthropicStreamUsage,
+}
+
+/// Delta of a `content_block_delta` or `message_delta` event
+#[derive(Deserialize, Default)]
+pub struct AnthropicStreamDelta {
+    /// Text generated since the previous delta
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Next fragment of the JSON input of a tool call
+    #[serde(default)]
+    pub partial_json: Option<String>,
+    /// Why generation stopped
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+}
+
+/// Error reported inside an Anthropic stream
+#[derive(Deserialize)]
+pub struct AnthropicStreamError {
+    /// Error type, e.g. `overloaded_error`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Human-readable error message
+    pub message: String,
+}
+
+/// A streamed Anthropic event
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicStreamEvent {
+    /// Start of the message, carrying the input token count
+    MessageStart {
+        /// The message being generated
+        message: AnthropicStreamMessage,
+    },
+    /// Start of a content block; tool calls are named here
+    ContentBlockStart {
+        /// Position of the block in the message
+        index: usize,
+        /// The block, without its content
+        content_block: AnthropicContentBlock,
+    },
+    /// Incremental content
+    ContentBlockDelta {
+        /// Position of the block in the message
+        #[serde(default)]
+        index: usize,
+        /// The content delta
+        delta: AnthropicStreamDelta,
+    },
+    /// Top-level message changes, carrying the stop reason and output tokens
+    MessageDelta {
+        /// The message delta
+        #[serde(default)]
+        delta: AnthropicStreamDelta,
+        /// Cumulative usage
+        #[serde(default)]
+        usage: AnthropicStreamUsage,
+    },
+    /// End of the message
+    MessageStop,
+    /// Error raised after the stream started
+    Error {
+        /// The error details
+        error: AnthropicStreamError,
+    },
+    /// Pings and content block ends
+    #[serde(other)]
+    Other,
+}
+
+/// Parses Anthropic stream events into chat chunks.
+///
+/// Input tokens arrive at the start of the message and output tokens with
+/// the final `message_delta`; both are reported on the last chunk, together
+/// with the tool calls assembled from their input fragments.
+#[derive(Debug, Default)]
+pub struct AnthropicStreamParser {
+    input_tokens: u32,
+    output_tokens: u32,
+    stop_reason: Option<String>,
+    tool_calls: BTreeMap<usize, ToolCall>,
+}
+
+impl AnthropicStreamParser {
+    /// Creates a parser for a new stream
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles a single SSE event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be parsed or reports an error.
+    pub fn handle(&mut self, event: &SseEvent) -> Result<StreamStep, InferenceError> {
+        let parsed: AnthropicStreamEvent = serde_json::from_str(&event.data)
+            .map_err(|e| InferenceError::ProviderError(format!("Parse error: {e}")))?;
+
+        match parsed {
+            AnthropicStreamEvent::MessageStart { message } => {
+                self.input_tokens = message.usage.input_tokens.unwrap_or(0);
+                self.output_tokens = message.usage.output_tokens.unwrap_or(0);
+                Ok(StreamStep::Skip)
+            }
+            AnthropicStreamEvent::ContentBlockStart {
+                index,
+                content_block: AnthropicContentBlock::ToolUse { id, name, .. },
+            } => {
+                self.tool_calls
+                    .insert(index, ToolCall::new(id, name, String::new()));
+                Ok(StreamStep::Skip)
+            }
+            AnthropicStreamEvent::ContentBlockDelta { index, delta } => {
+                if let Some(fragment) = delta.partial_json {
+                    if let Some(call) = self.tool_calls.get_mut(&index) {
+                        call.arguments.push_str(&fragment);
+                    }
+                }
+                Ok(delta
+                    .text
+                    .filter(|text| !text.is_empty())
+                    .map_or(StreamStep::Skip, |text| {
+                        StreamStep::Chunk(ChatChunk::delta(text))
+                    }))
+            }
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                if let Some(output_tokens) = usage.output_tokens {
+                    self.output_tokens = output_tokens;
+                }
+                if delta.stop_reason.is_some() {
+                    self.stop_reason = delta.stop_reason;
+                }
+                Ok(StreamStep::Skip)
+            }
+            AnthropicStreamEvent::MessageStop => Ok(StreamStep::Done(Some(ChatChunk {
+                delta: String::new(),
+                usage: Some(Usage {
+                    prompt_tokens: self.input_tokens,
+                    completion_tokens: self.output_tokens,
+                    total_tokens: self.input_tokens + self.output_tokens,
+                }),
+                finish_reason: self
+                    .stop_reason
+                    .take()
+                    .or_else(|| Some("end_turn".to_string())),
+                tool_calls: std::mem::take(&mut self.tool_calls).into_values().collect(),
+            }))),
+            AnthropicStreamEvent::Error { error } => Err(match error.kind.as_str() {
+                "overloaded_error" | "rate_limit_error" => InferenceError::RateLimit,
+                _ => InferenceError::ProviderError(error.message),
+            }),
+            AnthropicStreamEvent::ContentBlockStart { .. } | AnthropicStreamEvent::Other => {
+                Ok(StreamStep::Skip)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(data: &str) -> SseEvent {
+        SseEvent {
+            event: None,
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parser_reports_usage_on_final_chunk() {
+        let mut parser = AnthropicStreamParser::new();
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"ping"}"#,
+        ];
+        for data in events {
+            assert!(matches!(
+                parser.handle(&event(data)).unwrap(),
+                StreamStep::Skip
+            ));
+        }
+
+        let step = parser
+            .handle(&event(
+                r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#,
+            ))
+            .unwrap();
+        assert!(matches!(step, StreamStep::Chunk(c) if c.delta == "Hi"));
+
+        parser
+            .handle(&event(
+                r#"{"type":"message_delta","delta":{"stop_reason":"max_tokens"},"usage":{"output_tokens":7}}"#,
+            ))
+            .unwrap();
+        let StreamStep::Done(Some(last)) =
+            parser.handle(&event(r#"{"type":"message_stop"}"#)).unwrap()
+        else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("max_tokens"));
+        let usage = last.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 12);
+        assert_eq!(usage.completion_tokens, 7);
+        assert_eq!(usage.total_tokens, 19);
+    }
+
+    #[test]
+    fn test_parser_maps_overloaded_errors_to_rate_limit() {
+        let mut parser = AnthropicStreamParser::new();
+        let result = parser.handle(&event(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        ));
+        assert!(matches!(result, Err(InferenceError::RateLimit)));
+
+        let result = parser.handle(&event(
+            r#"{"type":"error","error":{"type":"api_error","message":"boom"}}"#,
+        ));
+        assert!(matches!(result, Err(InferenceError::ProviderError(msg)) if msg == "boom"));
+    }
+
+    #[test]
+    fn test_parser_assembles_tool_calls() {
+        let mut parser = AnthropicStreamParser::new();
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":20,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"tool_use","id":"toolu_1","name":"read_file","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"{\"path\": "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"input_json_delta","partial_json":"\"a.rs\"}"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":15}}"#,
+        ];
+        for data in events {
+            assert!(matches!(
+                parser.handle(&event(data)).unwrap(),
+                StreamStep::Skip
+            ));
+        }
+
+        let StreamStep::Done(Some(last)) =
+            parser.handle(&event(r#"{"type":"message_stop"}"#)).unwrap()
+        else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("tool_use"));
+        assert_eq!(
+            last.tool_calls,
+            [ToolCall::new("toolu_1", "read_file", r#"{"path": "a.rs"}"#)]
+        );
+    }
+}
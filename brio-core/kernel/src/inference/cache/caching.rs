@@ -0,0 +1,157 @@
+//! Provider that answers repeated requests from a response cache.
+
+use crate::inference::cache::{ResponseCache, is_cacheable};
+use crate::inference::provider::LLMProvider;
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// A provider that forwards requests to another provider and caches the
+/// responses to cacheable ones.
+///
+/// Cached responses carry no usage, since answering them consumed no
+/// tokens. Streams are served from the cache as a single chunk; streams
+/// that miss are passed through without being cached. A response that
+/// cannot be cached is still returned. Cache files are read and written on
+/// the blocking thread pool.
+pub struct CachingProvider {
+    name: String,
+    inner: Arc<dyn LLMProvider>,
+    cache: Arc<ResponseCache>,
+}
+
+impl CachingProvider {
+    /// Creates a provider caching the responses of `inner`, registered as
+    /// `name`, in `cache`
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        inner: Arc<dyn LLMProvider>,
+        cache: Arc<ResponseCache>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            inner,
+            cache,
+        }
+    }
+
+    /// Returns the cache responses are stored in
+    #[must_use]
+    pub fn cache(&self) -> &Arc<ResponseCache> {
+        &self.cache
+    }
+
+    async fn store(&self, request: ChatRequest, response: ChatResponse) {
+        let cache = self.cache.clone();
+        match tokio::task::spawn_blocking(move || cache.put(&request, &response)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!(provider = %self.name, "Failed to cache inference response: {e}"),
+            Err(e) => warn!(provider = %self.name, "Failed to cache inference response: {e}"),
+        }
+    }
+
+    async fn lookup(&self, request: &ChatRequest) -> Option<ChatResponse> {
+        if !is_cacheable(request) {
+            return None;
+        }
+        let cache = self.cache.clone();
+        let key = request.clone();
+        let response = tokio::task::spawn_blocking(move || cache.get(&key))
+            .await
+            .ok()
+            .flatten();
+        let metric = if response.is_some() {
+            "brio_inference_cache_hits_total"
+        } else {
+            "brio_inference_cache_misses_total"
+        };
+        metrics::counter!(metric, "provider" => self.name.clone()).increment(1);
+        response.map(|response| {
+            debug!(provider = %self.name, model = %request.model, "Inference cache hit");
+            ChatResponse {
+                usage: None,
+                ..response
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl LLMProvider for CachingProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        if let Some(response) = self.lookup(&request).await {
+            return Ok(response);
+        }
+        let response = self.inner.chat(request.clone()).await?;
+        if is_cacheable(&request) {
+            self.store(request, response.clone()).await;
+        }
+        Ok(response)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        match self.lookup(&request).await {
+            Some(response) => Ok(ChatStream::from_response(response)),
+            None => self.inner.chat_stream(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::RequestOptions;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct CountingProvider {
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl LLMProvider for CountingProvider {
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ChatResponse::with_usage(format!("answer {call}"), 10, 5))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_hit_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = Arc::new(CountingProvider {
+            calls: AtomicU32::new(0),
+        });
+        let provider = CachingProvider::new(
+            "openai",
+            inner.clone(),
+            Arc::new(ResponseCache::new(dir.path())),
+        );
+        let request = ChatRequest::with_message("gpt-4", "Review this diff")
+            .with_options(RequestOptions::new().with_temperature(0.0));
+
+        let first = provider.chat(request.clone()).await.unwrap();
+        let second = provider.chat(request.clone()).await.unwrap();
+        assert_eq!(first.content, "answer 1");
+        assert_eq!(second.content, "answer 1");
+        assert!(first.usage.is_some());
+        assert!(second.usage.is_none());
+
+        let sampled = request.with_options(RequestOptions::new().with_temperature(0.8));
+        assert_eq!(
+            provider.chat(sampled.clone()).await.unwrap().content,
+            "answer 2"
+        );
+        assert_eq!(provider.chat(sampled).await.unwrap().content, "answer 3");
+
+        // Requests without a temperature sample with the provider's default
+        let unset = ChatRequest::with_message("gpt-4", "Review this diff");
+        assert_eq!(
+            provider.chat(unset.clone()).await.unwrap().content,
+            "answer 4"
+        );
+        assert_eq!(provider.chat(unset).await.unwrap().content, "answer 5");
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 5);
+    }
+}
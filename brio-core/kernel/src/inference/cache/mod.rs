@@ -0,0 +1,395 @@
+//! Caching of inference responses.
+//!
+//! A [`ResponseCache`] stores responses on disk, one JSON file per request,
+//! keyed by the hash of the whole request. The [`CachingProvider`] wraps a
+//! provider and answers repeated identical requests from the cache, so
+//! retries and parallel branches re-issuing a prompt do not pay for it twice.
+
+pub mod caching;
+
+pub use caching::CachingProvider;
+
+use crate::inference::cassette::request_key;
+use crate::inference::types::{ChatRequest, ChatResponse, InferenceError};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default maximum number of cached responses
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Extension of cache entry files
+const ENTRY_EXTENSION: &str = "json";
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Seconds since the Unix epoch when the response was stored
+    stored_at: u64,
+    response: ChatResponse,
+}
+
+/// Hit and miss counts and size of a response cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Number of cached responses
+    pub entries: usize,
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that found no valid response
+    pub misses: u64,
+}
+
+/// On-disk cache of inference responses.
+///
+/// Only deterministic requests are cached, see [`is_cacheable`]. Once the
+/// cache holds more than its maximum number of entries, the oldest are
+/// evicted until a tenth of the entries are free again, so the directory is
+/// only scanned once every so many writes.
+///
+/// Every method does blocking file I/O; async callers run them through
+/// `tokio::task::spawn_blocking`.
+#[derive(Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Option<Duration>,
+    max_entries: usize,
+    /// Number of entry files, counted on first use and tracked after that
+    count: Mutex<Option<usize>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    /// Creates a cache stored in `dir`, without expiry
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: None,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            count: Mutex::new(None),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets how long a cached response stays valid
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the maximum number of cached responses
+    #[must_use]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Returns the directory the cache is stored in
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached response to a request, if one is stored and has
+    /// not expired
+    pub fn get(&self, request: &ChatRequest) -> Option<ChatResponse> {
+        let response = self
+            .load(&self.entry_path(request))
+            .map(|entry| entry.response);
+        let counter = if response.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        response
+    }
+
+    /// Stores the response to a request, evicting the oldest entries if the
+    /// cache is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if the entry cannot be written.
+    pub fn put(
+        &self,
+        request: &ChatRequest,
+        response: &ChatResponse,
+    ) -> Result<(), InferenceError> {
+        let mut count = self.count.lock();
+        let path = self.entry_path(request);
+        let entry = CacheEntry {
+            stored_at: now_secs(),
+            response: response.clone(),
+        };
+        let mut write = || -> std::io::Result<()> {
+            let mut entries = self.counted(&mut count)?;
+            std::fs::create_dir_all(&self.dir)?;
+            let replaced = path.exists();
+            let json = serde_json::to_vec(&entry)?;
+            let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+            std::fs::write(&tmp, json)?;
+            std::fs::rename(&tmp, &path)?;
+            if !replaced {
+                entries += 1;
+            }
+            if entries > self.max_entries {
+                entries = self.evict()?;
+            }
+            *count = Some(entries);
+            Ok(())
+        };
+        let written = write();
+        if written.is_err() {
+            // Recount on the next use, since the write may have been partial
+            *count = None;
+        }
+        written.map_err(|e| {
+            InferenceError::ConfigError(format!(
+                "Cannot write response cache {}: {e}",
+                self.dir.display()
+            ))
+        })
+    }
+
+    /// Removes every cached response, returning how many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if the cache cannot be read or
+    /// an entry cannot be removed.
+    pub fn clear(&self) -> Result<usize, InferenceError> {
+        let mut count = self.count.lock();
+        // Recount on the next use if only some entries could be removed
+        *count = None;
+        let clear = || -> std::io::Result<usize> {
+            let entries = self.entries()?;
+            for (path, _) in &entries {
+                std::fs::remove_file(path)?;
+            }
+            Ok(entries.len())
+        };
+        let cleared = clear();
+        if cleared.is_ok() {
+            *count = Some(0);
+        }
+        cleared.map_err(|e| {
+            InferenceError::ConfigError(format!(
+                "Cannot clear response cache {}: {e}",
+                self.dir.display()
+            ))
+        })
+    }
+
+    /// Returns the number of cached responses
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counted(&mut self.count.lock()).unwrap_or(0)
+    }
+
+    /// Returns true if no response is cached
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the hit and miss counts and the number of cached responses
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn entry_path(&self, request: &ChatRequest) -> PathBuf {
+        self.dir
+            .join(request_key(request))
+            .with_extension(ENTRY_EXTENSION)
+    }
+
+    fn load(&self, path: &Path) -> Option<CacheEntry> {
+        let contents = std::fs::read(path).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&contents).ok()?;
+        let age = Duration::from_secs(now_secs().saturating_sub(entry.stored_at));
+        if self.ttl.is_some_and(|ttl| age >= ttl) {
+            let mut count = self.count.lock();
+            if std::fs::remove_file(path).is_ok() {
+                *count = count.map(|entries| entries.saturating_sub(1));
+            }
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Returns the number of entries, counting the entry files if they
+    /// were not counted yet
+    fn counted(&self, count: &mut Option<usize>) -> std::io::Result<usize> {
+        if let Some(entries) = *count {
+            return Ok(entries);
+        }
+        let entries = self.entries()?.len();
+        *count = Some(entries);
+        Ok(entries)
+    }
+
+    /// Lists the entry files with their modification times
+    fn entries(&self) -> std::io::Result<Vec<(PathBuf, SystemTime)>> {
+        let dir = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut entries = Vec::new();
+        for entry in dir {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION) {
+                entries.push((path, entry.metadata()?.modified()?));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Removes the oldest entries until a tenth of the maximum is free,
+    /// returning the number of entries left
+    fn evict(&self) -> std::io::Result<usize> {
+        let mut entries = self.entries()?;
+        let keep = self.max_entries - self.max_entries / 10;
+        if entries.len() <= keep {
+            return Ok(entries.len());
+        }
+        entries.sort_by_key(|(_, modified)| *modified);
+        let excess = entries.len() - keep;
+        for (path, _) in entries.into_iter().take(excess) {
+            std::fs::remove_file(path)?;
+        }
+        Ok(keep)
+    }
+}
+
+/// Returns true if a request may be answered from the cache.
+///
+/// Only requests that explicitly set a temperature of zero are cached.
+/// Requests without a temperature sample with the provider's default, which
+/// is above zero for every supported provider, so they expect varied answers
+/// just like requests with a higher temperature.
+#[must_use]
+pub fn is_cacheable(request: &ChatRequest) -> bool {
+    request
+        .options
+        .temperature
+        .is_some_and(|temperature| temperature <= 0.0)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::RequestOptions;
+
+    #[test]
+    fn test_cache_round_trip_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path().join("cache"));
+        let request = ChatRequest::with_message("gpt-4", "Review this diff");
+
+        assert!(cache.get(&request).is_none());
+        cache.put(&request, &ChatResponse::new("LGTM")).unwrap();
+        assert_eq!(cache.get(&request).unwrap().content, "LGTM");
+
+        let other = ChatRequest::with_message("gpt-4", "Review that diff");
+        assert!(cache.get(&other).is_none());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                entries: 1,
+                hits: 1,
+                misses: 2
+            }
+        );
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expired_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path()).with_ttl(Duration::ZERO);
+        let request = ChatRequest::with_message("gpt-4", "Hi");
+
+        cache.put(&request, &ChatResponse::new("Hello")).unwrap();
+        assert!(cache.get(&request).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path()).with_max_entries(2);
+        let requests: Vec<ChatRequest> = (0..3)
+            .map(|i| ChatRequest::with_message("gpt-4", format!("Question {i}")))
+            .collect();
+
+        for request in &requests {
+            cache.put(request, &ChatResponse::new("Answer")).unwrap();
+            // Modification times order the entries
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&requests[0]).is_none());
+        assert!(cache.get(&requests[2]).is_some());
+    }
+
+    #[test]
+    fn test_eviction_frees_a_tenth_of_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path()).with_max_entries(10);
+        let request = |i| ChatRequest::with_message("gpt-4", format!("Question {i}"));
+
+        for i in 0..10 {
+            cache
+                .put(&request(i), &ChatResponse::new("Answer"))
+                .unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Storing a known request again replaces its entry
+        cache.put(&request(9), &ChatResponse::new("Again")).unwrap();
+        assert_eq!(cache.len(), 10);
+
+        cache
+            .put(&request(10), &ChatResponse::new("Answer"))
+            .unwrap();
+        assert_eq!(cache.len(), 9);
+        assert_eq!(ResponseCache::new(dir.path()).len(), 9);
+        assert!(cache.get(&request(1)).is_none());
+        assert!(cache.get(&request(2)).is_some());
+    }
+
+    #[test]
+    fn test_only_zero_temperature_requests_are_cacheable() {
+        let request = ChatRequest::with_message("gpt-4", "Hi");
+        assert!(!is_cacheable(&request));
+        assert!(is_cacheable(
+            &request
+                .clone()
+                .with_options(RequestOptions::new().with_temperature(0.0))
+        ));
+        assert!(!is_cacheable(
+            &request.with_options(RequestOptions::new().with_temperature(0.7))
+        ));
+    }
+}
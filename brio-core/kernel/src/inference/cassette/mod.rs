@@ -0,0 +1,417 @@
+//! Record and replay of inference traffic.
+//!
+//! A [`Cassette`] is a JSON lines file of request/response pairs: a header
+//! line with the format version, then one line per interaction. The
+//! [`RecordingProvider`] wraps a real provider and appends every exchange to
+//! a cassette; the [`ReplayProvider`] answers from a cassette without any
+//! network access, so agent runs can be reproduced in CI.
+
+pub mod recording;
+pub mod replay;
+
+pub use recording::RecordingProvider;
+pub use replay::ReplayProvider;
+
+use crate::inference::types::{ChatRequest, ChatResponse, InferenceError};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Version of the cassette file format
+pub const CASSETTE_VERSION: u32 = 2;
+
+/// Version of the cassette format storing all interactions in one JSON
+/// document, which can still be loaded
+const LEGACY_CASSETTE_VERSION: u32 = 1;
+
+/// How replayed requests are matched against recorded ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// The whole request must be identical
+    #[default]
+    Strict,
+    /// Only the model and the roles and text of the messages must match,
+    /// ignoring whitespace differences, options, tools and tool call IDs
+    Fuzzy,
+}
+
+/// A recorded request and the response it received.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Hash of the request, see [`request_key`]
+    pub key: String,
+    /// The request sent to the provider
+    pub request: ChatRequest,
+    /// The response the provider returned
+    pub response: ChatResponse,
+}
+
+/// First line of a cassette file.
+#[derive(Serialize, Deserialize)]
+struct CassetteHeader {
+    version: u32,
+}
+
+/// A cassette in the legacy single-document format.
+#[derive(Deserialize)]
+struct LegacyCassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+}
+
+/// A file of recorded inference interactions.
+///
+/// Interactions are kept in recording order. A request recorded several
+/// times is replayed with its responses in order, repeating the last one
+/// once they are used up.
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    interactions: Mutex<Vec<Interaction>>,
+    cursors: Mutex<HashMap<String, usize>>,
+    /// Whether the file holds exactly the recorded interactions in the
+    /// current format, so new ones can be appended to it
+    appendable: Mutex<bool>,
+}
+
+impl Cassette {
+    /// Creates an empty cassette that will be written to `path`
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            interactions: Mutex::new(Vec::new()),
+            cursors: Mutex::new(HashMap::new()),
+            appendable: Mutex::new(false),
+        }
+    }
+
+    /// Loads a cassette from `path`.
+    ///
+    /// Cassettes in the legacy single-document format are loaded too; the
+    /// first interaction recorded to one rewrites it in the current format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if the file cannot be read, is
+    /// not a cassette, or has an unsupported version.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, InferenceError> {
+        let path = path.into();
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            InferenceError::ConfigError(format!("Cannot read cassette {}: {e}", path.display()))
+        })?;
+        let invalid = |e: serde_json::Error| {
+            InferenceError::ConfigError(format!("Invalid cassette {}: {e}", path.display()))
+        };
+
+        let (version, interactions, appendable) =
+            if let Ok(file) = serde_json::from_str::<LegacyCassetteFile>(&contents) {
+                (file.version, file.interactions, false)
+            } else {
+                let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+                let header: CassetteHeader =
+                    serde_json::from_str(lines.next().unwrap_or_default()).map_err(invalid)?;
+                let interactions = lines
+                    .map(serde_json::from_str)
+                    .collect::<Result<Vec<Interaction>, _>>()
+                    .map_err(invalid)?;
+                (header.version, interactions, true)
+            };
+        let expected = if appendable {
+            CASSETTE_VERSION
+        } else {
+            LEGACY_CASSETTE_VERSION
+        };
+        if version != expected {
+            return Err(InferenceError::ConfigError(format!(
+                "Unsupported cassette version {version} in {}",
+                path.display()
+            )));
+        }
+
+        Ok(Self {
+            path,
+            interactions: Mutex::new(interactions),
+            cursors: Mutex::new(HashMap::new()),
+            appendable: Mutex::new(appendable),
+        })
+    }
+
+    /// Loads the cassette at `path` if it exists, or creates an empty one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be loaded.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, InferenceError> {
+        let path = path.into();
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::new(path))
+        }
+    }
+
+    /// Returns the file the cassette is stored in
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of recorded interactions
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.interactions.lock().len()
+    }
+
+    /// Returns true if nothing has been recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.interactions.lock().is_empty()
+    }
+
+    /// Appends an interaction and writes it to disk.
+    ///
+    /// The interaction is appended to the file as one line, so recording
+    /// does not rewrite earlier interactions. The whole file is written
+    /// instead the first time a new or legacy cassette is recorded to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if the cassette cannot be
+    /// written. The interaction is recorded in memory regardless.
+    pub fn record(
+        &self,
+        request: &ChatRequest,
+        response: &ChatResponse,
+    ) -> Result<(), InferenceError> {
+        let interaction = Interaction {
+            key: request_key(request),
+            request: request.clone(),
+            response: response.clone(),
+        };
+        let mut appendable = self.appendable.lock();
+        self.interactions.lock().push(interaction.clone());
+        if !*appendable {
+            return self.write_all(&mut appendable);
+        }
+        let append = || -> std::io::Result<()> {
+            let mut line = serde_json::to_vec(&interaction)?;
+            line.push(b'\n');
+            std::fs::OpenOptions::new()
+                .append(true)
+                .open(&self.path)?
+                .write_all(&line)
+        };
+        append().map_err(|e| {
+            // Rewrite the whole file next time, in case a partial line was written
+            *appendable = false;
+            self.write_error(&e)
+        })
+    }
+
+    /// Writes the cassette to disk, replacing the file atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if the file cannot be written.
+    pub fn save(&self) -> Result<(), InferenceError> {
+        self.write_all(&mut self.appendable.lock())
+    }
+
+    fn write_all(&self, appendable: &mut bool) -> Result<(), InferenceError> {
+        let interactions = self.interactions.lock().clone();
+        let write = || -> std::io::Result<()> {
+            if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut contents = serde_json::to_vec(&CassetteHeader {
+                version: CASSETTE_VERSION,
+            })?;
+            for interaction in &interactions {
+                contents.push(b'\n');
+                contents.extend(serde_json::to_vec(interaction)?);
+            }
+            contents.push(b'\n');
+            let tmp = self
+                .path
+                .with_extension(format!("tmp.{}", std::process::id()));
+            std::fs::write(&tmp, contents)?;
+            std::fs::rename(&tmp, &self.path)
+        };
+        write().map_err(|e| self.write_error(&e))?;
+        *appendable = true;
+        Ok(())
+    }
+
+    fn write_error(&self, error: &std::io::Error) -> InferenceError {
+        InferenceError::ConfigError(format!(
+            "Cannot write cassette {}: {error}",
+            self.path.display()
+        ))
+    }
+
+    /// Finds the recorded response for a request, advancing past it.
+    ///
+    /// Returns `None` if no recorded request matches.
+    pub fn next_response(&self, request: &ChatRequest, mode: MatchMode) -> Option<ChatResponse> {
+        let key = match mode {
+            MatchMode::Strict => request_key(request),
+            MatchMode::Fuzzy => fuzzy_key(request),
+        };
+
+        let interactions = self.interactions.lock();
+        let matches: Vec<&Interaction> = interactions
+            .iter()
+            .filter(|interaction| match mode {
+                MatchMode::Strict => interaction.key == key,
+                MatchMode::Fuzzy => fuzzy_key(&interaction.request) == key,
+            })
+            .collect();
+        let last = matches.len().checked_sub(1)?;
+
+        let mut cursors = self.cursors.lock();
+        let cursor = cursors.entry(key).or_insert(0);
+        let interaction = matches[(*cursor).min(last)];
+        *cursor += 1;
+        Some(interaction.response.clone())
+    }
+}
+
+/// Returns the hash identifying a request: SHA-256 of its JSON form.
+#[must_use]
+pub fn request_key(request: &ChatRequest) -> String {
+    let json = serde_json::to_vec(request).unwrap_or_default();
+    hex::encode(Sha256::digest(json))
+}
+
+/// Returns the hash used for fuzzy matching: the model and the role and
+/// whitespace-normalized text of each message.
+#[must_use]
+pub fn fuzzy_key(request: &ChatRequest) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.model.as_bytes());
+    for message in &request.messages {
+        hasher.update(b"\n");
+        hasher.update(format!("{:?}", message.role).as_bytes());
+        hasher.update(b":");
+        let words: Vec<&str> = message.content.split_whitespace().collect();
+        hasher.update(words.join(" ").as_bytes());
+        for call in &message.tool_calls {
+            hasher.update(b"\n");
+            hasher.update(call.name.as_bytes());
+            hasher.update(call.arguments.as_bytes());
+        }
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::RequestOptions;
+
+    #[test]
+    fn test_keys_distinguish_requests() {
+        let request = ChatRequest::with_message("gpt-4", "Hello  world");
+        let reformatted = ChatRequest::with_message("gpt-4", "Hello world\n")
+            .with_options(RequestOptions::new().with_temperature(0.0));
+
+        assert_eq!(request_key(&request), request_key(&request.clone()));
+        assert_ne!(request_key(&request), request_key(&reformatted));
+        assert_eq!(fuzzy_key(&request), fuzzy_key(&reformatted));
+        assert_ne!(
+            fuzzy_key(&request),
+            fuzzy_key(&ChatRequest::with_message("gpt-4o", "Hello world"))
+        );
+    }
+
+    #[test]
+    fn test_recorded_responses_survive_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/run.json");
+        let request = ChatRequest::with_message("gpt-4", "Plan the work");
+
+        let cassette = Cassette::new(&path);
+        cassette
+            .record(&request, &ChatResponse::new("first"))
+            .unwrap();
+        cassette
+            .record(&request, &ChatResponse::new("second"))
+            .unwrap();
+
+        // Each interaction is appended as one line after the header
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+
+        let replay = Cassette::load(&path).unwrap();
+        assert_eq!(replay.len(), 2);
+        let next = || {
+            replay
+                .next_response(&request, MatchMode::Strict)
+                .map(|r| r.content)
+        };
+        assert_eq!(next().as_deref(), Some("first"));
+        assert_eq!(next().as_deref(), Some("second"));
+        assert_eq!(next().as_deref(), Some("second"));
+
+        let other = ChatRequest::with_message("gpt-4", "Something else");
+        assert!(replay.next_response(&other, MatchMode::Strict).is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        std::fs::write(&path, r#"{"version": 99, "interactions": []}"#).unwrap();
+        assert!(matches!(
+            Cassette::load(&path),
+            Err(InferenceError::ConfigError(_))
+        ));
+        std::fs::write(&path, "{\"version\": 99}\n").unwrap();
+        assert!(matches!(
+            Cassette::load(&path),
+            Err(InferenceError::ConfigError(_))
+        ));
+        assert!(
+            Cassette::open(dir.path().join("missing.json"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_legacy_cassettes_are_rewritten_on_first_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        let request = ChatRequest::with_message("gpt-4", "Plan the work");
+        let legacy = serde_json::json!({
+            "version": 1,
+            "interactions": [{
+                "key": request_key(&request),
+                "request": request,
+                "response": ChatResponse::new("first"),
+            }],
+        });
+        std::fs::write(&path, serde_json::to_string_pretty(&legacy).unwrap()).unwrap();
+
+        let cassette = Cassette::open(&path).unwrap();
+        assert_eq!(cassette.len(), 1);
+        cassette
+            .record(&request, &ChatResponse::new("second"))
+            .unwrap();
+        cassette
+            .record(&request, &ChatResponse::new("third"))
+            .unwrap();
+
+        let replay = Cassette::load(&path).unwrap();
+        let responses: Vec<String> = (0..3)
+            .filter_map(|_| replay.next_response(&request, MatchMode::Strict))
+            .map(|r| r.content)
+            .collect();
+        assert_eq!(responses, ["first", "second", "third"]);
+    }
+}
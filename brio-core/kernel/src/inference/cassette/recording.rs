@@ -0,0 +1,67 @@
+//! Provider that records inference traffic to a cassette.
+
+use crate::inference::cassette::Cassette;
+use crate::inference::provider::LLMProvider;
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// A provider that forwards requests to another provider and records every
+/// successful exchange to a cassette.
+///
+/// Failed requests are not recorded. Streams are collected before they are
+/// recorded and handed back as a single chunk. A response whose interaction
+/// cannot be written to the cassette file is still returned.
+pub struct RecordingProvider {
+    inner: Arc<dyn LLMProvider>,
+    cassette: Arc<Cassette>,
+}
+
+impl RecordingProvider {
+    /// Creates a provider recording the traffic of `inner` to `cassette`
+    #[must_use]
+    pub fn new(inner: Arc<dyn LLMProvider>, cassette: Arc<Cassette>) -> Self {
+        Self { inner, cassette }
+    }
+
+    /// Returns the cassette being recorded to
+    #[must_use]
+    pub fn cassette(&self) -> &Arc<Cassette> {
+        &self.cassette
+    }
+
+    async fn record(&self, request: ChatRequest, response: ChatResponse) {
+        let cassette = self.cassette.clone();
+        let model = request.model.clone();
+        match tokio::task::spawn_blocking(move || cassette.record(&request, &response)).await {
+            Ok(Ok(())) => debug!(
+                model = %model,
+                interactions = self.cassette.len(),
+                "Recorded inference interaction"
+            ),
+            Ok(Err(e)) => warn!(model = %model, "Failed to record inference interaction: {e}"),
+            Err(e) => warn!(model = %model, "Failed to record inference interaction: {e}"),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for RecordingProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        let response = self.inner.chat(request.clone()).await?;
+        self.record(request, response.clone()).await;
+        Ok(response)
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        let response = self
+            .inner
+            .chat_stream(request.clone())
+            .await?
+            .collect_response()
+            .await?;
+        self.record(request, response.clone()).await;
+        Ok(ChatStream::from_response(response))
+    }
+}
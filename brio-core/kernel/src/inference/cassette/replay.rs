@@ -0,0 +1,130 @@
+//! Provider that answers from a recorded cassette.
+
+use crate::inference::cassette::{Cassette, MatchMode, request_key};
+use crate::inference::provider::LLMProvider;
+use crate::inference::types::{ChatRequest, ChatResponse, InferenceError};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::error;
+
+/// A provider that serves responses from a cassette without network access.
+///
+/// Requests with no recorded match fail with a `ProviderError` naming the
+/// request hash, so a diverging run is reported instead of hanging or
+/// silently calling a live model.
+pub struct ReplayProvider {
+    cassette: Arc<Cassette>,
+    mode: MatchMode,
+}
+
+impl ReplayProvider {
+    /// Creates a provider replaying `cassette`
+    #[must_use]
+    pub fn new(cassette: Arc<Cassette>, mode: MatchMode) -> Self {
+        Self { cassette, mode }
+    }
+
+    /// Returns how requests are matched
+    #[must_use]
+    pub fn mode(&self) -> MatchMode {
+        self.mode
+    }
+}
+
+#[async_trait]
+impl LLMProvider for ReplayProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        if let Some(response) = self.cassette.next_response(&request, self.mode) {
+            return Ok(response);
+        }
+
+        let key = request_key(&request);
+        error!(
+            model = %request.model,
+            key = %key,
+            cassette = %self.cassette.path().display(),
+            "No recorded response for inference request"
+        );
+        Err(InferenceError::ProviderError(format!(
+            "Cassette miss: no recorded response for request {key} (model '{}') in {}",
+            request.model,
+            self.cassette.path().display()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::cassette::RecordingProvider;
+    use crate::inference::types::{Message, Role};
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl LLMProvider for EchoProvider {
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+            let last = request.messages.last().map(|m| m.content.clone());
+            Ok(ChatResponse::new(format!(
+                "echo: {}",
+                last.unwrap_or_default()
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replays_recorded_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        let recorder =
+            RecordingProvider::new(Arc::new(EchoProvider), Arc::new(Cassette::new(&path)));
+        recorder
+            .chat(ChatRequest::with_message("gpt-4", "first"))
+            .await
+            .unwrap();
+        let streamed = recorder
+            .chat_stream(ChatRequest::with_message("gpt-4", "second"))
+            .await
+            .unwrap()
+            .collect_response()
+            .await
+            .unwrap();
+        assert_eq!(streamed.content, "echo: second");
+
+        let cassette = Arc::new(Cassette::load(&path).unwrap());
+        let strict = ReplayProvider::new(Arc::clone(&cassette), MatchMode::Strict);
+        let replayed = strict
+            .chat(ChatRequest::with_message("gpt-4", "second"))
+            .await
+            .unwrap();
+        assert_eq!(replayed.content, "echo: second");
+
+        let reworded = ChatRequest::new("gpt-4", vec![Message::new(Role::User, " first\n")]);
+        let miss = strict.chat(reworded.clone()).await;
+        assert!(
+            matches!(&miss, Err(InferenceError::ProviderError(msg)) if msg.contains("Cassette miss")),
+            "Expected cassette miss, got {miss:?}"
+        );
+
+        let fuzzy = ReplayProvider::new(cassette, MatchMode::Fuzzy);
+        assert_eq!(fuzzy.chat(reworded).await.unwrap().content, "echo: first");
+    }
+
+    #[tokio::test]
+    async fn test_recording_failures_do_not_fail_the_request() {
+        let dir = tempfile::tempdir().unwrap();
+        // The cassette's directory is a file, so it can never be written
+        let blocked = dir.path().join("blocked");
+        std::fs::write(&blocked, "").unwrap();
+        let cassette = Arc::new(Cassette::new(blocked.join("session.json")));
+
+        let recorder = RecordingProvider::new(Arc::new(EchoProvider), Arc::clone(&cassette));
+        let response = recorder
+            .chat(ChatRequest::with_message("gpt-4", "first"))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "echo: first");
+        assert_eq!(cassette.len(), 1);
+    }
+}
@@ -0,0 +1,202 @@
+//! Health monitoring of inference providers.
+//!
+//! A [`ProviderHealth`] keeps the outcome and latency of a provider's recent
+//! requests next to its circuit breaker, and exports both as metrics. The
+//! [`MonitoredProvider`] wraps a provider and records every request it
+//! forwards.
+
+pub mod monitored;
+
+pub use monitored::MonitoredProvider;
+
+use crate::inference::types::{CircuitBreaker, CircuitBreakerState, CircuitBreakerStats};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Number of recent requests kept for latency and error rates
+pub const RECENT_WINDOW: usize = 100;
+
+/// Outcome of a finished request.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    latency: Duration,
+    success: bool,
+}
+
+/// Latency and error rate over a provider's recent requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RecentStats {
+    /// Number of requests in the window
+    pub requests: usize,
+    /// Number of those that failed
+    pub errors: usize,
+    /// Share of requests that failed, between 0 and 1
+    pub error_rate: f64,
+    /// Average latency in milliseconds, if any request finished
+    pub average_latency_ms: Option<u64>,
+    /// 95th percentile latency in milliseconds, if any request finished
+    pub p95_latency_ms: Option<u64>,
+}
+
+/// Health of a single registered provider.
+#[derive(Debug)]
+pub struct ProviderHealth {
+    name: String,
+    circuit_breaker: Option<Arc<RwLock<CircuitBreaker>>>,
+    recent: Mutex<VecDeque<Sample>>,
+}
+
+impl ProviderHealth {
+    /// Creates the health record of the provider `name`
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            circuit_breaker: None,
+            recent: Mutex::new(VecDeque::with_capacity(RECENT_WINDOW)),
+        }
+    }
+
+    /// Attaches the circuit breaker guarding the provider
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<RwLock<CircuitBreaker>>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Returns the name of the provider
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the statistics of the circuit breaker, if the provider has one
+    pub async fn circuit(&self) -> Option<CircuitBreakerStats> {
+        match &self.circuit_breaker {
+            Some(circuit_breaker) => Some(circuit_breaker.read().await.stats()),
+            None => None,
+        }
+    }
+
+    /// Opens the circuit breaker, failing requests fast until it is reset
+    /// or its reset timeout elapses. Returns false if there is none.
+    pub async fn trip(&self) -> bool {
+        self.update_circuit(CircuitBreaker::force_open).await
+    }
+
+    /// Closes the circuit breaker, letting requests through again.
+    /// Returns false if there is none.
+    pub async fn reset(&self) -> bool {
+        self.update_circuit(CircuitBreaker::force_close).await
+    }
+
+    async fn update_circuit(&self, update: fn(&mut CircuitBreaker)) -> bool {
+        let Some(circuit_breaker) = &self.circuit_breaker else {
+            return false;
+        };
+        update(&mut *circuit_breaker.write().await);
+        self.export_metrics().await;
+        true
+    }
+
+    /// Records the outcome of a finished request
+    pub fn record(&self, latency: Duration, success: bool) {
+        let mut recent = self.recent.lock();
+        if recent.len() == RECENT_WINDOW {
+            recent.pop_front();
+        }
+        recent.push_back(Sample { latency, success });
+    }
+
+    /// Returns the latency and error rate of the recent requests
+    #[must_use]
+    pub fn recent(&self) -> RecentStats {
+        let recent = self.recent.lock();
+        if recent.is_empty() {
+            return RecentStats::default();
+        }
+
+        let errors = recent.iter().filter(|sample| !sample.success).count();
+        let mut latencies: Vec<Duration> = recent.iter().map(|sample| sample.latency).collect();
+        latencies.sort_unstable();
+        let total: Duration = latencies.iter().sum();
+        let count = u32::try_from(latencies.len()).unwrap_or(u32::MAX);
+        let p95 = latencies[(latencies.len() * 95).div_ceil(100) - 1];
+        let millis = |latency: Duration| u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+
+        #[allow(clippy::cast_precision_loss)]
+        let error_rate = errors as f64 / recent.len() as f64;
+        RecentStats {
+            requests: recent.len(),
+            errors,
+            error_rate,
+            average_latency_ms: Some(millis(total / count)),
+            p95_latency_ms: Some(millis(p95)),
+        }
+    }
+
+    /// Sets the circuit breaker gauges of the provider
+    pub async fn export_metrics(&self) {
+        let Some(stats) = self.circuit().await else {
+            return;
+        };
+        let provider = self.name.clone();
+        metrics::gauge!("brio_inference_circuit_state", "provider" => provider.clone())
+            .set(stats.state.gauge_value());
+        metrics::gauge!(
+            "brio_inference_circuit_consecutive_failures",
+            "provider" => provider
+        )
+        .set(f64::from(stats.consecutive_failures));
+    }
+
+    /// Returns true if the circuit breaker currently fails requests fast
+    pub async fn is_open(&self) -> bool {
+        self.circuit()
+            .await
+            .is_some_and(|stats| stats.state == CircuitBreakerState::Open)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_stats_cover_the_window() {
+        let health = ProviderHealth::new("openai");
+        assert_eq!(health.recent(), RecentStats::default());
+
+        for ms in 1..=RECENT_WINDOW as u64 + 20 {
+            health.record(Duration::from_millis(ms), ms % 10 != 0);
+        }
+
+        let stats = health.recent();
+        assert_eq!(stats.requests, RECENT_WINDOW);
+        assert_eq!(stats.errors, 10);
+        assert!((stats.error_rate - 0.1).abs() < f64::EPSILON);
+        assert_eq!(stats.p95_latency_ms, Some(115));
+        assert_eq!(stats.average_latency_ms, Some(70));
+    }
+
+    #[tokio::test]
+    async fn test_trip_and_reset_circuit() {
+        let breaker = Arc::new(RwLock::new(CircuitBreaker::default()));
+        let health = ProviderHealth::new("anthropic").with_circuit_breaker(Arc::clone(&breaker));
+
+        assert!(health.trip().await);
+        assert!(health.is_open().await);
+        assert!(!breaker.write().await.try_acquire());
+
+        assert!(health.reset().await);
+        assert_eq!(
+            health.circuit().await.map(|stats| stats.state),
+            Some(CircuitBreakerState::Closed)
+        );
+        assert!(!ProviderHealth::new("chain").trip().await);
+    }
+}
@@ -0,0 +1,95 @@
+//! Provider that records the health of the requests it forwards.
+
+use crate::inference::health::ProviderHealth;
+use crate::inference::provider::LLMProvider;
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+/// A provider that records the latency and outcome of every request to
+/// another provider.
+///
+/// Streams are measured until they are opened, not until they finish.
+pub struct MonitoredProvider {
+    inner: Arc<dyn LLMProvider>,
+    health: Arc<ProviderHealth>,
+}
+
+impl MonitoredProvider {
+    /// Creates a provider recording the requests to `inner` in `health`
+    #[must_use]
+    pub fn new(inner: Arc<dyn LLMProvider>, health: Arc<ProviderHealth>) -> Self {
+        Self { inner, health }
+    }
+
+    async fn finish<T>(
+        &self,
+        started: Instant,
+        result: Result<T, InferenceError>,
+    ) -> Result<T, InferenceError> {
+        let latency = started.elapsed();
+        let outcome = if result.is_ok() { "success" } else { "error" };
+        metrics::histogram!(
+            "brio_inference_request_duration_seconds",
+            "provider" => self.health.name().to_string(),
+            "outcome" => outcome
+        )
+        .record(latency.as_secs_f64());
+        self.health.record(latency, result.is_ok());
+        self.health.export_metrics().await;
+        result
+    }
+}
+
+#[async_trait]
+impl LLMProvider for MonitoredProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        let started = Instant::now();
+        let result = self.inner.chat(request).await;
+        self.finish(started, result).await
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        let started = Instant::now();
+        let result = self.inner.chat_stream(request).await;
+        self.finish(started, result).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FlakyProvider;
+
+    #[async_trait]
+    impl LLMProvider for FlakyProvider {
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+            if request.model == "broken" {
+                Err(InferenceError::ProviderError("HTTP 500".to_string()))
+            } else {
+                Ok(ChatResponse::new("ok"))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_recorded() {
+        let health = Arc::new(ProviderHealth::new("openai"));
+        let provider = MonitoredProvider::new(Arc::new(FlakyProvider), Arc::clone(&health));
+
+        provider
+            .chat(ChatRequest::with_message("gpt-4", "Hi"))
+            .await
+            .unwrap();
+        provider
+            .chat(ChatRequest::with_message("broken", "Hi"))
+            .await
+            .unwrap_err();
+
+        let stats = health.recent();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.errors, 1);
+    }
+}
@@ -0,0 +1,498 @@
+//! Local model server HTTP client implementation.
+//!
+//! This module provides the HTTP client for model servers running next to
+//! the kernel: Ollama through its native API, and llama.cpp server through
+//! its `OpenAI`-compatible API.
+
+use crate::inference::local::mapping::{
+    LocalModel, OllamaChatResponse, OllamaTagsResponse, OpenAIModelsResponse, create_request,
+    map_response, validate_options,
+};
+use crate::inference::local::streaming::{OllamaStreamParser, line_stream};
+use crate::inference::openai::{
+    DEFAULT_MAX_RETRIES, OpenAIChatResponse, OpenAIStreamParser, RetryConfig,
+    create_request as create_openai_request, create_stream_request as create_openai_stream_request,
+    map_response as map_openai_response,
+};
+use crate::inference::provider::LLMProvider;
+use crate::inference::ratelimit::RateLimiter;
+use crate::inference::sse::{DEFAULT_CHUNK_TIMEOUT_MS, event_stream};
+use crate::inference::types::{
+    ChatRequest, ChatResponse, ChatStream, CircuitBreaker, CircuitBreakerConfig, InferenceError,
+};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// Default base URL of an Ollama server
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434/";
+/// Default base URL of a llama.cpp server
+pub const DEFAULT_LLAMA_CPP_URL: &str = "http://localhost:8080/";
+
+/// The kind of local model server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalServerKind {
+    /// Ollama, using its native `/api/chat` endpoint
+    Ollama,
+    /// llama.cpp server, using its `OpenAI`-compatible endpoint
+    LlamaCpp,
+}
+
+impl LocalServerKind {
+    /// Returns the URL the server listens on by default
+    #[must_use]
+    pub const fn default_base_url(self) -> &'static str {
+        match self {
+            Self::Ollama => DEFAULT_OLLAMA_URL,
+            Self::LlamaCpp => DEFAULT_LLAMA_CPP_URL,
+        }
+    }
+
+    const fn chat_path(self) -> &'static str {
+        match self {
+            Self::Ollama => "api/chat",
+            Self::LlamaCpp => "v1/chat/completions",
+        }
+    }
+
+    const fn models_path(self) -> &'static str {
+        match self {
+            Self::Ollama => "api/tags",
+            Self::LlamaCpp => "v1/models",
+        }
+    }
+}
+
+/// Configuration for the local provider
+pub struct LocalConfig {
+    /// The kind of server
+    pub server: LocalServerKind,
+    /// The base URL of the server
+    pub base_url: Url,
+    /// API key, for llama.cpp servers started with `--api-key`
+    pub api_key: Option<SecretString>,
+    /// How long Ollama keeps the model loaded after a request (e.g. "30m", "-1")
+    pub keep_alive: Option<String>,
+    /// Maximum number of retries for transient errors
+    pub max_retries: Option<u32>,
+    /// Base delay in milliseconds for exponential backoff
+    pub base_delay_ms: Option<u64>,
+    /// Circuit breaker configuration for resilience
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Maximum time to wait for the next chunk of a stream, in milliseconds
+    pub chunk_timeout_ms: Option<u64>,
+    /// Limiter told about the rate limit headers of each response
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl LocalConfig {
+    /// Creates a new config with default settings
+    #[must_use]
+    pub fn new(server: LocalServerKind, base_url: Url) -> Self {
+        Self {
+            server,
+            base_url,
+            api_key: None,
+            keep_alive: None,
+            max_retries: None,
+            base_delay_ms: None,
+            circuit_breaker: None,
+            chunk_timeout_ms: None,
+            rate_limiter: None,
+        }
+    }
+
+    /// Creates a config for a server on its default local URL
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hardcoded URL is invalid.
+    pub fn with_default_url(server: LocalServerKind) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            server,
+            Url::parse(server.default_base_url())
+                .map_err(|e| anyhow::anyhow!("Invalid hardcoded URL: {e}"))?,
+        ))
+    }
+
+    /// Sets the API key
+    #[must_use]
+    pub fn with_api_key(mut self, api_key: SecretString) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Sets how long Ollama keeps the model loaded after a request
+    #[must_use]
+    pub fn with_keep_alive(mut self, keep_alive: impl Into<String>) -> Self {
+        self.keep_alive = Some(keep_alive.into());
+        self
+    }
+
+    /// Sets the maximum number of retries
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Sets the base delay for exponential backoff
+    #[must_use]
+    pub fn with_base_delay_ms(mut self, delay_ms: u64) -> Self {
+        self.base_delay_ms = Some(delay_ms);
+        self
+    }
+
+    /// Sets the circuit breaker configuration
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    /// Sets the maximum time to wait for the next chunk of a stream
+    #[must_use]
+    pub fn with_chunk_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.chunk_timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Sets the limiter told about the rate limit headers of each response
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+}
+
+/// Provider implementation for local model servers.
+pub struct LocalProvider {
+    client: Client,
+    config: LocalConfig,
+    retry_config: RetryConfig,
+    chunk_timeout: Duration,
+    circuit_breaker: Arc<RwLock<CircuitBreaker>>,
+}
+
+impl LocalProvider {
+    /// Creates a new local provider with the given configuration.
+    #[must_use]
+    pub fn new(config: LocalConfig) -> Self {
+        let max_retries = config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let base_delay_ms = config.base_delay_ms.unwrap_or(1000);
+        let cb_config = config.circuit_breaker.unwrap_or_default();
+        let chunk_timeout =
+            Duration::from_millis(config.chunk_timeout_ms.unwrap_or(DEFAULT_CHUNK_TIMEOUT_MS));
+
+        Self {
+            client: Client::new(),
+            retry_config: RetryConfig::new()
+                .with_max_retries(max_retries)
+                .with_base_delay_ms(base_delay_ms),
+            chunk_timeout,
+            config,
+            circuit_breaker: Arc::new(RwLock::new(CircuitBreaker::new(cb_config))),
+        }
+    }
+
+    /// Returns the kind of server this provider talks to
+    #[must_use]
+    pub fn server(&self) -> LocalServerKind {
+        self.config.server
+    }
+
+    /// Lists the models available on the server.
+    ///
+    /// For Ollama these are the pulled models; llama.cpp server reports the
+    /// model it was started with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached or the listing cannot
+    /// be parsed.
+    pub async fn list_models(&self) -> Result<Vec<LocalModel>, InferenceError> {
+        let url = self.url(self.config.server.models_path())?;
+        let res = self
+            .authorize(self.client.get(url))
+            .send()
+            .await
+            .map_err(|e| InferenceError::NetworkError(e.to_string()))?;
+        if res.status() != StatusCode::OK {
+            return Err(Self::map_error_response(res).await.0);
+        }
+
+        match self.config.server {
+            LocalServerKind::Ollama => {
+                let body: OllamaTagsResponse = Self::parse_body(res).await.map_err(|e| e.0)?;
+                Ok(body.models)
+            }
+            LocalServerKind::LlamaCpp => {
+                let body: OpenAIModelsResponse = Self::parse_body(res).await.map_err(|e| e.0)?;
+                Ok(body
+                    .data
+                    .into_iter()
+                    .map(|model| LocalModel {
+                        name: model.id,
+                        size: None,
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    fn url(&self, path: &str) -> Result<Url, InferenceError> {
+        self.config
+            .base_url
+            .join(path)
+            .map_err(|e| InferenceError::ConfigError(format!("Invalid URL join: {e}")))
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.config.api_key {
+            Some(key) => request.header("Authorization", format!("Bearer {}", key.expose_secret())),
+            None => request,
+        }
+    }
+
+    /// Returns the circuit breaker guarding requests, shared with monitoring
+    #[must_use]
+    pub fn circuit_breaker(&self) -> Arc<RwLock<CircuitBreaker>> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
+    /// Sends a request body to the chat endpoint
+    async fn send<B: Serialize>(
+        &self,
+        body: &B,
+    ) -> Result<reqwest::Response, (InferenceError, bool)> {
+        let url = self
+            .url(self.config.server.chat_path())
+            .map_err(|e| (e, false))?;
+
+        let res = self
+            .authorize(self.client.post(url))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                (
+                    InferenceError::NetworkError(e.to_string()),
+                    true, // Retry network errors, e.g. a server that is still starting
+                )
+            })?;
+        if let Some(limiter) = &self.config.rate_limiter {
+            limiter.observe(res.headers());
+        }
+        Ok(res)
+    }
+
+    /// Makes a single request attempt, parsing the response body
+    async fn make_request<B: Serialize, T: DeserializeOwned>(
+        &self,
+        body: &B,
+    ) -> Result<T, (InferenceError, bool)> {
+        let res = self.send(body).await?;
+        if res.status() == StatusCode::OK {
+            Self::parse_body(res).await
+        } else {
+            Err(Self::map_error_response(res).await)
+        }
+    }
+
+    /// Starts a streaming request, returning the response once its status is OK
+    async fn open_stream<B: Serialize>(
+        &self,
+        body: &B,
+    ) -> Result<reqwest::Response, (InferenceError, bool)> {
+        let res = self.send(body).await?;
+        if res.status() == StatusCode::OK {
+            Ok(res)
+        } else {
+            Err(Self::map_error_response(res).await)
+        }
+    }
+
+    async fn parse_body<T: DeserializeOwned>(
+        res: reqwest::Response,
+    ) -> Result<T, (InferenceError, bool)> {
+        res.json().await.map_err(|e| {
+            (
+                InferenceError::ProviderError(format!("Parse error: {e}")),
+                false,
+            )
+        })
+    }
+
+    /// Runs `attempt` with retries, guarded by the circuit breaker
+    async fn with_retries<T, F, Fut>(&self, attempt_fn: F) -> Result<T, InferenceError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, (InferenceError, bool)>>,
+    {
+        let can_execute = {
+            let mut cb = self.circuit_breaker.write().await;
+            cb.try_acquire()
+        };
+
+        if !can_execute {
+            return Err(InferenceError::CircuitBreakerOpen(
+                "Local model server circuit is open".to_string(),
+            ));
+        }
+
+        let mut last_error = InferenceError::NetworkError("No attempts made".to_string());
+
+        for attempt in 0..=self.retry_config.max_retries {
+            match attempt_fn().await {
+                Ok(response) => {
+                    let mut cb = self.circuit_breaker.write().await;
+                    cb.record_success();
+                    return Ok(response);
+                }
+                Err((error, should_retry)) => {
+                    last_error = error;
+
+                    if !should_retry || attempt >= self.retry_config.max_retries {
+                        break;
+                    }
+
+                    // Wait at least as long as the provider asked us to
+                    let backoff = self.retry_config.calculate_backoff_delay(attempt);
+                    let delay = self
+                        .config
+                        .rate_limiter
+                        .as_ref()
+                        .and_then(|limiter| limiter.retry_delay())
+                        .map_or(backoff, |asked| asked.max(backoff));
+                    let delay_ms: u64 = delay.as_millis().try_into().unwrap_or(u64::MAX);
+                    warn!(
+                        attempt = attempt + 1,
+                        max_retries = self.retry_config.max_retries,
+                        delay_ms = delay_ms,
+                        error = %last_error,
+                        "Local model server request failed, retrying after backoff"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        let mut cb = self.circuit_breaker.write().await;
+        cb.record_failure();
+
+        debug!(
+            attempts = self.retry_config.max_retries + 1,
+            "All local model server retry attempts exhausted"
+        );
+        Err(last_error)
+    }
+
+    /// Maps an unsuccessful response to an error and whether to retry it
+    async fn map_error_response(res: reqwest::Response) -> (InferenceError, bool) {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => (InferenceError::RateLimit, true),
+            StatusCode::BAD_REQUEST if text.contains("context") => {
+                (InferenceError::ContextLengthExceeded, false)
+            }
+            // Ollama answers 503 while its request queue is full
+            StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => (
+                InferenceError::ProviderError(format!("HTTP {status}: {text}")),
+                true,
+            ),
+            _ => (
+                InferenceError::ProviderError(format!("HTTP {status}: {text}")),
+                false,
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl LLMProvider for LocalProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        validate_options(&request.options)?;
+        match self.config.server {
+            LocalServerKind::Ollama => {
+                let provider_req = create_request(request, false, self.config.keep_alive.clone());
+                self.with_retries(|| async {
+                    let body: OllamaChatResponse = self.make_request(&provider_req).await?;
+                    map_response(body).map_err(|msg| (InferenceError::ProviderError(msg), false))
+                })
+                .await
+            }
+            LocalServerKind::LlamaCpp => {
+                let provider_req = create_openai_request(request);
+                self.with_retries(|| async {
+                    let body: OpenAIChatResponse = self.make_request(&provider_req).await?;
+                    map_openai_response(body)
+                        .map_err(|msg| (InferenceError::ProviderError(msg), false))
+                })
+                .await
+            }
+        }
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        validate_options(&request.options)?;
+        match self.config.server {
+            LocalServerKind::Ollama => {
+                let provider_req = create_request(request, true, self.config.keep_alive.clone());
+                let response = self
+                    .with_retries(|| self.open_stream(&provider_req))
+                    .await?;
+
+                let mut parser = OllamaStreamParser::new();
+                Ok(line_stream(response, self.chunk_timeout, move |line| {
+                    parser.handle(&line)
+                }))
+            }
+            LocalServerKind::LlamaCpp => {
+                let provider_req = create_openai_stream_request(request);
+                let response = self
+                    .with_retries(|| self.open_stream(&provider_req))
+                    .await?;
+
+                let mut parser = OpenAIStreamParser::new();
+                Ok(event_stream(response, self.chunk_timeout, move |event| {
+                    parser.handle(&event)
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_config_defaults() -> anyhow::Result<()> {
+        let config = LocalConfig::with_default_url(LocalServerKind::Ollama)?
+            .with_keep_alive("30m")
+            .with_max_retries(1);
+        assert_eq!(config.base_url.as_str(), DEFAULT_OLLAMA_URL);
+        assert_eq!(config.keep_alive.as_deref(), Some("30m"));
+
+        let provider = LocalProvider::new(config);
+        assert_eq!(provider.server(), LocalServerKind::Ollama);
+        assert_eq!(provider.retry_config.max_retries, 1);
+        assert_eq!(
+            provider.url("api/chat")?.as_str(),
+            "http://localhost:11434/api/chat"
+        );
+        Ok(())
+    }
+}
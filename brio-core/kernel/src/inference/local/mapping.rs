@@ -0,0 +1,355 @@
+//! Local model server type mapping.
+//!
+//! This module provides types for mapping between internal and Ollama API
+//! formats, and for the model listings of both Ollama and llama.cpp server.
+//! llama.cpp server speaks the `OpenAI` chat completions format, so its
+//! requests reuse the `OpenAI` mapping.
+
+use crate::inference::openai::OpenAITool;
+use crate::inference::types::{
+    ChatRequest, ChatResponse, InferenceError, Message, RequestOptions, ResponseFormat, Role,
+    ToolCall, Usage,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Highest sampling temperature accepted by local model servers
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+/// Ollama API chat request format
+#[derive(Serialize)]
+pub struct OllamaChatRequest {
+    /// The model identifier (e.g., "llama3.1:8b")
+    pub model: String,
+    /// The conversation messages
+    pub messages: Vec<OllamaMessage>,
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<OpenAITool>,
+    /// Whether to stream the completion as newline-delimited JSON
+    pub stream: bool,
+    /// Output format, sent only for JSON mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Sampling options
+    #[serde(skip_serializing_if = "OllamaOptions::is_empty")]
+    pub options: OllamaOptions,
+    /// How long the model stays loaded after the request (e.g. "10m", "-1")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<String>,
+}
+
+/// Ollama API sampling options
+#[derive(Serialize, Default)]
+pub struct OllamaOptions {
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+    /// Sequences that stop generation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Seed for deterministic sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl OllamaOptions {
+    /// Returns true if no option is set
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none()
+            && self.top_p.is_none()
+            && self.num_predict.is_none()
+            && self.stop.is_empty()
+            && self.seed.is_none()
+    }
+}
+
+impl From<RequestOptions> for OllamaOptions {
+    fn from(options: RequestOptions) -> Self {
+        Self {
+            temperature: options.temperature,
+            top_p: options.top_p,
+            num_predict: options.max_tokens,
+            stop: options.stop,
+            seed: options.seed,
+        }
+    }
+}
+
+/// Ollama API message format
+#[derive(Serialize, Deserialize)]
+pub struct OllamaMessage {
+    /// The role of the message sender
+    pub role: Role,
+    /// The message text
+    #[serde(default)]
+    pub content: String,
+    /// Tool calls made by the assistant
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<OllamaToolCall>,
+}
+
+/// Ollama API tool call format
+#[derive(Serialize, Deserialize)]
+pub struct OllamaToolCall {
+    /// The function being called
+    pub function: OllamaFunctionCall,
+}
+
+/// Ollama API function call details
+#[derive(Serialize, Deserialize)]
+pub struct OllamaFunctionCall {
+    /// Name of the function
+    pub name: String,
+    /// Arguments as a JSON object
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl From<Message> for OllamaMessage {
+    fn from(message: Message) -> Self {
+        Self {
+            role: message.role,
+            content: message.content,
+            tool_calls: message.tool_calls.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<&ToolCall> for OllamaToolCall {
+    fn from(call: &ToolCall) -> Self {
+        // Ollama expects an object; pass unparseable arguments through as a string
+        let arguments = call
+            .parsed_arguments()
+            .unwrap_or_else(|_| Value::String(call.arguments.clone()));
+        Self {
+            function: OllamaFunctionCall {
+                name: call.name.clone(),
+                arguments,
+            },
+        }
+    }
+}
+
+/// Ollama API chat response format, also used for each streamed line
+#[derive(Deserialize)]
+pub struct OllamaChatResponse {
+    /// The generated message, or the delta of a streamed line
+    #[serde(default)]
+    pub message: Option<OllamaMessage>,
+    /// Whether generation has finished
+    #[serde(default)]
+    pub done: bool,
+    /// Why generation stopped, set once done
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    /// Number of tokens in the prompt
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    /// Number of tokens generated
+    #[serde(default)]
+    pub eval_count: Option<u32>,
+    /// Error reported by the server
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl OllamaChatResponse {
+    /// Returns the token usage, if the server reported it
+    #[must_use]
+    pub fn usage(&self) -> Option<Usage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        let prompt_tokens = self.prompt_eval_count.unwrap_or(0);
+        let completion_tokens = self.eval_count.unwrap_or(0);
+        Some(Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens.saturating_add(completion_tokens),
+        })
+    }
+}
+
+/// A model available on a local model server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalModel {
+    /// Name to request the model by
+    pub name: String,
+    /// Size of the model weights in bytes, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// Ollama API model listing (`/api/tags`)
+#[derive(Deserialize)]
+pub struct OllamaTagsResponse {
+    /// Models pulled on the server
+    #[serde(default)]
+    pub models: Vec<LocalModel>,
+}
+
+/// A model entry of an `OpenAI`-compatible model listing
+#[derive(Deserialize)]
+pub struct OpenAIModelEntry {
+    /// Model identifier
+    pub id: String,
+}
+
+/// `OpenAI`-compatible model listing (`/v1/models`), served by llama.cpp
+#[derive(Deserialize)]
+pub struct OpenAIModelsResponse {
+    /// Models loaded on the server
+    #[serde(default)]
+    pub data: Vec<OpenAIModelEntry>,
+}
+
+/// Checks request options against the local server limits
+///
+/// # Errors
+///
+/// Returns `InferenceError::InvalidRequest` if an option is out of range.
+pub fn validate_options(options: &RequestOptions) -> Result<(), InferenceError> {
+    options.validate(MAX_TEMPERATURE)
+}
+
+/// Creates an Ollama API request from internal types
+#[must_use]
+pub fn create_request(
+    request: ChatRequest,
+    stream: bool,
+    keep_alive: Option<String>,
+) -> OllamaChatRequest {
+    let format = match request.options.response_format {
+        ResponseFormat::Text => None,
+        ResponseFormat::Json => Some("json".to_string()),
+    };
+
+    OllamaChatRequest {
+        model: request.model,
+        messages: request.messages.into_iter().map(Into::into).collect(),
+        tools: request.tools.into_iter().map(Into::into).collect(),
+        stream,
+        format,
+        options: request.options.into(),
+        keep_alive,
+    }
+}
+
+/// Maps an Ollama API response to internal `ChatResponse`
+///
+/// Ollama does not assign tool call identifiers, so calls are numbered in
+/// the order they were made.
+///
+/// # Errors
+///
+/// Returns an error if the response carries an error or no message.
+pub fn map_response(body: OllamaChatResponse) -> Result<ChatResponse, String> {
+    if let Some(error) = body.error {
+        return Err(error);
+    }
+    let usage = body.usage();
+    let message = body
+        .message
+        .ok_or_else(|| "No message returned".to_string())?;
+
+    Ok(ChatResponse {
+        content: message.content,
+        usage,
+        tool_calls: message
+            .tool_calls
+            .into_iter()
+            .enumerate()
+            .map(|(idx, call)| map_tool_call(idx, call))
+            .collect(),
+    })
+}
+
+/// Maps the `idx`th tool call of a response to an internal `ToolCall`
+#[must_use]
+pub fn map_tool_call(idx: usize, call: OllamaToolCall) -> ToolCall {
+    let arguments = match call.function.arguments {
+        Value::String(arguments) => arguments,
+        Value::Null => String::new(),
+        arguments => arguments.to_string(),
+    };
+    ToolCall::new(format!("call_{idx}"), call.function.name, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::types::ToolDefinition;
+
+    #[test]
+    fn test_create_request_with_options() {
+        let options = RequestOptions::new()
+            .with_temperature(0.2)
+            .with_max_tokens(64)
+            .with_stop(vec!["END".to_string()])
+            .with_response_format(ResponseFormat::Json);
+        let request = ChatRequest::with_message("llama3.1", "Hi")
+            .with_options(options)
+            .with_tools(vec![ToolDefinition::new(
+                "done",
+                "Finish",
+                serde_json::json!({"type": "object"}),
+            )]);
+
+        let json =
+            serde_json::to_value(create_request(request, false, Some("10m".to_string()))).unwrap();
+        assert_eq!(json["stream"], false);
+        assert_eq!(json["format"], "json");
+        assert_eq!(json["keep_alive"], "10m");
+        assert_eq!(json["options"]["num_predict"], 64);
+        assert_eq!(json["options"]["stop"][0], "END");
+        assert!(json["options"].get("seed").is_none());
+        assert_eq!(json["tools"][0]["function"]["name"], "done");
+
+        let plain = create_request(ChatRequest::with_message("llama3.1", "Hi"), true, None);
+        let json = serde_json::to_value(plain).unwrap();
+        assert!(json.get("options").is_none());
+        assert!(json.get("format").is_none());
+        assert!(json.get("keep_alive").is_none());
+    }
+
+    #[test]
+    fn test_tool_call_arguments_are_sent_as_objects() {
+        let message = Message::tool_calls(
+            "",
+            vec![ToolCall::new("call_0", "read_file", r#"{"path":"a.rs"}"#)],
+        );
+        let json = serde_json::to_value(OllamaMessage::from(message)).unwrap();
+        assert_eq!(
+            json["tool_calls"][0]["function"]["arguments"]["path"],
+            "a.rs"
+        );
+    }
+
+    #[test]
+    fn test_map_response_numbers_tool_calls() {
+        let body: OllamaChatResponse = serde_json::from_value(serde_json::json!({
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": "read_file", "arguments": {"path": "a.rs"}}}]
+            },
+            "done": true,
+            "prompt_eval_count": 10,
+            "eval_count": 5
+        }))
+        .unwrap();
+
+        let response = map_response(body).unwrap();
+        assert_eq!(response.tool_calls[0].id, "call_0");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"path":"a.rs"}"#);
+        assert_eq!(response.usage.unwrap().total_tokens, 15);
+    }
+}
@@ -0,0 +1,20 @@
+//! Local model server provider implementation.
+//!
+//! This module provides integration with model servers running on the
+//! developer's machine, so agents can run without a hosted API: Ollama
+//! through its native chat API and llama.cpp server through its
+//! `OpenAI`-compatible API.
+
+pub mod client;
+pub mod mapping;
+pub mod streaming;
+
+pub use client::{
+    DEFAULT_LLAMA_CPP_URL, DEFAULT_OLLAMA_URL, LocalConfig, LocalProvider, LocalServerKind,
+};
+pub use mapping::{
+    LocalModel, MAX_TEMPERATURE, OllamaChatRequest, OllamaChatResponse, OllamaFunctionCall,
+    OllamaMessage, OllamaOptions, OllamaTagsResponse, OllamaToolCall, OpenAIModelEntry,
+    OpenAIModelsResponse, create_request, map_response, validate_options,
+};
+pub use streaming::{NdjsonDecoder, OllamaStreamParser, line_stream};
@@ -0,0 +1,239 @@
+//! Local model server streaming support.
+//!
+//! Ollama streams chat completions as newline-delimited JSON rather than
+//! Server-Sent Events. This module decodes such bodies into lines and turns
+//! them into a [`ChatStream`]; llama.cpp server streams SSE and reuses the
+//! `OpenAI` parser instead.
+
+use crate::inference::local::mapping::{OllamaChatResponse, map_tool_call};
+use crate::inference::sse::StreamStep;
+use crate::inference::types::{ChatChunk, ChatStream, InferenceError, ToolCall};
+use futures_util::stream;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Incremental decoder for newline-delimited JSON bodies.
+///
+/// Bytes may be pushed in arbitrary pieces; lines are returned once their
+/// terminating newline has been received. Blank lines are skipped.
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonDecoder {
+    /// Creates a decoder with an empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds bytes into the decoder and returns all completed lines
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if !line.is_empty() {
+                lines.push(line.to_string());
+            }
+        }
+        lines
+    }
+
+    /// Returns the unterminated last line, if any
+    pub fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&self.buffer).trim().to_string();
+        self.buffer.clear();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Parses Ollama stream lines into chat chunks.
+///
+/// Tool calls arrive whole on intermediate lines and are reported on the
+/// final chunk.
+#[derive(Debug, Default)]
+pub struct OllamaStreamParser {
+    tool_calls: Vec<ToolCall>,
+}
+
+impl OllamaStreamParser {
+    /// Creates a parser for a new stream
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles a single line of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line cannot be parsed or reports an error.
+    pub fn handle(&mut self, line: &str) -> Result<StreamStep, InferenceError> {
+        let chunk: OllamaChatResponse = serde_json::from_str(line)
+            .map_err(|e| InferenceError::ProviderError(format!("Parse error: {e}")))?;
+        if let Some(error) = chunk.error {
+            return Err(InferenceError::ProviderError(error));
+        }
+
+        let usage = chunk.usage();
+        let delta = match chunk.message {
+            Some(message) => {
+                for call in message.tool_calls {
+                    let call = map_tool_call(self.tool_calls.len(), call);
+                    self.tool_calls.push(call);
+                }
+                message.content
+            }
+            None => String::new(),
+        };
+
+        if chunk.done {
+            return Ok(StreamStep::Done(Some(ChatChunk {
+                delta,
+                usage,
+                finish_reason: chunk.done_reason.or_else(|| Some("stop".to_string())),
+                tool_calls: std::mem::take(&mut self.tool_calls),
+            })));
+        }
+
+        if delta.is_empty() {
+            Ok(StreamStep::Skip)
+        } else {
+            Ok(StreamStep::Chunk(ChatChunk::delta(delta)))
+        }
+    }
+}
+
+struct LineStreamState<F> {
+    response: reqwest::Response,
+    decoder: NdjsonDecoder,
+    pending: VecDeque<String>,
+    handle: F,
+    chunk_timeout: Duration,
+    finished: bool,
+}
+
+/// Turns a newline-delimited JSON HTTP response into a [`ChatStream`].
+///
+/// Each line is passed to `handle`. The stream ends after
+/// [`StreamStep::Done`] or the first error; a body that ends before the
+/// handler reports completion yields a network error.
+pub fn line_stream<F>(response: reqwest::Response, chunk_timeout: Duration, handle: F) -> ChatStream
+where
+    F: FnMut(String) -> Result<StreamStep, InferenceError> + Send + 'static,
+{
+    let state = LineStreamState {
+        response,
+        decoder: NdjsonDecoder::new(),
+        pending: VecDeque::new(),
+        handle,
+        chunk_timeout,
+        finished: false,
+    };
+
+    ChatStream::new(stream::unfold(state, |mut state| async move {
+        loop {
+            if state.finished {
+                return None;
+            }
+
+            if let Some(line) = state.pending.pop_front() {
+                match (state.handle)(line) {
+                    Ok(StreamStep::Chunk(chunk)) => return Some((Ok(chunk), state)),
+                    Ok(StreamStep::Skip) => continue,
+                    Ok(StreamStep::Done(chunk)) => {
+                        state.finished = true;
+                        return chunk.map(|chunk| (Ok(chunk), state));
+                    }
+                    Err(error) => {
+                        state.finished = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+
+            let next = tokio::time::timeout(state.chunk_timeout, state.response.chunk()).await;
+            let error = match next {
+                Ok(Ok(Some(bytes))) => {
+                    let lines = state.decoder.push(&bytes);
+                    state.pending.extend(lines);
+                    continue;
+                }
+                Ok(Ok(None)) => {
+                    if let Some(line) = state.decoder.finish() {
+                        state.pending.push_back(line);
+                        continue;
+                    }
+                    InferenceError::NetworkError("Stream ended before completion".to_string())
+                }
+                Ok(Err(e)) => InferenceError::NetworkError(e.to_string()),
+                Err(_) => InferenceError::NetworkError(format!(
+                    "No stream data received for {} ms",
+                    state.chunk_timeout.as_millis()
+                )),
+            };
+            state.finished = true;
+            return Some((Err(error), state));
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_lines_split_across_pushes() {
+        let mut decoder = NdjsonDecoder::new();
+        assert!(decoder.push(b"{\"a\":").is_empty());
+        let lines = decoder.push(b"1}\n\n{\"b\":2}\r\n{\"c\"");
+        assert_eq!(lines, vec!["{\"a\":1}", "{\"b\":2}"]);
+        assert_eq!(decoder.finish().as_deref(), Some("{\"c\""));
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn test_parser_reports_usage_on_done() {
+        let mut parser = OllamaStreamParser::new();
+        let step = parser
+            .handle(r#"{"message":{"role":"assistant","content":"Hi"},"done":false}"#)
+            .unwrap();
+        assert!(matches!(step, StreamStep::Chunk(chunk) if chunk.delta == "Hi"));
+
+        let step = parser
+            .handle(r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":3,"eval_count":2}"#)
+            .unwrap();
+        let StreamStep::Done(Some(last)) = step else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+        assert_eq!(last.usage.unwrap().total_tokens, 5);
+
+        assert!(parser.handle(r#"{"error":"model not found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parser_reports_tool_calls_on_done() {
+        let mut parser = OllamaStreamParser::new();
+        let step = parser
+            .handle(r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"ls","arguments":{"path":"."}}}]},"done":false}"#)
+            .unwrap();
+        assert!(matches!(step, StreamStep::Skip));
+
+        let StreamStep::Done(Some(last)) = parser
+            .handle(r#"{"message":{"role":"assistant","content":""},"done":true}"#)
+            .unwrap()
+        else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(
+            last.tool_calls,
+            [ToolCall::new("call_0", "ls", r#"{"path":"."}"#)]
+        );
+    }
+}
@@ -1,16 +1,27 @@
 //! Inference provider abstractions for the Brio kernel.
 //!
 //! This module provides a unified interface for interacting with
-//! various LLM providers (Anthropic, OpenAI, etc.).
+//! various LLM providers (Anthropic, OpenAI, local model servers, etc.).
 
 pub mod anthropic;
+pub mod cache;
+pub mod cassette;
+pub mod health;
+pub mod local;
 pub mod openai;
 pub mod provider;
+pub mod ratelimit;
 pub mod registry;
+pub mod sse;
 pub mod types;
 
 pub use anthropic::{AnthropicConfig, AnthropicProvider};
+pub use cache::{CachingProvider, ResponseCache};
+pub use cassette::{Cassette, MatchMode, RecordingProvider, ReplayProvider};
+pub use health::{MonitoredProvider, ProviderHealth};
+pub use local::{LocalConfig, LocalModel, LocalProvider, LocalServerKind};
 pub use openai::{OpenAIConfig, OpenAIProvider};
 pub use provider::LLMProvider;
+pub use ratelimit::{Priority, RateLimitedProvider, RateLimiter, RequestContext};
 pub use registry::ProviderRegistry;
 pub use types::*;
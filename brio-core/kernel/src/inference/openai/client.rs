@@ -3,18 +3,23 @@
 //! This module provides the HTTP client for communicating with `OpenAI`'s API.
 
 use crate::inference::openai::mapping::{
-    OpenAIChatRequest, OpenAIChatResponse, create_request, map_response,
+    OpenAIChatRequest, OpenAIChatResponse, create_request, create_stream_request, map_response,
+    validate_options,
 };
-use crate::inference::openai::streaming::{DEFAULT_MAX_RETRIES, RetryConfig};
+use crate::inference::openai::streaming::{DEFAULT_MAX_RETRIES, OpenAIStreamParser, RetryConfig};
 use crate::inference::provider::LLMProvider;
+use crate::inference::ratelimit::RateLimiter;
+use crate::inference::sse::{DEFAULT_CHUNK_TIMEOUT_MS, event_stream};
 use crate::inference::types::{
-    ChatRequest, ChatResponse, CircuitBreaker, CircuitBreakerConfig, InferenceError,
+    ChatRequest, ChatResponse, ChatStream, CircuitBreaker, CircuitBreakerConfig, InferenceError,
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::{Client, StatusCode, Url};
 use secrecy::{ExposeSecret, SecretString};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
@@ -30,6 +35,10 @@ pub struct OpenAIConfig {
     pub base_delay_ms: Option<u64>,
     /// Circuit breaker configuration for resilience
     pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Maximum time to wait for the next chunk of a stream, in milliseconds
+    pub chunk_timeout_ms: Option<u64>,
+    /// Limiter told about the rate limit headers of each response
+    pub rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl OpenAIConfig {
@@ -42,6 +51,8 @@ impl OpenAIConfig {
             max_retries: None,
             base_delay_ms: None,
             circuit_breaker: None,
+            chunk_timeout_ms: None,
+            rate_limiter: None,
         }
     }
 
@@ -65,6 +76,20 @@ impl OpenAIConfig {
         self.circuit_breaker = Some(config);
         self
     }
+
+    /// Sets the maximum time to wait for the next chunk of a stream
+    #[must_use]
+    pub fn with_chunk_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.chunk_timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    /// Sets the limiter told about the rate limit headers of each response
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
 }
 
 /// Provider implementation for `OpenAI`'s API.
@@ -72,6 +97,7 @@ pub struct OpenAIProvider {
     client: Client,
     config: OpenAIConfig,
     retry_config: RetryConfig,
+    chunk_timeout: Duration,
     circuit_breaker: Arc<RwLock<CircuitBreaker>>,
 }
 
@@ -82,17 +108,26 @@ impl OpenAIProvider {
         let max_retries = config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
         let base_delay_ms = config.base_delay_ms.unwrap_or(1000);
         let cb_config = config.circuit_breaker.unwrap_or_default();
+        let chunk_timeout =
+            Duration::from_millis(config.chunk_timeout_ms.unwrap_or(DEFAULT_CHUNK_TIMEOUT_MS));
 
         Self {
             client: Client::new(),
             retry_config: RetryConfig::new()
                 .with_max_retries(max_retries)
                 .with_base_delay_ms(base_delay_ms),
+            chunk_timeout,
             config,
             circuit_breaker: Arc::new(RwLock::new(CircuitBreaker::new(cb_config))),
         }
     }
 
+    /// Returns the circuit breaker guarding requests, shared with monitoring
+    #[must_use]
+    pub fn circuit_breaker(&self) -> Arc<RwLock<CircuitBreaker>> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
     /// Makes a single request attempt
     async fn make_request(
         &self,
@@ -108,10 +143,107 @@ impl OpenAIProvider {
                 true, // Retry network errors
             )
         })?;
+        self.observe_limits(&res);
 
         self.map_api_response(res).await
     }
 
+    /// Starts a streaming request, returning the response once its status is OK
+    async fn open_stream(
+        &self,
+        provider_req: &OpenAIChatRequest,
+    ) -> Result<reqwest::Response, (InferenceError, bool)> {
+        let request = self
+            .build_api_request(provider_req)
+            .map_err(|e| (e, false))?;
+
+        let res = request
+            .send()
+            .await
+            .map_err(|e| (InferenceError::NetworkError(e.to_string()), true))?;
+        self.observe_limits(&res);
+
+        if res.status() == StatusCode::OK {
+            Ok(res)
+        } else {
+            Err(Self::map_error_response(res).await)
+        }
+    }
+
+    /// Reports the rate limit headers of a response to the limiter
+    fn observe_limits(&self, res: &reqwest::Response) {
+        if let Some(limiter) = &self.config.rate_limiter {
+            limiter.observe(res.headers());
+        }
+    }
+
+    /// Runs `attempt` with retries, guarded by the circuit breaker
+    async fn with_retries<T, F, Fut>(&self, attempt_fn: F) -> Result<T, InferenceError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, (InferenceError, bool)>>,
+    {
+        // Check circuit breaker first
+        let can_execute = {
+            let mut cb = self.circuit_breaker.write().await;
+            cb.try_acquire()
+        };
+
+        if !can_execute {
+            return Err(InferenceError::CircuitBreakerOpen(
+                "OpenAI circuit is open".to_string(),
+            ));
+        }
+
+        let mut last_error = InferenceError::NetworkError("No attempts made".to_string());
+
+        for attempt in 0..=self.retry_config.max_retries {
+            match attempt_fn().await {
+                Ok(response) => {
+                    // Record success on circuit breaker
+                    let mut cb = self.circuit_breaker.write().await;
+                    cb.record_success();
+                    return Ok(response);
+                }
+                Err((error, should_retry)) => {
+                    last_error = error;
+
+                    if !should_retry || attempt >= self.retry_config.max_retries {
+                        break;
+                    }
+
+                    // Wait at least as long as the provider asked us to
+                    let backoff = self.retry_config.calculate_backoff_delay(attempt);
+                    let delay = self
+                        .config
+                        .rate_limiter
+                        .as_ref()
+                        .and_then(|limiter| limiter.retry_delay())
+                        .map_or(backoff, |asked| asked.max(backoff));
+                    let delay_ms: u64 = delay.as_millis().try_into().unwrap_or(u64::MAX);
+                    warn!(
+                        attempt = attempt + 1,
+                        max_retries = self.retry_config.max_retries,
+                        delay_ms = delay_ms,
+                        error = %last_error,
+                        "OpenAI request failed, retrying after backoff"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        // Record failure on circuit breaker
+        let mut cb = self.circuit_breaker.write().await;
+        cb.record_failure();
+
+        debug!(
+            attempts = self.retry_config.max_retries + 1,
+            "All OpenAI retry attempts exhausted"
+        );
+        Err(last_error)
+    }
+
     fn build_api_request(
         &self,
         provider_req: &OpenAIChatRequest,
@@ -148,16 +280,23 @@ impl OpenAIProvider {
 
                 map_response(body).map_err(|msg| (InferenceError::ProviderError(msg), false))
             }
-            StatusCode::TOO_MANY_REQUESTS => Err((InferenceError::RateLimit, true)),
+            _ => Err(Self::map_error_response(res).await),
+        }
+    }
+
+    /// Maps an unsuccessful response to an error and whether to retry it
+    async fn map_error_response(res: reqwest::Response) -> (InferenceError, bool) {
+        match res.status() {
+            StatusCode::TOO_MANY_REQUESTS => (InferenceError::RateLimit, true),
             StatusCode::BAD_REQUEST => {
                 let text = res.text().await.unwrap_or_default();
                 if text.contains("context_length_exceeded") {
-                    Err((InferenceError::ContextLengthExceeded, false))
+                    (InferenceError::ContextLengthExceeded, false)
                 } else {
-                    Err((
+                    (
                         InferenceError::ProviderError(format!("Bad Request: {text}")),
                         false,
-                    ))
+                    )
                 }
             }
             StatusCode::INTERNAL_SERVER_ERROR
@@ -166,18 +305,18 @@ impl OpenAIProvider {
             | StatusCode::GATEWAY_TIMEOUT => {
                 let status = res.status();
                 let text = res.text().await.unwrap_or_default();
-                Err((
+                (
                     InferenceError::ProviderError(format!("HTTP {status}: {text}")),
                     true,
-                ))
+                )
             }
             _ => {
                 let status = res.status();
                 let text = res.text().await.unwrap_or_default();
-                Err((
+                (
                     InferenceError::ProviderError(format!("HTTP {status}: {text}")),
                     false,
-                ))
+                )
             }
         }
     }
@@ -186,60 +325,22 @@ impl OpenAIProvider {
 #[async_trait]
 impl LLMProvider for OpenAIProvider {
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
-        // Check circuit breaker first
-        let can_execute = {
-            let mut cb = self.circuit_breaker.write().await;
-            cb.try_acquire()
-        };
-
-        if !can_execute {
-            return Err(InferenceError::CircuitBreakerOpen(
-                "OpenAI circuit is open".to_string(),
-            ));
-        }
-
-        let provider_req = create_request(request.model, request.messages);
-
-        let mut last_error = InferenceError::NetworkError("No attempts made".to_string());
-
-        for attempt in 0..=self.retry_config.max_retries {
-            match self.make_request(&provider_req).await {
-                Ok(response) => {
-                    // Record success on circuit breaker
-                    let mut cb = self.circuit_breaker.write().await;
-                    cb.record_success();
-                    return Ok(response);
-                }
-                Err((error, should_retry)) => {
-                    last_error = error;
-
-                    if !should_retry || attempt >= self.retry_config.max_retries {
-                        break;
-                    }
-
-                    let delay = self.retry_config.calculate_backoff_delay(attempt);
-                    let delay_ms: u64 = delay.as_millis().try_into().unwrap_or(u64::MAX);
-                    warn!(
-                        attempt = attempt + 1,
-                        max_retries = self.retry_config.max_retries,
-                        delay_ms = delay_ms,
-                        error = %last_error,
-                        "OpenAI request failed, retrying after backoff"
-                    );
-                    tokio::time::sleep(delay).await;
-                }
-            }
-        }
+        validate_options(&request.options)?;
+        let provider_req = create_request(request);
+        self.with_retries(|| self.make_request(&provider_req)).await
+    }
 
-        // Record failure on circuit breaker
-        let mut cb = self.circuit_breaker.write().await;
-        cb.record_failure();
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        validate_options(&request.options)?;
+        let provider_req = create_stream_request(request);
+        let response = self
+            .with_retries(|| self.open_stream(&provider_req))
+            .await?;
 
-        debug!(
-            attempts = self.retry_config.max_retries + 1,
-            "All OpenAI retry attempts exhausted"
-        );
-        Err(last_error)
+        let mut parser = OpenAIStreamParser::new();
+        Ok(event_stream(response, self.chunk_timeout, move |event| {
+            parser.handle(&event)
+        }))
     }
 }
 
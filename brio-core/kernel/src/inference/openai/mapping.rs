@@ -2,8 +2,21 @@
 //!
 //! This module provides types for mapping between internal and `OpenAI` API formats.
 
-use crate::inference::types::{ChatResponse, Message, Usage};
+use crate::inference::types::{
+    ChatRequest, ChatResponse, InferenceError, Message, RequestOptions, ResponseFormat, Role,
+    ToolCall, ToolDefinition, Usage,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Tool type used by the `OpenAI` API for function tools
+const FUNCTION_TYPE: &str = "function";
+
+/// Highest sampling temperature accepted by the `OpenAI` API
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+/// Maximum number of stop sequences accepted by the `OpenAI` API
+pub const MAX_STOP_SEQUENCES: usize = 4;
 
 /// `OpenAI` API chat request format
 #[derive(Serialize)]
@@ -11,14 +24,172 @@ pub struct OpenAIChatRequest {
     /// The model identifier (e.g., "gpt-4", "gpt-3.5-turbo")
     pub model: String,
     /// The conversation messages
-    pub messages: Vec<Message>,
+    pub messages: Vec<OpenAIMessage>,
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<OpenAITool>,
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Sequences that stop generation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Seed for deterministic sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Response format, sent only for JSON mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<OpenAIResponseFormat>,
+    /// Whether to stream the completion as server-sent events
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+    /// Streaming options, sent only for streamed requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAIStreamOptions>,
+}
+
+/// `OpenAI` API response format
+#[derive(Serialize)]
+pub struct OpenAIResponseFormat {
+    /// Format type, e.g. `json_object`
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// `OpenAI` API streaming options
+#[derive(Serialize)]
+pub struct OpenAIStreamOptions {
+    /// Whether to send token usage on a final chunk
+    pub include_usage: bool,
+}
+
+/// `OpenAI` API message format
+#[derive(Serialize, Deserialize)]
+pub struct OpenAIMessage {
+    /// The role of the message sender
+    pub role: Role,
+    /// The message text; null for assistant messages that only call tools
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Tool calls made by the assistant
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<OpenAIToolCall>,
+    /// The call a tool message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+/// `OpenAI` API tool call format
+#[derive(Serialize, Deserialize)]
+pub struct OpenAIToolCall {
+    /// Identifier of the call
+    pub id: String,
+    /// Tool type, always "function"
+    #[serde(rename = "type", default = "function_type")]
+    pub kind: String,
+    /// The function being called
+    pub function: OpenAIFunctionCall,
+}
+
+/// `OpenAI` API function call details
+#[derive(Serialize, Deserialize)]
+pub struct OpenAIFunctionCall {
+    /// Name of the function
+    pub name: String,
+    /// Arguments as a JSON string
+    #[serde(default)]
+    pub arguments: String,
+}
+
+/// `OpenAI` API tool definition format
+#[derive(Serialize)]
+pub struct OpenAITool {
+    /// Tool type, always "function"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The function definition
+    pub function: OpenAIFunction,
+}
+
+/// `OpenAI` API function definition
+#[derive(Serialize)]
+pub struct OpenAIFunction {
+    /// Name of the function
+    pub name: String,
+    /// What the function does
+    pub description: String,
+    /// JSON schema of the arguments
+    pub parameters: Value,
+}
+
+fn function_type() -> String {
+    FUNCTION_TYPE.to_string()
+}
+
+impl From<Message> for OpenAIMessage {
+    fn from(message: Message) -> Self {
+        let content = if message.content.is_empty() && !message.tool_calls.is_empty() {
+            None
+        } else {
+            Some(message.content)
+        };
+
+        Self {
+            role: message.role,
+            content,
+            tool_calls: message.tool_calls.into_iter().map(Into::into).collect(),
+            tool_call_id: message.tool_call_id,
+        }
+    }
+}
+
+impl From<ToolCall> for OpenAIToolCall {
+    fn from(call: ToolCall) -> Self {
+        Self {
+            id: call.id,
+            kind: function_type(),
+            function: OpenAIFunctionCall {
+                name: call.name,
+                arguments: call.arguments,
+            },
+        }
+    }
+}
+
+impl From<OpenAIToolCall> for ToolCall {
+    fn from(call: OpenAIToolCall) -> Self {
+        Self {
+            id: call.id,
+            name: call.function.name,
+            arguments: call.function.arguments,
+        }
+    }
+}
+
+impl From<ToolDefinition> for OpenAITool {
+    fn from(tool: ToolDefinition) -> Self {
+        Self {
+            kind: function_type(),
+            function: OpenAIFunction {
+                name: tool.name,
+                description: tool.description,
+                parameters: tool.parameters,
+            },
+        }
+    }
 }
 
 /// `OpenAI` API choice structure
 #[derive(Deserialize)]
 pub struct OpenAIChoice {
     /// The generated message
-    pub message: Message,
+    pub message: OpenAIMessage,
 }
 
 /// `OpenAI` API usage information
@@ -52,23 +223,78 @@ pub struct OpenAIChatResponse {
 pub fn map_response(body: OpenAIChatResponse) -> Result<ChatResponse, String> {
     let choice = body
         .choices
-        .first()
+        .into_iter()
+        .next()
         .ok_or_else(|| "No choices returned".to_string())?;
 
     Ok(ChatResponse {
-        content: choice.message.content.clone(),
+        content: choice.message.content.unwrap_or_default(),
         usage: body.usage.map(|u| Usage {
             prompt_tokens: u.prompt,
             completion_tokens: u.completion,
             total_tokens: u.total,
         }),
+        tool_calls: choice
+            .message
+            .tool_calls
+            .into_iter()
+            .map(Into::into)
+            .collect(),
     })
 }
 
+/// Checks request options against the `OpenAI` API limits
+///
+/// # Errors
+///
+/// Returns `InferenceError::InvalidRequest` if an option is out of range.
+pub fn validate_options(options: &RequestOptions) -> Result<(), InferenceError> {
+    options.validate(MAX_TEMPERATURE)?;
+    if options.stop.len() > MAX_STOP_SEQUENCES {
+        return Err(InferenceError::InvalidRequest(format!(
+            "at most {MAX_STOP_SEQUENCES} stop sequences are supported, got {}",
+            options.stop.len()
+        )));
+    }
+    Ok(())
+}
+
 /// Creates an `OpenAI` API request from internal types
 #[must_use]
-pub fn create_request(model: String, messages: Vec<Message>) -> OpenAIChatRequest {
-    OpenAIChatRequest { model, messages }
+pub fn create_request(request: ChatRequest) -> OpenAIChatRequest {
+    let options = request.options;
+    let response_format = match options.response_format {
+        ResponseFormat::Text => None,
+        ResponseFormat::Json => Some(OpenAIResponseFormat {
+            kind: "json_object".to_string(),
+        }),
+    };
+
+    OpenAIChatRequest {
+        model: request.model,
+        messages: request.messages.into_iter().map(Into::into).collect(),
+        tools: request.tools.into_iter().map(Into::into).collect(),
+        temperature: options.temperature,
+        top_p: options.top_p,
+        max_tokens: options.max_tokens,
+        stop: options.stop,
+        seed: options.seed,
+        response_format,
+        stream: false,
+        stream_options: None,
+    }
+}
+
+/// Creates a streaming `OpenAI` API request that reports usage on the final chunk
+#[must_use]
+pub fn create_stream_request(request: ChatRequest) -> OpenAIChatRequest {
+    OpenAIChatRequest {
+        stream: true,
+        stream_options: Some(OpenAIStreamOptions {
+            include_usage: true,
+        }),
+        ..create_request(request)
+    }
 }
 
 #[cfg(test)]
@@ -76,32 +302,142 @@ mod tests {
     use super::*;
     use crate::inference::types::{Message, Role};
 
+    fn assistant(content: &str) -> OpenAIMessage {
+        OpenAIMessage {
+            role: Role::Assistant,
+            content: Some(content.to_string()),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
     #[test]
     fn test_create_request() {
         let messages = vec![
-            Message {
-                role: Role::User,
-                content: "Hello".to_string(),
-            },
-            Message {
-                role: Role::Assistant,
-                content: "Hi there".to_string(),
-            },
+            Message::new(Role::User, "Hello"),
+            Message::new(Role::Assistant, "Hi there"),
         ];
 
-        let request = create_request("gpt-4".to_string(), messages);
+        let request = create_request(ChatRequest::new("gpt-4", messages));
         assert_eq!(request.model, "gpt-4");
         assert_eq!(request.messages.len(), 2);
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("stream").is_none());
+        assert!(json.get("tools").is_none());
+        assert!(json.get("temperature").is_none());
+        assert!(json.get("response_format").is_none());
+    }
+
+    #[test]
+    fn test_create_request_with_options() {
+        let options = RequestOptions::new()
+            .with_temperature(0.0)
+            .with_top_p(0.5)
+            .with_max_tokens(128)
+            .with_stop(vec!["END".to_string()])
+            .with_seed(7)
+            .with_response_format(ResponseFormat::Json);
+        let request =
+            create_request(ChatRequest::with_message("gpt-4", "Review").with_options(options));
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["temperature"], 0.0);
+        assert_eq!(json["top_p"], 0.5);
+        assert_eq!(json["max_tokens"], 128);
+        assert_eq!(json["stop"][0], "END");
+        assert_eq!(json["seed"], 7);
+        assert_eq!(json["response_format"]["type"], "json_object");
+    }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(validate_options(&RequestOptions::new().with_temperature(1.8)).is_ok());
+        assert!(validate_options(&RequestOptions::new().with_temperature(2.5)).is_err());
+
+        let stop = (0..5).map(|i| format!("stop{i}")).collect();
+        assert!(matches!(
+            validate_options(&RequestOptions::new().with_stop(stop)),
+            Err(InferenceError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_request_with_tools() {
+        let messages = vec![
+            Message::new(Role::User, "Read lib.rs"),
+            Message::tool_calls(
+                "",
+                vec![ToolCall::new("call_1", "read_file", r#"{"path":"lib.rs"}"#)],
+            ),
+            Message::tool_result("call_1", "fn main() {}"),
+        ];
+        let tools = vec![ToolDefinition::new(
+            "read_file",
+            "Read a file",
+            serde_json::json!({"type": "object", "properties": {"path": {"type": "string"}}}),
+        )];
+
+        let request = create_request(ChatRequest::new("gpt-4", messages).with_tools(tools));
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["tools"][0]["type"], "function");
+        assert_eq!(json["tools"][0]["function"]["name"], "read_file");
+        assert_eq!(json["tools"][0]["function"]["parameters"]["type"], "object");
+
+        let call_message = &json["messages"][1];
+        assert!(call_message["content"].is_null());
+        assert_eq!(call_message["tool_calls"][0]["id"], "call_1");
+        assert_eq!(call_message["tool_calls"][0]["type"], "function");
+        assert_eq!(
+            call_message["tool_calls"][0]["function"]["arguments"],
+            r#"{"path":"lib.rs"}"#
+        );
+
+        let result_message = &json["messages"][2];
+        assert_eq!(result_message["role"], "tool");
+        assert_eq!(result_message["tool_call_id"], "call_1");
+    }
+
+    #[test]
+    fn test_map_response_with_tool_calls() {
+        let body: OpenAIChatResponse = serde_json::from_str(
+            r#"{
+                "choices": [{
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_9",
+                            "type": "function",
+                            "function": {"name": "ls", "arguments": "{\"path\":\".\"}"}
+                        }]
+                    }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let response = map_response(body).unwrap();
+        assert_eq!(response.content, "");
+        assert_eq!(
+            response.tool_calls,
+            vec![ToolCall::new("call_9", "ls", r#"{"path":"."}"#)]
+        );
+    }
+
+    #[test]
+    fn test_create_stream_request() {
+        let request = create_stream_request(ChatRequest::new("gpt-4", vec![]));
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["stream"], true);
+        assert_eq!(json["stream_options"]["include_usage"], true);
     }
 
     #[test]
     fn test_map_response_success() {
         let body = OpenAIChatResponse {
             choices: vec![OpenAIChoice {
-                message: Message {
-                    role: Role::Assistant,
-                    content: "Test response".to_string(),
-                },
+                message: assistant("Test response"),
             }],
             usage: Some(OpenAIUsage {
                 prompt: 10,
@@ -134,10 +470,7 @@ mod tests {
     fn test_map_response_no_usage() {
         let body = OpenAIChatResponse {
             choices: vec![OpenAIChoice {
-                message: Message {
-                    role: Role::Assistant,
-                    content: "No usage".to_string(),
-                },
+                message: assistant("No usage"),
             }],
             usage: None,
         };
@@ -8,9 +8,12 @@ pub mod streaming;
 
 pub use client::{OpenAIConfig, OpenAIProvider};
 pub use mapping::{
-    OpenAIChatRequest, OpenAIChatResponse, OpenAIChoice, OpenAIUsage, create_request, map_response,
+    MAX_STOP_SEQUENCES, MAX_TEMPERATURE, OpenAIChatRequest, OpenAIChatResponse, OpenAIChoice,
+    OpenAIFunction, OpenAIFunctionCall, OpenAIMessage, OpenAIResponseFormat, OpenAIStreamOptions,
+    OpenAITool, OpenAIToolCall, OpenAIUsage, create_request, create_stream_request, map_response,
+    validate_options,
 };
 pub use streaming::{
-    DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_RETRIES, MAX_DELAY_MS, RetryConfig, StreamingConfig,
-    rand_jitter_factor,
+    DEFAULT_BASE_DELAY_MS, DEFAULT_MAX_RETRIES, MAX_DELAY_MS, OpenAIStreamChunk,
+    OpenAIStreamParser, RetryConfig, StreamingConfig, rand_jitter_factor,
 };
@@ -1,8 +1,13 @@
 //! `OpenAI` API streaming support.
 //!
-//! This module provides retry mechanisms for streaming requests.
-//! Future enhancements will include actual SSE (Server-Sent Events) streaming support.
+//! This module provides retry mechanisms for requests and the parser for
+//! SSE (Server-Sent Events) chat completion chunks.
 
+use crate::inference::openai::mapping::OpenAIUsage;
+use crate::inference::sse::{SseEvent, StreamStep};
+use crate::inference::types::{ChatChunk, InferenceError, ToolCall, Usage};
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 /// Default maximum number of retries for transient errors
@@ -82,6 +87,173 @@ pub fn rand_jitter_factor() -> u64 {
     u64::from(nanos % 1000)
 }
 
+/// Delta of a streamed `OpenAI` choice
+#[derive(Deserialize, Default)]
+pub struct OpenAIDelta {
+    /// Text generated since the previous chunk
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Pieces of the tool calls being made
+    #[serde(default)]
+    pub tool_calls: Vec<OpenAIToolCallDelta>,
+}
+
+/// Piece of a streamed `OpenAI` tool call
+#[derive(Deserialize)]
+pub struct OpenAIToolCallDelta {
+    /// Position of the call among the calls of the choice
+    pub index: usize,
+    /// Identifier of the call, sent with its first piece
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Piece of the function being called
+    #[serde(default)]
+    pub function: OpenAIFunctionDelta,
+}
+
+/// Piece of a streamed `OpenAI` function call
+#[derive(Deserialize, Default)]
+pub struct OpenAIFunctionDelta {
+    /// Name of the function, sent with the first piece of the call
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Next fragment of the JSON arguments
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+/// Choice of a streamed `OpenAI` chunk
+#[derive(Deserialize)]
+pub struct OpenAIStreamChoice {
+    /// The generated delta
+    #[serde(default)]
+    pub delta: OpenAIDelta,
+    /// Why generation stopped, set on the last choice chunk
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// Error reported inside an `OpenAI` stream
+#[derive(Deserialize)]
+pub struct OpenAIStreamError {
+    /// Human-readable error message
+    pub message: String,
+}
+
+/// A streamed `OpenAI` chat completion chunk
+#[derive(Deserialize)]
+pub struct OpenAIStreamChunk {
+    /// The choices in this chunk; empty on the usage chunk
+    #[serde(default)]
+    pub choices: Vec<OpenAIStreamChoice>,
+    /// Token usage, sent on the last chunk when requested
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+    /// Error raised after the stream started
+    #[serde(default)]
+    pub error: Option<OpenAIStreamError>,
+}
+
+/// Parses `OpenAI` stream events into chat chunks.
+///
+/// The finish reason arrives on the last choice chunk and the usage on a
+/// separate chunk after it; both are reported on a single final chunk,
+/// together with the tool calls assembled from their pieces.
+#[derive(Debug, Default)]
+pub struct OpenAIStreamParser {
+    finish_reason: Option<String>,
+    tool_calls: BTreeMap<usize, ToolCall>,
+    final_sent: bool,
+}
+
+impl OpenAIStreamParser {
+    /// Creates a parser for a new stream
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles a single SSE event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be parsed or reports an error.
+    pub fn handle(&mut self, event: &SseEvent) -> Result<StreamStep, InferenceError> {
+        if event.data.trim() == "[DONE]" {
+            let last = (!self.final_sent).then(|| ChatChunk {
+                finish_reason: self.finish_reason.take(),
+                tool_calls: self.take_tool_calls(),
+                ..ChatChunk::default()
+            });
+            return Ok(StreamStep::Done(last));
+        }
+
+        let chunk: OpenAIStreamChunk = serde_json::from_str(&event.data)
+            .map_err(|e| InferenceError::ProviderError(format!("Parse error: {e}")))?;
+        if let Some(error) = chunk.error {
+            return Err(InferenceError::ProviderError(error.message));
+        }
+
+        let mut delta = String::new();
+        for choice in chunk.choices {
+            if let Some(content) = choice.delta.content {
+                delta.push_str(&content);
+            }
+            for piece in choice.delta.tool_calls {
+                self.add_tool_call_piece(piece);
+            }
+            if choice.finish_reason.is_some() {
+                self.finish_reason = choice.finish_reason;
+            }
+        }
+
+        if let Some(usage) = chunk.usage {
+            self.final_sent = true;
+            return Ok(StreamStep::Chunk(ChatChunk {
+                delta,
+                usage: Some(Usage {
+                    prompt_tokens: usage.prompt,
+                    completion_tokens: usage.completion,
+                    total_tokens: usage.total,
+                }),
+                finish_reason: self
+                    .finish_reason
+                    .take()
+                    .or_else(|| Some("stop".to_string())),
+                tool_calls: self.take_tool_calls(),
+            }));
+        }
+
+        if delta.is_empty() {
+            Ok(StreamStep::Skip)
+        } else {
+            Ok(StreamStep::Chunk(ChatChunk::delta(delta)))
+        }
+    }
+
+    /// Adds a piece of a tool call to the call at its index
+    fn add_tool_call_piece(&mut self, piece: OpenAIToolCallDelta) {
+        let call = self
+            .tool_calls
+            .entry(piece.index)
+            .or_insert_with(|| ToolCall::new(String::new(), String::new(), String::new()));
+        if let Some(id) = piece.id {
+            call.id = id;
+        }
+        if let Some(name) = piece.function.name {
+            call.name.push_str(&name);
+        }
+        if let Some(arguments) = piece.function.arguments {
+            call.arguments.push_str(&arguments);
+        }
+    }
+
+    /// Returns the assembled tool calls in the order they were made
+    fn take_tool_calls(&mut self) -> Vec<ToolCall> {
+        std::mem::take(&mut self.tool_calls).into_values().collect()
+    }
+}
+
 /// Streaming configuration for `OpenAI` chat completions
 #[derive(Debug, Clone)]
 pub struct StreamingConfig {
@@ -180,6 +352,107 @@ mod tests {
         }
     }
 
+    fn data(data: &str) -> SseEvent {
+        SseEvent {
+            event: None,
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_stream_parser_reports_usage_on_final_chunk() {
+        let mut parser = OpenAIStreamParser::new();
+
+        let step = parser
+            .handle(&data(
+                r#"{"choices":[{"delta":{"role":"assistant","content":"Hi"}}]}"#,
+            ))
+            .unwrap();
+        assert!(matches!(step, StreamStep::Chunk(c) if c.delta == "Hi" && !c.is_final()));
+
+        let step = parser
+            .handle(&data(
+                r#"{"choices":[{"delta":{},"finish_reason":"stop"}]}"#,
+            ))
+            .unwrap();
+        assert!(matches!(step, StreamStep::Skip));
+
+        let step = parser
+            .handle(&data(
+                r#"{"choices":[],"usage":{"prompt_tokens":5,"completion_tokens":1,"total_tokens":6}}"#,
+            ))
+            .unwrap();
+        let StreamStep::Chunk(last) = step else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+        assert_eq!(last.usage.unwrap().total_tokens, 6);
+
+        assert!(matches!(
+            parser.handle(&data("[DONE]")).unwrap(),
+            StreamStep::Done(None)
+        ));
+    }
+
+    #[test]
+    fn test_stream_parser_finishes_without_usage() {
+        let mut parser = OpenAIStreamParser::new();
+        parser
+            .handle(&data(
+                r#"{"choices":[{"delta":{},"finish_reason":"length"}]}"#,
+            ))
+            .unwrap();
+        let StreamStep::Done(Some(last)) = parser.handle(&data("[DONE]")).unwrap() else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("length"));
+        assert!(last.usage.is_none());
+    }
+
+    #[test]
+    fn test_stream_parser_assembles_tool_calls() {
+        let mut parser = OpenAIStreamParser::new();
+        let events = [
+            r#"{"choices":[{"delta":{"role":"assistant","content":null,"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"read_file","arguments":""}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"path\":"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_2","function":{"name":"ls","arguments":"{}"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"a.rs\"}"}}]}}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#,
+        ];
+        for event in events {
+            assert!(matches!(
+                parser.handle(&data(event)).unwrap(),
+                StreamStep::Skip
+            ));
+        }
+
+        let StreamStep::Chunk(last) = parser
+            .handle(&data(
+                r#"{"choices":[],"usage":{"prompt_tokens":5,"completion_tokens":9,"total_tokens":14}}"#,
+            ))
+            .unwrap()
+        else {
+            panic!("expected final chunk");
+        };
+        assert_eq!(last.finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(
+            last.tool_calls,
+            [
+                ToolCall::new("call_1", "read_file", r#"{"path":"a.rs"}"#),
+                ToolCall::new("call_2", "ls", "{}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_parser_surfaces_errors() {
+        let mut parser = OpenAIStreamParser::new();
+        let result = parser.handle(&data(r#"{"error":{"message":"server overloaded"}}"#));
+        assert!(
+            matches!(result, Err(InferenceError::ProviderError(msg)) if msg == "server overloaded")
+        );
+    }
+
     #[test]
     fn test_streaming_config_default() {
         let config = StreamingConfig::default();
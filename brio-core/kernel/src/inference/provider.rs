@@ -2,7 +2,7 @@
 //!
 //! This module defines the common interface for all LLM providers.
 
-use crate::inference::types::{ChatRequest, ChatResponse, InferenceError};
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
 use async_trait::async_trait;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
@@ -12,6 +12,16 @@ use tracing::{debug, info, warn};
 pub trait LLMProvider: Send + Sync {
     /// Executes a chat completion request.
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError>;
+
+    /// Executes a chat completion request, streaming the generated tokens.
+    ///
+    /// Errors before the first chunk (e.g. rate limits) are returned directly;
+    /// later failures are yielded by the stream. Dropping the stream cancels
+    /// the request. Providers without native streaming yield the finished
+    /// response as a single chunk.
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        self.chat(request).await.map(ChatStream::from_response)
+    }
 }
 
 /// A provider that chains multiple LLM providers as fallbacks.
@@ -46,6 +56,18 @@ impl FallbackProviderChain {
         self
     }
 
+    /// Adds a shared provider to the chain.
+    #[must_use]
+    pub fn add_provider_arc(
+        mut self,
+        name: impl Into<String>,
+        provider: Arc<dyn LLMProvider>,
+    ) -> Self {
+        self.providers.push(provider);
+        self.provider_names.push(name.into());
+        self
+    }
+
     /// Returns the number of providers in the chain.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -117,4 +139,37 @@ impl LLMProvider for FallbackProviderChain {
         warn!("All providers in fallback chain failed");
         Err(InferenceError::AllProvidersFailed)
     }
+
+    /// Falls back only while opening the stream; once a provider has started
+    /// streaming, its errors are passed on to the caller.
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        if self.providers.is_empty() {
+            return Err(InferenceError::ProviderNotFound(
+                "Fallback chain is empty".to_string(),
+            ));
+        }
+
+        for (provider, name) in self.providers.iter().zip(self.provider_names.iter()) {
+            match provider.chat_stream(request.clone()).await {
+                Ok(stream) => {
+                    info!(provider = %name, "Provider started stream in fallback chain");
+                    return Ok(stream);
+                }
+                Err(err) => {
+                    warn!(
+                        provider = %name,
+                        error = %err,
+                        retryable = err.is_retryable(),
+                        "Provider failed to start stream in fallback chain"
+                    );
+                    if !err.is_retryable() && !err.is_circuit_breaker() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        warn!("All providers in fallback chain failed");
+        Err(InferenceError::AllProvidersFailed)
+    }
 }
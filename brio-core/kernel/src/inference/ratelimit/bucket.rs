@@ -0,0 +1,107 @@
+//! Token bucket used to pace requests and tokens per minute.
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A bucket refilling continuously up to its capacity.
+///
+/// The level may drop below zero when more was used than taken up front,
+/// delaying later callers until the debt is refilled.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    level: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket allowing `per_minute` units per minute
+    #[must_use]
+    pub fn per_minute(per_minute: u64, now: Instant) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let capacity = per_minute.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            level: capacity,
+            updated: now,
+        }
+    }
+
+    /// Returns the most the bucket can hold
+    #[must_use]
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    /// Returns the units available at `now`
+    pub fn available(&mut self, now: Instant) -> f64 {
+        self.refill(now);
+        self.level
+    }
+
+    /// Returns how long until `amount` units are available, zero if they
+    /// already are. Amounts above the capacity wait for a full bucket.
+    pub fn wait_for(&mut self, amount: f64, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = amount.min(self.capacity) - self.level;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing / self.refill_per_sec)
+        }
+    }
+
+    /// Removes `amount` units, going into debt if there are not enough
+    pub fn take(&mut self, amount: f64, now: Instant) {
+        self.refill(now);
+        self.level -= amount;
+    }
+
+    /// Returns `amount` units, up to the capacity
+    pub fn give_back(&mut self, amount: f64, now: Instant) {
+        self.refill(now);
+        self.level = (self.level + amount).min(self.capacity);
+    }
+
+    /// Lowers the level to what the provider reports as remaining
+    pub fn limit_to(&mut self, remaining: f64, now: Instant) {
+        self.refill(now);
+        self.level = self.level.min(remaining);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.level = (self.level + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::per_minute(60, start);
+
+        bucket.take(60.0, start);
+        assert_eq!(bucket.wait_for(1.0, start), Duration::from_secs(1));
+        assert_eq!(
+            bucket.wait_for(1.0, start + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+
+        bucket.take(10.0, start + Duration::from_secs(1));
+        bucket.give_back(100.0, start + Duration::from_secs(1));
+        assert!((bucket.available(start + Duration::from_secs(1)) - 60.0).abs() < 1e-9);
+
+        bucket.limit_to(5.0, start + Duration::from_secs(1));
+        assert_eq!(
+            bucket.wait_for(500.0, start + Duration::from_secs(1)),
+            Duration::from_secs(55)
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Caller context attached to inference requests.
+//!
+//! The context travels with the future issuing a request rather than with
+//! the request itself, so rate limiters deep inside the registry can queue
+//! requests fairly without every provider passing it along.
+
+use std::future::Future;
+
+/// Caller recorded for requests issued outside any [`RequestContext`] scope
+pub const DEFAULT_CALLER: &str = "kernel";
+
+/// How urgently a request should be admitted.
+///
+/// Interactive requests are admitted before any waiting background request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// A user or client is waiting on the answer
+    #[default]
+    Interactive,
+    /// Part of a task running without anyone waiting on each step
+    Background,
+}
+
+impl Priority {
+    /// Returns the name used in metrics and logs
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Background => "background",
+        }
+    }
+}
+
+/// Who issued an inference request and how urgent it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Caller the request is queued for, usually a plugin ID
+    pub caller: String,
+    /// Admission priority
+    pub priority: Priority,
+}
+
+impl RequestContext {
+    /// Creates a context for `caller`
+    #[must_use]
+    pub fn new(caller: impl Into<String>, priority: Priority) -> Self {
+        Self {
+            caller: caller.into(),
+            priority,
+        }
+    }
+
+    /// Returns the context of the running request scope, or an interactive
+    /// kernel context outside any scope
+    #[must_use]
+    pub fn current() -> Self {
+        REQUEST_CONTEXT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Runs `future` with this context attached to the requests it issues
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        REQUEST_CONTEXT.scope(self, future).await
+    }
+}
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        Self::new(DEFAULT_CALLER, Priority::Interactive)
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_context_is_scoped_to_future() {
+        assert_eq!(RequestContext::current().caller, DEFAULT_CALLER);
+
+        let context = RequestContext::new("coder", Priority::Background);
+        let seen = context
+            .clone()
+            .scope(async { RequestContext::current() })
+            .await;
+
+        assert_eq!(seen, context);
+        assert_eq!(RequestContext::current().priority, Priority::Interactive);
+    }
+}
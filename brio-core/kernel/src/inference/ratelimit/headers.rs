@@ -0,0 +1,208 @@
+//! Parsing of rate limit response headers.
+//!
+//! Understands the standard `Retry-After` header, `OpenAI`-style
+//! `x-ratelimit-*` headers (resets given as durations such as `6m0s`) and
+//! `Anthropic`-style `anthropic-ratelimit-*` headers (resets given as
+//! RFC 3339 timestamps).
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+
+/// Rate limit state reported by a provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitHeaders {
+    /// How long the provider asked us to wait before retrying
+    pub retry_after: Option<Duration>,
+    /// Requests left in the current window
+    pub remaining_requests: Option<u64>,
+    /// Time until the request window resets
+    pub reset_requests: Option<Duration>,
+    /// Tokens left in the current window
+    pub remaining_tokens: Option<u64>,
+    /// Time until the token window resets
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimitHeaders {
+    /// Parses the rate limit headers of a response
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::parse(headers, Utc::now())
+    }
+
+    /// Parses the rate limit headers of a response received at `now`
+    #[must_use]
+    pub fn parse(headers: &HeaderMap, now: DateTime<Utc>) -> Self {
+        let text = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let number = |name: &str| text(name).and_then(|v| v.trim().parse::<u64>().ok());
+        let reset = |duration_name: &str, timestamp_name: &str| {
+            text(duration_name)
+                .and_then(parse_duration)
+                .or_else(|| text(timestamp_name).and_then(|v| until_timestamp(v, now)))
+        };
+
+        let retry_after = text("retry-after-ms")
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+            .or_else(|| text("retry-after").and_then(|v| parse_retry_after(v, now)));
+
+        Self {
+            retry_after,
+            remaining_requests: number("x-ratelimit-remaining-requests")
+                .or_else(|| number("anthropic-ratelimit-requests-remaining")),
+            reset_requests: reset(
+                "x-ratelimit-reset-requests",
+                "anthropic-ratelimit-requests-reset",
+            ),
+            remaining_tokens: number("x-ratelimit-remaining-tokens")
+                .or_else(|| number("anthropic-ratelimit-tokens-remaining")),
+            reset_tokens: reset(
+                "x-ratelimit-reset-tokens",
+                "anthropic-ratelimit-tokens-reset",
+            ),
+        }
+    }
+
+    /// Returns how long no request should be sent, if the provider asked to
+    /// wait or reported an exhausted window
+    #[must_use]
+    pub fn blocked_for(&self) -> Option<Duration> {
+        let exhausted = |remaining: Option<u64>, reset: Option<Duration>| {
+            reset.filter(|_| remaining == Some(0))
+        };
+        [
+            self.retry_after,
+            exhausted(self.remaining_requests, self.reset_requests),
+            exhausted(self.remaining_tokens, self.reset_tokens),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+}
+
+/// Parses a `Retry-After` value: delay seconds or an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .and_then(|at| (at.with_timezone(&Utc) - now).to_std().ok())
+}
+
+/// Returns the time from `now` until an RFC 3339 timestamp, zero if passed
+fn until_timestamp(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let at = DateTime::parse_from_rfc3339(value.trim()).ok()?;
+    Some(
+        (at.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parses a duration such as `1s`, `6m0s`, `1.5s` or `20ms`.
+///
+/// A bare number is taken as seconds.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let amount: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 1e-3,
+            "us" | "µs" => 1e-6,
+            "ns" => 1e-9,
+            _ => return None,
+        };
+        total += amount * scale;
+        rest = &rest[unit_len..];
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                (
+                    HeaderName::from_static(name),
+                    HeaderValue::from_str(value).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_duration("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_openai_headers() {
+        let parsed = RateLimitHeaders::from_headers(&headers(&[
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "1m30s"),
+            ("x-ratelimit-remaining-tokens", "1200"),
+            ("x-ratelimit-reset-tokens", "250ms"),
+        ]));
+
+        assert_eq!(parsed.remaining_requests, Some(0));
+        assert_eq!(parsed.remaining_tokens, Some(1200));
+        assert_eq!(parsed.reset_tokens, Some(Duration::from_millis(250)));
+        assert_eq!(parsed.blocked_for(), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn test_anthropic_headers_and_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let parsed = RateLimitHeaders::parse(
+            &headers(&[
+                ("retry-after", "3"),
+                ("anthropic-ratelimit-tokens-remaining", "0"),
+                ("anthropic-ratelimit-tokens-reset", "2024-05-01T12:00:10Z"),
+            ]),
+            now,
+        );
+
+        assert_eq!(parsed.retry_after, Some(Duration::from_secs(3)));
+        assert_eq!(parsed.reset_tokens, Some(Duration::from_secs(10)));
+        assert_eq!(parsed.blocked_for(), Some(Duration::from_secs(10)));
+
+        let date = RateLimitHeaders::parse(
+            &headers(&[("retry-after", "Wed, 01 May 2024 12:00:05 GMT")]),
+            now,
+        );
+        assert_eq!(date.retry_after, Some(Duration::from_secs(5)));
+        assert_eq!(RateLimitHeaders::default().blocked_for(), None);
+    }
+}
@@ -0,0 +1,411 @@
+//! Per-provider rate limiter with a fair admission queue.
+
+use crate::inference::ratelimit::bucket::TokenBucket;
+use crate::inference::ratelimit::context::{Priority, RequestContext};
+use crate::inference::ratelimit::headers::RateLimitHeaders;
+use crate::inference::types::ChatRequest;
+use parking_lot::Mutex;
+use reqwest::header::HeaderMap;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::debug;
+
+/// Characters per token assumed when estimating the size of a prompt
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Estimates the tokens a request will use: its prompt at four characters
+/// per token plus its completion limit, if set
+#[must_use]
+pub fn estimate_tokens(request: &ChatRequest) -> u64 {
+    let chars: usize = request
+        .messages
+        .iter()
+        .map(|message| {
+            message.content.len()
+                + message
+                    .tool_calls
+                    .iter()
+                    .map(|call| call.name.len() + call.arguments.len())
+                    .sum::<usize>()
+        })
+        .sum();
+    let prompt = u64::try_from(chars / CHARS_PER_TOKEN).unwrap_or(u64::MAX);
+    prompt.saturating_add(u64::from(request.options.max_tokens.unwrap_or(0)))
+}
+
+/// Converts a count to bucket units; counts are far below the precision limit
+#[allow(clippy::cast_precision_loss)]
+fn units(count: u64) -> f64 {
+    count as f64
+}
+
+/// A place in the admission queue.
+///
+/// Tickets are ordered by priority, then by the caller's virtual round, so
+/// a caller with many queued requests takes turns with everyone else
+/// instead of going first with all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Ticket {
+    priority: Priority,
+    round: u64,
+    seq: u64,
+}
+
+enum Admission {
+    Admitted,
+    Wait(Duration),
+    Queued,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+    blocked_until: Option<Instant>,
+    queue: BTreeSet<Ticket>,
+    caller_rounds: HashMap<String, u64>,
+    round: u64,
+    next_seq: u64,
+}
+
+impl LimiterState {
+    fn enqueue(&mut self, context: &RequestContext) -> Ticket {
+        let last = self
+            .caller_rounds
+            .get(&context.caller)
+            .copied()
+            .unwrap_or(0);
+        let round = last.max(self.round) + 1;
+        self.caller_rounds.insert(context.caller.clone(), round);
+
+        let ticket = Ticket {
+            priority: context.priority,
+            round,
+            seq: self.next_seq,
+        };
+        self.next_seq += 1;
+        self.queue.insert(ticket);
+        ticket
+    }
+
+    fn try_admit(&mut self, ticket: Ticket, tokens: u64, now: Instant) -> Admission {
+        if self.queue.first() != Some(&ticket) {
+            return Admission::Queued;
+        }
+
+        let tokens = units(tokens);
+        let blocked = self
+            .blocked_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        let wait = [
+            blocked,
+            self.requests
+                .as_mut()
+                .map_or(Duration::ZERO, |bucket| bucket.wait_for(1.0, now)),
+            self.tokens
+                .as_mut()
+                .map_or(Duration::ZERO, |bucket| bucket.wait_for(tokens, now)),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(Duration::ZERO);
+        if !wait.is_zero() {
+            return Admission::Wait(wait);
+        }
+
+        if let Some(bucket) = &mut self.requests {
+            bucket.take(1.0, now);
+        }
+        if let Some(bucket) = &mut self.tokens {
+            bucket.take(tokens.min(bucket.capacity()), now);
+        }
+        self.queue.remove(&ticket);
+        self.round = self.round.max(ticket.round);
+        let round = self.round;
+        self.caller_rounds.retain(|_, last| *last > round);
+        Admission::Admitted
+    }
+}
+
+/// Paces the requests sent to one provider.
+///
+/// Requests wait in a queue until the request and token budgets of the
+/// current minute allow them, and while the provider has asked callers to
+/// back off through `Retry-After` or exhausted rate limit headers.
+/// Interactive requests go before background ones; within a priority,
+/// callers take turns.
+#[derive(Debug)]
+pub struct RateLimiter {
+    name: String,
+    state: Mutex<LimiterState>,
+    changed: Notify,
+}
+
+impl RateLimiter {
+    /// Creates a limiter for the provider `name` that only honors the
+    /// limits the provider reports
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            state: Mutex::new(LimiterState {
+                requests: None,
+                tokens: None,
+                blocked_until: None,
+                queue: BTreeSet::new(),
+                caller_rounds: HashMap::new(),
+                round: 0,
+                next_seq: 0,
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Limits the number of requests per minute
+    #[must_use]
+    pub fn with_requests_per_minute(mut self, requests: u64) -> Self {
+        self.state.get_mut().requests = Some(TokenBucket::per_minute(requests, Instant::now()));
+        self
+    }
+
+    /// Limits the number of tokens per minute
+    #[must_use]
+    pub fn with_tokens_per_minute(mut self, tokens: u64) -> Self {
+        self.state.get_mut().tokens = Some(TokenBucket::per_minute(tokens, Instant::now()));
+        self
+    }
+
+    /// Returns the name of the provider being limited
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of requests waiting to be admitted
+    #[must_use]
+    pub fn queued(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+
+    /// Waits until a request of the current [`RequestContext`], expected to
+    /// use `estimated_tokens`, may be sent. Returns the time spent waiting.
+    ///
+    /// Dropping the future gives up the place in the queue.
+    pub async fn acquire(&self, estimated_tokens: u64) -> Duration {
+        let context = RequestContext::current();
+        let started = Instant::now();
+        let mut place = QueuePlace {
+            limiter: self,
+            ticket: self.state.lock().enqueue(&context),
+            admitted: false,
+        };
+        self.report_depth();
+
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let admission =
+                self.state
+                    .lock()
+                    .try_admit(place.ticket, estimated_tokens, Instant::now());
+            match admission {
+                Admission::Admitted => {
+                    place.admitted = true;
+                    break;
+                }
+                Admission::Wait(delay) => {
+                    tokio::select! {
+                        () = tokio::time::sleep(delay) => {}
+                        () = &mut notified => {}
+                    }
+                }
+                Admission::Queued => notified.await,
+            }
+        }
+
+        // The next request in line may be admissible now
+        self.changed.notify_waiters();
+        self.report_depth();
+        let waited = started.elapsed();
+        metrics::histogram!(
+            "brio_inference_queue_wait_seconds",
+            "provider" => self.name.clone(),
+            "priority" => context.priority.as_str()
+        )
+        .record(waited.as_secs_f64());
+        waited
+    }
+
+    /// Corrects the token budget once a request reports the tokens it
+    /// actually used instead of the estimate it was admitted with
+    pub fn settle(&self, estimated_tokens: u64, used_tokens: u64) {
+        {
+            let mut state = self.state.lock();
+            let Some(bucket) = &mut state.tokens else {
+                return;
+            };
+            let now = Instant::now();
+            if used_tokens > estimated_tokens {
+                bucket.take(units(used_tokens - estimated_tokens), now);
+            } else {
+                bucket.give_back(units(estimated_tokens - used_tokens), now);
+            }
+        }
+        self.changed.notify_waiters();
+    }
+
+    /// Updates the limiter from the rate limit headers of a response
+    pub fn observe(&self, headers: &HeaderMap) {
+        let limits = RateLimitHeaders::from_headers(headers);
+        if limits == RateLimitHeaders::default() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        if let (Some(bucket), Some(remaining)) = (&mut state.requests, limits.remaining_requests) {
+            bucket.limit_to(units(remaining), now);
+        }
+        if let (Some(bucket), Some(remaining)) = (&mut state.tokens, limits.remaining_tokens) {
+            bucket.limit_to(units(remaining), now);
+        }
+        if let Some(delay) = limits.blocked_for() {
+            let until = now + delay;
+            if !matches!(state.blocked_until, Some(blocked) if blocked >= until) {
+                state.blocked_until = Some(until);
+            }
+            debug!(
+                provider = %self.name,
+                delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                "Provider asked to back off"
+            );
+            metrics::counter!("brio_inference_rate_limited_total", "provider" => self.name.clone())
+                .increment(1);
+        }
+    }
+
+    /// Returns how long the provider asked callers to back off, if at all
+    #[must_use]
+    pub fn retry_delay(&self) -> Option<Duration> {
+        let until = self.state.lock().blocked_until?;
+        let delay = until.saturating_duration_since(Instant::now());
+        (!delay.is_zero()).then_some(delay)
+    }
+
+    fn report_depth(&self) {
+        let depth = units(u64::try_from(self.queued()).unwrap_or(u64::MAX));
+        metrics::gauge!("brio_inference_queue_depth", "provider" => self.name.clone()).set(depth);
+    }
+}
+
+/// Removes an abandoned request from the queue.
+struct QueuePlace<'a> {
+    limiter: &'a RateLimiter,
+    ticket: Ticket,
+    admitted: bool,
+}
+
+impl Drop for QueuePlace<'_> {
+    fn drop(&mut self) {
+        if !self.admitted {
+            self.limiter.state.lock().queue.remove(&self.ticket);
+            self.limiter.changed.notify_waiters();
+            self.limiter.report_depth();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::sync::Arc;
+
+    fn context(caller: &str, priority: Priority) -> RequestContext {
+        RequestContext::new(caller, priority)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_are_paced_per_minute() {
+        let limiter = RateLimiter::new("openai").with_requests_per_minute(2);
+
+        assert_eq!(limiter.acquire(0).await, Duration::ZERO);
+        assert_eq!(limiter.acquire(0).await, Duration::ZERO);
+        let waited = limiter.acquire(0).await;
+        assert!(waited >= Duration::from_secs(29), "waited {waited:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_after_blocks_all_callers() {
+        let limiter = RateLimiter::new("anthropic");
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("5"));
+        limiter.observe(&headers);
+
+        assert_eq!(limiter.retry_delay(), Some(Duration::from_secs(5)));
+        let waited = limiter.acquire(10).await;
+        assert!(waited >= Duration::from_secs(5), "waited {waited:?}");
+        assert_eq!(limiter.retry_delay(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_queue_is_fair_and_prefers_interactive() {
+        let limiter = Arc::new(RateLimiter::new("openai").with_requests_per_minute(1));
+        limiter.acquire(0).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut handles = Vec::new();
+        for (caller, priority) in [
+            ("coder", Priority::Background),
+            ("coder", Priority::Background),
+            ("coder", Priority::Background),
+            ("reviewer", Priority::Background),
+            ("chat", Priority::Interactive),
+        ] {
+            let limiter = Arc::clone(&limiter);
+            let order = Arc::clone(&order);
+            handles.push(tokio::spawn(context(caller, priority).scope(async move {
+                limiter.acquire(0).await;
+                order.lock().push(caller);
+            })));
+            // Let each request take its place before the next one arrives
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(limiter.queued(), 5);
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(
+            *order.lock(),
+            vec!["chat", "coder", "reviewer", "coder", "coder"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_request_leaves_queue() {
+        let limiter = RateLimiter::new("openai").with_tokens_per_minute(100);
+        limiter.acquire(100).await;
+
+        let abandoned = tokio::time::timeout(Duration::from_secs(1), limiter.acquire(50)).await;
+        assert!(abandoned.is_err());
+        assert_eq!(limiter.queued(), 0);
+
+        // Returning unused tokens lets the next request through at once
+        limiter.settle(100, 20);
+        assert_eq!(limiter.acquire(50).await, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        use crate::inference::types::RequestOptions;
+
+        let request = ChatRequest::with_message("gpt-4", "x".repeat(400))
+            .with_options(RequestOptions::new().with_max_tokens(50));
+        assert_eq!(estimate_tokens(&request), 150);
+    }
+}
@@ -0,0 +1,19 @@
+//! Provider-aware rate limiting of inference requests.
+//!
+//! Each provider gets a [`RateLimiter`] pacing its requests and tokens per
+//! minute and honoring the `Retry-After` and rate limit headers it sends.
+//! The [`RateLimitedProvider`] wraps a provider so every caller of the
+//! registry waits in the same queue, where interactive requests go first
+//! and plugins take turns, as described by the [`RequestContext`] of the
+//! calling future.
+
+pub mod bucket;
+pub mod context;
+pub mod headers;
+pub mod limiter;
+pub mod provider;
+
+pub use context::{Priority, RequestContext};
+pub use headers::RateLimitHeaders;
+pub use limiter::{RateLimiter, estimate_tokens};
+pub use provider::RateLimitedProvider;
@@ -0,0 +1,104 @@
+//! Provider that admits requests through a rate limiter.
+
+use crate::inference::provider::LLMProvider;
+use crate::inference::ratelimit::limiter::{RateLimiter, estimate_tokens};
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use std::sync::Arc;
+
+/// A provider that waits for its rate limiter before forwarding requests.
+///
+/// Requests are admitted with an estimate of the tokens they will use,
+/// corrected once the provider reports actual usage. Failed requests give
+/// their estimated tokens back.
+pub struct RateLimitedProvider {
+    inner: Arc<dyn LLMProvider>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedProvider {
+    /// Creates a provider admitting requests to `inner` through `limiter`
+    #[must_use]
+    pub fn new(inner: Arc<dyn LLMProvider>, limiter: Arc<RateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// Returns the limiter requests are admitted through
+    #[must_use]
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+}
+
+#[async_trait]
+impl LLMProvider for RateLimitedProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        let estimate = estimate_tokens(&request);
+        self.limiter.acquire(estimate).await;
+
+        let result = self.inner.chat(request).await;
+        let used = match &result {
+            Ok(response) => response
+                .usage
+                .as_ref()
+                .map_or(estimate, |usage| u64::from(usage.total_tokens)),
+            Err(_) => 0,
+        };
+        self.limiter.settle(estimate, used);
+        result
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        let estimate = estimate_tokens(&request);
+        self.limiter.acquire(estimate).await;
+
+        let stream = match self.inner.chat_stream(request).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.limiter.settle(estimate, 0);
+                return Err(e);
+            }
+        };
+        let limiter = Arc::clone(&self.limiter);
+        Ok(ChatStream::new(stream.inspect(move |chunk| {
+            if let Some(usage) = chunk.as_ref().ok().and_then(|chunk| chunk.usage.as_ref()) {
+                limiter.settle(estimate, u64::from(usage.total_tokens));
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct MeteredProvider;
+
+    #[async_trait]
+    impl LLMProvider for MeteredProvider {
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+            Ok(ChatResponse::with_usage("done", 900, 100))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_actual_usage_is_charged() {
+        let limiter = Arc::new(RateLimiter::new("openai").with_tokens_per_minute(1200));
+        let provider = RateLimitedProvider::new(Arc::new(MeteredProvider), Arc::clone(&limiter));
+
+        // Estimated at a few tokens, but charged the 1000 reported
+        provider
+            .chat(ChatRequest::with_message("gpt-4", "Hi"))
+            .await
+            .unwrap();
+
+        let started = tokio::time::Instant::now();
+        provider
+            .chat(ChatRequest::with_message("gpt-4", "x".repeat(2000)))
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(14));
+    }
+}
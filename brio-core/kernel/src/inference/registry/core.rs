@@ -3,7 +3,10 @@
 //! This module provides the [`ProviderRegistry`] which allows concurrent
 //! registration and use of multiple LLM providers.
 
+use crate::inference::cache::ResponseCache;
+use crate::inference::health::ProviderHealth;
 use crate::inference::provider::LLMProvider;
+use crate::inference::ratelimit::RateLimiter;
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,9 +16,55 @@ use tracing::debug;
 ///
 /// Allows routing requests to different providers by name, enabling
 /// concurrent use of multiple LLM backends (`OpenAI`, Anthropic, etc.).
+/// Requests can also be routed by model name using pattern rules such as
+/// `claude-*`.
 pub struct ProviderRegistry {
     pub(crate) providers: RwLock<HashMap<String, Arc<dyn LLMProvider>>>,
     pub(crate) default_provider: RwLock<Option<String>>,
+    pub(crate) routes: RwLock<Vec<ModelRoute>>,
+    pub(crate) cache: RwLock<Option<Arc<ResponseCache>>>,
+    pub(crate) rate_limiters: RwLock<HashMap<String, Arc<RateLimiter>>>,
+    pub(crate) health: RwLock<HashMap<String, Arc<ProviderHealth>>>,
+}
+
+/// A rule routing models matching a pattern to a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelRoute {
+    /// Model name pattern; `*` matches any sequence of characters
+    pub pattern: String,
+    /// Name of the provider the models are routed to
+    pub provider: String,
+}
+
+impl ModelRoute {
+    /// Returns true if the model name matches this route's pattern
+    #[must_use]
+    pub fn matches(&self, model: &str) -> bool {
+        matches_model_pattern(&self.pattern, model)
+    }
+}
+
+/// Returns true if a model name matches a pattern in which `*` matches any
+/// sequence of characters. A pattern without `*` must match exactly.
+#[must_use]
+pub fn matches_model_pattern(pattern: &str, model: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let prefix = parts.next().unwrap_or_default();
+    let Some(mut rest) = model.strip_prefix(prefix) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((suffix, middle)) = parts.split_last() else {
+        // No wildcard: the pattern must match exactly
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(suffix)
 }
 
 impl ProviderRegistry {
@@ -25,6 +74,10 @@ impl ProviderRegistry {
         Self {
             providers: RwLock::new(HashMap::new()),
             default_provider: RwLock::new(None),
+            routes: RwLock::new(Vec::new()),
+            cache: RwLock::new(None),
+            rate_limiters: RwLock::new(HashMap::new()),
+            health: RwLock::new(HashMap::new()),
         }
     }
 
@@ -52,6 +105,11 @@ impl ProviderRegistry {
         *default = Some(name);
     }
 
+    /// Returns the name of the default provider, if one was set
+    pub fn default_name(&self) -> Option<String> {
+        self.default_provider.read().clone()
+    }
+
     /// Gets a provider by name
     pub fn get(&self, name: &str) -> Option<Arc<dyn LLMProvider>> {
         let providers = self.providers.read();
@@ -74,6 +132,72 @@ impl ProviderRegistry {
         }
     }
 
+    /// Sets the response cache shared by the registered providers
+    pub fn set_response_cache(&self, cache: Arc<ResponseCache>) {
+        *self.cache.write() = Some(cache);
+    }
+
+    /// Returns the response cache shared by the registered providers, if
+    /// caching is enabled
+    pub fn response_cache(&self) -> Option<Arc<ResponseCache>> {
+        self.cache.read().clone()
+    }
+
+    /// Sets the rate limiter requests to the named provider wait for
+    pub fn set_rate_limiter(&self, name: impl Into<String>, limiter: Arc<RateLimiter>) {
+        self.rate_limiters.write().insert(name.into(), limiter);
+    }
+
+    /// Returns the rate limiter of the named provider, if it has one
+    pub fn rate_limiter(&self, name: &str) -> Option<Arc<RateLimiter>> {
+        self.rate_limiters.read().get(name).cloned()
+    }
+
+    /// Sets the health record of the named provider
+    pub fn set_health(&self, name: impl Into<String>, health: Arc<ProviderHealth>) {
+        self.health.write().insert(name.into(), health);
+    }
+
+    /// Returns the health record of the named provider, if it is monitored
+    pub fn health(&self, name: &str) -> Option<Arc<ProviderHealth>> {
+        self.health.read().get(name).cloned()
+    }
+
+    /// Routes models matching the pattern to the named provider.
+    ///
+    /// Routes are matched in the order they were added; the first match wins.
+    pub fn add_route(&self, pattern: impl Into<String>, provider: impl Into<String>) {
+        let route = ModelRoute {
+            pattern: pattern.into(),
+            provider: provider.into(),
+        };
+        debug!(pattern = %route.pattern, provider_name = %route.provider, "Adding model route");
+        self.routes.write().push(route);
+    }
+
+    /// Lists all model routes in matching order
+    pub fn routes(&self) -> Vec<ModelRoute> {
+        self.routes.read().clone()
+    }
+
+    /// Returns the name of the provider the model is routed to, if any route matches
+    pub fn route(&self, model: &str) -> Option<String> {
+        let routes = self.routes.read();
+        routes
+            .iter()
+            .find(|route| route.matches(model))
+            .map(|route| route.provider.clone())
+    }
+
+    /// Gets the provider for a model, falling back to the default provider
+    /// if no route matches
+    pub fn provider_for_model(&self, model: &str) -> Option<Arc<dyn LLMProvider>> {
+        match self.route(model) {
+            Some(name) => self.get(&name),
+            None => self.default_provider(),
+        }
+    }
+
     /// Lists all registered provider names
     pub fn list_providers(&self) -> Vec<String> {
         let providers = self.providers.read();
@@ -121,6 +245,7 @@ mod tests {
             Ok(ChatResponse {
                 content: self.response.clone(),
                 usage: None,
+                tool_calls: Vec::new(),
             })
         }
     }
@@ -243,6 +368,53 @@ mod tests {
         assert!(default.is_some());
     }
 
+    #[test]
+    fn route_pattern_matching() {
+        let route = |pattern: &str| ModelRoute {
+            pattern: pattern.to_string(),
+            provider: "p".to_string(),
+        };
+
+        assert!(route("claude-*").matches("claude-3-opus"));
+        assert!(!route("claude-*").matches("gpt-4"));
+        assert!(route("gpt-4").matches("gpt-4"));
+        assert!(!route("gpt-4").matches("gpt-4o"));
+        assert!(route("*").matches("anything"));
+        assert!(route("*-mini").matches("gpt-4o-mini"));
+        assert!(route("llama*instruct").matches("llama-3-8b-instruct"));
+        assert!(!route("llama*instruct").matches("llama-3-8b"));
+        assert!(!route("a*ab").matches("ab"));
+    }
+
+    #[test]
+    fn provider_for_model_should_use_first_matching_route() {
+        let registry = ProviderRegistry::new();
+        registry.register(
+            "openai",
+            MockProvider {
+                response: "OpenAI".to_string(),
+            },
+        );
+        registry.register(
+            "anthropic",
+            MockProvider {
+                response: "Anthropic".to_string(),
+            },
+        );
+        registry.set_default("openai");
+        registry.add_route("claude-3-haiku", "openai");
+        registry.add_route("claude-*", "anthropic");
+
+        assert_eq!(
+            registry.route("claude-3-opus").as_deref(),
+            Some("anthropic")
+        );
+        assert_eq!(registry.route("claude-3-haiku").as_deref(), Some("openai"));
+        assert_eq!(registry.route("gpt-4"), None);
+        assert!(registry.provider_for_model("gpt-4").is_some());
+        assert_eq!(registry.routes().len(), 2);
+    }
+
     #[test]
     fn register_arc_works_correctly() {
         let registry = ProviderRegistry::new();
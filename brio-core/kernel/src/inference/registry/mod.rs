@@ -5,5 +5,6 @@
 
 pub mod core;
 pub mod routing;
+pub mod settings;
 
-pub use core::ProviderRegistry;
+pub use core::{ModelRoute, ProviderRegistry, matches_model_pattern};
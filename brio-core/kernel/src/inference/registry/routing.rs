@@ -3,7 +3,7 @@
 //! This module provides request routing to registered providers.
 
 use crate::inference::registry::core::ProviderRegistry;
-use crate::inference::types::{ChatRequest, ChatResponse, InferenceError};
+use crate::inference::types::{ChatRequest, ChatResponse, ChatStream, InferenceError};
 
 impl ProviderRegistry {
     /// Sends a chat request to the named provider
@@ -35,13 +35,68 @@ impl ProviderRegistry {
 
         provider.chat(request).await
     }
+
+    /// Sends a chat request to the provider its model is routed to
+    ///
+    /// Uses the default provider if no route matches the model.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no provider serves the model or if the chat request fails.
+    pub async fn chat_for_model(
+        &self,
+        request: ChatRequest,
+    ) -> Result<ChatResponse, InferenceError> {
+        let provider = self
+            .provider_for_model(&request.model)
+            .ok_or_else(|| InferenceError::ProviderNotFound(request.model.clone()))?;
+
+        provider.chat(request).await
+    }
+
+    /// Streams a chat completion from the provider its model is routed to
+    ///
+    /// Uses the default provider if no route matches the model.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no provider serves the model or if the stream cannot be started.
+    pub async fn chat_stream_for_model(
+        &self,
+        request: ChatRequest,
+    ) -> Result<ChatStream, InferenceError> {
+        let provider = self
+            .provider_for_model(&request.model)
+            .ok_or_else(|| InferenceError::ProviderNotFound(request.model.clone()))?;
+
+        provider.chat_stream(request).await
+    }
+
+    /// Streams a chat completion from the named provider
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider is not found or if the stream cannot be started.
+    pub async fn chat_stream(
+        &self,
+        provider_name: &str,
+        request: ChatRequest,
+    ) -> Result<ChatStream, InferenceError> {
+        let provider = self
+            .get(provider_name)
+            .ok_or_else(|| InferenceError::ProviderNotFound(provider_name.to_string()))?;
+
+        provider.chat_stream(request).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::inference::provider::LLMProvider;
-    use crate::inference::types::{ChatRequest, ChatResponse, InferenceError, Message, Role};
+    use crate::inference::types::{
+        ChatRequest, ChatResponse, InferenceError, Message, RequestOptions, Role,
+    };
     use async_trait::async_trait;
 
     struct MockProvider {
@@ -54,6 +109,7 @@ mod tests {
             Ok(ChatResponse {
                 content: self.response.clone(),
                 usage: None,
+                tool_calls: Vec::new(),
             })
         }
     }
@@ -73,7 +129,11 @@ mod tests {
             messages: vec![Message {
                 role: Role::User,
                 content: "Hi".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         };
 
         let response = registry.chat("openai", request).await.unwrap();
@@ -87,6 +147,8 @@ mod tests {
         let request = ChatRequest {
             model: "gpt-4".to_string(),
             messages: vec![],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         };
 
         let result = registry.chat("nonexistent", request).await;
@@ -113,7 +175,11 @@ mod tests {
             messages: vec![Message {
                 role: Role::User,
                 content: "Hello".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         };
 
         let response = registry.chat_default(request).await.unwrap();
@@ -128,6 +194,8 @@ mod tests {
         let request = ChatRequest {
             model: "gpt-4".to_string(),
             messages: vec![],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         };
 
         let result = registry.chat_default(request).await;
@@ -153,10 +221,65 @@ mod tests {
             messages: vec![Message {
                 role: Role::User,
                 content: "Hello".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             }],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         };
 
         let response = registry.chat_default(request).await.unwrap();
         assert_eq!(response.content, "OpenAI default");
     }
+
+    #[tokio::test]
+    async fn chat_for_model_should_route_by_model_pattern() {
+        let registry = ProviderRegistry::new();
+        registry.register(
+            "openai",
+            MockProvider {
+                response: "OpenAI".to_string(),
+            },
+        );
+        registry.register(
+            "anthropic",
+            MockProvider {
+                response: "Anthropic".to_string(),
+            },
+        );
+        registry.set_default("openai");
+        registry.add_route("claude-*", "anthropic");
+
+        let request = |model: &str| ChatRequest {
+            model: model.to_string(),
+            messages: vec![],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
+        };
+
+        let routed = registry
+            .chat_for_model(request("claude-3-opus"))
+            .await
+            .unwrap();
+        assert_eq!(routed.content, "Anthropic");
+
+        let unrouted = registry.chat_for_model(request("gpt-4")).await.unwrap();
+        assert_eq!(unrouted.content, "OpenAI");
+    }
+
+    #[tokio::test]
+    async fn chat_for_model_should_error_when_route_target_missing() {
+        let registry = ProviderRegistry::new();
+        registry.add_route("claude-*", "anthropic");
+
+        let request = ChatRequest {
+            model: "claude-3-opus".to_string(),
+            messages: vec![],
+            tools: Vec::new(),
+            options: RequestOptions::default(),
+        };
+
+        let result = registry.chat_for_model(request).await;
+        assert!(matches!(result, Err(InferenceError::ProviderNotFound(_))));
+    }
 }
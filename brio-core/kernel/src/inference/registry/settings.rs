@@ -0,0 +1,591 @@
+//! Provider registry construction from configuration.
+//!
+//! This module builds a [`ProviderRegistry`] from [`InferenceSettings`]:
+//! one rate limited provider per configured entry, model routes from their
+//! patterns and fallback chains registered under their own names.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Url;
+use secrecy::SecretString;
+
+use crate::inference::anthropic::{AnthropicConfig, AnthropicProvider};
+use crate::inference::cache::{CachingProvider, ResponseCache};
+use crate::inference::cassette::{Cassette, MatchMode, RecordingProvider, ReplayProvider};
+use crate::inference::health::{MonitoredProvider, ProviderHealth};
+use crate::inference::local::{LocalConfig, LocalProvider, LocalServerKind};
+use crate::inference::openai::{OpenAIConfig, OpenAIProvider};
+use crate::inference::provider::{FallbackProviderChain, LLMProvider};
+use crate::inference::ratelimit::{RateLimitedProvider, RateLimiter};
+use crate::inference::registry::core::ProviderRegistry;
+use crate::inference::types::{CircuitBreakerConfig, InferenceError};
+use crate::infrastructure::config::inference::{
+    CacheSettings, CassetteMatching, CassetteMode, CassetteSettings, CircuitBreakerSettings,
+    InferenceSettings, ProviderKind, ProviderSettings, RateLimitSettings,
+};
+
+/// Name of the provider built from the legacy `OpenAI` settings
+pub const LEGACY_DEFAULT_PROVIDER: &str = "default";
+/// Name of the provider built from the legacy `Anthropic` key
+pub const LEGACY_ANTHROPIC_PROVIDER: &str = "anthropic";
+
+const DEFAULT_OPENAI_BASE_URL: &str = "https://openrouter.ai/api/v1/";
+const DEFAULT_ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1/";
+
+impl ProviderRegistry {
+    /// Builds a registry from inference settings.
+    ///
+    /// If no providers are listed, the legacy `openai_*` settings register an
+    /// `OpenAI`-compatible provider named `default`, and an `anthropic_api_key`
+    /// registers an `anthropic` provider serving `claude-*` models.
+    ///
+    /// Every provider waits for its own rate limiter, shared by all callers
+    /// and by the fallback chains it is a member of.
+    ///
+    /// With a response cache configured, every registered provider answers
+    /// repeated requests from it. With a cassette configured, every
+    /// registered provider records to it or is replaced by a replay of it,
+    /// keeping names and routes intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::ConfigError` if a name is duplicated, a base
+    /// URL is invalid, a fallback chain or the default refers to an unknown
+    /// provider, or the cassette cannot be loaded.
+    pub fn from_settings(settings: &InferenceSettings) -> Result<Self, InferenceError> {
+        let registry = if settings.providers.is_empty() {
+            Self::from_legacy_settings(settings)?
+        } else {
+            Self::from_provider_settings(settings)?
+        };
+
+        // Caching goes beneath recording so that cache hits are recorded too
+        if let Some(cache) = &settings.cache {
+            registry.apply_cache(cache);
+        }
+        if let Some(cassette) = &settings.cassette {
+            registry.apply_cassette(cassette)?;
+        }
+        Ok(registry)
+    }
+
+    fn from_provider_settings(settings: &InferenceSettings) -> Result<Self, InferenceError> {
+        let registry = Self::new();
+        let mut names = HashSet::new();
+
+        for provider in &settings.providers {
+            if !names.insert(provider.name.as_str()) {
+                return Err(duplicate_name(&provider.name));
+            }
+            let limiter = Arc::new(rate_limiter(&provider.name, provider.rate_limit));
+            let (built, health) = build_provider(provider, &limiter)?;
+            registry.register_limited(provider.name.clone(), built, health, limiter);
+        }
+
+        // Chains are routed first so they take precedence over their members
+        for fallback in &settings.fallbacks {
+            if !names.insert(fallback.name.as_str()) {
+                return Err(duplicate_name(&fallback.name));
+            }
+            let mut chain = FallbackProviderChain::new();
+            for member in &fallback.providers {
+                let provider = registry.get(member).ok_or_else(|| {
+                    InferenceError::ConfigError(format!(
+                        "Fallback chain '{}' refers to unknown provider '{member}'",
+                        fallback.name
+                    ))
+                })?;
+                chain = chain.add_provider_arc(member.clone(), provider);
+            }
+            for pattern in &fallback.models {
+                registry.add_route(pattern.clone(), fallback.name.clone());
+            }
+            let health = Arc::new(ProviderHealth::new(&fallback.name));
+            registry.register(
+                fallback.name.clone(),
+                MonitoredProvider::new(Arc::new(chain), Arc::clone(&health)),
+            );
+            registry.set_health(fallback.name.clone(), health);
+        }
+
+        for provider in &settings.providers {
+            for pattern in &provider.models {
+                registry.add_route(pattern.clone(), provider.name.clone());
+            }
+        }
+
+        let default = settings
+            .default_provider
+            .clone()
+            .unwrap_or_else(|| settings.providers[0].name.clone());
+        if !names.contains(default.as_str()) {
+            return Err(InferenceError::ConfigError(format!(
+                "Default provider '{default}' is not configured"
+            )));
+        }
+        registry.set_default(default);
+
+        Ok(registry)
+    }
+
+    /// Registers a provider monitored by `health`, behind its rate limiter
+    fn register_limited(
+        &self,
+        name: String,
+        provider: Arc<dyn LLMProvider>,
+        health: ProviderHealth,
+        limiter: Arc<RateLimiter>,
+    ) {
+        let health = Arc::new(health);
+        let monitored = MonitoredProvider::new(provider, Arc::clone(&health));
+        self.register(
+            name.clone(),
+            RateLimitedProvider::new(Arc::new(monitored), Arc::clone(&limiter)),
+        );
+        self.set_health(name.clone(), health);
+        self.set_rate_limiter(name, limiter);
+    }
+
+    fn apply_cache(&self, settings: &CacheSettings) {
+        let mut cache = ResponseCache::new(&settings.path);
+        if let Some(ttl) = settings.ttl_secs {
+            cache = cache.with_ttl(Duration::from_secs(ttl));
+        }
+        if let Some(max_entries) = settings.max_entries {
+            cache = cache.with_max_entries(max_entries);
+        }
+        let cache = Arc::new(cache);
+
+        for name in self.list_providers() {
+            if let Some(inner) = self.get(&name) {
+                let caching = CachingProvider::new(name.clone(), inner, Arc::clone(&cache));
+                self.register(name, caching);
+            }
+        }
+        self.set_response_cache(cache);
+    }
+
+    fn apply_cassette(&self, settings: &CassetteSettings) -> Result<(), InferenceError> {
+        let names = self.list_providers();
+        match settings.mode {
+            CassetteMode::Record => {
+                let cassette = Arc::new(Cassette::open(&settings.path)?);
+                for name in names {
+                    if let Some(inner) = self.get(&name) {
+                        let recording = RecordingProvider::new(inner, Arc::clone(&cassette));
+                        self.register(name, recording);
+                    }
+                }
+            }
+            CassetteMode::Replay => {
+                let cassette = Arc::new(Cassette::load(&settings.path)?);
+                let mode = match settings.matching {
+                    CassetteMatching::Strict => MatchMode::Strict,
+                    CassetteMatching::Fuzzy => MatchMode::Fuzzy,
+                };
+                for name in names {
+                    self.register(name, ReplayProvider::new(Arc::clone(&cassette), mode));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn from_legacy_settings(settings: &InferenceSettings) -> Result<Self, InferenceError> {
+        let registry = Self::new();
+
+        let openai_key = settings
+            .openai_api_key
+            .clone()
+            .unwrap_or_else(|| SecretString::new("sk-placeholder".into()));
+        let openai_base = settings
+            .openai_base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_OPENAI_BASE_URL);
+        let limiter = Arc::new(RateLimiter::new(LEGACY_DEFAULT_PROVIDER));
+        let openai = OpenAIProvider::new(
+            OpenAIConfig::new(openai_key, parse_url(LEGACY_DEFAULT_PROVIDER, openai_base)?)
+                .with_rate_limiter(Arc::clone(&limiter)),
+        );
+        let health = ProviderHealth::new(LEGACY_DEFAULT_PROVIDER)
+            .with_circuit_breaker(openai.circuit_breaker());
+        registry.register_limited(
+            LEGACY_DEFAULT_PROVIDER.to_string(),
+            Arc::new(openai),
+            health,
+            limiter,
+        );
+        registry.set_default(LEGACY_DEFAULT_PROVIDER);
+
+        if let Some(key) = settings.anthropic_api_key.clone() {
+            let limiter = Arc::new(RateLimiter::new(LEGACY_ANTHROPIC_PROVIDER));
+            let anthropic = AnthropicProvider::new(
+                AnthropicConfig::new(
+                    key,
+                    parse_url(LEGACY_ANTHROPIC_PROVIDER, DEFAULT_ANTHROPIC_BASE_URL)?,
+                )
+                .with_rate_limiter(Arc::clone(&limiter)),
+            );
+            let health = ProviderHealth::new(LEGACY_ANTHROPIC_PROVIDER)
+                .with_circuit_breaker(anthropic.circuit_breaker());
+            registry.register_limited(
+                LEGACY_ANTHROPIC_PROVIDER.to_string(),
+                Arc::new(anthropic),
+                health,
+                limiter,
+            );
+            registry.add_route("claude-*", LEGACY_ANTHROPIC_PROVIDER);
+        }
+
+        Ok(registry)
+    }
+}
+
+fn build_provider(
+    settings: &ProviderSettings,
+    limiter: &Arc<RateLimiter>,
+) -> Result<(Arc<dyn LLMProvider>, ProviderHealth), InferenceError> {
+    let api_key = settings
+        .api_key
+        .clone()
+        .unwrap_or_else(|| SecretString::new(String::new().into()));
+
+    let health = ProviderHealth::new(&settings.name);
+    let built: (Arc<dyn LLMProvider>, ProviderHealth) = match settings.kind {
+        ProviderKind::OpenAI => {
+            let base_url = settings
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_OPENAI_BASE_URL);
+            let mut config = OpenAIConfig::new(api_key, parse_url(&settings.name, base_url)?)
+                .with_rate_limiter(Arc::clone(limiter));
+            if let Some(max_retries) = settings.max_retries {
+                config = config.with_max_retries(max_retries);
+            }
+            if let Some(delay_ms) = settings.base_delay_ms {
+                config = config.with_base_delay_ms(delay_ms);
+            }
+            if let Some(circuit_breaker) = settings.circuit_breaker {
+                config = config.with_circuit_breaker(circuit_breaker_config(circuit_breaker));
+            }
+            let provider = OpenAIProvider::new(config);
+            let health = health.with_circuit_breaker(provider.circuit_breaker());
+            (Arc::new(provider), health)
+        }
+        ProviderKind::Anthropic => {
+            let base_url = settings
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_ANTHROPIC_BASE_URL);
+            let mut config = AnthropicConfig::new(api_key, parse_url(&settings.name, base_url)?)
+                .with_rate_limiter(Arc::clone(limiter));
+            if let Some(max_retries) = settings.max_retries {
+                config = config.with_max_retries(max_retries);
+            }
+            if let Some(delay_ms) = settings.base_delay_ms {
+                config = config.with_base_delay_ms(delay_ms);
+            }
+            if let Some(max_tokens) = settings.max_tokens {
+                config = config.with_max_tokens(max_tokens);
+            }
+            if let Some(circuit_breaker) = settings.circuit_breaker {
+                config = config.with_circuit_breaker(circuit_breaker_config(circuit_breaker));
+            }
+            let provider = AnthropicProvider::new(config);
+            let health = health.with_circuit_breaker(provider.circuit_breaker());
+            (Arc::new(provider), health)
+        }
+        ProviderKind::Ollama | ProviderKind::LlamaCpp => {
+            let server = if settings.kind == ProviderKind::Ollama {
+                LocalServerKind::Ollama
+            } else {
+                LocalServerKind::LlamaCpp
+            };
+            let base_url = settings
+                .base_url
+                .as_deref()
+                .unwrap_or(server.default_base_url());
+            let mut config = LocalConfig::new(server, parse_url(&settings.name, base_url)?)
+                .with_rate_limiter(Arc::clone(limiter));
+            if let Some(api_key) = settings.api_key.clone() {
+                config = config.with_api_key(api_key);
+            }
+            if let Some(keep_alive) = settings.keep_alive.clone() {
+                config = config.with_keep_alive(keep_alive);
+            }
+            if let Some(max_retries) = settings.max_retries {
+                config = config.with_max_retries(max_retries);
+            }
+            if let Some(delay_ms) = settings.base_delay_ms {
+                config = config.with_base_delay_ms(delay_ms);
+            }
+            if let Some(circuit_breaker) = settings.circuit_breaker {
+                config = config.with_circuit_breaker(circuit_breaker_config(circuit_breaker));
+            }
+            let provider = LocalProvider::new(config);
+            let health = health.with_circuit_breaker(provider.circuit_breaker());
+            (Arc::new(provider), health)
+        }
+    };
+
+    Ok(built)
+}
+
+fn rate_limiter(name: &str, settings: Option<RateLimitSettings>) -> RateLimiter {
+    let settings = settings.unwrap_or_default();
+    let mut limiter = RateLimiter::new(name);
+    if let Some(requests) = settings.requests_per_minute {
+        limiter = limiter.with_requests_per_minute(requests);
+    }
+    if let Some(tokens) = settings.tokens_per_minute {
+        limiter = limiter.with_tokens_per_minute(tokens);
+    }
+    limiter
+}
+
+fn circuit_breaker_config(settings: CircuitBreakerSettings) -> CircuitBreakerConfig {
+    let mut config = CircuitBreakerConfig::new();
+    if let Some(threshold) = settings.failure_threshold {
+        config = config.with_failure_threshold(threshold);
+    }
+    if let Some(timeout_ms) = settings.reset_timeout_ms {
+        config = config.with_reset_timeout_ms(timeout_ms);
+    }
+    if let Some(max_calls) = settings.half_open_max_calls {
+        config = config.with_half_open_max_calls(max_calls);
+    }
+    config
+}
+
+fn parse_url(provider: &str, url: &str) -> Result<Url, InferenceError> {
+    Url::parse(url).map_err(|e| {
+        InferenceError::ConfigError(format!(
+            "Invalid base URL '{url}' for provider '{provider}': {e}"
+        ))
+    })
+}
+
+fn duplicate_name(name: &str) -> InferenceError {
+    InferenceError::ConfigError(format!("Provider '{name}' is configured more than once"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::inference::FallbackSettings;
+
+    fn provider(name: &str, kind: ProviderKind, models: &[&str]) -> ProviderSettings {
+        ProviderSettings {
+            name: name.to_string(),
+            kind,
+            base_url: None,
+            api_key: None,
+            models: models.iter().map(ToString::to_string).collect(),
+            max_retries: Some(1),
+            base_delay_ms: None,
+            max_tokens: None,
+            keep_alive: None,
+            circuit_breaker: Some(CircuitBreakerSettings::default()),
+            rate_limit: None,
+        }
+    }
+
+    #[test]
+    fn legacy_settings_register_default_and_anthropic() {
+        let registry = ProviderRegistry::from_settings(&InferenceSettings::default()).unwrap();
+        assert_eq!(registry.list_providers(), vec!["default".to_string()]);
+
+        let settings = InferenceSettings {
+            anthropic_api_key: Some(SecretString::new("sk-ant".into())),
+            ..InferenceSettings::default()
+        };
+        let registry = ProviderRegistry::from_settings(&settings).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.route("claude-3-opus").as_deref(),
+            Some("anthropic")
+        );
+        assert_eq!(registry.route("gpt-4"), None);
+    }
+
+    #[test]
+    fn providers_routes_and_fallbacks_are_built() {
+        let settings = InferenceSettings {
+            providers: vec![
+                provider("openai", ProviderKind::OpenAI, &["gpt-*"]),
+                provider("anthropic", ProviderKind::Anthropic, &["claude-*"]),
+                provider("ollama", ProviderKind::Ollama, &["llama*"]),
+            ],
+            fallbacks: vec![FallbackSettings {
+                name: "resilient".to_string(),
+                providers: vec!["anthropic".to_string(), "openai".to_string()],
+                models: vec!["claude-3-opus*".to_string()],
+            }],
+            default_provider: Some("resilient".to_string()),
+            ..InferenceSettings::default()
+        };
+
+        let registry = ProviderRegistry::from_settings(&settings).unwrap();
+        assert_eq!(registry.len(), 4);
+        assert_eq!(
+            registry.route("claude-3-opus-20240229").as_deref(),
+            Some("resilient")
+        );
+        assert_eq!(
+            registry.route("claude-3-haiku").as_deref(),
+            Some("anthropic")
+        );
+        assert_eq!(registry.route("gpt-4o").as_deref(), Some("openai"));
+        assert_eq!(registry.route("llama3.1:8b").as_deref(), Some("ollama"));
+        assert!(registry.provider_for_model("mistral").is_some());
+        assert!(registry.rate_limiter("anthropic").is_some());
+        assert!(registry.rate_limiter("resilient").is_none());
+        assert!(registry.health("resilient").is_some());
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let duplicate = InferenceSettings {
+            providers: vec![
+                provider("openai", ProviderKind::OpenAI, &[]),
+                provider("openai", ProviderKind::Anthropic, &[]),
+            ],
+            ..InferenceSettings::default()
+        };
+
+        let mut bad_url = provider("openai", ProviderKind::OpenAI, &[]);
+        bad_url.base_url = Some("not a url".to_string());
+        let bad_url = InferenceSettings {
+            providers: vec![bad_url],
+            ..InferenceSettings::default()
+        };
+
+        let unknown_member = InferenceSettings {
+            providers: vec![provider("openai", ProviderKind::OpenAI, &[])],
+            fallbacks: vec![FallbackSettings {
+                name: "chain".to_string(),
+                providers: vec!["missing".to_string()],
+                models: Vec::new(),
+            }],
+            ..InferenceSettings::default()
+        };
+
+        let unknown_default = InferenceSettings {
+            providers: vec![provider("openai", ProviderKind::OpenAI, &[])],
+            default_provider: Some("missing".to_string()),
+            ..InferenceSettings::default()
+        };
+
+        for settings in [duplicate, bad_url, unknown_member, unknown_default] {
+            assert!(matches!(
+                ProviderRegistry::from_settings(&settings),
+                Err(InferenceError::ConfigError(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn tripped_circuit_fails_fast() {
+        use crate::inference::types::ChatRequest;
+
+        let settings = InferenceSettings {
+            providers: vec![provider("openai", ProviderKind::OpenAI, &[])],
+            ..InferenceSettings::default()
+        };
+        let registry = ProviderRegistry::from_settings(&settings).unwrap();
+        let health = registry.health("openai").unwrap();
+        assert!(health.trip().await);
+
+        let result = registry
+            .get("openai")
+            .unwrap()
+            .chat(ChatRequest::with_message("gpt-4", "Hi"))
+            .await;
+        assert!(matches!(result, Err(InferenceError::CircuitBreakerOpen(_))));
+        assert_eq!(health.recent().errors, 1);
+    }
+
+    #[tokio::test]
+    async fn cache_answers_before_providers() {
+        use crate::inference::types::{ChatRequest, ChatResponse, RequestOptions};
+
+        let dir = tempfile::tempdir().unwrap();
+        let request = ChatRequest::with_message("gpt-4", "Review")
+            .with_options(RequestOptions::new().with_temperature(0.0));
+        ResponseCache::new(dir.path())
+            .put(&request, &ChatResponse::new("cached review"))
+            .unwrap();
+
+        let settings = InferenceSettings {
+            cache: Some(CacheSettings {
+                path: dir.path().to_path_buf(),
+                ttl_secs: Some(3600),
+                max_entries: None,
+            }),
+            ..InferenceSettings::default()
+        };
+        let registry = ProviderRegistry::from_settings(&settings).unwrap();
+
+        let response = registry.chat_for_model(request).await.unwrap();
+        assert_eq!(response.content, "cached review");
+        assert_eq!(registry.response_cache().unwrap().stats().hits, 1);
+        assert!(
+            ProviderRegistry::from_settings(&InferenceSettings::default())
+                .unwrap()
+                .response_cache()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_cassette_replaces_providers() {
+        use crate::inference::types::{ChatRequest, ChatResponse};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        let request = ChatRequest::with_message("claude-3-opus", "Plan");
+        Cassette::new(&path)
+            .record(&request, &ChatResponse::new("recorded plan"))
+            .unwrap();
+
+        let settings = InferenceSettings {
+            anthropic_api_key: Some(SecretString::new("sk-ant".into())),
+            cassette: Some(CassetteSettings {
+                mode: CassetteMode::Replay,
+                path: path.clone(),
+                matching: CassetteMatching::Strict,
+            }),
+            ..InferenceSettings::default()
+        };
+        let registry = ProviderRegistry::from_settings(&settings).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.route("claude-3-opus").as_deref(),
+            Some("anthropic")
+        );
+
+        let response = registry.chat_for_model(request).await.unwrap();
+        assert_eq!(response.content, "recorded plan");
+        assert!(
+            registry
+                .chat_for_model(ChatRequest::with_message("gpt-4", "Other"))
+                .await
+                .is_err()
+        );
+
+        let missing = InferenceSettings {
+            cassette: Some(CassetteSettings {
+                mode: CassetteMode::Replay,
+                path: dir.path().join("missing.json"),
+                matching: CassetteMatching::Fuzzy,
+            }),
+            ..InferenceSettings::default()
+        };
+        assert!(matches!(
+            ProviderRegistry::from_settings(&missing),
+            Err(InferenceError::ConfigError(_))
+        ));
+    }
+}
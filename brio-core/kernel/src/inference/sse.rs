@@ -0,0 +1,218 @@
+//! Server-Sent Events support for streaming providers.
+//!
+//! This module decodes `text/event-stream` bodies into events and turns a
+//! streaming HTTP response into a [`ChatStream`] using a provider-specific
+//! event handler.
+
+use crate::inference::types::{ChatChunk, ChatStream, InferenceError};
+use futures_util::stream;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default time to wait for the next chunk of a stream (in milliseconds)
+pub const DEFAULT_CHUNK_TIMEOUT_MS: u64 = 30000;
+
+/// A single Server-Sent Event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The event type, if the server named it
+    pub event: Option<String>,
+    /// The event data, with multiple `data:` lines joined by newlines
+    pub data: String,
+}
+
+/// Incremental decoder for `text/event-stream` bodies.
+///
+/// Bytes may be pushed in arbitrary pieces; events are returned once their
+/// terminating blank line has been received.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    /// Creates a decoder with an empty buffer
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds bytes into the decoder and returns all completed events
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some((end, separator)) = find_event_end(&self.buffer) {
+            let block: Vec<u8> = self.buffer.drain(..end + separator).collect();
+            if let Some(event) = parse_block(&String::from_utf8_lossy(&block[..end])) {
+                events.push(event);
+            }
+        }
+        events
+    }
+}
+
+/// Finds the end of the first complete event and the length of its separator
+fn find_event_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    (0..buffer.len()).find_map(|i| {
+        let rest = &buffer[i..];
+        if rest.starts_with(b"\r\n\r\n") {
+            Some((i, 4))
+        } else if rest.starts_with(b"\n\n") || rest.starts_with(b"\r\r") {
+            Some((i, 2))
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_block(block: &str) -> Option<SseEvent> {
+    let mut event = SseEvent::default();
+    let mut data_lines = Vec::new();
+
+    for line in block.lines() {
+        if line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event.event = Some(value.to_string()),
+            "data" => data_lines.push(value),
+            _ => {}
+        }
+    }
+
+    if data_lines.is_empty() {
+        return None;
+    }
+    event.data = data_lines.join("\n");
+    Some(event)
+}
+
+/// What a provider makes of a single event.
+#[derive(Debug)]
+pub enum StreamStep {
+    /// Yield a chunk and keep reading
+    Chunk(ChatChunk),
+    /// Ignore the event
+    Skip,
+    /// The completion is finished, optionally with a last chunk
+    Done(Option<ChatChunk>),
+}
+
+struct EventStreamState<F> {
+    response: reqwest::Response,
+    decoder: SseDecoder,
+    pending: VecDeque<SseEvent>,
+    handle: F,
+    chunk_timeout: Duration,
+    finished: bool,
+}
+
+/// Turns a streaming HTTP response into a [`ChatStream`].
+///
+/// Each decoded event is passed to `handle`. The stream ends after
+/// [`StreamStep::Done`] or the first error; a body that ends before the
+/// handler reports completion yields a network error.
+pub fn event_stream<F>(
+    response: reqwest::Response,
+    chunk_timeout: Duration,
+    handle: F,
+) -> ChatStream
+where
+    F: FnMut(SseEvent) -> Result<StreamStep, InferenceError> + Send + 'static,
+{
+    let state = EventStreamState {
+        response,
+        decoder: SseDecoder::new(),
+        pending: VecDeque::new(),
+        handle,
+        chunk_timeout,
+        finished: false,
+    };
+
+    ChatStream::new(stream::unfold(state, |mut state| async move {
+        loop {
+            if state.finished {
+                return None;
+            }
+
+            if let Some(event) = state.pending.pop_front() {
+                match (state.handle)(event) {
+                    Ok(StreamStep::Chunk(chunk)) => return Some((Ok(chunk), state)),
+                    Ok(StreamStep::Skip) => continue,
+                    Ok(StreamStep::Done(chunk)) => {
+                        state.finished = true;
+                        return chunk.map(|chunk| (Ok(chunk), state));
+                    }
+                    Err(error) => {
+                        state.finished = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+
+            let next = tokio::time::timeout(state.chunk_timeout, state.response.chunk()).await;
+            let error = match next {
+                Ok(Ok(Some(bytes))) => {
+                    let events = state.decoder.push(&bytes);
+                    state.pending.extend(events);
+                    continue;
+                }
+                Ok(Ok(None)) => {
+                    InferenceError::NetworkError("Stream ended before completion".to_string())
+                }
+                Ok(Err(e)) => InferenceError::NetworkError(e.to_string()),
+                Err(_) => InferenceError::NetworkError(format!(
+                    "No stream data received for {} ms",
+                    state.chunk_timeout.as_millis()
+                )),
+            };
+            state.finished = true;
+            return Some((Err(error), state));
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_events_split_across_pushes() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(b"event: ping\nda").is_empty());
+        let events = decoder.push(b"ta: {\"a\":1}\n\ndata: second\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("ping".to_string()),
+                    data: "{\"a\":1}".to_string(),
+                },
+                SseEvent {
+                    event: None,
+                    data: "second".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handles_crlf_comments_and_multiline_data() {
+        let mut decoder = SseDecoder::new();
+        let events = decoder.push(b": keep-alive\r\n\r\ndata: line one\r\ndata: line two\r\n\r\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "line one\nline two");
+    }
+
+    #[test]
+    fn test_keeps_multibyte_characters_split_across_pushes() {
+        let mut decoder = SseDecoder::new();
+        let bytes = "data: caf\u{e9}\n\n".as_bytes();
+        assert!(decoder.push(&bytes[..10]).is_empty());
+        let events = decoder.push(&bytes[10..]);
+        assert_eq!(events[0].data, "caf\u{e9}");
+    }
+}
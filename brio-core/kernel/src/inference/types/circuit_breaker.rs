@@ -3,6 +3,7 @@
 //! This module provides circuit breaker pattern implementation to prevent
 //! cascading failures in the inference system.
 
+use serde::Serialize;
 use std::time::{Duration, Instant};
 
 /// Default failure threshold before circuit breaker opens
@@ -63,7 +64,8 @@ impl Default for CircuitBreakerConfig {
 }
 
 /// The state of a circuit breaker.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CircuitBreakerState {
     /// Circuit is closed, requests flow through normally
     #[default]
@@ -74,6 +76,18 @@ pub enum CircuitBreakerState {
     HalfOpen,
 }
 
+impl CircuitBreakerState {
+    /// Returns the value exported for the state: 0 closed, 1 half-open, 2 open
+    #[must_use]
+    pub const fn gauge_value(self) -> f64 {
+        match self {
+            Self::Closed => 0.0,
+            Self::HalfOpen => 1.0,
+            Self::Open => 2.0,
+        }
+    }
+}
+
 /// Circuit breaker statistics for monitoring.
 #[derive(Debug, Clone, Default)]
 pub struct CircuitBreakerStats {
@@ -92,6 +106,7 @@ pub struct CircuitBreakerStats {
 }
 
 /// A circuit breaker for preventing cascading failures.
+#[derive(Debug)]
 pub struct CircuitBreaker {
     config: CircuitBreakerConfig,
     state: CircuitBreakerState,
@@ -20,6 +20,9 @@ pub enum InferenceError {
     /// Configuration error
     #[error("Configuration Error: {0}")]
     ConfigError(String),
+    /// Request rejected before being sent, e.g. an option out of range
+    #[error("Invalid Request: {0}")]
+    InvalidRequest(String),
     /// Provider not found in registry
     #[error("Provider Not Found: {0}")]
     ProviderNotFound(String),
@@ -29,6 +32,9 @@ pub enum InferenceError {
     /// All providers in chain failed
     #[error("All Providers Failed")]
     AllProvidersFailed,
+    /// A token budget of the calling task or plugin is used up
+    #[error("Budget Exceeded: {0}")]
+    BudgetExceeded(String),
 }
 
 impl InferenceError {
@@ -45,7 +51,9 @@ impl InferenceError {
             | Self::AllProvidersFailed
             | Self::ContextLengthExceeded
             | Self::ConfigError(_)
-            | Self::ProviderNotFound(_) => false,
+            | Self::InvalidRequest(_)
+            | Self::ProviderNotFound(_)
+            | Self::BudgetExceeded(_) => false,
         }
     }
 
@@ -77,7 +85,9 @@ mod tests {
         assert!(!InferenceError::AllProvidersFailed.is_retryable());
         assert!(!InferenceError::ContextLengthExceeded.is_retryable());
         assert!(!InferenceError::ConfigError("invalid".to_string()).is_retryable());
+        assert!(!InferenceError::InvalidRequest("seed".to_string()).is_retryable());
         assert!(!InferenceError::ProviderNotFound("missing".to_string()).is_retryable());
+        assert!(!InferenceError::BudgetExceeded("task".to_string()).is_retryable());
     }
 
     #[test]
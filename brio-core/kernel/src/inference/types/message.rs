@@ -2,6 +2,7 @@
 //!
 //! This module contains message and role definitions used in conversations.
 
+use crate::inference::types::tool::ToolCall;
 use serde::{Deserialize, Serialize};
 
 /// The role of a message in a conversation.
@@ -14,6 +15,8 @@ pub enum Role {
     User,
     /// Assistant response
     Assistant,
+    /// Result of a tool call
+    Tool,
 }
 
 /// A message in a conversation.
@@ -23,6 +26,43 @@ pub struct Message {
     pub role: Role,
     /// The content of the message
     pub content: String,
+    /// Tool calls made by the assistant in this message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// The call a tool message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    /// Creates a new message
+    #[must_use]
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    /// Creates an assistant message that calls tools
+    #[must_use]
+    pub fn tool_calls(content: impl Into<String>, tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            tool_calls,
+            ..Self::new(Role::Assistant, content)
+        }
+    }
+
+    /// Creates a message carrying the result of a tool call
+    #[must_use]
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new(Role::Tool, content)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -42,6 +82,10 @@ mod tests {
         let assistant = Role::Assistant;
         let json = serde_json::to_string(&assistant).unwrap();
         assert_eq!(json, "\"assistant\"");
+
+        let tool = Role::Tool;
+        let json = serde_json::to_string(&tool).unwrap();
+        assert_eq!(json, "\"tool\"");
     }
 
     #[test]
@@ -58,22 +102,25 @@ mod tests {
 
     #[test]
     fn test_message_creation() {
-        let msg = Message {
-            role: Role::User,
-            content: "Hello".to_string(),
-        };
+        let msg = Message::new(Role::User, "Hello");
         assert!(matches!(msg.role, Role::User));
         assert_eq!(msg.content, "Hello");
     }
 
     #[test]
     fn test_message_serialization() {
-        let msg = Message {
-            role: Role::Assistant,
-            content: "Hi there".to_string(),
-        };
+        let msg = Message::new(Role::Assistant, "Hi there");
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"role\":\"assistant\""));
         assert!(json.contains("\"content\":\"Hi there\""));
+        assert!(!json.contains("tool_calls"));
+    }
+
+    #[test]
+    fn test_tool_result_message() {
+        let msg = Message::tool_result("call_1", "file contents");
+        assert_eq!(msg.role, Role::Tool);
+        assert_eq!(msg.tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(msg.content, "file contents");
     }
 }
@@ -5,8 +5,11 @@
 pub mod circuit_breaker;
 pub mod error;
 pub mod message;
+pub mod options;
 pub mod request;
 pub mod response;
+pub mod stream;
+pub mod tool;
 
 // Re-export all types for convenience
 pub use circuit_breaker::{
@@ -15,5 +18,8 @@ pub use circuit_breaker::{
 };
 pub use error::InferenceError;
 pub use message::{Message, Role};
+pub use options::{RequestOptions, ResponseFormat};
 pub use request::ChatRequest;
 pub use response::{ChatResponse, Usage};
+pub use stream::{ChatChunk, ChatStream};
+pub use tool::{ToolCall, ToolDefinition};
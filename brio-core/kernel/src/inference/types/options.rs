@@ -0,0 +1,164 @@
+//! Request options for inference operations.
+//!
+//! This module contains the sampling parameters, stop sequences and response
+//! format controls carried with a chat request. Providers check the options
+//! against their own limits before sending a request.
+
+use serde::{Deserialize, Serialize};
+
+use crate::inference::types::error::InferenceError;
+
+/// Format the model must answer in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text
+    #[default]
+    Text,
+    /// A single JSON object
+    Json,
+}
+
+/// Sampling and output controls for a chat request.
+///
+/// Unset options use the provider's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestOptions {
+    /// Sampling temperature; lower is more deterministic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Sequences that stop generation when produced
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Seed for best-effort deterministic sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Format of the response
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+impl RequestOptions {
+    /// Creates options using the provider's defaults
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sampling temperature
+    #[must_use]
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the nucleus sampling probability mass
+    #[must_use]
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Sets the maximum number of tokens to generate
+    #[must_use]
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Sets the stop sequences
+    #[must_use]
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    /// Sets the sampling seed
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the response format
+    #[must_use]
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
+
+    /// Checks the options against the limits shared by all providers.
+    ///
+    /// `max_temperature` is the provider's upper bound for the temperature.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::InvalidRequest` if an option is out of range.
+    pub fn validate(&self, max_temperature: f32) -> Result<(), InferenceError> {
+        if let Some(temperature) = self
+            .temperature
+            .filter(|t| !(0.0..=max_temperature).contains(t))
+        {
+            return Err(InferenceError::InvalidRequest(format!(
+                "temperature must be between 0 and {max_temperature}, got {temperature}"
+            )));
+        }
+        if let Some(top_p) = self.top_p.filter(|p| !(0.0..=1.0).contains(p)) {
+            return Err(InferenceError::InvalidRequest(format!(
+                "top_p must be between 0 and 1, got {top_p}"
+            )));
+        }
+        if self.max_tokens == Some(0) {
+            return Err(InferenceError::InvalidRequest(
+                "max_tokens must be greater than 0".to_string(),
+            ));
+        }
+        if self.stop.iter().any(String::is_empty) {
+            return Err(InferenceError::InvalidRequest(
+                "stop sequences must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ranges() {
+        assert!(RequestOptions::new().validate(1.0).is_ok());
+        assert!(
+            RequestOptions::new()
+                .with_temperature(0.0)
+                .with_top_p(0.9)
+                .with_max_tokens(256)
+                .with_stop(vec!["END".to_string()])
+                .validate(1.0)
+                .is_ok()
+        );
+
+        let too_hot = RequestOptions::new().with_temperature(1.5);
+        assert!(too_hot.validate(2.0).is_ok());
+        assert!(matches!(
+            too_hot.validate(1.0),
+            Err(InferenceError::InvalidRequest(_))
+        ));
+
+        for invalid in [
+            RequestOptions::new().with_temperature(f32::NAN),
+            RequestOptions::new().with_top_p(1.1),
+            RequestOptions::new().with_max_tokens(0),
+            RequestOptions::new().with_stop(vec![String::new()]),
+        ] {
+            assert!(invalid.validate(2.0).is_err(), "{invalid:?}");
+        }
+    }
+}
@@ -3,14 +3,23 @@
 //! This module contains request definitions for chat completions.
 
 use crate::inference::types::message::Message;
+use crate::inference::types::options::RequestOptions;
+use crate::inference::types::tool::ToolDefinition;
+use serde::{Deserialize, Serialize};
 
 /// Request for a chat completion.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
     /// The model to use for completion
     pub model: String,
     /// The conversation history
     pub messages: Vec<Message>,
+    /// Tools the model may call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolDefinition>,
+    /// Sampling and output controls
+    #[serde(default)]
+    pub options: RequestOptions,
 }
 
 impl ChatRequest {
@@ -20,6 +29,8 @@ impl ChatRequest {
         Self {
             model: model.into(),
             messages,
+            tools: Vec::new(),
+            options: RequestOptions::default(),
         }
     }
 
@@ -27,13 +38,7 @@ impl ChatRequest {
     #[must_use]
     pub fn with_message(model: impl Into<String>, content: impl Into<String>) -> Self {
         use crate::inference::types::message::Role;
-        Self {
-            model: model.into(),
-            messages: vec![Message {
-                role: Role::User,
-                content: content.into(),
-            }],
-        }
+        Self::new(model, vec![Message::new(Role::User, content)])
     }
 
     /// Adds a message to the conversation
@@ -43,10 +48,21 @@ impl ChatRequest {
         role: crate::inference::types::message::Role,
         content: impl Into<String>,
     ) -> Self {
-        self.messages.push(Message {
-            role,
-            content: content.into(),
-        });
+        self.messages.push(Message::new(role, content));
+        self
+    }
+
+    /// Offers tools to the model
+    #[must_use]
+    pub fn with_tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Sets the sampling and output controls
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
         self
     }
 }
@@ -58,10 +74,7 @@ mod tests {
 
     #[test]
     fn test_chat_request_new() {
-        let messages = vec![Message {
-            role: Role::User,
-            content: "Hello".to_string(),
-        }];
+        let messages = vec![Message::new(Role::User, "Hello")];
         let request = ChatRequest::new("gpt-4", messages);
         assert_eq!(request.model, "gpt-4");
         assert_eq!(request.messages.len(), 1);
@@ -2,10 +2,11 @@
 //!
 //! This module contains response and usage definitions for chat completions.
 
+use crate::inference::types::tool::ToolCall;
 use serde::{Deserialize, Serialize};
 
 /// Token usage information for a completion request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Usage {
     /// Number of tokens in the prompt
     pub prompt_tokens: u32,
@@ -22,6 +23,9 @@ pub struct ChatResponse {
     pub content: String,
     /// Token usage information, if available
     pub usage: Option<Usage>,
+    /// Tool calls requested by the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
 }
 
 impl ChatResponse {
@@ -31,6 +35,7 @@ impl ChatResponse {
         Self {
             content: content.into(),
             usage: None,
+            tool_calls: Vec::new(),
         }
     }
 
@@ -50,6 +55,7 @@ impl ChatResponse {
                 completion_tokens: completion,
                 total_tokens: prompt + completion,
             }),
+            tool_calls: Vec::new(),
         }
     }
 
@@ -65,6 +71,12 @@ impl ChatResponse {
         self.usage.is_some()
     }
 
+    /// Returns true if the model requested tool calls
+    #[must_use]
+    pub fn has_tool_calls(&self) -> bool {
+        !self.tool_calls.is_empty()
+    }
+
     /// Returns the total token count if available
     #[must_use]
     pub fn total_tokens(&self) -> Option<u32> {
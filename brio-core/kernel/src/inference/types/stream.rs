@@ -0,0 +1,155 @@
+//! Streaming types for inference operations.
+//!
+//! This module contains the chunk and stream definitions for token-by-token
+//! chat completions.
+
+use crate::inference::types::error::InferenceError;
+use crate::inference::types::response::{ChatResponse, Usage};
+use crate::inference::types::tool::ToolCall;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An incremental piece of a streamed chat completion.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatChunk {
+    /// Text generated since the previous chunk
+    pub delta: String,
+    /// Token usage, reported on the final chunk if the provider supplies it
+    pub usage: Option<Usage>,
+    /// Why generation stopped, set on the final chunk
+    pub finish_reason: Option<String>,
+    /// Tool calls the model made, assembled from their deltas and set on
+    /// the final chunk
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl ChatChunk {
+    /// Creates a chunk carrying generated text
+    #[must_use]
+    pub fn delta(delta: impl Into<String>) -> Self {
+        Self {
+            delta: delta.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns true if this chunk ends the completion
+    #[must_use]
+    pub fn is_final(&self) -> bool {
+        self.finish_reason.is_some() || self.usage.is_some()
+    }
+}
+
+/// A stream of chat completion chunks.
+///
+/// Dropping the stream cancels the underlying request.
+pub struct ChatStream {
+    inner: BoxStream<'static, Result<ChatChunk, InferenceError>>,
+}
+
+impl ChatStream {
+    /// Wraps a stream of chunks
+    pub fn new(
+        inner: impl Stream<Item = Result<ChatChunk, InferenceError>> + Send + 'static,
+    ) -> Self {
+        Self {
+            inner: inner.boxed(),
+        }
+    }
+
+    /// Creates a stream yielding a finished response as a single chunk
+    #[must_use]
+    pub fn from_response(response: ChatResponse) -> Self {
+        let chunk = ChatChunk {
+            delta: response.content,
+            usage: response.usage,
+            finish_reason: Some("stop".to_string()),
+            tool_calls: response.tool_calls,
+        };
+        Self::new(stream::once(async move { Ok(chunk) }))
+    }
+
+    /// Consumes the stream and assembles the full response.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error yielded by the stream.
+    pub async fn collect_response(mut self) -> Result<ChatResponse, InferenceError> {
+        let mut response = ChatResponse::new(String::new());
+        while let Some(chunk) = self.next().await {
+            let chunk = chunk?;
+            response.content.push_str(&chunk.delta);
+            if chunk.usage.is_some() {
+                response.usage = chunk.usage;
+            }
+            response.tool_calls.extend(chunk.tool_calls);
+        }
+        Ok(response)
+    }
+}
+
+impl Stream for ChatStream {
+    type Item = Result<ChatChunk, InferenceError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for ChatStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChatStream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_from_response_yields_single_final_chunk() {
+        let mut stream = ChatStream::from_response(ChatResponse::with_usage("Hello", 3, 1));
+        let chunk = stream.next().await.unwrap().unwrap();
+        assert_eq!(chunk.delta, "Hello");
+        assert!(chunk.is_final());
+        assert_eq!(chunk.usage.unwrap().total_tokens, 4);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_collect_response_joins_deltas() {
+        let chunks = vec![
+            Ok(ChatChunk::delta("Hel")),
+            Ok(ChatChunk::delta("lo")),
+            Ok(ChatChunk {
+                usage: Some(Usage {
+                    prompt_tokens: 2,
+                    completion_tokens: 2,
+                    total_tokens: 4,
+                }),
+                finish_reason: Some("tool_calls".to_string()),
+                tool_calls: vec![ToolCall::new("call_1", "ls", r#"{"path":"."}"#)],
+                ..ChatChunk::default()
+            }),
+        ];
+        let response = ChatStream::new(stream::iter(chunks))
+            .collect_response()
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Hello");
+        assert_eq!(response.total_tokens(), Some(4));
+        assert_eq!(response.tool_calls[0].name, "ls");
+    }
+
+    #[tokio::test]
+    async fn test_collect_response_propagates_errors() {
+        let chunks = vec![Ok(ChatChunk::delta("Hel")), Err(InferenceError::RateLimit)];
+        let result = ChatStream::new(stream::iter(chunks))
+            .collect_response()
+            .await;
+        assert!(matches!(result, Err(InferenceError::RateLimit)));
+    }
+}
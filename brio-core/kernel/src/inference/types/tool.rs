@@ -0,0 +1,93 @@
+//! Tool calling types for inference operations.
+//!
+//! This module contains the definitions of tools offered to a model and the
+//! calls a model makes to them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A tool the model may call, described by a JSON schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    /// Unique name of the tool
+    pub name: String,
+    /// What the tool does, shown to the model
+    pub description: String,
+    /// JSON schema of the tool's arguments
+    pub parameters: Value,
+}
+
+impl ToolDefinition {
+    /// Creates a new tool definition
+    #[must_use]
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: Value) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        }
+    }
+}
+
+/// A call to a tool requested by the model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Provider-assigned identifier, echoed back with the tool result
+    pub id: String,
+    /// Name of the tool to call
+    pub name: String,
+    /// Arguments as a JSON object string
+    pub arguments: String,
+}
+
+impl ToolCall {
+    /// Creates a new tool call
+    #[must_use]
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            arguments: arguments.into(),
+        }
+    }
+
+    /// Parses the arguments as JSON, treating empty arguments as an empty object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments are not valid JSON.
+    pub fn parsed_arguments(&self) -> Result<Value, serde_json::Error> {
+        if self.arguments.trim().is_empty() {
+            return Ok(Value::Object(serde_json::Map::new()));
+        }
+        serde_json::from_str(&self.arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsed_arguments() {
+        let call = ToolCall::new("call_1", "read_file", r#"{"path":"src/lib.rs"}"#);
+        assert_eq!(call.parsed_arguments().unwrap()["path"], "src/lib.rs");
+
+        let empty = ToolCall::new("call_2", "done", "");
+        assert!(
+            empty
+                .parsed_arguments()
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .is_empty()
+        );
+
+        let invalid = ToolCall::new("call_3", "done", "{");
+        assert!(invalid.parsed_arguments().is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Pub/sub event delivery configuration for the Brio kernel.
+//!
+//! This module defines how often delivery of an event to a failing
+//! subscriber is retried before the event is moved to the dead letter topic,
+//! and how long handled events are kept in the log.
+
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Event delivery settings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct EventSettings {
+    /// Attempts at handling an event before it is dead-lettered (default: 5).
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry in milliseconds; doubles after every
+    /// further failure (default: 500).
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Longest delay between retries in milliseconds (default: 30000).
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Hours handled events and dead letters are kept in the log; 0 keeps
+    /// them forever (default: 168).
+    #[serde(default = "default_retention_hours")]
+    pub retention_hours: u64,
+}
+
+impl EventSettings {
+    /// Returns how long handled events are kept, or `None` to keep them
+    /// forever.
+    #[must_use]
+    pub fn retention(&self) -> Option<Duration> {
+        (self.retention_hours > 0).then(|| Duration::from_secs(self.retention_hours * 3600))
+    }
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            retention_hours: default_retention_hours(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    500
+}
+
+fn default_max_backoff_ms() -> u64 {
+    30000
+}
+
+fn default_retention_hours() -> u64 {
+    168
+}
@@ -1,17 +1,179 @@
 //! Inference provider configuration for the Brio kernel.
 //!
-//! This module defines AI/LLM API settings.
+//! This module defines AI/LLM API settings: the providers to register, the
+//! model patterns routed to each of them and the fallback chains built from
+//! them.
 
 use secrecy::SecretString;
 use serde::Deserialize;
+use std::path::PathBuf;
 
 /// Inference provider settings.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct InferenceSettings {
-    /// `OpenAI` API key.
+    /// `OpenAI` API key. Only used when no providers are listed.
     pub openai_api_key: Option<SecretString>,
-    /// `Anthropic` API key.
+    /// `Anthropic` API key. Only used when no providers are listed.
     pub anthropic_api_key: Option<SecretString>,
-    /// Base URL for `OpenAI` API.
+    /// Base URL for `OpenAI` API. Only used when no providers are listed.
     pub openai_base_url: Option<String>,
+    /// Providers to register.
+    #[serde(default)]
+    pub providers: Vec<ProviderSettings>,
+    /// Fallback chains, registered as providers of their own.
+    #[serde(default)]
+    pub fallbacks: Vec<FallbackSettings>,
+    /// Provider or fallback chain used for unrouted models.
+    /// Uses the first listed provider if unset.
+    #[serde(default)]
+    pub default_provider: Option<String>,
+    /// Records inference traffic to, or replays it from, a cassette file.
+    #[serde(default)]
+    pub cassette: Option<CassetteSettings>,
+    /// Caches responses to identical requests. Disabled if unset.
+    #[serde(default)]
+    pub cache: Option<CacheSettings>,
+}
+
+/// API spoken by a provider.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// `OpenAI`-compatible chat completions API.
+    OpenAI,
+    /// `Anthropic` messages API.
+    Anthropic,
+    /// Local Ollama server, using its native chat API.
+    Ollama,
+    /// Local llama.cpp server.
+    #[serde(rename = "llama_cpp")]
+    LlamaCpp,
+}
+
+/// A single inference provider.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProviderSettings {
+    /// Name the provider is registered under.
+    pub name: String,
+    /// API spoken by the provider.
+    pub kind: ProviderKind,
+    /// Base URL of the API. Uses the vendor's public endpoint if unset.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// API key.
+    #[serde(default)]
+    pub api_key: Option<SecretString>,
+    /// Model patterns routed to this provider, e.g. `gpt-*`.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Maximum number of retries for transient failures.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Base delay between retries in milliseconds.
+    #[serde(default)]
+    pub base_delay_ms: Option<u64>,
+    /// Token limit for requests that do not set their own
+    /// (`Anthropic` only).
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// How long the model stays loaded after a request, e.g. `30m`
+    /// (Ollama only).
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+    /// Circuit breaker settings. The circuit breaker is disabled if unset.
+    #[serde(default)]
+    pub circuit_breaker: Option<CircuitBreakerSettings>,
+    /// Request and token limits. Only limits reported by the provider are
+    /// honored if unset.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitSettings>,
+}
+
+/// Rate limits for a provider, shared by all of its callers.
+///
+/// Unset limits are not enforced locally.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+pub struct RateLimitSettings {
+    /// Maximum number of requests per minute.
+    #[serde(default)]
+    pub requests_per_minute: Option<u64>,
+    /// Maximum number of prompt and completion tokens per minute.
+    #[serde(default)]
+    pub tokens_per_minute: Option<u64>,
+}
+
+/// Circuit breaker settings for a provider.
+///
+/// Unset values use the kernel defaults.
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+pub struct CircuitBreakerSettings {
+    /// Number of failures before opening the circuit.
+    #[serde(default)]
+    pub failure_threshold: Option<u32>,
+    /// Time in milliseconds to wait before attempting a reset.
+    #[serde(default)]
+    pub reset_timeout_ms: Option<u64>,
+    /// Maximum number of test calls while half-open.
+    #[serde(default)]
+    pub half_open_max_calls: Option<u32>,
+}
+
+/// A chain of providers tried in order until one succeeds.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FallbackSettings {
+    /// Name the chain is registered under.
+    pub name: String,
+    /// Names of the providers in the chain, in order.
+    pub providers: Vec<String>,
+    /// Model patterns routed to this chain. Chains are matched before
+    /// individual providers.
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+/// Whether a cassette is being recorded or replayed.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Forward requests to the providers and record every exchange.
+    Record,
+    /// Answer from the cassette without contacting any provider.
+    Replay,
+}
+
+/// How replayed requests are matched against recorded ones.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMatching {
+    /// The whole request must be identical.
+    #[default]
+    Strict,
+    /// Only the model and message text must match, ignoring whitespace.
+    Fuzzy,
+}
+
+/// Record/replay settings for reproducible agent runs.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CassetteSettings {
+    /// Whether to record or replay.
+    pub mode: CassetteMode,
+    /// Cassette file.
+    pub path: PathBuf,
+    /// How replayed requests are matched (default: strict).
+    #[serde(default)]
+    pub matching: CassetteMatching,
+}
+
+/// Response cache settings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheSettings {
+    /// Directory the cached responses are stored in.
+    pub path: PathBuf,
+    /// Seconds a cached response stays valid. Never expires if unset.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    /// Maximum number of cached responses; the oldest are evicted first
+    /// (default: 10000).
+    #[serde(default)]
+    pub max_entries: Option<usize>,
 }
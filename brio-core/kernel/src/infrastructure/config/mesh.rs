@@ -3,6 +3,8 @@
 //! This module defines distributed mesh node settings.
 
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Mesh networking settings.
 #[derive(Debug, Deserialize, Clone)]
@@ -11,4 +13,73 @@ pub struct MeshSettings {
     pub node_id: Option<String>,
     /// Port to listen on for mesh connections.
     pub port: Option<u16>,
+    /// Address other nodes reach this node's mesh server at
+    /// (default: `127.0.0.1:<port>`).
+    #[serde(default)]
+    pub advertise_address: Option<String>,
+    /// Mesh addresses of nodes to join the cluster through.
+    #[serde(default)]
+    pub bootstrap_nodes: Vec<String>,
+    /// Interval between heartbeats to known nodes in milliseconds (default: 2000).
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+    /// Silence after which a node is suspected to be down in milliseconds
+    /// (default: 6000).
+    #[serde(default = "default_suspect_after_ms")]
+    pub suspect_after_ms: u64,
+    /// Silence after which a node is considered dead and no longer routed to,
+    /// in milliseconds (default: 20000).
+    #[serde(default = "default_dead_after_ms")]
+    pub dead_after_ms: u64,
+    /// Mutual TLS for mesh connections. Connections are cleartext if unset.
+    #[serde(default)]
+    pub tls: Option<MeshTlsSettings>,
+    /// Components each node may call on this node, by node ID; `"*"` allows
+    /// every component. Any node may call any component if empty. Requires
+    /// `tls`, since only certificates prove which node is calling.
+    #[serde(default)]
+    pub allowed_calls: HashMap<String, Vec<String>>,
+}
+
+/// Mutual TLS settings for mesh connections.
+///
+/// Every node presents a certificate signed by the cluster CA that names its
+/// node ID as a DNS subject alternative name, and only accepts peers doing
+/// the same.
+#[derive(Debug, Deserialize, Clone)]
+pub struct MeshTlsSettings {
+    /// PEM file with the cluster CA certificate.
+    pub ca_cert: PathBuf,
+    /// PEM file with this node's certificate.
+    pub cert: PathBuf,
+    /// PEM file with this node's private key.
+    pub key: PathBuf,
+}
+
+impl Default for MeshSettings {
+    fn default() -> Self {
+        Self {
+            node_id: None,
+            port: None,
+            advertise_address: None,
+            bootstrap_nodes: Vec::new(),
+            heartbeat_interval_ms: default_heartbeat_interval_ms(),
+            suspect_after_ms: default_suspect_after_ms(),
+            dead_after_ms: default_dead_after_ms(),
+            tls: None,
+            allowed_calls: HashMap::new(),
+        }
+    }
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    2000
+}
+
+fn default_suspect_after_ms() -> u64 {
+    6000
+}
+
+fn default_dead_after_ms() -> u64 {
+    20000
 }
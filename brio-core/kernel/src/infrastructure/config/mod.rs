@@ -2,7 +2,8 @@
 //!
 //! This module provides structured configuration for various
 //! domains including server, database, telemetry, mesh networking,
-//! inference providers, sandbox policies, and branching orchestration.
+//! inference providers, planning, plugins, sandbox policies, branching orchestration,
+//! inference usage accounting, pub/sub event delivery and the supervisor.
 //!
 //! # Example
 //!
@@ -14,22 +15,35 @@
 
 pub mod branching;
 pub mod database;
+pub mod events;
 pub mod inference;
 pub mod mesh;
+pub mod planner;
+pub mod plugins;
 pub mod sandbox;
 pub mod server;
+pub mod supervisor;
 pub mod telemetry;
+pub mod usage;
 
 // Re-export all config types for backward compatibility
 pub use branching::BranchingSettings;
 pub use database::DatabaseSettings;
-pub use inference::InferenceSettings;
-pub use mesh::MeshSettings;
+pub use events::EventSettings;
+pub use inference::{
+    CacheSettings, CassetteMatching, CassetteMode, CassetteSettings, CircuitBreakerSettings,
+    FallbackSettings, InferenceSettings, ProviderKind, ProviderSettings, RateLimitSettings,
+};
+pub use mesh::{MeshSettings, MeshTlsSettings};
+pub use planner::PlannerSettings;
+pub use plugins::PluginSettings;
 pub use sandbox::SandboxSettings;
 pub use server::ServerSettings;
+pub use supervisor::{SupervisorSettings, VerificationSettings, VerificationStepSettings};
 pub use telemetry::TelemetrySettings;
+pub use usage::{PriceSettings, UsageSettings};
 
-use config::{Config, ConfigError, Environment};
+use config::{Config, ConfigError, Environment, File, FileFormat};
 use serde::Deserialize;
 
 /// Top-level configuration for the Brio kernel.
@@ -45,16 +59,32 @@ pub struct Settings {
     pub mesh: Option<MeshSettings>,
     /// Inference provider settings.
     pub inference: Option<InferenceSettings>,
+    /// Planner settings.
+    #[serde(default)]
+    pub planner: PlannerSettings,
+    /// Plugin settings.
+    #[serde(default)]
+    pub plugins: PluginSettings,
     /// Sandbox settings.
     #[serde(default)]
     pub sandbox: SandboxSettings,
     /// Branching orchestrator settings.
     #[serde(default)]
     pub branching: BranchingSettings,
+    /// Inference usage budgets and prices.
+    #[serde(default)]
+    pub usage: UsageSettings,
+    /// Pub/sub event delivery retries.
+    #[serde(default)]
+    pub events: EventSettings,
+    /// Supervisor workspace and verification policy.
+    #[serde(default)]
+    pub supervisor: SupervisorSettings,
 }
 
 impl Settings {
-    /// Creates a new settings instance from environment variables and defaults.
+    /// Creates a new settings instance from defaults, an optional `brio.toml`
+    /// in the working directory, and environment variables.
     ///
     /// # Errors
     ///
@@ -68,6 +98,8 @@ impl Settings {
             .set_default("server.port", 9090)?
             .set_default("telemetry.service_name", "brio-kernel")?
             .set_default("telemetry.sampling_ratio", 1.0)?
+            // Lists such as inference providers are easiest to declare in a file
+            .add_source(File::new("brio.toml", FileFormat::Toml).required(false))
             // Merge in Environment variables
             .add_source(Environment::with_prefix("BRIO").separator("__"))
             .build()?;
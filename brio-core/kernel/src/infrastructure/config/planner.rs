@@ -0,0 +1,44 @@
+//! Planner configuration for the Brio kernel.
+//!
+//! This module defines how the host-side planner prompts the inference
+//! provider to decompose objectives.
+
+use serde::Deserialize;
+
+/// Planner settings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlannerSettings {
+    /// Model requested from the provider (default: "best-available").
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// Registered provider to plan with. Uses the provider the model is routed
+    /// to if unset.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Prompt template with `{objective}` and `{max_steps}` placeholders.
+    /// Uses the built-in prompt if unset.
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    /// Maximum number of steps in a plan (default: 10)
+    #[serde(default = "default_max_steps")]
+    pub max_steps: usize,
+}
+
+impl Default for PlannerSettings {
+    fn default() -> Self {
+        Self {
+            model: default_model(),
+            provider: None,
+            prompt_template: None,
+            max_steps: default_max_steps(),
+        }
+    }
+}
+
+fn default_model() -> String {
+    "best-available".to_string()
+}
+
+fn default_max_steps() -> usize {
+    10
+}
@@ -0,0 +1,38 @@
+//! Plugin configuration for the Brio kernel.
+//!
+//! This module defines where plugins are loaded from and where their
+//! compiled components are cached.
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Plugin loading settings.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PluginSettings {
+    /// Directory scanned for plugins. Defaults to `plugins` in the working directory.
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// Directory for compiled component artifacts. Defaults to `.cache`
+    /// inside the plugin directory.
+    #[serde(default)]
+    pub cache_dir: Option<String>,
+}
+
+impl PluginSettings {
+    /// Returns the plugin directory, resolving the default.
+    #[must_use]
+    pub fn directory(&self) -> PathBuf {
+        self.directory.as_ref().map_or_else(
+            || std::env::current_dir().unwrap_or_default().join("plugins"),
+            PathBuf::from,
+        )
+    }
+
+    /// Returns the compiled component cache directory, resolving the default.
+    #[must_use]
+    pub fn cache_dir(&self) -> PathBuf {
+        self.cache_dir
+            .as_ref()
+            .map_or_else(|| self.directory().join(".cache"), PathBuf::from)
+    }
+}
@@ -1,13 +1,19 @@
 //! Sandbox configuration for the Brio kernel.
 //!
-//! This module defines filesystem sandbox security settings.
+//! This module defines filesystem sandbox security settings and the
+//! resource limits applied to guest components.
 
+use crate::engine::limits::ResourceLimits;
 use serde::Deserialize;
 
-/// Sandbox settings for controlling allowed paths.
+/// Sandbox settings for controlling allowed paths and guest resources.
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct SandboxSettings {
     /// Paths that are allowed in the sandbox.
     #[serde(default)]
     pub allowed_paths: Vec<String>,
+    /// Default resource limits for plugins, and the ceiling for limits
+    /// requested in plugin manifests.
+    #[serde(default)]
+    pub limits: ResourceLimits,
 }
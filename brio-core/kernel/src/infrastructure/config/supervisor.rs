@@ -0,0 +1,52 @@
+//! Supervisor configuration for the Brio kernel.
+//!
+//! This module defines the workspace the supervisor component begins task
+//! sessions on and the checks it runs before completing a task. The kernel
+//! hands both to the supervisor through the `supervisor-config` interface.
+
+use serde::{Deserialize, Serialize};
+
+/// Supervisor settings.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SupervisorSettings {
+    /// Directory task sessions are begun on. Tasks have no session if unset.
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// Checks run before a task is completed.
+    #[serde(default)]
+    pub verification: VerificationSettings,
+}
+
+/// Verification policy applied by the supervisor.
+///
+/// Serializes to the JSON returned by `supervisor-config.verification-policy`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct VerificationSettings {
+    /// Checks run in order. Tasks are completed without checks if empty.
+    #[serde(default)]
+    pub steps: Vec<VerificationStepSettings>,
+    /// Failed verification rounds after which a task fails. Uses the
+    /// supervisor's default (3) if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rounds: Option<u32>,
+}
+
+/// A single verification check.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VerificationStepSettings {
+    /// Runs a command in the task's session.
+    Command {
+        /// Program followed by its arguments.
+        command: Vec<String>,
+        /// Tool component running the command. Uses `shell` if unset.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool: Option<String>,
+    },
+    /// Asks a reviewer agent to review the changes.
+    Review {
+        /// Agent performing the review. Uses `agent_reviewer` if unset.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+    },
+}
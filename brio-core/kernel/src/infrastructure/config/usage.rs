@@ -0,0 +1,34 @@
+//! Inference usage configuration for the Brio kernel.
+//!
+//! This module defines the token budgets enforced on guest inference calls
+//! and the price table used to estimate their cost.
+
+use serde::Deserialize;
+
+/// Usage accounting settings.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct UsageSettings {
+    /// Maximum tokens a single task may consume. Unlimited if unset.
+    #[serde(default)]
+    pub max_tokens_per_task: Option<u64>,
+    /// Maximum tokens a single plugin may consume across all of its tasks
+    /// until its budget is reset through the REST API. Unlimited if unset.
+    #[serde(default)]
+    pub max_tokens_per_plugin: Option<u64>,
+    /// Prices used for cost estimates. The first entry matching a model wins.
+    #[serde(default)]
+    pub prices: Vec<PriceSettings>,
+}
+
+/// Price of a model or family of models.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PriceSettings {
+    /// Model name or pattern, e.g. `gpt-4o*`; `*` matches any characters.
+    pub model: String,
+    /// Price in USD per million prompt tokens.
+    #[serde(default)]
+    pub prompt_per_million: f64,
+    /// Price in USD per million completion tokens.
+    #[serde(default)]
+    pub completion_per_million: f64,
+}
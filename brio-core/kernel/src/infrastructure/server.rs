@@ -3,7 +3,7 @@
 //! This module provides the control plane HTTP server with health checks,
 //! metrics, profiling endpoints, and WebSocket support for real-time communication.
 
-use crate::api::session_routes;
+use crate::api::api_router;
 use crate::host::BrioHostState;
 use crate::infrastructure::config::Settings;
 use crate::ws::handler::ws_router;
@@ -103,7 +103,7 @@ pub async fn run_server(config: &Settings, host_state: Arc<BrioHostState>) -> an
         .route("/health/ready", get(health_check))
         .route("/metrics", get(move || std::future::ready(handle.render())))
         .route("/debug/pprof/profile", get(pprof_profile))
-        .merge(session_routes())
+        .merge(api_router())
         .with_state(host_state.clone());
 
     let app = control_plane.merge(ws_router(host_state));
@@ -14,6 +14,8 @@ pub mod api;
 pub mod branch_manager;
 /// WebAssembly component engine and runtime.
 pub mod engine;
+/// Durable pub/sub event log and delivery.
+pub mod events;
 /// Host state and WIT interface implementations.
 pub mod host;
 /// LLM inference providers and registry.
@@ -22,10 +24,14 @@ pub mod inference;
 pub mod infrastructure;
 /// Distributed mesh networking.
 pub mod mesh;
+/// Task planning backed by the inference registry.
+pub mod planner;
 /// Plugin registry and management.
 pub mod registry;
 /// SQL store and query policy.
 pub mod store;
+/// Inference token accounting, budgets and cost estimates.
+pub mod usage;
 /// Virtual file system for sandboxed operations.
 pub mod vfs;
 /// WebSocket broadcaster for real-time updates.
@@ -3,10 +3,15 @@
 //! This is the main entry point for the Brio kernel, responsible for
 //! initializing all subsystems: telemetry, plugin registry, inference providers,
 //! host state, mesh server, and control plane.
+//!
+//! Running `brio-kernel migrate status` or `brio-kernel migrate up` inspects or
+//! applies the database schema migrations and exits without starting the kernel.
 
 use anyhow::Context;
 use brio_kernel::host::BrioHostState;
 use brio_kernel::infrastructure::{audit, config::Settings, server, telemetry::TelemetryBuilder};
+use brio_kernel::mesh::{CallPolicy, MeshTls};
+use brio_kernel::store::{MigrationState, Migrator};
 use secrecy::ExposeSecret;
 use std::sync::Arc;
 use tokio::signal;
@@ -16,14 +21,19 @@ use tracing::{error, info};
 async fn main() -> anyhow::Result<()> {
     let config = Arc::new(Settings::new().context("Failed to load configuration")?);
 
+    if let Some(command) = parse_migrate_command()? {
+        return run_migrate_command(&config, command).await;
+    }
+
     init_telemetry(&config).context("Failed to initialize telemetry")?;
     log_startup();
 
-    let plugin_registry = init_plugin_registry().await?;
+    let plugin_registry = init_plugin_registry(&config).await?;
     let registry = init_inference_provider(&config)?;
-    let state = init_host_state(&config, registry, &plugin_registry).await?;
+    let mesh_tls = load_mesh_tls(&config)?;
+    let state = init_host_state(&config, registry, &plugin_registry, mesh_tls.clone()).await?;
 
-    start_mesh_server(&config, &state);
+    start_mesh_server(&config, &state, mesh_tls);
     start_control_plane(&config, &state);
 
     info!("Brio Kernel Initialized. Waiting for shutdown signal...");
@@ -33,6 +43,64 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Subcommands of `brio-kernel migrate`.
+enum MigrateCommand {
+    /// Print the state of every known migration.
+    Status,
+    /// Apply all pending migrations.
+    Up,
+}
+
+fn parse_migrate_command() -> anyhow::Result<Option<MigrateCommand>> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("migrate") {
+        return Ok(None);
+    }
+
+    match args.next().as_deref() {
+        Some("status") => Ok(Some(MigrateCommand::Status)),
+        Some("up") => Ok(Some(MigrateCommand::Up)),
+        other => anyhow::bail!(
+            "Unknown migrate command {other:?}. Usage: brio-kernel migrate <status|up>"
+        ),
+    }
+}
+
+async fn run_migrate_command(config: &Settings, command: MigrateCommand) -> anyhow::Result<()> {
+    let pool = brio_kernel::store::open_pool(config.database.url.expose_secret())
+        .await
+        .context("Failed to open database")?;
+    let migrator = Migrator::new();
+
+    match command {
+        MigrateCommand::Status => {
+            for status in migrator.status(&pool).await? {
+                let state = match status.state {
+                    MigrationState::Applied { applied_at } => format!("applied {applied_at}"),
+                    MigrationState::Pending => "pending".to_string(),
+                    MigrationState::Drifted { .. } => "CHECKSUM MISMATCH".to_string(),
+                };
+                println!(
+                    "{:>4}  {:<30}  {}",
+                    status.version, state, status.description
+                );
+            }
+        }
+        MigrateCommand::Up => {
+            let applied = migrator.run(&pool).await?;
+            if applied.is_empty() {
+                println!("Database schema is up to date");
+            } else {
+                for version in applied {
+                    println!("Applied migration {version}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn init_telemetry(config: &Settings) -> anyhow::Result<()> {
     let mut builder = TelemetryBuilder::new("brio-kernel", "0.1.0")
         .with_log_level("debug")
@@ -52,20 +120,33 @@ fn log_startup() {
     });
 }
 
-async fn init_plugin_registry()
--> anyhow::Result<std::sync::Arc<brio_kernel::registry::PluginRegistry>> {
+async fn init_plugin_registry(
+    config: &Settings,
+) -> anyhow::Result<std::sync::Arc<brio_kernel::registry::PluginRegistry>> {
     let engine_config = brio_kernel::engine::linker::create_engine_config();
     let engine = wasmtime::Engine::new(&engine_config)?;
-    let mut registry = brio_kernel::registry::PluginRegistry::new(engine);
-    let plugins_dir = std::env::current_dir().unwrap_or_default().join("plugins");
-
-    if let Err(e) = registry.load_from_directory(&plugins_dir).await {
-        error!("Failed to load plugins from {:?}: {:?}", plugins_dir, e);
-    } else {
-        let plugins = registry.list_plugins();
-        info!("Loaded {} plugins from {:?}", plugins.len(), plugins_dir);
-        for p in &plugins {
-            info!(" - Plugin: {} ({:?})", p.id, p.path);
+    let mut registry = brio_kernel::registry::PluginRegistry::new(engine)?
+        .with_cache_dir(config.plugins.cache_dir())
+        .with_default_limits(config.sandbox.limits);
+    let plugins_dir = config.plugins.directory();
+
+    match registry.load_from_directory(&plugins_dir).await {
+        Err(e) => error!("Failed to load plugins from {:?}: {:?}", plugins_dir, e),
+        Ok(report) => {
+            let plugins = registry.list_plugins();
+            info!("Loaded {} plugins from {:?}", plugins.len(), plugins_dir);
+            for p in &plugins {
+                info!(
+                    " - Plugin: {} {} ({:?}) permissions={:?}",
+                    p.id,
+                    p.version.as_deref().unwrap_or("(no manifest)"),
+                    p.path,
+                    p.permissions
+                );
+            }
+            for failure in &report.failures {
+                error!(" - Rejected plugin {:?}: {}", failure.path, failure.error);
+            }
         }
     }
 
@@ -75,27 +156,16 @@ async fn init_plugin_registry()
 fn init_inference_provider(
     config: &Settings,
 ) -> anyhow::Result<brio_kernel::inference::ProviderRegistry> {
-    let openai_key = config
-        .inference
-        .as_ref()
-        .and_then(|i| i.openai_api_key.clone())
-        .unwrap_or_else(|| secrecy::SecretString::new("sk-placeholder".into()));
-
-    let openai_base = config
-        .inference
-        .as_ref()
-        .and_then(|i| i.openai_base_url.clone())
-        .unwrap_or_else(|| "https://openrouter.ai/api/v1/".to_string());
-
-    let provider_config = brio_kernel::inference::OpenAIConfig::new(
-        openai_key,
-        reqwest::Url::parse(&openai_base).context("Invalid OpenAI Base URL")?,
-    );
-    let provider = brio_kernel::inference::OpenAIProvider::new(provider_config);
-
-    let registry = brio_kernel::inference::ProviderRegistry::new();
-    registry.register_arc("default", std::sync::Arc::new(provider));
-    registry.set_default("default");
+    let settings = config.inference.clone().unwrap_or_default();
+    let registry = brio_kernel::inference::ProviderRegistry::from_settings(&settings)
+        .context("Invalid inference provider configuration")?;
+
+    for route in registry.routes() {
+        info!(
+            "Routing models '{}' to provider '{}'",
+            route.pattern, route.provider
+        );
+    }
 
     Ok(registry)
 }
@@ -104,6 +174,7 @@ async fn init_host_state(
     config: &Settings,
     registry: brio_kernel::inference::ProviderRegistry,
     plugin_registry: &std::sync::Arc<brio_kernel::registry::PluginRegistry>,
+    mesh_tls: Option<MeshTls>,
 ) -> anyhow::Result<std::sync::Arc<BrioHostState>> {
     let db_url = config.database.url.expose_secret();
 
@@ -131,16 +202,91 @@ async fn init_host_state(
         .context("Failed to initialize host state")?
     };
 
+    let state = match mesh_tls {
+        Some(tls) => state.with_mesh_tls(tls),
+        None => state,
+    };
+    let state = state
+        .with_planner(brio_kernel::planner::Planner::from_settings(
+            &config.planner,
+        ))
+        .with_usage_tracker(brio_kernel::usage::UsageTracker::from_settings(
+            &config.usage,
+        ))
+        .with_event_retry(brio_kernel::events::RetryPolicy::from_settings(
+            &config.events,
+        ))
+        .with_supervisor_settings(config.supervisor.clone());
+
+    let subscribers = state
+        .resume_event_delivery()
+        .await
+        .context("Failed to resume event delivery")?;
+    info!("Resumed event delivery to {subscribers} subscribers");
+    if let Some(retention) = config.events.retention() {
+        drop(state.spawn_event_pruning(retention));
+    }
     Ok(std::sync::Arc::new(state))
 }
 
-fn start_mesh_server(config: &Settings, state: &std::sync::Arc<BrioHostState>) {
-    let Some(node_id) = config.mesh.as_ref().and_then(|m| m.node_id.clone()) else {
+fn load_mesh_tls(config: &Settings) -> anyhow::Result<Option<MeshTls>> {
+    let Some(mesh) = config.mesh.as_ref() else {
+        return Ok(None);
+    };
+    let Some(tls) = mesh.tls.as_ref() else {
+        // Without TLS the caller's node ID is only its own claim, so an
+        // allowlist keyed by node ID would be trivially bypassed.
+        anyhow::ensure!(
+            mesh.allowed_calls.is_empty(),
+            "mesh.allowed_calls requires mesh.tls: over cleartext any node can claim to be a listed node"
+        );
+        return Ok(None);
+    };
+    let tls = MeshTls::load(tls).context("Failed to load mesh TLS certificates")?;
+    info!("Mesh connections use mutual TLS");
+    Ok(Some(tls))
+}
+
+fn start_mesh_server(
+    config: &Settings,
+    state: &std::sync::Arc<BrioHostState>,
+    mesh_tls: Option<MeshTls>,
+) {
+    let Some(settings) = config.mesh.clone() else {
+        return;
+    };
+    let Some(node_id) = settings.node_id.clone() else {
         return;
     };
 
     let state_clone = state.clone();
-    let port = config.mesh.as_ref().and_then(|m| m.port).unwrap_or(50051);
+    let port = settings.port.unwrap_or(50051);
+    let advertise_address = settings
+        .advertise_address
+        .clone()
+        .unwrap_or_else(|| format!("127.0.0.1:{port}"));
+    let Ok(address) = brio_kernel::mesh::types::NodeAddress::new(&advertise_address) else {
+        error!("Invalid mesh advertise address: {}", advertise_address);
+        return;
+    };
+    let id =
+        brio_kernel::mesh::types::NodeId::try_from_str(&node_id).expect("Node ID should be valid");
+
+    if let Some(router) = state.remote_router() {
+        let local = brio_kernel::mesh::types::NodeInfo::new(
+            id.clone(),
+            address.clone(),
+            state.local_capabilities(),
+            0,
+        )
+        .expect("Node info should be valid");
+        let membership = brio_kernel::mesh::Membership::new(
+            local,
+            router.clone(),
+            brio_kernel::mesh::MembershipConfig::from_settings(&settings),
+        );
+        drop(membership.spawn());
+    }
 
     tokio::spawn(async move {
         let addr_str = format!("0.0.0.0:{port}");
@@ -149,13 +295,24 @@ fn start_mesh_server(config: &Settings, state: &std::sync::Arc<BrioHostState>) {
             return;
         };
 
-        let id = brio_kernel::mesh::types::NodeId::try_from_str(&node_id)
-            .expect("Node ID should be valid");
-        let service = brio_kernel::mesh::service::MeshService::new(state_clone, id);
+        let service = brio_kernel::mesh::service::MeshService::new(state_clone, id)
+            .with_address(address)
+            .with_policy(CallPolicy::new(settings.allowed_calls.clone()));
+
+        let mut server = tonic::transport::Server::builder();
+        if let Some(tls) = &mesh_tls {
+            server = match server.tls_config(tls.server_config()) {
+                Ok(server) => server,
+                Err(e) => {
+                    error!("Invalid mesh TLS configuration: {:?}", e);
+                    return;
+                }
+            };
+        }
 
         info!("Mesh gRPC server listening on {}", addr);
 
-        if let Err(e) = tonic::transport::Server::builder()
+        if let Err(e) = server
             .add_service(
                 brio_kernel::mesh::grpc::mesh_transport_server::MeshTransportServer::new(service),
             )
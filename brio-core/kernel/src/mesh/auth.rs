@@ -0,0 +1,95 @@
+//! Authentication and authorization of incoming mesh calls.
+
+use std::collections::HashMap;
+use tonic::{Request, Status};
+
+use crate::mesh::tls::certificate_names;
+
+/// Wildcard granting a node every component
+pub const ANY_COMPONENT: &str = "*";
+
+/// Components each node may call on this node.
+///
+/// An empty policy lets every node call every component.
+#[derive(Debug, Clone, Default)]
+pub struct CallPolicy {
+    allowed: HashMap<String, Vec<String>>,
+}
+
+impl CallPolicy {
+    /// Creates a policy from the components allowed per node ID.
+    #[must_use]
+    pub fn new(allowed: HashMap<String, Vec<String>>) -> Self {
+        Self { allowed }
+    }
+
+    /// Returns true if `caller` may call `component`.
+    ///
+    /// Callers that did not identify themselves are only allowed if the
+    /// policy is empty.
+    #[must_use]
+    pub fn allows(&self, caller: &str, component: &str) -> bool {
+        if self.allowed.is_empty() {
+            return true;
+        }
+        self.allowed.get(caller).is_some_and(|components| {
+            components
+                .iter()
+                .any(|allowed| allowed == ANY_COMPONENT || allowed == component)
+        })
+    }
+}
+
+/// Checks that the sender of `request` is the node `claimed`.
+///
+/// Requests received over mutual TLS must carry a client certificate naming
+/// the claimed node ID. Cleartext requests cannot be checked and are trusted.
+///
+/// # Errors
+///
+/// Returns `Unauthenticated` if the client certificate does not name the
+/// claimed node.
+pub fn authenticate<T>(request: &Request<T>, claimed: &str) -> Result<(), Status> {
+    let Some(certs) = request.peer_certs() else {
+        return Ok(());
+    };
+    let names = certs
+        .first()
+        .map(|cert| certificate_names(cert))
+        .unwrap_or_default();
+    if !claimed.is_empty() && names.iter().any(|name| name == claimed) {
+        return Ok(());
+    }
+    tracing::warn!(
+        claimed,
+        ?names,
+        "Rejected mesh request from unverified node"
+    );
+    Err(Status::unauthenticated(format!(
+        "Client certificate does not belong to node '{claimed}'"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_policy_allows_everything() {
+        assert!(CallPolicy::default().allows("", "agent_coder"));
+    }
+
+    #[test]
+    fn test_policy_limits_callers_to_listed_components() {
+        let policy = CallPolicy::new(HashMap::from([
+            ("node-a".to_string(), vec!["agent_coder".to_string()]),
+            ("node-b".to_string(), vec![ANY_COMPONENT.to_string()]),
+        ]));
+
+        assert!(policy.allows("node-a", "agent_coder"));
+        assert!(!policy.allows("node-a", "tool_shell"));
+        assert!(policy.allows("node-b", "tool_shell"));
+        assert!(!policy.allows("node-c", "agent_coder"));
+        assert!(!policy.allows("", "agent_coder"));
+    }
+}
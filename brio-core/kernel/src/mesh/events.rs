@@ -1,13 +1,66 @@
 //! Event bus for pub/sub messaging between plugins.
+//!
+//! Subscriptions are topic patterns in which `*` matches any run of
+//! characters, so `branch:*` matches every branch event. Events published on
+//! one node reach subscribers on other nodes through the mesh; each event
+//! carries an ID so retried deliveries are only logged once.
 
 use parking_lot::RwLock;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::mesh::Payload;
+
+/// An event published to a topic.
+#[derive(Debug, Clone)]
+pub struct MeshEvent {
+    /// Unique ID of the event, shared by all its deliveries
+    pub id: String,
+    /// Topic the event was published to
+    pub topic: String,
+    /// Event data
+    pub payload: Payload,
+}
+
+impl MeshEvent {
+    /// Creates an event with a new unique ID
+    #[must_use]
+    pub fn new(topic: impl Into<String>, payload: Payload) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            topic: topic.into(),
+            payload,
+        }
+    }
+}
+
+/// Returns true if `topic` matches `pattern`, where `*` in the pattern
+/// matches any run of characters.
+#[must_use]
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = topic.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard: the whole topic must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 /// Event bus for managing topic subscriptions.
 #[derive(Clone, Default)]
 pub struct EventBus {
-    /// Map of topic names to sets of subscribed plugin IDs
+    /// Map of topic patterns to sets of subscribed plugin IDs
     subscriptions: Arc<RwLock<HashMap<String, HashSet<String>>>>,
 }
 
@@ -22,7 +75,7 @@ impl EventBus {
     ///
     /// # Arguments
     ///
-    /// * `topic` - The topic to subscribe to.
+    /// * `topic` - The topic or topic pattern to subscribe to.
     /// * `plugin_id` - The ID of the plugin subscribing.
     pub fn subscribe(&self, topic: String, plugin_id: String) {
         let mut subs = self.subscriptions.write();
@@ -37,12 +90,58 @@ impl EventBus {
     ///
     /// # Returns
     ///
-    /// A vector of plugin IDs subscribed to the topic.
+    /// A sorted vector of the IDs of plugins subscribed to a pattern
+    /// matching the topic.
     #[must_use]
     pub fn subscribers(&self, topic: &str) -> Vec<String> {
         let subs = self.subscriptions.read();
-        subs.get(topic)
-            .map(|s| s.iter().cloned().collect())
-            .unwrap_or_default()
+        let mut subscribers: Vec<String> = subs
+            .iter()
+            .filter(|(pattern, _)| topic_matches(pattern, topic))
+            .flat_map(|(_, plugins)| plugins.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        subscribers.sort();
+        subscribers
+    }
+
+    /// Returns the topic patterns with at least one subscriber, which this
+    /// node advertises to other nodes.
+    #[must_use]
+    pub fn patterns(&self) -> Vec<String> {
+        let mut patterns: Vec<String> = self.subscriptions.read().keys().cloned().collect();
+        patterns.sort();
+        patterns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_patterns() {
+        assert!(topic_matches("branch:created", "branch:created"));
+        assert!(!topic_matches("branch:created", "branch:created:now"));
+        assert!(topic_matches("branch:*", "branch:merged"));
+        assert!(!topic_matches("branch:*", "proposal:milestones"));
+        assert!(topic_matches("*", "proposal:milestones"));
+        assert!(topic_matches("*:milestones", "proposal:milestones"));
+        assert!(topic_matches("proposal:*:done", "proposal:plan:done"));
+        assert!(!topic_matches("proposal:*:done", "proposal:plan:started"));
+    }
+
+    #[test]
+    fn test_wildcard_subscribers() {
+        let bus = EventBus::new();
+        bus.subscribe("branch:*".to_string(), "foreman".to_string());
+        bus.subscribe("branch:merged".to_string(), "foreman".to_string());
+        bus.subscribe("branch:merged".to_string(), "reviewer".to_string());
+
+        assert_eq!(bus.subscribers("branch:merged"), ["foreman", "reviewer"]);
+        assert_eq!(bus.subscribers("branch:created"), ["foreman"]);
+        assert!(bus.subscribers("proposal:milestones").is_empty());
+        assert_eq!(bus.patterns(), ["branch:*", "branch:merged"]);
     }
 }
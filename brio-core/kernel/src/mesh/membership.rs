@@ -0,0 +1,229 @@
+//! Cluster membership for distributed Brio nodes.
+//!
+//! A node joins the cluster through its configured bootstrap addresses,
+//! then heartbeats every node it knows about and periodically pulls the
+//! membership list of one of them to learn about nodes that joined
+//! elsewhere. Nodes that stop answering are marked suspect and then dead;
+//! dead nodes are no longer routed to until they answer again.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::infrastructure::config::MeshSettings;
+use crate::mesh::grpc::NodeEntry;
+use crate::mesh::remote::RemoteRouter;
+use crate::mesh::types::{NodeAddress, NodeId, NodeInfo, ValidationError};
+
+/// Liveness of a known node, as observed by this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeState {
+    /// The node answered recently
+    Alive,
+    /// The node has not answered for a while but is still routed to
+    Suspect,
+    /// The node has not answered for too long and is no longer routed to
+    Dead,
+}
+
+impl NodeState {
+    /// Returns the name used in logs
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Alive => "alive",
+            Self::Suspect => "suspect",
+            Self::Dead => "dead",
+        }
+    }
+}
+
+/// Returns the current Unix timestamp in seconds.
+pub(crate) fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl From<&NodeInfo> for NodeEntry {
+    fn from(info: &NodeInfo) -> Self {
+        Self {
+            node_id: info.id().to_string(),
+            address: info.address().to_string(),
+            capabilities: info.capabilities().to_vec(),
+            last_seen: info.last_seen(),
+        }
+    }
+}
+
+impl TryFrom<NodeEntry> for NodeInfo {
+    type Error = ValidationError;
+
+    fn try_from(entry: NodeEntry) -> Result<Self, Self::Error> {
+        NodeInfo::new(
+            NodeId::try_from_str(&entry.node_id)?,
+            NodeAddress::new(&entry.address)?,
+            entry.capabilities,
+            entry.last_seen,
+        )
+    }
+}
+
+/// Timing and bootstrap addresses of the membership protocol.
+#[derive(Debug, Clone)]
+pub struct MembershipConfig {
+    /// Interval between heartbeat rounds
+    pub heartbeat_interval: Duration,
+    /// Silence after which a node is marked suspect
+    pub suspect_after: Duration,
+    /// Silence after which a node is marked dead
+    pub dead_after: Duration,
+    /// Addresses to join the cluster through
+    pub bootstrap_nodes: Vec<NodeAddress>,
+}
+
+impl MembershipConfig {
+    /// Creates the configuration from the mesh settings, skipping invalid
+    /// bootstrap addresses
+    #[must_use]
+    pub fn from_settings(settings: &MeshSettings) -> Self {
+        let bootstrap_nodes = settings
+            .bootstrap_nodes
+            .iter()
+            .filter_map(|address| match NodeAddress::new(address) {
+                Ok(address) => Some(address),
+                Err(e) => {
+                    warn!(address = %address, "Ignoring bootstrap node: {e}");
+                    None
+                }
+            })
+            .collect();
+        Self {
+            heartbeat_interval: Duration::from_millis(settings.heartbeat_interval_ms),
+            suspect_after: Duration::from_millis(settings.suspect_after_ms),
+            dead_after: Duration::from_millis(settings.dead_after_ms),
+            bootstrap_nodes,
+        }
+    }
+}
+
+impl Default for MembershipConfig {
+    fn default() -> Self {
+        Self::from_settings(&MeshSettings::default())
+    }
+}
+
+/// Keeps the membership of a [`RemoteRouter`] up to date.
+pub struct Membership {
+    local: NodeInfo,
+    router: RemoteRouter,
+    config: MembershipConfig,
+    next_peer: AtomicUsize,
+}
+
+impl Membership {
+    /// Creates the membership of the node described by `local`, tracked in
+    /// `router`
+    #[must_use]
+    pub fn new(local: NodeInfo, router: RemoteRouter, config: MembershipConfig) -> Self {
+        Self {
+            local,
+            router,
+            config,
+            next_peer: AtomicUsize::new(0),
+        }
+    }
+
+    /// Joins the cluster through the bootstrap nodes. Returns true if any
+    /// of them answered.
+    pub async fn join(&self) -> bool {
+        let mut joined = false;
+        for address in &self.config.bootstrap_nodes {
+            if address == self.local.address() {
+                continue;
+            }
+            match self
+                .router
+                .join(address, &self.local, self.config.heartbeat_interval)
+                .await
+            {
+                Ok(discovered) => {
+                    info!(bootstrap = %address, discovered, "Joined mesh");
+                    joined = true;
+                }
+                Err(e) => debug!(bootstrap = %address, "Failed to join mesh: {e}"),
+            }
+        }
+        joined
+    }
+
+    /// Runs one round of the protocol: rejoins if no other node is alive,
+    /// heartbeats every known node, updates their states and pulls the
+    /// membership list of one live node.
+    pub async fn tick(&self) {
+        let timeout = self.config.heartbeat_interval;
+        let members = self.router.members();
+        if !members.iter().any(|(_, state)| *state != NodeState::Dead) {
+            self.join().await;
+        }
+
+        let heartbeats = members
+            .iter()
+            .map(|(info, _)| self.router.heartbeat(info.id(), timeout));
+        for (result, (info, _)) in futures_util::future::join_all(heartbeats)
+            .await
+            .into_iter()
+            .zip(&members)
+        {
+            if let Err(e) = result {
+                debug!(node = %info.id(), "Heartbeat failed: {e}");
+            }
+        }
+        self.router
+            .sweep(self.config.suspect_after, self.config.dead_after);
+
+        let Some(peer) = self.pick_peer() else {
+            return;
+        };
+        if let Err(e) = self.router.sync_members(&peer, timeout).await {
+            debug!(node = %peer, "Failed to pull membership: {e}");
+        }
+    }
+
+    /// Picks the live node to pull the membership list from, in turn.
+    fn pick_peer(&self) -> Option<NodeId> {
+        let alive: Vec<NodeId> = self
+            .router
+            .members()
+            .into_iter()
+            .filter(|(_, state)| *state == NodeState::Alive)
+            .map(|(info, _)| info.id().clone())
+            .collect();
+        if alive.is_empty() {
+            return None;
+        }
+        let turn = self.next_peer.fetch_add(1, Ordering::Relaxed);
+        Some(alive[turn % alive.len()].clone())
+    }
+
+    /// Joins the cluster and runs the protocol every heartbeat interval
+    pub async fn run(self) {
+        self.join().await;
+        let mut interval = tokio::time::interval(self.config.heartbeat_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.tick().await;
+        }
+    }
+
+    /// Runs the protocol in a background task
+    #[must_use]
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+}
@@ -1,17 +1,26 @@
 //! Distributed mesh networking for inter-node communication.
 
+/// Authentication and authorization of incoming calls.
+pub mod auth;
 /// Event bus for pub/sub messaging between nodes.
 pub mod events;
 /// gRPC transport implementation.
 pub mod grpc;
+/// Cluster discovery and failure detection.
+pub mod membership;
 /// Remote node management and registry.
 pub mod remote;
 /// Mesh service implementation.
 pub mod service;
+/// Mutual TLS for mesh connections.
+pub mod tls;
 /// Core types for mesh networking.
 pub mod types;
 
+pub use auth::CallPolicy;
+pub use membership::{Membership, MembershipConfig, NodeState};
 pub use service::MeshService;
+pub use tls::MeshTls;
 pub use types::*;
 
 use tokio::sync::oneshot;
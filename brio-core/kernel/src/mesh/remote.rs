@@ -2,23 +2,72 @@
 //!
 //! This module provides connection pooling and routing for inter-node
 //! communication via gRPC in a distributed Brio cluster.
+//!
+//! Events are forwarded to every node subscribed to their topic through a
+//! per-node outbox: a single worker sends the node's events one at a time
+//! and retries each until the node acknowledges it, so events reach every
+//! node at least once and in the order they were published. Outboxes hold
+//! at most [`OUTBOX_CAPACITY`] events and keep them while their node is
+//! down; a node's outbox and worker are dropped when the node is removed.
 
 use anyhow::{Result, anyhow};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
 use tonic::transport::Channel;
+use tracing::{Span, debug, field, info, instrument, warn};
 
+use crate::mesh::events::{MeshEvent, topic_matches};
 use crate::mesh::grpc::mesh_transport_client::MeshTransportClient;
+use crate::mesh::grpc::{
+    HeartbeatRequest, JoinRequest, ListNodesRequest, MembershipResponse, PublishRequest,
+    publish_request::Payload as PublishPayload,
+};
+use crate::mesh::membership::{NodeState, unix_now};
+use crate::mesh::tls::MeshTls;
 use crate::mesh::types::{NodeAddress, NodeId, NodeInfo};
 use crate::mesh::{MeshMessage, Payload};
 
+/// Maximum number of nodes a call routed by capability is tried on.
+pub const MAX_ROUTE_ATTEMPTS: usize = 3;
+
+/// Time a node has to acknowledge an event before it is sent again.
+pub const PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of events queued for a node beyond which new events for it are
+/// dropped.
+pub const OUTBOX_CAPACITY: usize = 1024;
+
+/// Delay before an unacknowledged event is first sent again.
+const PUBLISH_RETRY_INITIAL: Duration = Duration::from_millis(100);
+
+/// Longest delay between attempts to send an event.
+const PUBLISH_RETRY_MAX: Duration = Duration::from_secs(5);
+
+/// A node a call routed by capability may be sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteCandidate {
+    /// ID of the node.
+    pub node_id: NodeId,
+    /// Liveness of the node.
+    pub state: NodeState,
+    /// Calls to the node that have not finished yet.
+    pub in_flight: usize,
+}
+
 /// Router for dispatching mesh calls to remote nodes via gRPC.
 /// Handles connection pooling and payload serialization.
 #[derive(Clone)]
 pub struct RemoteRouter {
+    local_node: Option<NodeId>,
+    tls: Option<Arc<MeshTls>>,
     registry: Arc<RwLock<NodeRegistry>>,
     clients: Arc<RwLock<HashMap<NodeId, MeshTransportClient<Channel>>>>,
+    outboxes: Arc<Mutex<HashMap<NodeId, mpsc::Sender<MeshEvent>>>>,
 }
 
 impl Default for RemoteRouter {
@@ -32,21 +81,212 @@ impl RemoteRouter {
     #[must_use]
     pub fn new() -> Self {
         Self {
+            local_node: None,
+            tls: None,
             registry: Arc::new(RwLock::new(NodeRegistry::new())),
             clients: Arc::new(RwLock::new(HashMap::new())),
+            outboxes: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    /// Registers a node with the router.
+    /// Sets the ID of the node the router runs on, which is never
+    /// registered as a remote node.
+    #[must_use]
+    pub fn with_local_node(mut self, node_id: NodeId) -> Self {
+        self.local_node = Some(node_id);
+        self
+    }
+
+    /// Connects to other nodes over mutual TLS instead of cleartext.
+    #[must_use]
+    pub fn with_tls(mut self, tls: MeshTls) -> Self {
+        self.tls = Some(Arc::new(tls));
+        self
+    }
+
+    /// Returns the ID of the node the router runs on, if set.
+    #[must_use]
+    pub fn local_node(&self) -> Option<&NodeId> {
+        self.local_node.as_ref()
+    }
+
+    /// Registers a node with the router, marking it alive.
+    ///
+    /// A cached connection to the node is dropped if its address changed.
     ///
     /// # Arguments
     ///
     /// * `info` - Information about the node to register.
     pub fn register_node(&self, info: NodeInfo) {
+        if self.local_node.as_ref() == Some(info.id()) {
+            return;
+        }
+        let moved = self.node_address(info.id()).as_ref() != Some(info.address());
+        if moved {
+            self.forget_client(info.id());
+        }
         let mut registry = self.registry.write();
         registry.register(info);
     }
 
+    /// Adds nodes learned from another node's membership list.
+    ///
+    /// Nodes already known keep their state, since only this node's own
+    /// heartbeats decide whether they are alive. Returns the number of new
+    /// nodes.
+    pub fn discover(&self, nodes: impl IntoIterator<Item = NodeInfo>) -> usize {
+        let mut registry = self.registry.write();
+        let mut discovered = 0;
+        for info in nodes {
+            if self.local_node.as_ref() == Some(info.id()) {
+                continue;
+            }
+            let id = info.id().clone();
+            if registry.discover(info) {
+                info!(node = %id, "Discovered mesh node");
+                discovered += 1;
+            }
+        }
+        discovered
+    }
+
+    /// Removes a node from the membership, dropping its connection and the
+    /// events still queued for it. Returns false if the node is unknown.
+    pub fn remove_node(&self, node_id: &NodeId) -> bool {
+        let removed = self.registry.write().remove(node_id);
+        self.forget_client(node_id);
+        if let Some(outbox) = self.outboxes.lock().remove(node_id) {
+            let dropped = OUTBOX_CAPACITY - outbox.capacity();
+            if dropped > 0 {
+                warn!(node = %node_id, dropped, "Dropping events queued for removed node");
+            }
+        }
+        removed
+    }
+
+    /// Returns every known node with its state, sorted by ID.
+    #[must_use]
+    pub fn members(&self) -> Vec<(NodeInfo, NodeState)> {
+        self.registry.read().members()
+    }
+
+    /// Returns the state of a node if known.
+    #[must_use]
+    pub fn node_state(&self, node_id: &NodeId) -> Option<NodeState> {
+        self.registry.read().state(node_id)
+    }
+
+    /// Records that a node answered, marking it alive. Unknown nodes are
+    /// ignored.
+    pub fn mark_seen(&self, node_id: &NodeId) {
+        self.registry.write().mark_seen(node_id, unix_now());
+    }
+
+    /// Marks nodes that have been silent for too long as suspect or dead,
+    /// dropping their cached connections.
+    pub fn sweep(&self, suspect_after: Duration, dead_after: Duration) {
+        let changes = self.registry.write().sweep(suspect_after, dead_after);
+        for (node_id, state) in changes {
+            warn!(node = %node_id, state = state.as_str(), "Mesh node stopped answering");
+            self.forget_client(&node_id);
+        }
+    }
+
+    /// Drops the cached connection to a node, if any.
+    pub fn forget_client(&self, node_id: &NodeId) {
+        self.clients.write().remove(node_id);
+    }
+
+    /// Sends a heartbeat to a node and marks it alive if it answers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node is unknown, cannot be reached or does
+    /// not answer within `timeout`. The cached connection is dropped so the
+    /// next attempt reconnects.
+    pub async fn heartbeat(&self, node_id: &NodeId, timeout: Duration) -> Result<()> {
+        let request = HeartbeatRequest {
+            node_id: self.local_node_name(),
+        };
+        let result = with_timeout(timeout, async {
+            let mut client = self.connect_or_get(node_id).await?;
+            Ok(client.heartbeat(request).await?.into_inner())
+        })
+        .await;
+        match result {
+            Ok(response) if response.ready => {
+                let mut registry = self.registry.write();
+                registry.mark_seen(node_id, unix_now());
+                registry.set_capabilities(node_id, response.capabilities);
+                registry.set_subscriptions(node_id, response.subscriptions);
+                Ok(())
+            }
+            Ok(_) => Err(anyhow!("Node {node_id} is not ready")),
+            Err(e) => {
+                self.forget_client(node_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Joins the cluster through the node listening at `address`, adding
+    /// the nodes it knows about. Returns the number of new nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node cannot be reached or does not answer
+    /// within `timeout`.
+    pub async fn join(
+        &self,
+        address: &NodeAddress,
+        local: &NodeInfo,
+        timeout: Duration,
+    ) -> Result<usize> {
+        let request = JoinRequest {
+            node: Some(local.into()),
+        };
+        let response = with_timeout(timeout, async {
+            let mut client = MeshTransportClient::new(self.connect(address, None).await?);
+            Ok(client.join(request).await?.into_inner())
+        })
+        .await?;
+        Ok(self.discover_response(response))
+    }
+
+    /// Pulls the membership list of a known node. Returns the number of new
+    /// nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node is unknown, cannot be reached or does
+    /// not answer within `timeout`.
+    pub async fn sync_members(&self, node_id: &NodeId, timeout: Duration) -> Result<usize> {
+        let request = ListNodesRequest {
+            node_id: self.local_node_name(),
+        };
+        let response = with_timeout(timeout, async {
+            let mut client = self.connect_or_get(node_id).await?;
+            Ok(client.list_nodes(request).await?.into_inner())
+        })
+        .await?;
+        Ok(self.discover_response(response))
+    }
+
+    fn discover_response(&self, response: MembershipResponse) -> usize {
+        let nodes = response
+            .nodes
+            .into_iter()
+            .filter_map(|entry| NodeInfo::try_from(entry).ok());
+        self.discover(nodes)
+    }
+
+    fn local_node_name(&self) -> String {
+        self.local_node
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default()
+    }
+
     /// Returns the address of a node if known.
     ///
     /// # Arguments
@@ -62,14 +302,194 @@ impl RemoteRouter {
     ///
     /// # Errors
     ///
-    /// Returns an error if the connection fails or the remote returns an error.
+    /// Returns an error if the node is dead, the connection fails or the
+    /// remote returns an error.
     pub async fn send(&self, target_node: &NodeId, message: MeshMessage) -> Result<Payload> {
-        let client = self.connect_or_get(target_node).await?;
+        self.call(target_node, message.target, message.method, message.payload)
+            .await
+    }
+
+    /// Returns the nodes advertising `capability` that calls can be routed
+    /// to, best first: alive nodes before suspect ones, then the nodes with
+    /// the fewest calls in flight.
+    #[must_use]
+    pub fn candidates(&self, capability: &str) -> Vec<RouteCandidate> {
+        self.registry.read().candidates(capability)
+    }
+
+    /// Calls `component` on a remote node advertising it, retrying on the
+    /// next best node if the call could not be delivered.
+    ///
+    /// At most [`MAX_ROUTE_ATTEMPTS`] nodes are tried. Calls are only
+    /// retried if the node could not be reached, so a call that reached a
+    /// node and failed there is never run twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no live node advertises `component`, the error
+    /// of a node the call was delivered to, or the error of the last attempt
+    /// if no node could be reached.
+    #[instrument(
+        name = "mesh.route",
+        skip(self, payload),
+        fields(node = field::Empty, attempts = field::Empty)
+    )]
+    pub async fn route(&self, component: &str, method: &str, payload: Payload) -> Result<Payload> {
+        let candidates = self.candidates(component);
+        if candidates.is_empty() {
+            return Err(anyhow!("No live node advertises '{component}'"));
+        }
+
+        let mut last_error = None;
+        for (attempt, candidate) in candidates.into_iter().take(MAX_ROUTE_ATTEMPTS).enumerate() {
+            let span = Span::current();
+            span.record("node", candidate.node_id.as_str());
+            span.record("attempts", attempt + 1);
+            info!(
+                node = %candidate.node_id,
+                state = candidate.state.as_str(),
+                in_flight = candidate.in_flight,
+                attempt = attempt + 1,
+                "Routing mesh call to remote node"
+            );
+            match self
+                .call(
+                    &candidate.node_id,
+                    component.to_string(),
+                    method.to_string(),
+                    payload.clone(),
+                )
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(e) if e.is::<Undelivered>() => {
+                    warn!(node = %candidate.node_id, "Remote mesh call failed: {e}");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No live node advertises '{component}'")))
+    }
+
+    /// Returns the known nodes with a subscription matching `topic`, as
+    /// advertised in their last heartbeat.
+    #[must_use]
+    pub fn subscribers(&self, topic: &str) -> Vec<NodeId> {
+        self.registry.read().subscribers(topic)
+    }
+
+    /// Queues `event` for every remote node subscribed to its topic.
+    /// Returns the number of nodes it was queued for.
+    ///
+    /// Each node's events are sent in the order they were queued, and each
+    /// is retried with backoff until the node acknowledges it, including
+    /// while the node is declared dead. Events are dropped if the node's
+    /// outbox is full, the node's call policy refuses them or the node is
+    /// removed.
+    #[must_use]
+    pub fn publish(&self, event: &MeshEvent) -> usize {
+        let nodes = self.subscribers(&event.topic);
+        let mut outboxes = self.outboxes.lock();
+        let mut queued = 0;
+        for node_id in &nodes {
+            let outbox = outboxes.entry(node_id.clone()).or_insert_with(|| {
+                let (tx, rx) = mpsc::channel(OUTBOX_CAPACITY);
+                tokio::spawn(self.clone().drain_outbox(node_id.clone(), rx));
+                tx
+            });
+            match outbox.try_send(event.clone()) {
+                Ok(()) => queued += 1,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!(node = %node_id, event = %event.id, topic = %event.topic,
+                        "Dropping event for node whose outbox is full");
+                }
+                // The worker only exits once the node was removed
+                Err(mpsc::error::TrySendError::Closed(_)) => {}
+            }
+        }
+        queued
+    }
+
+    /// Sends the events queued for a node one at a time, until the node is
+    /// removed from the membership.
+    async fn drain_outbox(self, node_id: NodeId, mut events: mpsc::Receiver<MeshEvent>) {
+        while let Some(event) = events.recv().await {
+            let mut delay = PUBLISH_RETRY_INITIAL;
+            loop {
+                match self.node_state(&node_id) {
+                    None => {
+                        debug!(node = %node_id, "Stopping outbox of removed node");
+                        return;
+                    }
+                    // Keep the event until the node answers again or is removed
+                    Some(NodeState::Dead) => {
+                        tokio::time::sleep(PUBLISH_RETRY_MAX).await;
+                        continue;
+                    }
+                    Some(_) => {}
+                }
+                match self.send_event(&node_id, &event).await {
+                    Ok(()) => break,
+                    Err(e) if is_permission_denied(&e) => {
+                        warn!(node = %node_id, event = %event.id, topic = %event.topic,
+                            "Dropping event the node refused: {e}");
+                        break;
+                    }
+                    Err(e) => {
+                        debug!(node = %node_id, event = %event.id, "Failed to deliver event: {e}");
+                        tokio::time::sleep(delay).await;
+                        delay = (delay * 2).min(PUBLISH_RETRY_MAX);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sends an event to a node, waiting for its acknowledgement.
+    async fn send_event(&self, node_id: &NodeId, event: &MeshEvent) -> Result<()> {
+        let request = PublishRequest {
+            node_id: self.local_node_name(),
+            event_id: event.id.clone(),
+            topic: event.topic.clone(),
+            payload: Some(match &event.payload {
+                Payload::Json(s) => PublishPayload::Json(s.to_string()),
+                Payload::Binary(b) => PublishPayload::Binary(b.to_vec()),
+            }),
+        };
+        let result = with_timeout(PUBLISH_TIMEOUT, async {
+            let mut client = self.connect_or_get(node_id).await?;
+            client.publish(request).await?;
+            Ok(())
+        })
+        .await;
+        if result.is_err() {
+            self.forget_client(node_id);
+        }
+        result
+    }
+
+    async fn call(
+        &self,
+        target_node: &NodeId,
+        target: String,
+        method: String,
+        payload: Payload,
+    ) -> Result<Payload> {
+        if self.node_state(target_node) == Some(NodeState::Dead) {
+            return Err(Undelivered(anyhow!("Node {target_node} is not answering")).into());
+        }
+        let _in_flight = InFlight::begin(&self.registry, target_node);
+        let client = self
+            .connect_or_get(target_node)
+            .await
+            .map_err(Undelivered)?;
 
         let request = tonic::Request::new(crate::mesh::grpc::MeshRequest {
-            target: message.target,
-            method: message.method,
-            payload: Some(match message.payload {
+            node_id: self.local_node_name(),
+            target,
+            method,
+            payload: Some(match payload {
                 Payload::Json(s) => crate::mesh::grpc::mesh_request::Payload::Json(*s),
                 Payload::Binary(b) => crate::mesh::grpc::mesh_request::Payload::Binary(*b),
             }),
@@ -78,7 +498,13 @@ impl RemoteRouter {
         // We need a mutable client for the call, so we clone the channel which is cheap
         let mut client = client.clone();
 
-        let response = client.call(request).await?.into_inner();
+        let response = match client.call(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::Unavailable => {
+                return Err(Undelivered(status.into()).into());
+            }
+            Err(status) => return Err(status.into()),
+        };
 
         match response.payload {
             Some(crate::mesh::grpc::mesh_response::Payload::Json(s)) => {
@@ -94,6 +520,18 @@ impl RemoteRouter {
         }
     }
 
+    /// Opens a channel to the mesh server listening at `address`, which
+    /// must prove to be `node` if TLS is enabled and the node is known.
+    async fn connect(&self, address: &NodeAddress, node: Option<&NodeId>) -> Result<Channel> {
+        let Some(tls) = &self.tls else {
+            let endpoint = Channel::from_shared(format!("http://{address}"))?;
+            return Ok(endpoint.connect().await?);
+        };
+        let endpoint = Channel::from_shared(format!("https://{address}"))?
+            .tls_config(tls.client_config(node))?;
+        Ok(endpoint.connect().await?)
+    }
+
     async fn connect_or_get(&self, node_id: &NodeId) -> Result<MeshTransportClient<Channel>> {
         // Fast path: check if connected
         {
@@ -108,11 +546,7 @@ impl RemoteRouter {
             .node_address(node_id)
             .ok_or_else(|| anyhow!("Node {node_id} not found in registry"))?;
 
-        // Format as http URL for tonic
-        let url = format!("http://{address}"); // Assuming HTTP/2 over cleartext for now
-        let endpoint = Channel::from_shared(url)?;
-        let channel = endpoint.connect().await?;
-        let client = MeshTransportClient::new(channel);
+        let client = MeshTransportClient::new(self.connect(&address, Some(node_id)).await?);
 
         {
             let mut clients = self.clients.write();
@@ -123,9 +557,70 @@ impl RemoteRouter {
     }
 }
 
+/// Runs `future`, failing if it does not finish within `timeout`.
+async fn with_timeout<T>(timeout: Duration, future: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_| anyhow!("No answer within {}ms", timeout.as_millis()))?
+}
+
+/// Error of a call that never reached the remote node, which can safely
+/// be sent to another node instead.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct Undelivered(anyhow::Error);
+
+/// Returns true if a node refused a request because its call policy does
+/// not allow this node to make it, so sending it again cannot succeed.
+fn is_permission_denied(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<tonic::Status>()
+        .is_some_and(|status| status.code() == tonic::Code::PermissionDenied)
+}
+
+/// Counts a call to a node as in flight until dropped.
+struct InFlight<'a> {
+    registry: &'a RwLock<NodeRegistry>,
+    node_id: &'a NodeId,
+}
+
+impl<'a> InFlight<'a> {
+    fn begin(registry: &'a RwLock<NodeRegistry>, node_id: &'a NodeId) -> Self {
+        registry.write().adjust_in_flight(node_id, true);
+        Self { registry, node_id }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.registry.write().adjust_in_flight(self.node_id, false);
+    }
+}
+
+/// A known node, how recently it answered and how busy it is.
+struct Member {
+    info: NodeInfo,
+    state: NodeState,
+    last_contact: Instant,
+    in_flight: usize,
+    subscriptions: Vec<String>,
+}
+
+impl Member {
+    fn new(info: NodeInfo) -> Self {
+        Self {
+            info,
+            state: NodeState::Alive,
+            last_contact: Instant::now(),
+            in_flight: 0,
+            subscriptions: Vec::new(),
+        }
+    }
+}
+
 /// Registry for tracking known nodes in the mesh.
 pub struct NodeRegistry {
-    nodes: HashMap<NodeId, NodeInfo>,
+    nodes: HashMap<NodeId, Member>,
 }
 
 impl Default for NodeRegistry {
@@ -149,7 +644,97 @@ impl NodeRegistry {
     ///
     /// * `info` - Information about the node.
     pub fn register(&mut self, info: NodeInfo) {
-        self.nodes.insert(info.id().clone(), info);
+        let mut member = Member::new(info);
+        if let Some(previous) = self.nodes.get(member.info.id()) {
+            member.in_flight = previous.in_flight;
+            member.subscriptions.clone_from(&previous.subscriptions);
+        }
+        self.nodes.insert(member.info.id().clone(), member);
+    }
+
+    /// Replaces the capabilities advertised by a node, if known.
+    pub fn set_capabilities(&mut self, id: &NodeId, capabilities: Vec<String>) {
+        if let Some(member) = self.nodes.get_mut(id) {
+            member.info.set_capabilities(capabilities);
+        }
+    }
+
+    /// Replaces the topic patterns a node subscribes to, if known.
+    pub fn set_subscriptions(&mut self, id: &NodeId, subscriptions: Vec<String>) {
+        if let Some(member) = self.nodes.get_mut(id) {
+            member.subscriptions = subscriptions;
+        }
+    }
+
+    /// Returns the known nodes subscribed to a pattern matching `topic`,
+    /// sorted by ID. Dead nodes are included, so events published while a
+    /// node is down reach it once it is back.
+    #[must_use]
+    pub fn subscribers(&self, topic: &str) -> Vec<NodeId> {
+        let mut nodes: Vec<NodeId> = self
+            .nodes
+            .values()
+            .filter(|member| {
+                member
+                    .subscriptions
+                    .iter()
+                    .any(|pattern| topic_matches(pattern, topic))
+            })
+            .map(|member| member.info.id().clone())
+            .collect();
+        nodes.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        nodes
+    }
+
+    /// Removes a node. Returns false if it was unknown.
+    pub fn remove(&mut self, id: &NodeId) -> bool {
+        self.nodes.remove(id).is_some()
+    }
+
+    /// Counts a call to a node as started or finished.
+    fn adjust_in_flight(&mut self, id: &NodeId, started: bool) {
+        if let Some(member) = self.nodes.get_mut(id) {
+            member.in_flight = if started {
+                member.in_flight + 1
+            } else {
+                member.in_flight.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Returns the live nodes advertising `capability`, alive nodes before
+    /// suspect ones, then by fewest calls in flight.
+    #[must_use]
+    pub fn candidates(&self, capability: &str) -> Vec<RouteCandidate> {
+        let mut candidates: Vec<RouteCandidate> = self
+            .nodes
+            .values()
+            .filter(|member| member.state != NodeState::Dead)
+            .filter(|member| member.info.has_capability(capability))
+            .map(|member| RouteCandidate {
+                node_id: member.info.id().clone(),
+                state: member.state,
+                in_flight: member.in_flight,
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            (a.state != NodeState::Alive, a.in_flight, a.node_id.as_str()).cmp(&(
+                b.state != NodeState::Alive,
+                b.in_flight,
+                b.node_id.as_str(),
+            ))
+        });
+        candidates
+    }
+
+    /// Registers a node unless it is already known. Returns true if it was
+    /// added.
+    pub fn discover(&mut self, info: NodeInfo) -> bool {
+        if self.nodes.contains_key(info.id()) {
+            return false;
+        }
+        self.register(info);
+        true
     }
 
     /// Gets information about a node.
@@ -163,7 +748,13 @@ impl NodeRegistry {
     /// Node information if found.
     #[must_use]
     pub fn get(&self, id: &NodeId) -> Option<&NodeInfo> {
-        self.nodes.get(id)
+        self.nodes.get(id).map(|member| &member.info)
+    }
+
+    /// Returns the state of a node if known.
+    #[must_use]
+    pub fn state(&self, id: &NodeId) -> Option<NodeState> {
+        self.nodes.get(id).map(|member| member.state)
     }
 
     /// Lists all registered nodes.
@@ -173,7 +764,63 @@ impl NodeRegistry {
     /// A vector of all node information.
     #[must_use]
     pub fn list(&self) -> Vec<NodeInfo> {
-        self.nodes.values().cloned().collect()
+        self.nodes
+            .values()
+            .map(|member| member.info.clone())
+            .collect()
+    }
+
+    /// Lists all registered nodes with their state, sorted by ID.
+    #[must_use]
+    pub fn members(&self) -> Vec<(NodeInfo, NodeState)> {
+        let mut members: Vec<_> = self
+            .nodes
+            .values()
+            .map(|member| (member.info.clone(), member.state))
+            .collect();
+        members.sort_by(|(a, _), (b, _)| a.id().as_str().cmp(b.id().as_str()));
+        members
+    }
+
+    /// Records that a node answered at the Unix time `timestamp`, marking
+    /// it alive. Returns false if the node is unknown.
+    pub fn mark_seen(&mut self, id: &NodeId, timestamp: u64) -> bool {
+        let Some(member) = self.nodes.get_mut(id) else {
+            return false;
+        };
+        member.info.update_last_seen(timestamp);
+        member.state = NodeState::Alive;
+        member.last_contact = Instant::now();
+        true
+    }
+
+    /// Marks nodes silent for longer than `suspect_after` as suspect and
+    /// longer than `dead_after` as dead.
+    ///
+    /// # Returns
+    ///
+    /// The nodes whose state changed, with their new state.
+    pub fn sweep(
+        &mut self,
+        suspect_after: Duration,
+        dead_after: Duration,
+    ) -> Vec<(NodeId, NodeState)> {
+        let mut changes = Vec::new();
+        for (id, member) in &mut self.nodes {
+            let silence = member.last_contact.elapsed();
+            let state = if silence >= dead_after {
+                NodeState::Dead
+            } else if silence >= suspect_after {
+                NodeState::Suspect
+            } else {
+                NodeState::Alive
+            };
+            if state != member.state {
+                member.state = state;
+                changes.push((id.clone(), state));
+            }
+        }
+        changes
     }
 }
 
@@ -200,4 +847,117 @@ mod tests {
         assert_eq!(list.len(), 1);
         assert_eq!(list[0].id(), &id);
     }
+
+    fn node(id: &str) -> NodeInfo {
+        NodeInfo::new(
+            NodeId::try_from_str(id).unwrap(),
+            NodeAddress::new("127.0.0.1:50051").unwrap(),
+            vec!["agent_coder".to_string()],
+            0,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_silent_nodes_become_suspect_then_dead() {
+        let mut registry = NodeRegistry::new();
+        let id = NodeId::try_from_str("node-b").unwrap();
+        registry.register(node("node-b"));
+        let suspect_after = Duration::from_secs(5);
+        let dead_after = Duration::from_secs(15);
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert_eq!(
+            registry.sweep(suspect_after, dead_after),
+            vec![(id.clone(), NodeState::Suspect)]
+        );
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        registry.sweep(suspect_after, dead_after);
+        assert_eq!(registry.state(&id), Some(NodeState::Dead));
+
+        assert!(registry.mark_seen(&id, 1_700_000_000));
+        assert_eq!(registry.state(&id), Some(NodeState::Alive));
+        assert_eq!(registry.get(&id).unwrap().last_seen(), 1_700_000_000);
+        assert!(registry.sweep(suspect_after, dead_after).is_empty());
+    }
+
+    #[test]
+    fn test_discovery_skips_known_and_local_nodes() {
+        let router = RemoteRouter::new().with_local_node(NodeId::try_from_str("node-a").unwrap());
+        router.register_node(node("node-b"));
+
+        let discovered = router.discover([node("node-a"), node("node-b"), node("node-c")]);
+
+        assert_eq!(discovered, 1);
+        let ids: Vec<String> = router
+            .members()
+            .into_iter()
+            .map(|(info, _)| info.id().to_string())
+            .collect();
+        assert_eq!(ids, ["node-b", "node-c"]);
+    }
+
+    #[tokio::test]
+    async fn test_outboxes_are_bounded_and_kept_until_the_node_is_removed() {
+        let router = RemoteRouter::new();
+        let id = NodeId::try_from_str("node-b").unwrap();
+        router.register_node(node("node-b"));
+        router
+            .registry
+            .write()
+            .set_subscriptions(&id, vec!["branch:*".to_string()]);
+        router.sweep(Duration::ZERO, Duration::ZERO);
+        assert_eq!(router.node_state(&id), Some(NodeState::Dead));
+
+        // Events for a dead node are queued until its outbox is full
+        let event = MeshEvent::new("branch:merged", Payload::Json(Box::default()));
+        let queued: usize = (0..2 * OUTBOX_CAPACITY)
+            .map(|_| router.publish(&event))
+            .sum();
+        assert!((OUTBOX_CAPACITY..=OUTBOX_CAPACITY + 1).contains(&queued));
+        assert_eq!(router.publish(&event), 0);
+
+        assert!(router.remove_node(&id));
+        assert!(router.outboxes.lock().is_empty());
+        assert!(router.members().is_empty());
+        assert_eq!(router.publish(&event), 0);
+        assert!(!router.remove_node(&id));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_candidates_prefer_alive_and_idle_nodes() {
+        let mut registry = NodeRegistry::new();
+        for id in ["node-a", "node-b", "node-c", "node-d"] {
+            registry.register(node(id));
+        }
+        let mut other = node("node-e");
+        other.set_capabilities(vec!["agent_reviewer".to_string()]);
+        registry.register(other);
+
+        let id = |id: &str| NodeId::try_from_str(id).unwrap();
+        registry.adjust_in_flight(&id("node-a"), true);
+        tokio::time::advance(Duration::from_secs(10)).await;
+        registry.mark_seen(&id("node-a"), 0);
+        registry.mark_seen(&id("node-b"), 0);
+        registry.mark_seen(&id("node-c"), 0);
+        tokio::time::advance(Duration::from_secs(10)).await;
+        registry.mark_seen(&id("node-a"), 0);
+        registry.mark_seen(&id("node-b"), 0);
+        registry.sweep(Duration::from_secs(5), Duration::from_secs(15));
+
+        let ranked: Vec<(String, NodeState, usize)> = registry
+            .candidates("agent_coder")
+            .into_iter()
+            .map(|c| (c.node_id.to_string(), c.state, c.in_flight))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("node-b".to_string(), NodeState::Alive, 0),
+                ("node-a".to_string(), NodeState::Alive, 1),
+                ("node-c".to_string(), NodeState::Suspect, 0),
+            ]
+        );
+    }
 }
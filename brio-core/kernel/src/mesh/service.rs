@@ -7,20 +7,26 @@ use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use crate::host::BrioHostState;
-use crate::host::mesh::MeshHandler;
 use crate::mesh::Payload;
+use crate::mesh::auth::{CallPolicy, authenticate};
+use crate::mesh::events::MeshEvent;
 use crate::mesh::grpc::{
-    HeartbeatRequest, HeartbeatResponse, MeshRequest, MeshResponse,
+    HeartbeatRequest, HeartbeatResponse, JoinRequest, ListNodesRequest, MembershipResponse,
+    MeshRequest, MeshResponse, NodeEntry, PublishRequest, PublishResponse,
     mesh_request::Payload as RequestPayload, mesh_response::Payload as ResponsePayload,
-    mesh_transport_server::MeshTransport,
+    mesh_transport_server::MeshTransport, publish_request::Payload as PublishPayload,
 };
-use crate::mesh::types::NodeId;
+use crate::mesh::membership::{NodeState, unix_now};
+use crate::mesh::remote::RemoteRouter;
+use crate::mesh::types::{NodeAddress, NodeId, NodeInfo};
 
 /// gRPC Service Implementation for `MeshTransport`.
 /// Handles incoming RPC calls and routes them to local components via `BrioHostState`.
 pub struct MeshService {
     host: Arc<BrioHostState>,
     node_id: NodeId,
+    address: Option<NodeAddress>,
+    policy: CallPolicy,
 }
 
 impl MeshService {
@@ -32,14 +38,76 @@ impl MeshService {
     /// * `node_id` - The ID of this node.
     #[must_use]
     pub fn new(host: Arc<BrioHostState>, node_id: NodeId) -> Self {
-        Self { host, node_id }
+        Self {
+            host,
+            node_id,
+            address: None,
+            policy: CallPolicy::default(),
+        }
+    }
+
+    /// Sets the components each node may call on this node.
+    #[must_use]
+    pub fn with_policy(mut self, policy: CallPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the address other nodes reach this node at, advertised in
+    /// membership lists.
+    #[must_use]
+    pub fn with_address(mut self, address: NodeAddress) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    fn remote_router(&self) -> Result<&RemoteRouter, Status> {
+        self.host
+            .remote_router()
+            .ok_or_else(|| Status::failed_precondition("Mesh membership is not enabled"))
+    }
+
+    /// Lists this node and every node it has not declared dead.
+    fn membership(&self, router: &RemoteRouter) -> MembershipResponse {
+        let local = self.address.as_ref().map(|address| NodeEntry {
+            node_id: self.node_id.to_string(),
+            address: address.to_string(),
+            capabilities: self.host.local_capabilities(),
+            last_seen: unix_now(),
+        });
+        let members = router.members();
+        let nodes = members
+            .iter()
+            .filter(|(_, state)| *state != NodeState::Dead)
+            .map(|(info, _)| NodeEntry::from(info));
+        MembershipResponse {
+            nodes: local.into_iter().chain(nodes).collect(),
+        }
+    }
+
+    /// Marks the node a request came from as seen, if it is known.
+    fn mark_seen(&self, node_id: &str) {
+        let (Some(router), Ok(node_id)) =
+            (self.host.remote_router(), NodeId::try_from_str(node_id))
+        else {
+            return;
+        };
+        router.mark_seen(&node_id);
     }
 }
 
 #[tonic::async_trait]
 impl MeshTransport for MeshService {
     async fn call(&self, request: Request<MeshRequest>) -> Result<Response<MeshResponse>, Status> {
+        authenticate(&request, &request.get_ref().node_id)?;
         let req = request.into_inner();
+        if !self.policy.allows(&req.node_id, &req.target) {
+            tracing::warn!(caller = %req.node_id, target = %req.target, "Rejected mesh call");
+            return Err(Status::permission_denied(format!(
+                "Node '{}' may not call '{}'",
+                req.node_id, req.target
+            )));
+        }
 
         let payload = match req.payload {
             Some(RequestPayload::Json(s)) => Payload::Json(Box::new(s)),
@@ -47,9 +115,12 @@ impl MeshTransport for MeshService {
             None => return Err(Status::invalid_argument("Missing payload")),
         };
 
-        // Execute call against local host
-        // Note: We use the raw component ID as the target, assuming incoming requests are for this node
-        match self.host.mesh_call(&req.target, &req.method, payload).await {
+        // Execute call against local host only, so calls are never passed on between nodes
+        match self
+            .host
+            .local_mesh_call(&req.target, &req.method, payload)
+            .await
+        {
             Ok(Payload::Json(s)) => Ok(Response::new(MeshResponse {
                 payload: Some(ResponsePayload::Json(*s)),
             })),
@@ -64,12 +135,11 @@ impl MeshTransport for MeshService {
 
     async fn heartbeat(
         &self,
-        _request: Request<HeartbeatRequest>,
+        request: Request<HeartbeatRequest>,
     ) -> Result<Response<HeartbeatResponse>, Status> {
-        let timestamp_secs = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        authenticate(&request, &request.get_ref().node_id)?;
+        self.mark_seen(&request.into_inner().node_id);
+        let timestamp_secs = unix_now();
         // Safe cast: Unix timestamps won't exceed i64 range until year 292 billion
         #[expect(clippy::cast_possible_wrap)]
         let timestamp = timestamp_secs as i64;
@@ -77,6 +147,85 @@ impl MeshTransport for MeshService {
             node_id: self.node_id.to_string(),
             ready: true,
             timestamp,
+            capabilities: self.host.local_capabilities(),
+            subscriptions: self.host.event_bus().patterns(),
+        }))
+    }
+
+    async fn join(
+        &self,
+        request: Request<JoinRequest>,
+    ) -> Result<Response<MembershipResponse>, Status> {
+        let router = self.remote_router()?;
+        let claimed = request
+            .get_ref()
+            .node
+            .as_ref()
+            .map(|node| node.node_id.clone())
+            .unwrap_or_default();
+        authenticate(&request, &claimed)?;
+        let entry = request
+            .into_inner()
+            .node
+            .ok_or_else(|| Status::invalid_argument("Missing node"))?;
+        let info =
+            NodeInfo::try_from(entry).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        tracing::info!(node = %info.id(), address = %info.address(), "Mesh node joined");
+        router.register_node(info);
+        Ok(Response::new(self.membership(router)))
+    }
+
+    async fn list_nodes(
+        &self,
+        request: Request<ListNodesRequest>,
+    ) -> Result<Response<MembershipResponse>, Status> {
+        let router = self.remote_router()?;
+        authenticate(&request, &request.get_ref().node_id)?;
+        self.mark_seen(&request.into_inner().node_id);
+        Ok(Response::new(self.membership(router)))
+    }
+
+    async fn publish(
+        &self,
+        request: Request<PublishRequest>,
+    ) -> Result<Response<PublishResponse>, Status> {
+        authenticate(&request, &request.get_ref().node_id)?;
+        let req = request.into_inner();
+        self.mark_seen(&req.node_id);
+        // Delivering an event calls each subscriber's handler, so the caller
+        // must be allowed to call every component it would reach.
+        let subscribers = self.host.event_bus().subscribers(&req.topic);
+        if let Some(denied) = subscribers
+            .iter()
+            .find(|subscriber| !self.policy.allows(&req.node_id, subscriber))
+        {
+            tracing::warn!(caller = %req.node_id, topic = %req.topic, subscriber = %denied, "Rejected mesh event");
+            return Err(Status::permission_denied(format!(
+                "Node '{}' may not publish '{}' to '{}'",
+                req.node_id, req.topic, denied
+            )));
+        }
+        let payload = match req.payload {
+            Some(PublishPayload::Json(s)) => Payload::Json(Box::new(s)),
+            Some(PublishPayload::Binary(b)) => Payload::Binary(Box::new(b)),
+            None => return Err(Status::invalid_argument("Missing payload")),
+        };
+        let event = MeshEvent {
+            id: req.event_id,
+            topic: req.topic,
+            payload,
+        };
+
+        // Acknowledge once the event is in this node's log, from where it is
+        // delivered to local subscribers. Retried events are already there.
+        let appended = self
+            .host
+            .record_event(&event)
+            .await
+            .map_err(|e| Status::unavailable(format!("Failed to log event: {e}")))?;
+        tracing::debug!(caller = %req.node_id, topic = %event.topic, event = %event.id, duplicate = !appended, "Received mesh event");
+        Ok(Response::new(PublishResponse {
+            duplicate: !appended,
         }))
     }
 }
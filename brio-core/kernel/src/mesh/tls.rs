@@ -0,0 +1,107 @@
+//! Mutual TLS for mesh connections.
+//!
+//! Nodes of a cluster share a CA. Each node presents a certificate signed by
+//! it that names the node ID as a DNS subject alternative name, so a peer can
+//! check that a caller is the node it claims to be.
+
+use anyhow::{Context, Result};
+use std::fs;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+
+use crate::infrastructure::config::MeshTlsSettings;
+use crate::mesh::types::NodeId;
+
+/// Cluster CA and node identity used for mesh connections.
+#[derive(Debug, Clone)]
+pub struct MeshTls {
+    ca: Certificate,
+    identity: Identity,
+}
+
+impl MeshTls {
+    /// Creates the configuration from PEM encoded certificates and key.
+    #[must_use]
+    pub fn from_pem(
+        ca_cert: impl AsRef<[u8]>,
+        cert: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+    ) -> Self {
+        Self {
+            ca: Certificate::from_pem(ca_cert),
+            identity: Identity::from_pem(cert, key),
+        }
+    }
+
+    /// Reads the PEM files named in the settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the files cannot be read.
+    pub fn load(settings: &MeshTlsSettings) -> Result<Self> {
+        let read = |path: &std::path::Path| {
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        Ok(Self::from_pem(
+            read(&settings.ca_cert)?,
+            read(&settings.cert)?,
+            read(&settings.key)?,
+        ))
+    }
+
+    /// Returns the server configuration, requiring clients to present a
+    /// certificate signed by the cluster CA.
+    #[must_use]
+    pub fn server_config(&self) -> ServerTlsConfig {
+        ServerTlsConfig::new()
+            .identity(self.identity.clone())
+            .client_ca_root(self.ca.clone())
+    }
+
+    /// Returns the client configuration for connecting to `node`, whose
+    /// certificate must name it. Without a node ID, the certificate must
+    /// name the host dialled instead.
+    #[must_use]
+    pub fn client_config(&self, node: Option<&NodeId>) -> ClientTlsConfig {
+        let config = ClientTlsConfig::new()
+            .ca_certificate(self.ca.clone())
+            .identity(self.identity.clone());
+        match node {
+            Some(node) => config.domain_name(node.as_str()),
+            None => config,
+        }
+    }
+}
+
+/// Returns the names a certificate is issued to: its DNS subject alternative
+/// names and common name.
+///
+/// Returns nothing if the certificate cannot be parsed.
+#[must_use]
+pub fn certificate_names(der: &[u8]) -> Vec<String> {
+    let Ok((_, cert)) = X509Certificate::from_der(der) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|san| {
+            san.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some((*dns).to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.extend(
+        cert.subject()
+            .iter_common_name()
+            .filter_map(|cn| cn.as_str().ok())
+            .map(str::to_string),
+    );
+    names
+}
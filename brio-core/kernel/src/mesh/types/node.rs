@@ -141,6 +141,17 @@ impl NodeInfo {
         self.capabilities.push(capability);
     }
 
+    /// Replaces the capabilities of the node.
+    pub fn set_capabilities(&mut self, capabilities: Vec<String>) {
+        self.capabilities = capabilities;
+    }
+
+    /// Returns true if the node advertises the given capability.
+    #[must_use]
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
     /// Returns a builder for constructing `NodeInfo` with validation.
     #[must_use]
     pub fn builder() -> NodeInfoBuilder {
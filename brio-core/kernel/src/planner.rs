@@ -0,0 +1,497 @@
+//! Task planning backed by the inference registry.
+//!
+//! The planner prompts an [`LLMProvider`] to decompose an objective into
+//! subtasks linked by prerequisites and parses the JSON plan from its reply.
+//! Replies that do not contain a usable plan, including plans with more
+//! steps than allowed or whose dependencies form a cycle, degrade to a
+//! single step carrying the whole objective, so planning never fails on
+//! model output alone; only provider errors are reported.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::inference::{ChatRequest, InferenceError, LLMProvider};
+use crate::infrastructure::config::PlannerSettings;
+
+/// Prompt used when no template is configured.
+///
+/// `{objective}` and `{max_steps}` are substituted before sending.
+pub const DEFAULT_PROMPT_TEMPLATE: &str = r#"You are a planning assistant. Break the objective below into at most {max_steps} concrete subtasks that can each be handed to a single agent.
+
+Reply with JSON only, in exactly this shape:
+{"steps": [{"id": "step-1", "description": "...", "depends_on": [], "outputs": ["..."], "capability": "coding"}]}
+
+"depends_on" lists the ids of the steps that must finish before a step can start; steps without prerequisites may run in parallel. "outputs" names what the step produces for the steps after it. "capability" is one of "coding", "reviewing" or "reasoning".
+
+Objective:
+{objective}"#;
+
+/// A single step of a plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subtask {
+    /// Identifier, unique within the plan.
+    pub id: String,
+    /// What the step should accomplish.
+    pub description: String,
+    /// Ids of the steps that must complete before this one starts.
+    pub depends_on: Vec<String>,
+    /// Artifacts the step is expected to produce for its successors.
+    pub outputs: Vec<String>,
+    /// Capability an agent needs to perform the step, if any.
+    pub capability: Option<String>,
+}
+
+impl Subtask {
+    /// Creates a step without prerequisites, outputs or capability.
+    #[must_use]
+    pub fn new(id: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            description: description.into(),
+            depends_on: Vec::new(),
+            outputs: Vec::new(),
+            capability: None,
+        }
+    }
+}
+
+/// A dependency graph of subtasks achieving an objective.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// Steps in the order the planner listed them.
+    pub steps: Vec<Subtask>,
+}
+
+impl Plan {
+    /// Returns a plan with a single step carrying the whole objective.
+    #[must_use]
+    pub fn single_step(objective: &str) -> Self {
+        Self {
+            steps: vec![Subtask::new("step-1", objective)],
+        }
+    }
+}
+
+/// Errors raised while planning.
+#[derive(Debug, thiserror::Error)]
+pub enum PlannerError {
+    /// The objective was empty.
+    #[error("Objective must not be empty")]
+    EmptyObjective,
+    /// The provider could not produce a reply.
+    #[error("Planner inference failed: {0}")]
+    Inference(#[from] InferenceError),
+}
+
+/// Decomposes objectives into plans using an LLM.
+#[derive(Debug, Clone)]
+pub struct Planner {
+    model: String,
+    provider: Option<String>,
+    prompt_template: String,
+    max_steps: usize,
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self::from_settings(&PlannerSettings::default())
+    }
+}
+
+impl Planner {
+    /// Creates a planner from configuration.
+    #[must_use]
+    pub fn from_settings(settings: &PlannerSettings) -> Self {
+        Self {
+            model: settings.model.clone(),
+            provider: settings.provider.clone(),
+            prompt_template: settings
+                .prompt_template
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PROMPT_TEMPLATE.to_string()),
+            max_steps: settings.max_steps.max(1),
+        }
+    }
+
+    /// Returns the name of the provider to plan with, or `None` to use the
+    /// provider the model is routed to.
+    #[must_use]
+    pub fn provider(&self) -> Option<&str> {
+        self.provider.as_deref()
+    }
+
+    /// Returns the model requested from the provider.
+    #[must_use]
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Returns the maximum number of steps in a plan.
+    #[must_use]
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Renders the prompt for an objective.
+    #[must_use]
+    pub fn render_prompt(&self, objective: &str) -> String {
+        self.prompt_template
+            .replace("{max_steps}", &self.max_steps.to_string())
+            .replace("{objective}", objective)
+    }
+
+    /// Asks `provider` to decompose `objective` into a plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the objective is empty or the provider fails.
+    /// Malformed replies fall back to [`Plan::single_step`].
+    pub async fn decompose(
+        &self,
+        provider: &dyn LLMProvider,
+        objective: &str,
+    ) -> Result<Plan, PlannerError> {
+        let request = self.plan_request(objective)?;
+        let response = provider.chat(request).await?;
+        Ok(self.parse_plan(&response.content, objective.trim()))
+    }
+
+    /// Builds the completion request asking for a plan for `objective`, for
+    /// callers that send it themselves. Its reply is parsed with
+    /// [`Planner::parse_plan`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the objective is empty.
+    pub fn plan_request(&self, objective: &str) -> Result<ChatRequest, PlannerError> {
+        let objective = objective.trim();
+        if objective.is_empty() {
+            return Err(PlannerError::EmptyObjective);
+        }
+        Ok(ChatRequest::with_message(
+            &self.model,
+            self.render_prompt(objective),
+        ))
+    }
+
+    /// Parses a plan from a model reply, falling back to a single step.
+    ///
+    /// Prerequisites naming unknown steps are removed. A plan with more than
+    /// [`Planner::max_steps`] steps or whose prerequisites form a cycle is
+    /// replaced by a single step; truncating it instead would run the kept
+    /// steps without the prerequisites that were cut.
+    #[must_use]
+    pub fn parse_plan(&self, reply: &str, objective: &str) -> Plan {
+        let Some(mut steps) = parse_steps(reply) else {
+            warn!("Planner reply did not contain a valid plan, using a single step");
+            return Plan::single_step(objective);
+        };
+
+        if steps.len() > self.max_steps {
+            warn!(
+                "Planner returned {} steps, more than the limit of {}, using a single step",
+                steps.len(),
+                self.max_steps
+            );
+            return Plan::single_step(objective);
+        }
+
+        prune_dependencies(&mut steps);
+        if has_cycle(&steps) {
+            warn!("Planner returned a plan with a dependency cycle, using a single step");
+            return Plan::single_step(objective);
+        }
+        Plan { steps }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPlan {
+    Object { steps: Vec<RawStep> },
+    List(Vec<RawStep>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawStep {
+    Object {
+        #[serde(default)]
+        id: Option<String>,
+        description: String,
+        #[serde(default, alias = "depends-on", alias = "dependsOn")]
+        depends_on: Vec<String>,
+        #[serde(default)]
+        outputs: Vec<String>,
+        #[serde(default)]
+        capability: Option<String>,
+    },
+    Text(String),
+}
+
+/// Extracts validated steps from a reply, or `None` if there are none.
+///
+/// Accepts `{"steps": [...]}` or a bare array, optionally inside a Markdown
+/// code fence or surrounded by prose. Missing or duplicate ids are replaced
+/// with positional ones that no other step uses; prerequisites are left as
+/// written.
+fn parse_steps(reply: &str) -> Option<Vec<Subtask>> {
+    let raw: RawPlan = serde_json::from_str(extract_json(reply)?).ok()?;
+    let raw_steps = match raw {
+        RawPlan::Object { steps } | RawPlan::List(steps) => steps,
+    };
+
+    // Explicit ids are claimed first, so a positional id never takes the id
+    // another step's prerequisites refer to.
+    let mut seen = HashSet::new();
+    let explicit: Vec<Option<String>> = raw_steps
+        .iter()
+        .map(|step| match step {
+            RawStep::Object { id: Some(id), .. } => {
+                let id = id.trim().to_string();
+                (!id.is_empty() && seen.insert(id.clone())).then_some(id)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut steps = Vec::with_capacity(raw_steps.len());
+    for (index, (step, id)) in raw_steps.into_iter().zip(explicit).enumerate() {
+        let (description, depends_on, outputs, capability) = match step {
+            RawStep::Object {
+                description,
+                depends_on,
+                outputs,
+                capability,
+                ..
+            } => (description, depends_on, outputs, capability),
+            RawStep::Text(description) => (description, Vec::new(), Vec::new(), None),
+        };
+        let description = description.trim();
+        if description.is_empty() {
+            return None;
+        }
+
+        let id = id.unwrap_or_else(|| {
+            let mut n = index + 1;
+            while seen.contains(&format!("step-{n}")) {
+                n += 1;
+            }
+            let id = format!("step-{n}");
+            seen.insert(id.clone());
+            id
+        });
+        steps.push(Subtask {
+            id,
+            description: description.to_string(),
+            depends_on: depends_on.iter().map(|d| d.trim().to_string()).collect(),
+            outputs: outputs
+                .into_iter()
+                .map(|o| o.trim().to_string())
+                .filter(|o| !o.is_empty())
+                .collect(),
+            capability: capability
+                .map(|c| c.trim().to_lowercase())
+                .filter(|c| !c.is_empty()),
+        });
+    }
+
+    (!steps.is_empty()).then_some(steps)
+}
+
+/// Removes duplicate, self-referencing and unknown prerequisites.
+fn prune_dependencies(steps: &mut [Subtask]) {
+    let ids: HashSet<String> = steps.iter().map(|s| s.id.clone()).collect();
+    for step in steps.iter_mut() {
+        let mut seen = HashSet::new();
+        let id = step.id.clone();
+        step.depends_on.retain(|dep| {
+            let known = ids.contains(dep) && *dep != id;
+            if !known {
+                warn!("Dropping invalid prerequisite '{dep}' of plan step '{id}'");
+            }
+            known && seen.insert(dep.clone())
+        });
+    }
+}
+
+/// Returns true if the prerequisites of `steps` form a cycle.
+///
+/// Expects prerequisites to have been pruned to known ids.
+fn has_cycle(steps: &[Subtask]) -> bool {
+    let mut remaining: HashMap<&str, usize> = steps
+        .iter()
+        .map(|s| (s.id.as_str(), s.depends_on.len()))
+        .collect();
+    let mut ready: Vec<&str> = remaining
+        .iter()
+        .filter(|(_, pending)| **pending == 0)
+        .map(|(id, _)| *id)
+        .collect();
+
+    let mut visited = 0;
+    while let Some(done) = ready.pop() {
+        visited += 1;
+        for step in steps
+            .iter()
+            .filter(|s| s.depends_on.iter().any(|d| d == done))
+        {
+            let pending = remaining
+                .get_mut(step.id.as_str())
+                .expect("every step is counted");
+            *pending -= 1;
+            if *pending == 0 {
+                ready.push(&step.id);
+            }
+        }
+    }
+    visited < steps.len()
+}
+
+/// Returns the outermost JSON object or array in `reply`.
+fn extract_json(reply: &str) -> Option<&str> {
+    let start = reply.find(['{', '['])?;
+    let close = if reply[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = reply.rfind(close)?;
+    (end > start).then(|| &reply[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::ChatResponse;
+
+    struct Reply(&'static str);
+
+    #[async_trait::async_trait]
+    impl LLMProvider for Reply {
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+            assert!(request.messages[0].content.contains("Ship the release"));
+            Ok(ChatResponse::new(self.0))
+        }
+    }
+
+    fn planner(max_steps: usize) -> Planner {
+        Planner::from_settings(&PlannerSettings {
+            max_steps,
+            ..PlannerSettings::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn decomposes_objective_into_steps() {
+        let provider = Reply(
+            "Here is the plan:\n```json\n{\"steps\": [{\"id\": \"a\", \"description\": \"Build\"}, {\"id\": \"b\", \"description\": \"Tag\"}]}\n```",
+        );
+        let plan = planner(10)
+            .decompose(&provider, "Ship the release")
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.steps,
+            vec![Subtask::new("a", "Build"), Subtask::new("b", "Tag")]
+        );
+    }
+
+    #[test]
+    fn parses_dependencies_outputs_and_capabilities() {
+        let plan = planner(10).parse_plan(
+            r#"{"steps": [
+                {"id": "design", "description": "Design the API", "outputs": ["api.md"], "capability": "Reasoning"},
+                {"id": "build", "description": "Build it", "depends-on": ["design", "design", "ghost"], "capability": "coding"},
+                {"id": "review", "description": "Review it", "depends_on": ["build", "review"]}
+            ]}"#,
+            "objective",
+        );
+        assert_eq!(plan.steps[0].outputs, vec!["api.md"]);
+        assert_eq!(plan.steps[0].capability.as_deref(), Some("reasoning"));
+        assert_eq!(plan.steps[1].depends_on, vec!["design"]);
+        assert_eq!(plan.steps[2].depends_on, vec!["build"]);
+        assert_eq!(plan.steps[2].capability, None);
+    }
+
+    #[test]
+    fn cyclic_plan_falls_back_to_single_step() {
+        let plan = planner(10).parse_plan(
+            r#"[
+                {"id": "a", "description": "A", "depends_on": ["c"]},
+                {"id": "b", "description": "B", "depends_on": ["a"]},
+                {"id": "c", "description": "C", "depends_on": ["b"]}
+            ]"#,
+            "objective",
+        );
+        assert_eq!(plan, Plan::single_step("objective"));
+    }
+
+    #[test]
+    fn plans_over_the_step_limit_fall_back_to_single_step() {
+        let plan = planner(1).parse_plan(
+            r#"[{"id": "a", "description": "A", "depends_on": ["b"]}, {"id": "b", "description": "B"}]"#,
+            "objective",
+        );
+        assert_eq!(plan, Plan::single_step("objective"));
+    }
+
+    #[tokio::test]
+    async fn malformed_reply_falls_back_to_single_step() {
+        let plan = planner(10)
+            .decompose(&Reply("I cannot help with that."), "Ship the release")
+            .await
+            .unwrap();
+        assert_eq!(plan, Plan::single_step("Ship the release"));
+    }
+
+    #[test]
+    fn accepts_bare_lists_and_fills_missing_ids() {
+        let plan = planner(10).parse_plan(
+            r#"["Write code", {"description": "Review"}, {"id": "x", "description": "Merge"}, {"id": "x", "description": "Deploy"}]"#,
+            "objective",
+        );
+        let ids: Vec<_> = plan.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["step-1", "step-2", "x", "step-4"]);
+    }
+
+    #[test]
+    fn positional_ids_do_not_collide_with_explicit_ones() {
+        let plan = planner(10).parse_plan(
+            r#"[
+                {"description": "Write code"},
+                {"id": "step-1", "description": "Review"},
+                {"id": "step-3", "description": "Merge", "depends_on": ["step-1"]}
+            ]"#,
+            "objective",
+        );
+        let ids: Vec<_> = plan.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["step-2", "step-1", "step-3"]);
+        assert_eq!(plan.steps[2].depends_on, vec!["step-1"]);
+    }
+
+    #[test]
+    fn rejects_empty_descriptions_and_plans() {
+        let planner = planner(10);
+        assert_eq!(
+            planner.parse_plan(r#"{"steps": []}"#, "objective"),
+            Plan::single_step("objective")
+        );
+        assert_eq!(
+            planner.parse_plan(r#"["a", " "]"#, "objective"),
+            Plan::single_step("objective")
+        );
+    }
+
+    #[test]
+    fn renders_custom_template() {
+        let planner = Planner::from_settings(&PlannerSettings {
+            prompt_template: Some("Plan {objective} in {max_steps} steps".to_string()),
+            max_steps: 3,
+            ..PlannerSettings::default()
+        });
+        assert_eq!(planner.render_prompt("X"), "Plan X in 3 steps");
+    }
+}
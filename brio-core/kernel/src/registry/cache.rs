@@ -0,0 +1,106 @@
+//! On-disk cache of compiled components.
+//!
+//! Compiling a component is by far the most expensive step of running a
+//! plugin. Compiled artifacts are serialized into a cache directory, keyed by
+//! the SHA-256 of the component bytes and the engine's precompile
+//! compatibility hash (which covers the wasmtime version and compilation
+//! settings), so restarts and repeated loads skip compilation entirely.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, warn};
+use wasmtime::Engine;
+use wasmtime::component::Component;
+
+/// File extension of serialized components.
+const CACHE_EXTENSION: &str = "cwasm";
+
+/// Distinguishes the temporary files of concurrent writes within a process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Directory-backed cache of serialized components.
+#[derive(Debug, Clone)]
+pub struct ComponentCache {
+    dir: PathBuf,
+}
+
+impl ComponentCache {
+    /// Creates a cache stored in `dir`. The directory is created on first write.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the cache directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Loads a component from the cache, compiling and caching it on a miss.
+    ///
+    /// Unusable cache entries are discarded and replaced. Failing to write the
+    /// cache is logged but does not fail the load.
+    ///
+    /// Compiling can take seconds and does blocking I/O, so async callers
+    /// should run this on the blocking thread pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component file cannot be read or compiled.
+    pub fn load_or_compile(&self, engine: &Engine, path: &Path) -> Result<Component> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read component {}", path.display()))?;
+        let entry = self.entry_path(engine, &bytes);
+
+        if entry.exists() {
+            // SAFETY: entries are only written by `store` from the output of
+            // `Component::serialize`, and the key pins the engine configuration.
+            match unsafe { Component::deserialize_file(engine, &entry) } {
+                Ok(component) => {
+                    debug!("Loaded compiled component {:?} from cache", path);
+                    return Ok(component);
+                }
+                Err(e) => warn!("Discarding unusable cache entry {:?}: {e:#}", entry),
+            }
+        }
+
+        let component = Component::new(engine, &bytes)
+            .with_context(|| format!("Failed to compile component {}", path.display()))?;
+        if let Err(e) = self.store(&entry, &component) {
+            warn!("Failed to cache compiled component {:?}: {e:#}", path);
+        }
+        Ok(component)
+    }
+
+    /// Computes the cache file for the given component bytes.
+    fn entry_path(&self, engine: &Engine, bytes: &[u8]) -> PathBuf {
+        let mut engine_hash = DefaultHasher::new();
+        engine
+            .precompile_compatibility_hash()
+            .hash(&mut engine_hash);
+
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        hasher.update(engine_hash.finish().to_le_bytes());
+        self.dir
+            .join(hex::encode(hasher.finalize()))
+            .with_extension(CACHE_EXTENSION)
+    }
+
+    /// Writes a compiled component, replacing the entry atomically.
+    fn store(&self, entry: &Path, component: &Component) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let tmp = entry.with_extension(format!(
+            "{CACHE_EXTENSION}.{}.{}",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&tmp, component.serialize()?)?;
+        std::fs::rename(&tmp, entry)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+//! Plugin manifests.
+//!
+//! A plugin may ship a sidecar manifest next to its component, e.g.
+//! `coder.toml` beside `coder.wasm`, describing who it is, which host
+//! permissions it needs and which interfaces it exports:
+//!
+//! ```toml
+//! id = "coder"
+//! version = "0.1.0"
+//! description = "Writes and edits code"
+//! permissions = ["fs:write", "ai:inference"]
+//! exports = ["brio:core/agent-runner"]
+//! capabilities = ["code-generation"]
+//!
+//! [limits]
+//! max_memory_bytes = 268435456
+//! timeout_ms = 60000
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::PluginLoadError;
+use crate::engine::limits::ResourceLimits;
+
+/// File extension of plugin manifests.
+pub const MANIFEST_EXTENSION: &str = "toml";
+
+/// Host permissions a plugin may request.
+pub const KNOWN_PERMISSIONS: &[&str] = &[
+    "mesh:send",
+    "storage:read",
+    "storage:write",
+    "fs:read",
+    "fs:write",
+    "ai:inference",
+];
+
+/// Declarative description of a plugin, read from its sidecar manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    /// Unique identifier the plugin is registered under.
+    pub id: String,
+    /// Semantic version of the plugin.
+    pub version: String,
+    /// Human readable description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Host permissions the plugin requires.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Interfaces the component must export, e.g. `brio:core/agent-runner`.
+    #[serde(default)]
+    pub exports: Vec<String>,
+    /// Free-form capabilities the plugin advertises for routing.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Resource budget per invocation, capped by the kernel's sandbox limits.
+    #[serde(default)]
+    pub limits: ResourceLimits,
+}
+
+impl PluginManifest {
+    /// Parses and validates a manifest from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is malformed or the manifest is invalid.
+    pub fn from_toml_str(source: &str) -> Result<Self, PluginLoadError> {
+        let manifest: Self = toml::from_str(source)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Reads, parses and validates a manifest file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is malformed or is invalid.
+    pub async fn load(path: &Path) -> Result<Self, PluginLoadError> {
+        let source = tokio::fs::read_to_string(path).await?;
+        Self::from_toml_str(&source)
+    }
+
+    /// Checks the manifest for invalid ids, versions and permissions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first problem found.
+    pub fn validate(&self) -> Result<(), PluginLoadError> {
+        if self.id.is_empty()
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(PluginLoadError::InvalidManifest(format!(
+                "id '{}' must be non-empty and contain only letters, digits, '-' or '_'",
+                self.id
+            )));
+        }
+
+        if !is_semver(&self.version) {
+            return Err(PluginLoadError::InvalidManifest(format!(
+                "version '{}' of plugin '{}' is not a semantic version",
+                self.version, self.id
+            )));
+        }
+
+        if let Some(permission) = self
+            .permissions
+            .iter()
+            .find(|p| !KNOWN_PERMISSIONS.contains(&p.as_str()))
+        {
+            return Err(PluginLoadError::UnknownPermission {
+                plugin: self.id.clone(),
+                permission: permission.clone(),
+            });
+        }
+
+        if self.exports.iter().any(String::is_empty) {
+            return Err(PluginLoadError::InvalidManifest(format!(
+                "plugin '{}' declares an empty export name",
+                self.id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true for `MAJOR.MINOR.PATCH` with optional pre-release and build suffixes.
+fn is_semver(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_manifest() {
+        let manifest = PluginManifest::from_toml_str(
+            r#"
+            id = "coder"
+            version = "1.2.0-beta.1"
+            description = "Writes code"
+            permissions = ["fs:write", "ai:inference"]
+            exports = ["brio:core/agent-runner"]
+            capabilities = ["code-generation"]
+
+            [limits]
+            fuel = 1000000
+            timeout_ms = 5000
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.id, "coder");
+        assert_eq!(manifest.permissions, vec!["fs:write", "ai:inference"]);
+        assert_eq!(manifest.exports, vec!["brio:core/agent-runner"]);
+        assert_eq!(manifest.capabilities, vec!["code-generation"]);
+        assert_eq!(manifest.limits.fuel, Some(1_000_000));
+        assert_eq!(manifest.limits.timeout_ms, Some(5000));
+    }
+
+    #[test]
+    fn optional_fields_default_to_empty() {
+        let manifest = PluginManifest::from_toml_str("id = \"x\"\nversion = \"0.1.0\"").unwrap();
+        assert!(manifest.description.is_none());
+        assert!(manifest.permissions.is_empty());
+        assert!(manifest.exports.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_permission() {
+        let result = PluginManifest::from_toml_str(
+            "id = \"x\"\nversion = \"0.1.0\"\npermissions = [\"net:raw\"]",
+        );
+        assert!(matches!(
+            result,
+            Err(PluginLoadError::UnknownPermission { permission, .. }) if permission == "net:raw"
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_id_and_version() {
+        assert!(matches!(
+            PluginManifest::from_toml_str("id = \"a b\"\nversion = \"0.1.0\""),
+            Err(PluginLoadError::InvalidManifest(_))
+        ));
+        assert!(matches!(
+            PluginManifest::from_toml_str("id = \"x\"\nversion = \"1.0\""),
+            Err(PluginLoadError::InvalidManifest(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        assert!(matches!(
+            PluginManifest::from_toml_str("id = \"x\"\nversion = \"0.1.0\"\nperms = []"),
+            Err(PluginLoadError::ManifestParse(_))
+        ));
+    }
+
+    #[test]
+    fn semver_accepts_suffixes() {
+        assert!(is_semver("1.0.0"));
+        assert!(is_semver("1.0.0-rc.1"));
+        assert!(is_semver("1.0.0+build.5"));
+        assert!(is_semver("1.0.0-rc.1+build.5"));
+        assert!(!is_semver("1.0"));
+        assert!(!is_semver("1.x.0"));
+    }
+}
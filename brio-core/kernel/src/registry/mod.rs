@@ -0,0 +1,420 @@
+//! Plugin registry for managing WebAssembly components.
+//!
+//! This module provides a registry for loading, managing, and instantiating
+//! WASM plugins with proper permission scoping and host state injection.
+//! Plugins describe themselves through a sidecar manifest, see [`manifest`].
+//! Each plugin is compiled and linked once and then instantiated per call
+//! from a cached [`InstancePre`].
+
+pub mod cache;
+pub mod manifest;
+
+pub use cache::ComponentCache;
+pub use manifest::PluginManifest;
+
+use crate::engine::limits::{ResourceLimits, limited_store, run_bounded};
+use crate::engine::linker::create_linker;
+use crate::host::BrioHostState;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tracing::{info, warn};
+use wasmtime::component::{Component, InstancePre, Linker};
+use wasmtime::{Engine, Store};
+
+/// Errors that prevent a single plugin from being registered.
+#[derive(Debug, thiserror::Error)]
+pub enum PluginLoadError {
+    /// The manifest file could not be read.
+    #[error("Failed to read manifest: {0}")]
+    ManifestRead(#[from] std::io::Error),
+    /// The manifest is not valid TOML or has unexpected fields.
+    #[error("Malformed manifest: {0}")]
+    ManifestParse(#[from] toml::de::Error),
+    /// The manifest parsed but contains invalid values.
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    /// The manifest requests a permission the host does not know.
+    #[error("Plugin '{plugin}' requests unknown permission '{permission}'")]
+    UnknownPermission {
+        /// Plugin requesting the permission.
+        plugin: String,
+        /// The unknown permission.
+        permission: String,
+    },
+    /// The plugin file is not a loadable component.
+    #[error("Plugin '{plugin}' is not a valid component: {reason}")]
+    InvalidComponent {
+        /// Plugin whose component failed to compile.
+        plugin: String,
+        /// Compilation error.
+        reason: String,
+    },
+    /// The manifest declares an export the component does not provide.
+    #[error("Plugin '{plugin}' declares export '{export}' but the component does not provide it")]
+    MissingExport {
+        /// Plugin declaring the export.
+        plugin: String,
+        /// The missing export.
+        export: String,
+    },
+    /// Another plugin is already registered under the same id.
+    #[error("Plugin id '{id}' is already registered from {existing:?}")]
+    DuplicateId {
+        /// The conflicting id.
+        id: String,
+        /// Path of the plugin that registered the id first.
+        existing: PathBuf,
+    },
+}
+
+/// Metadata about a loaded plugin.
+#[derive(Debug, Clone)]
+pub struct PluginMetadata {
+    /// Unique identifier for the plugin.
+    pub id: String,
+    /// Path to the plugin file.
+    pub path: PathBuf,
+    /// Permissions granted to the plugin.
+    pub permissions: Vec<String>,
+    /// Plugin version, if declared in a manifest.
+    pub version: Option<String>,
+    /// Human readable description, if declared in a manifest.
+    pub description: Option<String>,
+    /// Interfaces the component was verified to export.
+    pub exports: Vec<String>,
+    /// Capabilities advertised by the plugin.
+    pub capabilities: Vec<String>,
+    /// Effective resource budget for each invocation of the plugin.
+    pub limits: ResourceLimits,
+}
+
+impl PluginMetadata {
+    /// Returns true if the plugin advertises the given capability.
+    #[must_use]
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
+
+/// A plugin that could not be registered.
+#[derive(Debug)]
+pub struct PluginLoadFailure {
+    /// Path of the plugin component.
+    pub path: PathBuf,
+    /// Why the plugin was rejected.
+    pub error: PluginLoadError,
+}
+
+/// Outcome of scanning a plugin directory.
+#[derive(Debug, Default)]
+pub struct PluginLoadReport {
+    /// Ids of the plugins that were registered.
+    pub loaded: Vec<String>,
+    /// Plugins that were rejected, with the reason.
+    pub failures: Vec<PluginLoadFailure>,
+}
+
+impl PluginLoadReport {
+    /// Returns true if every plugin in the directory was registered.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Compiles a component, going through the on-disk cache if configured.
+fn compile(engine: &Engine, cache: Option<&ComponentCache>, path: &Path) -> Result<Component> {
+    match cache {
+        Some(cache) => cache.load_or_compile(engine, path),
+        None => Component::from_file(engine, path).context("Failed to load component"),
+    }
+}
+
+/// Registry for managing dynamic plugins.
+pub struct PluginRegistry {
+    plugins: HashMap<String, PluginMetadata>,
+    engine: Engine,
+    linker: Linker<BrioHostState>,
+    cache: Option<ComponentCache>,
+    prepared: HashMap<String, InstancePre<BrioHostState>>,
+    default_limits: ResourceLimits,
+}
+
+impl PluginRegistry {
+    /// Creates a new, empty registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host linker cannot be created.
+    pub fn new(engine: Engine) -> Result<Self> {
+        let linker = create_linker(&engine)?;
+        Ok(Self {
+            plugins: HashMap::new(),
+            engine,
+            linker,
+            cache: None,
+            prepared: HashMap::new(),
+            default_limits: ResourceLimits::default(),
+        })
+    }
+
+    /// Sets the resource limits applied to plugins and used as the ceiling
+    /// for limits requested in plugin manifests.
+    #[must_use]
+    pub fn with_default_limits(mut self, limits: ResourceLimits) -> Self {
+        self.default_limits = limits;
+        self
+    }
+
+    /// Persists compiled components in `dir` so they survive restarts.
+    #[must_use]
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache = Some(ComponentCache::new(dir));
+        self
+    }
+
+    /// Returns a reference to the underlying WASM engine.
+    #[must_use]
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Scans a directory for .wasm files and registers them.
+    ///
+    /// Each component is registered using its sidecar manifest (`name.toml`
+    /// next to `name.wasm`) when present. Plugins without a manifest are
+    /// registered under their file stem with no permissions, exporting
+    /// whatever interfaces their component provides. A plugin that
+    /// fails to load does not stop the scan; it is recorded in the returned
+    /// report instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read.
+    pub async fn load_from_directory<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<PluginLoadReport> {
+        let path = path.as_ref();
+        let mut report = PluginLoadReport::default();
+        if !path.exists() {
+            warn!("Plugin directory does not exist: {:?}", path);
+            return Ok(report);
+        }
+
+        let mut components = Vec::new();
+        let mut entries = fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("wasm") {
+                components.push(path);
+            }
+        }
+        // Sort so duplicate ids are resolved the same way on every start
+        components.sort();
+
+        for path in components {
+            match self.register_plugin(&path).await {
+                Ok(id) => report.loaded.push(id),
+                Err(error) => {
+                    warn!("Failed to load plugin {:?}: {}", path, error);
+                    report.failures.push(PluginLoadFailure { path, error });
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Registers a single plugin file and returns its id.
+    ///
+    /// Every plugin is compiled and linked immediately so that broken
+    /// components are rejected at load time.
+    async fn register_plugin(&mut self, path: &Path) -> Result<String, PluginLoadError> {
+        let manifest_path = path.with_extension(manifest::MANIFEST_EXTENSION);
+        let manifest = if fs::try_exists(&manifest_path).await? {
+            Some(PluginManifest::load(&manifest_path).await?)
+        } else {
+            None
+        };
+
+        let id = match &manifest {
+            Some(manifest) => manifest.id.clone(),
+            None => path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        };
+        if let Some(existing) = self.plugins.get(&id) {
+            return Err(PluginLoadError::DuplicateId {
+                id,
+                existing: existing.path.clone(),
+            });
+        }
+
+        let declared = manifest
+            .as_ref()
+            .map(|m| m.exports.clone())
+            .unwrap_or_default();
+        let (pre, exports) = self.verify_component(&id, path, &declared).await?;
+
+        let metadata = if let Some(manifest) = manifest {
+            PluginMetadata {
+                id: id.clone(),
+                path: path.to_path_buf(),
+                permissions: manifest.permissions,
+                version: Some(manifest.version),
+                description: manifest.description,
+                exports,
+                capabilities: manifest.capabilities,
+                limits: manifest.limits.capped_by(&self.default_limits),
+            }
+        } else {
+            warn!(
+                "Plugin {} has no manifest, registering without permissions",
+                id
+            );
+            PluginMetadata {
+                id: id.clone(),
+                path: path.to_path_buf(),
+                permissions: vec![],
+                version: None,
+                description: None,
+                exports,
+                capabilities: vec![],
+                limits: self.default_limits,
+            }
+        };
+
+        info!("Loading plugin: {} from {:?}", id, path);
+        self.prepared.insert(id.clone(), pre);
+        self.plugins.insert(id.clone(), metadata);
+        Ok(id)
+    }
+
+    /// Compiles and links a component and checks that it exports every
+    /// interface in `declared`.
+    ///
+    /// Returns the linked component and the verified exports: the declared
+    /// ones, or every interface the component exports if none were declared.
+    /// Compilation runs on the blocking thread pool.
+    async fn verify_component(
+        &self,
+        plugin: &str,
+        path: &Path,
+        declared: &[String],
+    ) -> Result<(InstancePre<BrioHostState>, Vec<String>), PluginLoadError> {
+        let invalid = |e: anyhow::Error| PluginLoadError::InvalidComponent {
+            plugin: plugin.to_string(),
+            reason: format!("{e:#}"),
+        };
+
+        let engine = self.engine.clone();
+        let cache = self.cache.clone();
+        let component_path = path.to_path_buf();
+        let component =
+            tokio::task::spawn_blocking(move || compile(&engine, cache.as_ref(), &component_path))
+                .await
+                .map_err(|e| invalid(e.into()))?
+                .map_err(invalid)?;
+
+        let exported: Vec<String> = component
+            .component_type()
+            .exports(&self.engine)
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        for export in declared {
+            // Versioned exports (`ns:pkg/iface@1.0.0`) satisfy an unversioned declaration
+            let provided = exported.iter().any(|name| {
+                name == export
+                    || name
+                        .strip_prefix(export.as_str())
+                        .is_some_and(|rest| rest.starts_with('@'))
+            });
+            if !provided {
+                return Err(PluginLoadError::MissingExport {
+                    plugin: plugin.to_string(),
+                    export: export.clone(),
+                });
+            }
+        }
+
+        let pre = self.linker.instantiate_pre(&component).map_err(invalid)?;
+        let exports = if declared.is_empty() {
+            exported
+        } else {
+            declared.to_vec()
+        };
+        Ok((pre, exports))
+    }
+
+    /// Returns the linked, ready-to-instantiate form of a plugin.
+    ///
+    /// Plugins are compiled and linked when they are registered, so
+    /// instantiating a plugin does not recompile it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin is not found.
+    pub fn prepare(&self, plugin_id: &str) -> Result<InstancePre<BrioHostState>> {
+        self.prepared
+            .get(plugin_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Plugin not found: {plugin_id}"))
+    }
+
+    /// Instantiates a plugin by ID, within the plugin's resource limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin is not found, if the component fails to load,
+    /// or if instantiation fails or exceeds the limits, in which case the error
+    /// is a [`GuestError`](crate::engine::GuestError).
+    pub async fn instantiate(
+        &self,
+        plugin_id: &str,
+        host_state: BrioHostState,
+    ) -> Result<Store<BrioHostState>> {
+        let pre = self.prepare(plugin_id)?;
+        let (permissions, limits) = self
+            .plugins
+            .get(plugin_id)
+            .map(|m| (m.permissions.clone(), m.limits))
+            .unwrap_or_default();
+
+        // Create a view of host state with plugin context
+        let plugin_state = host_state.with_plugin_context(plugin_id.to_string(), permissions);
+
+        let mut store = limited_store(&self.engine, plugin_state, &limits)?;
+        run_bounded(&limits, plugin_id, pre.instantiate_async(&mut store)).await?;
+
+        Ok(store)
+    }
+
+    /// Lists all registered plugins.
+    ///
+    /// # Returns
+    ///
+    /// A vector of plugin metadata.
+    #[must_use]
+    pub fn list_plugins(&self) -> Vec<PluginMetadata> {
+        self.plugins.values().cloned().collect()
+    }
+
+    /// Gets metadata for a specific plugin.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the plugin to look up.
+    ///
+    /// # Returns
+    ///
+    /// Plugin metadata if found.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<PluginMetadata> {
+        self.plugins.get(id).cloned()
+    }
+}
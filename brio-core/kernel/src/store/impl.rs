@@ -6,8 +6,9 @@
 use anyhow::Result;
 use sqlx::{
     Column, Row, TypeInfo, ValueRef,
-    sqlite::{SqlitePool, SqliteRow},
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow},
 };
+use std::str::FromStr;
 use tracing::instrument;
 
 use crate::store::policy::{PolicyError, QueryPolicy};
@@ -35,6 +36,16 @@ pub struct GenericRow {
     pub values: Vec<String>,
 }
 
+/// Opens a `SQLite` connection pool, creating the database file if it does not exist.
+///
+/// # Errors
+///
+/// Returns an error if the URL is invalid or the database cannot be opened.
+pub async fn open_pool(db_url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(db_url)?.create_if_missing(true);
+    SqlitePoolOptions::new().connect_with(options).await
+}
+
 /// SQL store with policy enforcement.
 pub struct SqlStore {
     pool: SqlitePool,
@@ -0,0 +1,20 @@
+-- Migration: Initial kernel schema
+-- Creates the task queue shared by the supervisor, agents and the WebSocket API
+
+-- Task queue: every unit of work tracked by the supervisor
+CREATE TABLE IF NOT EXISTS tasks (
+    id INTEGER PRIMARY KEY,
+    content TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'pending',
+    assigned_agent TEXT,
+    failure_reason TEXT,
+    parent_id INTEGER,  -- Parent task for decomposed subtasks, NULL for root tasks
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))  -- ISO8601 timestamp
+);
+
+-- Index for the supervisor's active task polling
+CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status, priority);
+
+-- Index for subtask lookups
+CREATE INDEX IF NOT EXISTS idx_tasks_parent ON tasks(parent_id);
@@ -68,13 +68,3 @@ CREATE INDEX IF NOT EXISTS idx_merge_status ON merge_queue(status) WHERE status
 
 -- Index for approval queries
 CREATE INDEX IF NOT EXISTS idx_merge_approver ON merge_queue(approved_by) WHERE approved_by IS NOT NULL;
-
--- Schema version tracking
-CREATE TABLE IF NOT EXISTS schema_migrations (
-    version INTEGER PRIMARY KEY,
-    applied_at TEXT NOT NULL,  -- ISO8601 timestamp
-    description TEXT
-);
-
-INSERT OR IGNORE INTO schema_migrations (version, applied_at, description) 
-VALUES (2, datetime('now'), 'Add branch persistence tables for branching orchestrator');
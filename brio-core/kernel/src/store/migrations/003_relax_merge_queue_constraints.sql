@@ -0,0 +1,31 @@
+-- Migration: Align merge_queue with the kernel merge request model
+-- Merge strategies are resolved by the merge strategy registry (e.g. 'union'),
+-- so the column no longer restricts them to a fixed list. A branch may also
+-- accumulate several merge requests over time (e.g. after a rejection).
+
+CREATE TABLE merge_queue_new (
+    id TEXT PRIMARY KEY,  -- UUID stored as TEXT
+    branch_id TEXT NOT NULL REFERENCES branches(id) ON DELETE CASCADE,
+    strategy TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected', 'merged', 'conflict')),
+    requires_approval INTEGER NOT NULL DEFAULT 1 CHECK (requires_approval IN (0, 1)),
+    approved_by TEXT,
+    approved_at TEXT,  -- ISO8601 timestamp
+    created_at TEXT NOT NULL  -- ISO8601 timestamp
+);
+
+INSERT INTO merge_queue_new (id, branch_id, strategy, status, requires_approval, approved_by, approved_at, created_at)
+SELECT id, branch_id, strategy, status, requires_approval, approved_by, approved_at, created_at FROM merge_queue;
+
+DROP TABLE merge_queue;
+
+ALTER TABLE merge_queue_new RENAME TO merge_queue;
+
+-- Index for per-branch merge request lookups
+CREATE INDEX IF NOT EXISTS idx_merge_branch ON merge_queue(branch_id);
+
+-- Index for pending merge requests
+CREATE INDEX IF NOT EXISTS idx_merge_status ON merge_queue(status) WHERE status IN ('pending', 'approved');
+
+-- Index for approval queries
+CREATE INDEX IF NOT EXISTS idx_merge_approver ON merge_queue(approved_by) WHERE approved_by IS NOT NULL;
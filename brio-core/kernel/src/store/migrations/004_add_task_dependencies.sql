@@ -0,0 +1,8 @@
+-- Migration: Dependency-aware plans
+-- Subtasks created from a plan record the ids of their prerequisite tasks as a
+-- JSON array, the capability the planner assigned them and, once completed,
+-- the output their agent returned so successors can build on it.
+
+ALTER TABLE tasks ADD COLUMN depends_on TEXT NOT NULL DEFAULT '[]';  -- JSON array of task ids
+ALTER TABLE tasks ADD COLUMN required_capability TEXT;
+ALTER TABLE tasks ADD COLUMN output TEXT;
@@ -0,0 +1,8 @@
+-- Migration: Task verification
+-- A task may run inside a VFS session, whose id verification commands receive.
+-- Failed verification rounds are counted and the latest feedback is kept so
+-- the agent retrying the task can address it.
+
+ALTER TABLE tasks ADD COLUMN session_id TEXT;
+ALTER TABLE tasks ADD COLUMN verification_rounds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE tasks ADD COLUMN feedback TEXT;
@@ -0,0 +1,19 @@
+-- Migration: Inference usage accounting
+-- Token usage is aggregated per plugin, task and model. Calls made outside a
+-- task are recorded with an empty task id so the key stays unique.
+
+CREATE TABLE IF NOT EXISTS inference_usage (
+    plugin_id TEXT NOT NULL,
+    task_id TEXT NOT NULL DEFAULT '',
+    model TEXT NOT NULL,
+    requests INTEGER NOT NULL DEFAULT 0,
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd REAL NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,  -- ISO8601 timestamp
+    PRIMARY KEY (plugin_id, task_id, model)
+);
+
+-- Index for per-task budget checks
+CREATE INDEX IF NOT EXISTS idx_inference_usage_task ON inference_usage(task_id);
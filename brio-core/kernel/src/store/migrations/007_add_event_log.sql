@@ -0,0 +1,46 @@
+-- Migration: Durable pub/sub event log
+-- Every published event is appended to the log with a sequence number.
+-- Subscribers consume matching events in sequence order and commit the last
+-- sequence they handled, so delivery resumes where it stopped after a restart.
+
+CREATE TABLE IF NOT EXISTS event_log (
+    sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_id TEXT NOT NULL UNIQUE,   -- Unique across retries, used to drop duplicates
+    topic TEXT NOT NULL,
+    payload_kind TEXT NOT NULL CHECK (payload_kind IN ('json', 'binary')),
+    payload BLOB NOT NULL,
+    published_at TEXT NOT NULL       -- ISO8601 timestamp
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_log_topic ON event_log(topic, sequence);
+
+-- Topic patterns each plugin subscribes to. `topic_glob` is the pattern as
+-- an SQLite GLOB expression.
+CREATE TABLE IF NOT EXISTS event_subscriptions (
+    subscriber TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    topic_glob TEXT NOT NULL,
+    PRIMARY KEY (subscriber, pattern)
+);
+
+-- Last sequence each subscriber handled, and the failed attempts at handling
+-- the event after it
+CREATE TABLE IF NOT EXISTS event_offsets (
+    subscriber TEXT PRIMARY KEY,
+    committed INTEGER NOT NULL DEFAULT 0,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL         -- ISO8601 timestamp
+);
+
+-- Events a subscriber failed to handle too many times
+CREATE TABLE IF NOT EXISTS event_dead_letters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_sequence INTEGER NOT NULL REFERENCES event_log(sequence),
+    subscriber TEXT NOT NULL,
+    topic TEXT NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    failed_at TEXT NOT NULL          -- ISO8601 timestamp
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_dead_letters_subscriber ON event_dead_letters(subscriber);
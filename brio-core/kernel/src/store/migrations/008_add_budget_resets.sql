@@ -0,0 +1,10 @@
+-- Migration: Plugin budget resets
+-- Resetting a plugin's token budget records its lifetime usage at that point;
+-- only tokens used beyond it count against the budget again. The usage
+-- ledger itself keeps every call.
+
+CREATE TABLE IF NOT EXISTS inference_budget_resets (
+    plugin_id TEXT PRIMARY KEY,
+    total_tokens INTEGER NOT NULL,  -- Lifetime tokens of the plugin when reset
+    reset_at TEXT NOT NULL          -- ISO8601 timestamp
+);
@@ -0,0 +1,520 @@
+//! Embedded schema migrations for the kernel database.
+//!
+//! Migrations are compiled into the binary from `store/migrations/*.sql` and
+//! applied in version order. Every applied migration is recorded in the
+//! `schema_migrations` table together with a checksum of its SQL, so a
+//! migration that was edited after being applied is detected on startup.
+
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use tracing::{info, instrument};
+
+/// DDL for the table tracking applied migrations.
+const SCHEMA_MIGRATIONS_DDL: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applied_at TEXT NOT NULL
+)";
+
+/// Migrations embedded in the kernel binary, in ascending version order.
+const EMBEDDED_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "Initial schema with task queue",
+        include_str!("migrations/001_initial_schema.sql"),
+    ),
+    Migration::new(
+        2,
+        "Add branch persistence tables for branching orchestrator",
+        include_str!("migrations/002_add_branch_tables.sql"),
+    ),
+    Migration::new(
+        3,
+        "Relax merge queue constraints for kernel merge requests",
+        include_str!("migrations/003_relax_merge_queue_constraints.sql"),
+    ),
+    Migration::new(
+        4,
+        "Add task dependencies, capabilities and outputs for plan execution",
+        include_str!("migrations/004_add_task_dependencies.sql"),
+    ),
+    Migration::new(
+        5,
+        "Add task sessions and verification state",
+        include_str!("migrations/005_add_task_verification.sql"),
+    ),
+    Migration::new(
+        6,
+        "Add inference usage accounting",
+        include_str!("migrations/006_add_inference_usage.sql"),
+    ),
+    Migration::new(
+        7,
+        "Add durable event log with subscriber offsets",
+        include_str!("migrations/007_add_event_log.sql"),
+    ),
+    Migration::new(
+        8,
+        "Add plugin token budget resets",
+        include_str!("migrations/008_add_budget_resets.sql"),
+    ),
+];
+
+/// Errors that can occur while migrating the database.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// Database-related error.
+    #[error("Database Error: {0}")]
+    DbError(#[from] sqlx::Error),
+    /// An applied migration no longer matches the embedded SQL.
+    #[error(
+        "Checksum mismatch for migration {version}: database has {applied}, binary has {embedded}"
+    )]
+    ChecksumMismatch {
+        /// Version of the drifted migration.
+        version: i64,
+        /// Checksum recorded when the migration was applied.
+        applied: String,
+        /// Checksum of the embedded migration.
+        embedded: String,
+    },
+    /// The database contains a migration this binary does not know about.
+    #[error("Database is at migration {0}, which is unknown to this kernel version")]
+    UnknownVersion(i64),
+}
+
+/// A single versioned schema migration.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Monotonically increasing version number.
+    pub version: i64,
+    /// Short human readable description.
+    pub description: &'static str,
+    /// SQL script, may contain multiple statements.
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// Creates a new migration.
+    #[must_use]
+    pub const fn new(version: i64, description: &'static str, sql: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            sql,
+        }
+    }
+
+    /// Returns the hex-encoded SHA-256 checksum of the migration SQL.
+    #[must_use]
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.sql.as_bytes()))
+    }
+}
+
+/// State of a single migration as reported by [`Migrator::status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationState {
+    /// Applied, with a matching checksum.
+    Applied {
+        /// Timestamp recorded when the migration was applied.
+        applied_at: String,
+    },
+    /// Not yet applied.
+    Pending,
+    /// Applied, but the embedded SQL has changed since.
+    Drifted {
+        /// Checksum recorded when the migration was applied.
+        applied_checksum: String,
+    },
+}
+
+/// Status line for a single migration.
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    /// Migration version.
+    pub version: i64,
+    /// Migration description.
+    pub description: &'static str,
+    /// Current state of the migration in the database.
+    pub state: MigrationState,
+}
+
+/// Applies embedded migrations to a `SQLite` database.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrator {
+    /// Creates a migrator over the migrations embedded in the kernel.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_migrations(EMBEDDED_MIGRATIONS.to_vec())
+    }
+
+    /// Creates a migrator over an explicit set of migrations.
+    #[must_use]
+    pub fn with_migrations(mut migrations: Vec<Migration>) -> Self {
+        migrations.sort_by_key(|m| m.version);
+        Self { migrations }
+    }
+
+    /// Returns the migrations known to this migrator, in version order.
+    #[must_use]
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// Reports the state of every known migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the migration table cannot be read, or if the
+    /// database has been migrated by a newer kernel.
+    pub async fn status(&self, pool: &SqlitePool) -> Result<Vec<MigrationStatus>, MigrationError> {
+        let applied = self.applied(pool).await?;
+
+        Ok(self
+            .migrations
+            .iter()
+            .map(|migration| {
+                let state = match applied.iter().find(|a| a.version == migration.version) {
+                    Some(record) if record.checksum == migration.checksum() => {
+                        MigrationState::Applied {
+                            applied_at: record.applied_at.clone(),
+                        }
+                    }
+                    Some(record) => MigrationState::Drifted {
+                        applied_checksum: record.checksum.clone(),
+                    },
+                    None => MigrationState::Pending,
+                };
+                MigrationStatus {
+                    version: migration.version,
+                    description: migration.description,
+                    state,
+                }
+            })
+            .collect())
+    }
+
+    /// Applies all pending migrations and returns the versions that were applied.
+    ///
+    /// Each migration runs in its own transaction. Already applied migrations
+    /// are verified against their recorded checksum first; any drift aborts
+    /// before touching the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an applied migration has drifted, if the database
+    /// is ahead of this kernel, or if a migration fails to apply.
+    #[instrument(skip_all)]
+    pub async fn run(&self, pool: &SqlitePool) -> Result<Vec<i64>, MigrationError> {
+        let status = self.status(pool).await?;
+
+        if let Some((version, applied_checksum)) = status.iter().find_map(|s| match &s.state {
+            MigrationState::Drifted { applied_checksum } => Some((s.version, applied_checksum)),
+            _ => None,
+        }) {
+            let embedded = self
+                .migrations
+                .iter()
+                .find(|m| m.version == version)
+                .map(Migration::checksum)
+                .unwrap_or_default();
+            return Err(MigrationError::ChecksumMismatch {
+                version,
+                applied: applied_checksum.clone(),
+                embedded,
+            });
+        }
+
+        let mut applied = Vec::new();
+        for (migration, status) in self.migrations.iter().zip(status) {
+            if status.state != MigrationState::Pending {
+                continue;
+            }
+
+            let mut tx = pool.begin().await?;
+            match apply(&mut tx, migration).await {
+                Ok(()) => tx.commit().await?,
+                Err(e) => {
+                    // Roll back eagerly so the schema lock is released before returning
+                    tx.rollback().await?;
+                    return Err(e.into());
+                }
+            }
+
+            info!(
+                version = migration.version,
+                description = migration.description,
+                "Applied schema migration"
+            );
+            applied.push(migration.version);
+        }
+
+        Ok(applied)
+    }
+
+    /// Reads applied migrations, creating the tracking table if needed.
+    async fn applied(&self, pool: &SqlitePool) -> Result<Vec<AppliedMigration>, MigrationError> {
+        sqlx::query(SCHEMA_MIGRATIONS_DDL).execute(pool).await?;
+        self.upgrade_legacy_table(pool).await?;
+
+        let rows = sqlx::query(
+            "SELECT version, checksum, applied_at FROM schema_migrations ORDER BY version",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let applied = rows
+            .iter()
+            .map(|row| {
+                Ok(AppliedMigration {
+                    version: row.try_get("version")?,
+                    checksum: row.try_get("checksum")?,
+                    applied_at: row.try_get("applied_at")?,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        if let Some(unknown) = applied
+            .iter()
+            .find(|a| !self.migrations.iter().any(|m| m.version == a.version))
+        {
+            return Err(MigrationError::UnknownVersion(unknown.version));
+        }
+
+        Ok(applied)
+    }
+
+    /// Adds the `checksum` column to a tracking table created by kernels
+    /// that recorded migrations without one.
+    ///
+    /// Those kernels applied the SQL that is embedded today, so the recorded
+    /// migrations are backfilled with their current checksum. Versions this
+    /// kernel does not know keep an empty checksum and are reported by
+    /// [`Migrator::run`] as unknown.
+    async fn upgrade_legacy_table(&self, pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        let has_checksum: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('schema_migrations') \
+             WHERE name = 'checksum'",
+        )
+        .fetch_one(pool)
+        .await?;
+        if has_checksum {
+            return Ok(());
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("ALTER TABLE schema_migrations ADD COLUMN checksum TEXT NOT NULL DEFAULT ''")
+            .execute(&mut *tx)
+            .await?;
+        for migration in &self.migrations {
+            sqlx::query("UPDATE schema_migrations SET checksum = ? WHERE version = ?")
+                .bind(migration.checksum())
+                .bind(migration.version)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        info!("Upgraded legacy schema_migrations table");
+        Ok(())
+    }
+}
+
+/// Runs a migration script and records it inside the given transaction.
+async fn apply(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    migration: &Migration,
+) -> Result<(), sqlx::Error> {
+    sqlx::raw_sql(migration.sql).execute(&mut **tx).await?;
+    sqlx::query(
+        "INSERT INTO schema_migrations (version, description, checksum, applied_at) \
+         VALUES (?, ?, ?, datetime('now'))",
+    )
+    .bind(migration.version)
+    .bind(migration.description)
+    .bind(migration.checksum())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// A row of the `schema_migrations` table.
+struct AppliedMigration {
+    version: i64,
+    checksum: String,
+    applied_at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory database")
+    }
+
+    #[tokio::test]
+    async fn run_applies_all_embedded_migrations() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::new();
+
+        let applied = migrator.run(&pool).await.unwrap();
+        assert_eq!(applied, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        for table in [
+            "tasks",
+            "branches",
+            "branch_executions",
+            "merge_queue",
+            "inference_usage",
+            "event_log",
+            "event_offsets",
+            "event_dead_letters",
+            "inference_budget_resets",
+        ] {
+            let count: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            )
+            .bind(table)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(count, 1, "missing table {table}");
+        }
+    }
+
+    #[tokio::test]
+    async fn run_is_idempotent() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::new();
+
+        migrator.run(&pool).await.unwrap();
+        let applied = migrator.run(&pool).await.unwrap();
+        assert!(applied.is_empty());
+
+        let status = migrator.status(&pool).await.unwrap();
+        assert!(
+            status
+                .iter()
+                .all(|s| matches!(s.state, MigrationState::Applied { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn status_reports_pending_migrations() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::new();
+
+        let status = migrator.status(&pool).await.unwrap();
+        assert_eq!(status.len(), 8);
+        assert!(status.iter().all(|s| s.state == MigrationState::Pending));
+    }
+
+    #[tokio::test]
+    async fn run_refuses_checksum_drift() {
+        let pool = memory_pool().await;
+        Migrator::with_migrations(vec![Migration::new(
+            1,
+            "first",
+            "CREATE TABLE a (id INTEGER)",
+        )])
+        .run(&pool)
+        .await
+        .unwrap();
+
+        let drifted = Migrator::with_migrations(vec![Migration::new(
+            1,
+            "first",
+            "CREATE TABLE a (id INTEGER, name TEXT)",
+        )]);
+
+        let result = drifted.run(&pool).await;
+        assert!(matches!(
+            result,
+            Err(MigrationError::ChecksumMismatch { version: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_refuses_unknown_applied_version() {
+        let pool = memory_pool().await;
+        Migrator::with_migrations(vec![
+            Migration::new(1, "first", "CREATE TABLE a (id INTEGER)"),
+            Migration::new(2, "second", "CREATE TABLE b (id INTEGER)"),
+        ])
+        .run(&pool)
+        .await
+        .unwrap();
+
+        let older = Migrator::with_migrations(vec![Migration::new(
+            1,
+            "first",
+            "CREATE TABLE a (id INTEGER)",
+        )]);
+
+        let result = older.run(&pool).await;
+        assert!(matches!(result, Err(MigrationError::UnknownVersion(2))));
+    }
+
+    #[tokio::test]
+    async fn run_upgrades_legacy_tracking_table() {
+        let pool = memory_pool().await;
+        sqlx::raw_sql(
+            "CREATE TABLE a (id INTEGER);
+             CREATE TABLE schema_migrations (
+                 version INTEGER PRIMARY KEY,
+                 applied_at TEXT NOT NULL,
+                 description TEXT
+             );
+             INSERT INTO schema_migrations (version, applied_at, description)
+             VALUES (1, datetime('now'), 'first');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let migrator = Migrator::with_migrations(vec![
+            Migration::new(1, "first", "CREATE TABLE a (id INTEGER)"),
+            Migration::new(2, "second", "CREATE TABLE b (id INTEGER)"),
+        ]);
+
+        assert_eq!(migrator.run(&pool).await.unwrap(), vec![2]);
+        let status = migrator.status(&pool).await.unwrap();
+        assert!(
+            status
+                .iter()
+                .all(|s| matches!(s.state, MigrationState::Applied { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_migration_is_not_recorded() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::with_migrations(vec![Migration::new(
+            1,
+            "broken",
+            "CREATE TABLE a (id INTEGER); THIS IS NOT SQL;",
+        )]);
+
+        assert!(migrator.run(&pool).await.is_err());
+
+        let status = migrator.status(&pool).await.unwrap();
+        assert_eq!(status[0].state, MigrationState::Pending);
+    }
+}
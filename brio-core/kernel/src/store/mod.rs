@@ -2,10 +2,13 @@
 
 /// Store implementation with `SQLite` backend.
 pub mod r#impl;
+/// Embedded schema migrations and migration runner.
+pub mod migrator;
 /// Query policy definitions and enforcement.
 pub mod policy;
 
-pub use r#impl::{SqlStore, StoreError};
+pub use r#impl::{SqlStore, StoreError, open_pool};
+pub use migrator::{Migration, MigrationError, MigrationState, MigrationStatus, Migrator};
 pub use policy::{PolicyError, PrefixPolicy, QueryPolicy};
 
 #[cfg(test)]
@@ -0,0 +1,261 @@
+//! `SQLite` ledger of inference token usage.
+//!
+//! Usage is aggregated in the `inference_usage` table, one row per plugin,
+//! task and model. Calls made outside a task are stored with an empty task id.
+//! Resetting a plugin's budget stores its lifetime usage at that point in
+//! `inference_budget_resets`, so usage since the reset can be told apart.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+
+use crate::inference::Usage;
+
+/// Shared filter of the ledger queries; `NULL` parameters match any value.
+const FILTER: &str = "(?1 IS NULL OR plugin_id = ?1) \
+                      AND (?2 IS NULL OR task_id = ?2) \
+                      AND (?3 IS NULL OR model = ?3)";
+
+/// Usage accumulated by a plugin for a model within one task.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageEntry {
+    /// Plugin that made the calls
+    pub plugin_id: String,
+    /// Task the calls were made for, if any
+    pub task_id: Option<String>,
+    /// Model the calls were made to
+    pub model: String,
+    /// Number of completed calls
+    pub requests: u64,
+    /// Prompt tokens consumed
+    pub prompt_tokens: u64,
+    /// Completion tokens generated
+    pub completion_tokens: u64,
+    /// Total tokens
+    pub total_tokens: u64,
+    /// Estimated cost in USD
+    pub cost_usd: f64,
+}
+
+/// Usage summed over a set of ledger entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageTotals {
+    /// Number of completed calls
+    pub requests: u64,
+    /// Prompt tokens consumed
+    pub prompt_tokens: u64,
+    /// Completion tokens generated
+    pub completion_tokens: u64,
+    /// Total tokens
+    pub total_tokens: u64,
+    /// Estimated cost in USD
+    pub cost_usd: f64,
+}
+
+/// Selects ledger entries; unset fields match every entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct UsageFilter {
+    /// Only entries of this plugin
+    #[serde(default)]
+    pub plugin_id: Option<String>,
+    /// Only entries of this task
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// Only entries of this model
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl UsageFilter {
+    /// Selects the entries of a plugin
+    #[must_use]
+    pub fn plugin(plugin_id: impl Into<String>) -> Self {
+        Self {
+            plugin_id: Some(plugin_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Selects the entries of a task
+    #[must_use]
+    pub fn task(task_id: impl Into<String>) -> Self {
+        Self {
+            task_id: Some(task_id.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Token usage ledger backed by the kernel database.
+#[derive(Debug, Clone)]
+pub struct UsageLedger {
+    pool: SqlitePool,
+}
+
+impl UsageLedger {
+    /// Creates a ledger on a migrated kernel database
+    #[must_use]
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds the usage of one call to the ledger.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn record(
+        &self,
+        plugin_id: &str,
+        task_id: Option<&str>,
+        model: &str,
+        usage: &Usage,
+        cost_usd: f64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO inference_usage (plugin_id, task_id, model, requests, prompt_tokens, \
+             completion_tokens, total_tokens, cost_usd, updated_at) \
+             VALUES (?, ?, ?, 1, ?, ?, ?, ?, ?) \
+             ON CONFLICT (plugin_id, task_id, model) DO UPDATE SET \
+             requests = requests + 1, \
+             prompt_tokens = prompt_tokens + excluded.prompt_tokens, \
+             completion_tokens = completion_tokens + excluded.completion_tokens, \
+             total_tokens = total_tokens + excluded.total_tokens, \
+             cost_usd = cost_usd + excluded.cost_usd, \
+             updated_at = excluded.updated_at",
+        )
+        .bind(plugin_id)
+        .bind(task_id.unwrap_or_default())
+        .bind(model)
+        .bind(usage.prompt_tokens)
+        .bind(usage.completion_tokens)
+        .bind(usage.total_tokens)
+        .bind(cost_usd)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the entries selected by `filter`, ordered by plugin, task and model.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn entries(&self, filter: &UsageFilter) -> Result<Vec<UsageEntry>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT plugin_id, task_id, model, requests, prompt_tokens, completion_tokens, \
+             total_tokens, cost_usd FROM inference_usage WHERE {FILTER} \
+             ORDER BY plugin_id, task_id, model"
+        ))
+        .bind(&filter.plugin_id)
+        .bind(&filter.task_id)
+        .bind(&filter.model)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let task_id: String = row.try_get("task_id")?;
+                Ok(UsageEntry {
+                    plugin_id: row.try_get("plugin_id")?,
+                    task_id: Some(task_id).filter(|id| !id.is_empty()),
+                    model: row.try_get("model")?,
+                    requests: count(row, "requests")?,
+                    prompt_tokens: count(row, "prompt_tokens")?,
+                    completion_tokens: count(row, "completion_tokens")?,
+                    total_tokens: count(row, "total_tokens")?,
+                    cost_usd: row.try_get("cost_usd")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the usage summed over the entries selected by `filter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn totals(&self, filter: &UsageFilter) -> Result<UsageTotals, sqlx::Error> {
+        let row = sqlx::query(&format!(
+            "SELECT COALESCE(SUM(requests), 0) AS requests, \
+             COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens, \
+             COALESCE(SUM(completion_tokens), 0) AS completion_tokens, \
+             COALESCE(SUM(total_tokens), 0) AS total_tokens, \
+             COALESCE(SUM(cost_usd), 0.0) AS cost_usd \
+             FROM inference_usage WHERE {FILTER}"
+        ))
+        .bind(&filter.plugin_id)
+        .bind(&filter.task_id)
+        .bind(&filter.model)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(UsageTotals {
+            requests: count(&row, "requests")?,
+            prompt_tokens: count(&row, "prompt_tokens")?,
+            completion_tokens: count(&row, "completion_tokens")?,
+            total_tokens: count(&row, "total_tokens")?,
+            cost_usd: row.try_get("cost_usd")?,
+        })
+    }
+
+    /// Starts counting a plugin's budget afresh from its current usage.
+    /// Returns the tokens the plugin had used since the previous reset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database write fails.
+    pub async fn reset_plugin_budget(&self, plugin_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let used = plugin_tokens_since_reset(&mut *tx, plugin_id).await?;
+        sqlx::query(
+            "INSERT INTO inference_budget_resets (plugin_id, total_tokens, reset_at) \
+             SELECT ?1, COALESCE(SUM(total_tokens), 0), ?2 FROM inference_usage \
+             WHERE plugin_id = ?1 \
+             ON CONFLICT (plugin_id) DO UPDATE SET \
+             total_tokens = excluded.total_tokens, reset_at = excluded.reset_at",
+        )
+        .bind(plugin_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(used)
+    }
+
+    /// Returns the tokens a plugin has used since its budget was last reset,
+    /// or in total if it never was.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn plugin_tokens_since_reset(&self, plugin_id: &str) -> Result<u64, sqlx::Error> {
+        plugin_tokens_since_reset(&self.pool, plugin_id).await
+    }
+}
+
+async fn plugin_tokens_since_reset<'e>(
+    executor: impl sqlx::SqliteExecutor<'e>,
+    plugin_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT MAX(COALESCE(SUM(total_tokens), 0) - COALESCE( \
+         (SELECT total_tokens FROM inference_budget_resets WHERE plugin_id = ?1), 0), 0) \
+         AS total_tokens FROM inference_usage WHERE plugin_id = ?1",
+    )
+    .bind(plugin_id)
+    .fetch_one(executor)
+    .await?;
+    count(&row, "total_tokens")
+}
+
+/// Reads a non-negative counter column.
+fn count(row: &SqliteRow, column: &str) -> Result<u64, sqlx::Error> {
+    let value: i64 = row.try_get(column)?;
+    u64::try_from(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
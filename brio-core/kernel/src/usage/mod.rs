@@ -0,0 +1,306 @@
+//! Inference usage accounting.
+//!
+//! Token usage of guest inference calls is recorded per plugin, task and
+//! model in the kernel database. A [`UsageTracker`] holds the price table used
+//! to estimate the cost of each call and the token budgets checked before
+//! each call is made.
+
+pub mod ledger;
+pub mod pricing;
+
+pub use ledger::{UsageEntry, UsageFilter, UsageLedger, UsageTotals};
+pub use pricing::{ModelPrice, PriceTable};
+
+use crate::inference::{InferenceError, Usage};
+use crate::infrastructure::config::UsageSettings;
+
+/// Token limits of a single task and of a single plugin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenBudgets {
+    /// Maximum tokens a task may consume, `None` for no limit
+    pub per_task: Option<u64>,
+    /// Maximum tokens a plugin may consume until its budget is reset with
+    /// [`UsageLedger::reset_plugin_budget`], `None` for no limit
+    pub per_plugin: Option<u64>,
+}
+
+/// Prices and budgets applied to guest inference calls.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    prices: PriceTable,
+    budgets: TokenBudgets,
+}
+
+impl UsageTracker {
+    /// Creates a tracker with the given prices and budgets
+    #[must_use]
+    pub fn new(prices: PriceTable, budgets: TokenBudgets) -> Self {
+        Self { prices, budgets }
+    }
+
+    /// Creates a tracker from configuration
+    #[must_use]
+    pub fn from_settings(settings: &UsageSettings) -> Self {
+        let prices = settings
+            .prices
+            .iter()
+            .fold(PriceTable::new(), |table, price| {
+                table.with_price(price.into())
+            });
+        Self::new(
+            prices,
+            TokenBudgets {
+                per_task: settings.max_tokens_per_task,
+                per_plugin: settings.max_tokens_per_plugin,
+            },
+        )
+    }
+
+    /// Returns the price table used for cost estimates
+    #[must_use]
+    pub fn prices(&self) -> &PriceTable {
+        &self.prices
+    }
+
+    /// Returns the enforced token budgets
+    #[must_use]
+    pub fn budgets(&self) -> TokenBudgets {
+        self.budgets
+    }
+
+    /// Checks that neither the task nor the plugin has used up its budget.
+    ///
+    /// Budgets are checked before a call, so the call that crosses a limit
+    /// completes and the next one is refused.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InferenceError::BudgetExceeded` if a budget is used up, or
+    /// `InferenceError::ProviderError` if usage cannot be read.
+    pub async fn check_budgets(
+        &self,
+        ledger: &UsageLedger,
+        plugin_id: &str,
+        task_id: Option<&str>,
+    ) -> Result<(), InferenceError> {
+        if let (Some(limit), Some(task_id)) = (self.budgets.per_task, task_id) {
+            let used = used_tokens(ledger, UsageFilter::task(task_id)).await?;
+            if used >= limit {
+                return Err(budget_exceeded(
+                    plugin_id,
+                    "task",
+                    format!("Task '{task_id}' has used {used} of its {limit} token budget"),
+                ));
+            }
+        }
+
+        if let Some(limit) = self.budgets.per_plugin {
+            let used = ledger
+                .plugin_tokens_since_reset(plugin_id)
+                .await
+                .map_err(|e| read_error(&e))?;
+            if used >= limit {
+                return Err(budget_exceeded(
+                    plugin_id,
+                    "plugin",
+                    format!("Plugin '{plugin_id}' has used {used} of its {limit} token budget"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the usage of a call in the ledger and in the Prometheus
+    /// metrics, returning its estimated cost in USD.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger cannot be written.
+    pub async fn record(
+        &self,
+        ledger: &UsageLedger,
+        plugin_id: &str,
+        task_id: Option<&str>,
+        model: &str,
+        usage: &Usage,
+    ) -> Result<f64, sqlx::Error> {
+        let cost = self.prices.cost(model, usage);
+
+        let labels = [
+            ("plugin", plugin_id.to_string()),
+            ("model", model.to_string()),
+        ];
+        metrics::counter!("brio_inference_requests_total", &labels).increment(1);
+        metrics::counter!("brio_inference_prompt_tokens_total", &labels)
+            .increment(u64::from(usage.prompt_tokens));
+        metrics::counter!("brio_inference_completion_tokens_total", &labels)
+            .increment(u64::from(usage.completion_tokens));
+        metrics::gauge!("brio_inference_cost_usd", &labels).increment(cost);
+
+        ledger
+            .record(plugin_id, task_id, model, usage, cost)
+            .await?;
+        Ok(cost)
+    }
+}
+
+async fn used_tokens(ledger: &UsageLedger, filter: UsageFilter) -> Result<u64, InferenceError> {
+    ledger
+        .totals(&filter)
+        .await
+        .map(|totals| totals.total_tokens)
+        .map_err(|e| read_error(&e))
+}
+
+fn read_error(error: &sqlx::Error) -> InferenceError {
+    InferenceError::ProviderError(format!("Cannot read token usage: {error}"))
+}
+
+fn budget_exceeded(plugin_id: &str, scope: &'static str, message: String) -> InferenceError {
+    metrics::counter!(
+        "brio_inference_budget_exceeded_total",
+        "plugin" => plugin_id.to_string(),
+        "scope" => scope,
+    )
+    .increment(1);
+    InferenceError::BudgetExceeded(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{Migrator, open_pool};
+
+    async fn ledger() -> UsageLedger {
+        let pool = open_pool("sqlite::memory:").await.unwrap();
+        Migrator::new().run(&pool).await.unwrap();
+        UsageLedger::new(pool)
+    }
+
+    fn usage(prompt_tokens: u32, completion_tokens: u32) -> Usage {
+        Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_aggregated_per_plugin_task_and_model() {
+        let ledger = ledger().await;
+        let tracker = UsageTracker::new(
+            PriceTable::new().with_price(ModelPrice::new("gpt-4o*", 2.5, 10.0)),
+            TokenBudgets::default(),
+        );
+
+        for _ in 0..2 {
+            tracker
+                .record(&ledger, "coder", Some("7"), "gpt-4o", &usage(1000, 100))
+                .await
+                .unwrap();
+        }
+        tracker
+            .record(&ledger, "coder", None, "llama3.1", &usage(50, 50))
+            .await
+            .unwrap();
+        tracker
+            .record(&ledger, "reviewer", Some("7"), "gpt-4o", &usage(10, 0))
+            .await
+            .unwrap();
+
+        let entries = ledger.entries(&UsageFilter::plugin("coder")).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].task_id, None);
+        assert_eq!(entries[1].task_id.as_deref(), Some("7"));
+        assert_eq!(entries[1].requests, 2);
+        assert_eq!(entries[1].total_tokens, 2200);
+        assert!((entries[1].cost_usd - 0.007).abs() < 1e-9);
+
+        let task = ledger.totals(&UsageFilter::task("7")).await.unwrap();
+        assert_eq!(task.requests, 3);
+        assert_eq!(task.total_tokens, 2210);
+
+        let all = ledger.totals(&UsageFilter::default()).await.unwrap();
+        assert_eq!(all.total_tokens, 2310);
+    }
+
+    #[tokio::test]
+    async fn test_budgets_refuse_calls_once_used_up() {
+        let ledger = ledger().await;
+        let tracker = UsageTracker::new(
+            PriceTable::new(),
+            TokenBudgets {
+                per_task: Some(1000),
+                per_plugin: Some(1500),
+            },
+        );
+
+        assert!(
+            tracker
+                .check_budgets(&ledger, "coder", Some("1"))
+                .await
+                .is_ok()
+        );
+        tracker
+            .record(&ledger, "coder", Some("1"), "gpt-4", &usage(900, 100))
+            .await
+            .unwrap();
+
+        let result = tracker.check_budgets(&ledger, "coder", Some("1")).await;
+        assert!(
+            matches!(&result, Err(InferenceError::BudgetExceeded(msg)) if msg.contains("Task '1'")),
+            "Expected task budget error, got {result:?}"
+        );
+
+        // Another task of the same plugin still has budget until the plugin runs out
+        assert!(
+            tracker
+                .check_budgets(&ledger, "coder", Some("2"))
+                .await
+                .is_ok()
+        );
+        tracker
+            .record(&ledger, "coder", Some("2"), "gpt-4", &usage(500, 0))
+            .await
+            .unwrap();
+        let result = tracker.check_budgets(&ledger, "coder", Some("2")).await;
+        assert!(
+            matches!(&result, Err(InferenceError::BudgetExceeded(msg)) if msg.contains("Plugin 'coder'")),
+            "Expected plugin budget error, got {result:?}"
+        );
+
+        // A reset gives the plugin its whole budget again, but not the task
+        assert_eq!(ledger.reset_plugin_budget("coder").await.unwrap(), 1500);
+        assert!(
+            tracker
+                .check_budgets(&ledger, "coder", Some("2"))
+                .await
+                .is_ok()
+        );
+        assert!(
+            tracker
+                .check_budgets(&ledger, "coder", Some("1"))
+                .await
+                .is_err()
+        );
+        tracker
+            .record(&ledger, "coder", Some("3"), "gpt-4", &usage(1500, 0))
+            .await
+            .unwrap();
+        assert!(
+            tracker
+                .check_budgets(&ledger, "coder", Some("3"))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            ledger.plugin_tokens_since_reset("coder").await.unwrap(),
+            1500
+        );
+        assert_eq!(
+            ledger.plugin_tokens_since_reset("reviewer").await.unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Price table for inference cost estimates.
+
+use crate::inference::Usage;
+use crate::inference::registry::matches_model_pattern;
+use crate::infrastructure::config::PriceSettings;
+
+/// Tokens per unit of a per-million price.
+const TOKENS_PER_MILLION: f64 = 1_000_000.0;
+
+/// Price of the models matching a pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelPrice {
+    /// Model name pattern; `*` matches any sequence of characters
+    pub pattern: String,
+    /// Price in USD per million prompt tokens
+    pub prompt_per_million: f64,
+    /// Price in USD per million completion tokens
+    pub completion_per_million: f64,
+}
+
+impl ModelPrice {
+    /// Creates a price for the models matching `pattern`
+    #[must_use]
+    pub fn new(
+        pattern: impl Into<String>,
+        prompt_per_million: f64,
+        completion_per_million: f64,
+    ) -> Self {
+        Self {
+            pattern: pattern.into(),
+            prompt_per_million,
+            completion_per_million,
+        }
+    }
+
+    /// Returns the cost in USD of the given usage at this price
+    #[must_use]
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (f64::from(usage.prompt_tokens) * self.prompt_per_million
+            + f64::from(usage.completion_tokens) * self.completion_per_million)
+            / TOKENS_PER_MILLION
+    }
+}
+
+impl From<&PriceSettings> for ModelPrice {
+    fn from(settings: &PriceSettings) -> Self {
+        Self::new(
+            settings.model.clone(),
+            settings.prompt_per_million,
+            settings.completion_per_million,
+        )
+    }
+}
+
+/// Prices of models, looked up by first matching pattern.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceTable {
+    prices: Vec<ModelPrice>,
+}
+
+impl PriceTable {
+    /// Creates an empty price table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a price, matched after the prices already added
+    #[must_use]
+    pub fn with_price(mut self, price: ModelPrice) -> Self {
+        self.prices.push(price);
+        self
+    }
+
+    /// Returns the price of a model, if any pattern matches it
+    #[must_use]
+    pub fn price_for(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices
+            .iter()
+            .find(|price| matches_model_pattern(&price.pattern, model))
+    }
+
+    /// Returns the estimated cost in USD of the given usage.
+    ///
+    /// Models without a price are free.
+    #[must_use]
+    pub fn cost(&self, model: &str, usage: &Usage) -> f64 {
+        self.price_for(model).map_or(0.0, |price| price.cost(usage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt_tokens: u32, completion_tokens: u32) -> Usage {
+        Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_price_wins() {
+        let table = PriceTable::new()
+            .with_price(ModelPrice::new("gpt-4o-mini*", 0.15, 0.6))
+            .with_price(ModelPrice::new("gpt-4o*", 2.5, 10.0));
+
+        let cost = table.cost("gpt-4o-2024-08-06", &usage(1_000_000, 100_000));
+        assert!((cost - 3.5).abs() < 1e-9);
+
+        let cost = table.cost("gpt-4o-mini", &usage(2_000_000, 0));
+        assert!((cost - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unpriced_models_are_free() {
+        let table = PriceTable::new().with_price(ModelPrice::new("claude-*", 3.0, 15.0));
+        assert!(table.price_for("llama3.1:8b").is_none());
+        assert!(table.cost("llama3.1:8b", &usage(500, 500)).abs() < f64::EPSILON);
+    }
+}
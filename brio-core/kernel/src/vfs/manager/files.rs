@@ -0,0 +1,326 @@
+//! File operations inside a session's working directory.
+//!
+//! Paths are interpreted relative to the session root. Absolute paths and
+//! `..` components are rejected up front, and every resolved target is
+//! checked against a sandbox policy scoped to the session root, so symlinks
+//! inside the session cannot be used to reach the rest of the file system.
+//! Writes never go through a symlink.
+
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+
+use super::session::SessionManager;
+use super::types::SessionError;
+use crate::vfs::policy::SandboxPolicy;
+
+/// Largest file, in bytes, that [`SessionFiles::read_file`] returns whole.
+pub const MAX_READ_BYTES: u64 = 10 * 1024 * 1024;
+
+/// An entry of a session directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryEntry {
+    /// File name of the entry.
+    pub name: String,
+    /// Whether the entry is a directory.
+    pub is_directory: bool,
+    /// Size of the entry in bytes.
+    pub size: u64,
+}
+
+/// Handle for reading and writing files within one session.
+///
+/// The handle only holds the session root, so file I/O does not keep the
+/// [`SessionManager`] locked.
+#[derive(Debug, Clone)]
+pub struct SessionFiles {
+    root: PathBuf,
+}
+
+impl SessionManager {
+    /// Returns a handle for file operations inside the given session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session does not exist.
+    pub fn files(&self, session_id: &str) -> Result<SessionFiles, SessionError> {
+        self.session_path(session_id)
+            .map(|root| SessionFiles { root })
+            .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))
+    }
+}
+
+impl SessionFiles {
+    /// Returns the session's working directory.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Reads a whole file as UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path escapes the session, the file is larger
+    /// than [`MAX_READ_BYTES`], or it cannot be read as UTF-8.
+    pub fn read_file(&self, path: &str) -> Result<String, SessionError> {
+        let target = self.resolve_existing(path)?;
+        let size = std::fs::metadata(&target)
+            .map_err(|e| io_error(&target, e))?
+            .len();
+        if size > MAX_READ_BYTES {
+            return Err(SessionError::FileTooLarge {
+                path: target,
+                size,
+                limit: MAX_READ_BYTES,
+            });
+        }
+        std::fs::read_to_string(&target).map_err(|e| io_error(&target, e))
+    }
+
+    /// Reads the lines `start_line..=end_line` of a file, 1-indexed.
+    ///
+    /// A range extending past the end of the file returns the lines that exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is invalid, the path escapes the session,
+    /// or the file cannot be read as UTF-8.
+    pub fn read_file_range(
+        &self,
+        path: &str,
+        start_line: u32,
+        end_line: u32,
+    ) -> Result<String, SessionError> {
+        if start_line == 0 || end_line < start_line {
+            return Err(SessionError::InvalidLineRange {
+                start: start_line,
+                end: end_line,
+            });
+        }
+
+        let target = self.resolve_existing(path)?;
+        let file = std::fs::File::open(&target).map_err(|e| io_error(&target, e))?;
+        let lines = BufReader::new(file)
+            .lines()
+            .skip(start_line as usize - 1)
+            .take((end_line - start_line) as usize + 1)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| io_error(&target, e))?;
+        Ok(lines.join("\n"))
+    }
+
+    /// Writes a file, creating missing parent directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path escapes the session or the file cannot be
+    /// written.
+    pub fn write_file(&self, path: &str, content: &str) -> Result<(), SessionError> {
+        let target = self.resolve(path)?;
+        if target == self.root {
+            return Err(SessionError::PolicyViolation(
+                "cannot write to the session root".to_string(),
+            ));
+        }
+
+        // Validate the deepest existing ancestor before creating anything, so
+        // a symlinked directory cannot redirect `create_dir_all`.
+        let policy = self.policy()?;
+        let existing = target
+            .ancestors()
+            .find(|p| p.exists())
+            .unwrap_or(&self.root);
+        policy
+            .validate_path(existing)
+            .map_err(|e| SessionError::PolicyViolation(e.to_string()))?;
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+            policy
+                .validate_path(parent)
+                .map_err(|e| SessionError::PolicyViolation(e.to_string()))?;
+        }
+        // `write` follows symlinks, including dangling ones that `exists`
+        // reports as missing, so the target itself must not be a link.
+        match std::fs::symlink_metadata(&target) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(SessionError::PolicyViolation(format!(
+                    "cannot write through symlink '{path}'"
+                )));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(io_error(&target, e)),
+        }
+        std::fs::write(&target, content).map_err(|e| io_error(&target, e))
+    }
+
+    /// Lists a directory, sorted by name. Symlinks are reported as themselves
+    /// and not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path escapes the session or is not a readable
+    /// directory.
+    pub fn list_directory(&self, path: &str) -> Result<Vec<DirectoryEntry>, SessionError> {
+        let target = self.resolve_existing(path)?;
+        let read_dir = std::fs::read_dir(&target)
+            .map_err(|e| SessionError::ReadDirectoryFailed(format!("{}: {e}", target.display())))?;
+
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry.map_err(|e| SessionError::ReadDirectoryFailed(e.to_string()))?;
+            let metadata = entry.metadata().map_err(|e| io_error(&entry.path(), e))?;
+            entries.push(DirectoryEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                is_directory: metadata.is_dir(),
+                size: metadata.len(),
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Joins a relative path onto the session root, rejecting absolute paths
+    /// and parent components.
+    fn resolve(&self, path: &str) -> Result<PathBuf, SessionError> {
+        let relative = Path::new(path);
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(SessionError::PolicyViolation(format!(
+                "path '{path}' must be relative to the session root and must not contain '..'"
+            )));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Resolves a path that must already exist, following symlinks to check
+    /// that it stays inside the session.
+    fn resolve_existing(&self, path: &str) -> Result<PathBuf, SessionError> {
+        let target = self.resolve(path)?;
+        if !target.exists() {
+            return Err(io_error(
+                &target,
+                std::io::Error::from(std::io::ErrorKind::NotFound),
+            ));
+        }
+        self.policy()?
+            .validate_path(&target)
+            .map_err(|e| SessionError::PolicyViolation(e.to_string()))?;
+        Ok(target)
+    }
+
+    fn policy(&self) -> Result<SandboxPolicy, SessionError> {
+        SandboxPolicy::for_root(&self.root)
+            .map_err(|_| SessionError::SessionDirectoryLost(self.root.clone()))
+    }
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> SessionError {
+    SessionError::FileOperationFailed {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn files() -> (tempfile::TempDir, SessionFiles) {
+        let dir = tempdir().unwrap();
+        let files = SessionFiles {
+            root: dir.path().to_path_buf(),
+        };
+        (dir, files)
+    }
+
+    #[test]
+    fn writes_and_reads_nested_files() {
+        let (_dir, files) = files();
+        files.write_file("src/lib.rs", "one\ntwo\nthree\n").unwrap();
+
+        assert_eq!(files.read_file("src/lib.rs").unwrap(), "one\ntwo\nthree\n");
+        assert_eq!(
+            files.read_file_range("src/lib.rs", 2, 3).unwrap(),
+            "two\nthree"
+        );
+        assert_eq!(files.read_file_range("src/lib.rs", 3, 10).unwrap(), "three");
+
+        let listing = files.list_directory(".").unwrap();
+        assert_eq!(
+            listing,
+            vec![DirectoryEntry {
+                name: "src".to_string(),
+                is_directory: true,
+                size: listing[0].size,
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_line_ranges() {
+        let (_dir, files) = files();
+        files.write_file("a.txt", "x").unwrap();
+        assert!(matches!(
+            files.read_file_range("a.txt", 0, 1),
+            Err(SessionError::InvalidLineRange { .. })
+        ));
+        assert!(matches!(
+            files.read_file_range("a.txt", 3, 2),
+            Err(SessionError::InvalidLineRange { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_session() {
+        let (_dir, files) = files();
+        for path in ["../escape.txt", "/etc/passwd", "a/../../b"] {
+            assert!(matches!(
+                files.write_file(path, "x"),
+                Err(SessionError::PolicyViolation(_))
+            ));
+            assert!(matches!(
+                files.read_file(path),
+                Err(SessionError::PolicyViolation(_))
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leaving_the_session() {
+        let outside = tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        let (_dir, files) = files();
+        std::os::unix::fs::symlink(outside.path(), files.root().join("link")).unwrap();
+
+        assert!(matches!(
+            files.read_file("link/secret.txt"),
+            Err(SessionError::PolicyViolation(_))
+        ));
+        assert!(matches!(
+            files.write_file("link/new.txt", "x"),
+            Err(SessionError::PolicyViolation(_))
+        ));
+        assert!(!outside.path().join("new.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_writes_through_dangling_symlinks() {
+        let outside = tempdir().unwrap();
+        let escape = outside.path().join("escape.txt");
+        let (_dir, files) = files();
+        std::os::unix::fs::symlink(&escape, files.root().join("dangling.txt")).unwrap();
+
+        assert!(matches!(
+            files.write_file("dangling.txt", "x"),
+            Err(SessionError::PolicyViolation(_))
+        ));
+        assert!(!escape.exists());
+    }
+}
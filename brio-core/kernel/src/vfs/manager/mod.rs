@@ -3,11 +3,13 @@
 //! This module manages temporary working directories for agents, providing
 //! copy-on-write isolation through reflinks and atomic commit/rollback semantics.
 
+pub mod files;
 pub mod isolation;
 pub mod session;
 pub mod types;
 
 // Re-export primary types for convenience
+pub use files::{DirectoryEntry, SessionFiles};
 pub use isolation::IsolationOps;
 pub use session::SessionManager;
-pub use types::SessionError;
+pub use types::{SessionChange, SessionError};
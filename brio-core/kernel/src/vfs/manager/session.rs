@@ -4,13 +4,15 @@
 //! copy-on-write isolation through reflinks and atomic commit/rollback semantics.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{info, instrument};
 use uuid::Uuid;
 
+use super::files::MAX_READ_BYTES;
 use super::isolation::IsolationOps;
-use super::types::{SessionError, SessionInfo};
+use super::types::{SessionChange, SessionError, SessionInfo};
 use crate::infrastructure::config::SandboxSettings;
+use crate::vfs::diff::{FileChange, compute_diff};
 use crate::vfs::policy::SandboxPolicy;
 
 /// Manages isolated file system sessions for agents.
@@ -179,6 +181,49 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Lists the files that differ between a session and its base directory,
+    /// ordered by path.
+    ///
+    /// File contents are included when they are UTF-8 text of at most
+    /// [`MAX_READ_BYTES`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session is not found or the directories
+    /// cannot be compared.
+    pub fn changes(&self, session_id: &str) -> Result<Vec<SessionChange>, SessionError> {
+        let session_info = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| SessionError::SessionNotFound(session_id.to_string()))?;
+        let base_path = session_info.base_path.as_path();
+        let session_path = self.root_temp_dir.join(session_id);
+
+        let mut changes = compute_diff(&session_path, base_path)
+            .map_err(|e| SessionError::DiffFailed(e.to_string()))?;
+        changes.sort_by(|a, b| change_path(a).cmp(change_path(b)));
+
+        Ok(changes
+            .into_iter()
+            .map(|change| {
+                let path = change_path(&change);
+                let before = match change {
+                    FileChange::Added(_) => None,
+                    _ => read_text(&base_path.join(path)),
+                };
+                let after = match change {
+                    FileChange::Deleted(_) => None,
+                    _ => read_text(&session_path.join(path)),
+                };
+                SessionChange {
+                    change,
+                    before,
+                    after,
+                }
+            })
+            .collect())
+    }
+
     /// Returns the number of active sessions.
     #[must_use]
     pub fn active_session_count(&self) -> usize {
@@ -215,6 +260,21 @@ impl SessionManager {
     }
 }
 
+fn change_path(change: &FileChange) -> &Path {
+    match change {
+        FileChange::Modified(path) | FileChange::Added(path) | FileChange::Deleted(path) => path,
+    }
+}
+
+/// Reads a file as UTF-8 text, or `None` if it is too large or not text.
+fn read_text(path: &Path) -> Option<String> {
+    let size = std::fs::metadata(path).ok()?.len();
+    if size > MAX_READ_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
 impl Default for SessionManager {
     fn default() -> Self {
         Self {
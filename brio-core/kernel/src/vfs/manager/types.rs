@@ -59,6 +59,44 @@ pub enum SessionError {
     /// Failed to read directory contents.
     #[error("Failed to read directory: {0}")]
     ReadDirectoryFailed(String),
+    /// A file operation inside a session failed.
+    #[error("File operation on '{path}' failed: {source}")]
+    FileOperationFailed {
+        /// Path the operation was applied to.
+        path: PathBuf,
+        /// Source error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A file is too large to be read in one piece.
+    #[error("File '{path}' is {size} bytes, larger than the {limit} byte limit")]
+    FileTooLarge {
+        /// Path of the file.
+        path: PathBuf,
+        /// Size of the file in bytes.
+        size: u64,
+        /// Maximum readable size in bytes.
+        limit: u64,
+    },
+    /// A requested line range is empty or not 1-indexed.
+    #[error("Invalid line range {start}..={end}: lines are 1-indexed and inclusive")]
+    InvalidLineRange {
+        /// First requested line.
+        start: u32,
+        /// Last requested line.
+        end: u32,
+    },
+}
+
+/// A file that differs between a session and its base directory.
+#[derive(Debug, Clone)]
+pub struct SessionChange {
+    /// The change, with the path relative to the session root.
+    pub change: crate::vfs::diff::FileChange,
+    /// Contents in the base directory, if the file exists there and is UTF-8 text.
+    pub before: Option<String>,
+    /// Contents in the session, if the file exists there and is UTF-8 text.
+    pub after: Option<String>,
 }
 
 /// Represents a session with its base path and snapshot hash.
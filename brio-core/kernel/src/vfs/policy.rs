@@ -52,6 +52,21 @@ impl SandboxPolicy {
         Ok(Self { allowed_paths })
     }
 
+    /// Creates a policy confining access to a single root directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root cannot be canonicalized.
+    pub fn for_root(root: &Path) -> Result<Self, PolicyError> {
+        let canonical = dunce::canonicalize(root).map_err(|e| PolicyError::InvalidConfig {
+            path: root.to_path_buf(),
+            source: e,
+        })?;
+        Ok(Self {
+            allowed_paths: vec![canonical],
+        })
+    }
+
     /// Creates an empty policy that allows everything.
     pub fn new_empty() -> Self {
         Self {
@@ -110,6 +125,7 @@ mod tests {
                     .ok_or_else(|| anyhow::anyhow!("Invalid path"))?
                     .to_string(),
             ],
+            ..Default::default()
         };
 
         let policy = SandboxPolicy::new(&settings).map_err(|e| anyhow::anyhow!(e))?;
@@ -1,3 +1,4 @@
+use super::diff::FileChange;
 use super::manager::{SessionError, SessionManager};
 use crate::infrastructure::config::SandboxSettings;
 use std::fs;
@@ -125,6 +126,65 @@ fn test_rollback_after_partial_changes() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_changes_lists_session_edits_with_contents() -> anyhow::Result<()> {
+    let temp_dir = tempdir()?;
+    let base_dir = temp_dir.path().join("base");
+    fs::create_dir(&base_dir)?;
+    fs::write(base_dir.join("edited.txt"), "original")?;
+    fs::write(base_dir.join("removed.txt"), "gone")?;
+    fs::write(base_dir.join("untouched.txt"), "same")?;
+
+    let mut manager = SessionManager::new(&SandboxSettings::default())?;
+    let session_id = manager.begin_session(&base_dir.to_string_lossy())?;
+    let session_path = manager
+        .session_path(&session_id)
+        .ok_or(anyhow::anyhow!("session path not found"))?;
+
+    fs::write(session_path.join("edited.txt"), "modified")?;
+    fs::write(session_path.join("added.txt"), "created")?;
+    fs::remove_file(session_path.join("removed.txt"))?;
+
+    let changes = manager.changes(&session_id)?;
+    let summary: Vec<_> = changes
+        .iter()
+        .map(|c| {
+            let (kind, path) = match &c.change {
+                FileChange::Added(p) => ("added", p),
+                FileChange::Modified(p) => ("modified", p),
+                FileChange::Deleted(p) => ("deleted", p),
+            };
+            (
+                kind,
+                path.to_string_lossy().into_owned(),
+                c.before.as_deref(),
+                c.after.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("added", "added.txt".to_string(), None, Some("created")),
+            (
+                "modified",
+                "edited.txt".to_string(),
+                Some("original"),
+                Some("modified")
+            ),
+            ("deleted", "removed.txt".to_string(), Some("gone"), None),
+        ]
+    );
+
+    manager.rollback_session(&session_id)?;
+    assert!(matches!(
+        manager.changes(&session_id),
+        Err(SessionError::SessionNotFound(_))
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_session_lifecycle() -> anyhow::Result<()> {
     let temp_dir = std::env::temp_dir().join("brio_tests");
@@ -190,6 +250,7 @@ fn test_begin_session_sandbox_violation() -> anyhow::Result<()> {
 
     let sandbox = SandboxSettings {
         allowed_paths: vec![allowed_path.to_string_lossy().to_string()],
+        ..Default::default()
     };
     let mut manager = SessionManager::new(&sandbox).map_err(|e| anyhow::anyhow!(e))?;
 
@@ -211,6 +272,7 @@ fn test_begin_session_sandbox_ok() -> anyhow::Result<()> {
 
     let sandbox = SandboxSettings {
         allowed_paths: vec![allowed_path.to_string_lossy().to_string()],
+        ..Default::default()
     };
     let mut manager = SessionManager::new(&sandbox).map_err(|e| anyhow::anyhow!(e))?;
 
@@ -8,6 +8,6 @@ pub mod types;
 pub use broadcaster::Broadcaster;
 pub use types::{
     BranchEvent, BranchResultSummary, BroadcastMessage, ClientId, ClientMessage, ClientResponse,
-    ConflictSummary, FileChangeSummary, MergeRequestEvent, ProgressUpdate, ResponseStatus,
-    SessionAction, SessionParams, WsError, WsMessage, WsPatch,
+    ConflictSummary, FileChangeSummary, InferenceDelta, MergeRequestEvent, ProgressUpdate,
+    ResponseStatus, SessionAction, SessionParams, WsError, WsMessage, WsPatch,
 };
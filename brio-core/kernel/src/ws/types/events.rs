@@ -240,6 +240,8 @@ pub enum WsMessage {
     MergeRequestEvent(super::merge::MergeRequestEvent),
     /// Progress update for long-running operations
     ProgressUpdate(super::metrics::ProgressUpdate),
+    /// Text streamed from an agent's chat completion
+    InferenceDelta(super::inference::InferenceDelta),
 }
 
 /// Client message types received from WebSocket clients
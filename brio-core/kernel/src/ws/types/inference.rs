@@ -0,0 +1,86 @@
+//! Inference streaming types for WebSocket broadcasting.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::inference::Usage;
+
+/// A piece of text streamed from an agent's chat completion.
+///
+/// Clients concatenate the deltas of a stream to show the completion as it
+/// is generated. The last event of a stream has `done` set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceDelta {
+    /// Identifier of the stream, unique within the kernel.
+    ///
+    /// This is not the resource handle the guest holds, which is only
+    /// unique within the guest's store.
+    stream_id: u64,
+    /// Plugin or agent that opened the stream, if known.
+    agent_id: Option<String>,
+    /// Text generated since the previous delta.
+    delta: String,
+    /// Token usage, set on the final delta if the provider reports it.
+    usage: Option<Usage>,
+    /// Whether the stream has finished.
+    done: bool,
+    /// Timestamp of the delta.
+    timestamp: DateTime<Utc>,
+}
+
+impl InferenceDelta {
+    /// Creates a new inference delta.
+    #[must_use]
+    pub fn new(
+        stream_id: u64,
+        agent_id: Option<String>,
+        delta: String,
+        usage: Option<Usage>,
+        done: bool,
+    ) -> Self {
+        Self {
+            stream_id,
+            agent_id,
+            delta,
+            usage,
+            done,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Returns the stream ID.
+    #[must_use]
+    pub fn stream_id(&self) -> u64 {
+        self.stream_id
+    }
+
+    /// Returns the agent that opened the stream, if known.
+    #[must_use]
+    pub fn agent_id(&self) -> Option<&str> {
+        self.agent_id.as_deref()
+    }
+
+    /// Returns the generated text.
+    #[must_use]
+    pub fn delta(&self) -> &str {
+        &self.delta
+    }
+
+    /// Returns the token usage, if reported.
+    #[must_use]
+    pub fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+
+    /// Returns true if this is the last delta of the stream.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Returns the timestamp of the delta.
+    #[must_use]
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod branch;
 pub mod events;
+pub mod inference;
 pub mod merge;
 pub mod metrics;
 
@@ -11,5 +12,6 @@ pub use events::{
     EventMetadata, ExecutionStrategy, MergeStrategy, OperationType, ResponseStatus, SessionAction,
     SessionParams, WsError, WsMessage, WsPatch,
 };
+pub use inference::InferenceDelta;
 pub use merge::MergeRequestEvent;
 pub use metrics::{ProgressUpdate, ProgressUpdateError};
@@ -0,0 +1,217 @@
+//! HTTP mock tests for the Anthropic provider.
+//!
+//! Uses wiremock to simulate streaming responses from the Anthropic API.
+
+use brio_kernel::inference::{
+    AnthropicConfig, AnthropicProvider, ChatRequest, InferenceError, LLMProvider, Message,
+    RequestOptions, ResponseFormat, Role,
+};
+use futures_util::StreamExt;
+use reqwest::Url;
+use secrecy::SecretString;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn create_provider_with_mock_server(server: &MockServer) -> AnthropicProvider {
+    let config = AnthropicConfig::new(
+        SecretString::new("test-api-key".into()),
+        Url::parse(&format!("{}/", server.uri())).unwrap(),
+    )
+    .with_max_retries(0); // Disable retries for faster tests
+    AnthropicProvider::new(config)
+}
+
+fn create_test_request() -> ChatRequest {
+    ChatRequest {
+        model: "claude-test".to_string(),
+        messages: vec![
+            Message {
+                role: Role::System,
+                content: "Be brief".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
+            },
+            Message {
+                role: Role::User,
+                content: "Hello".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
+            },
+        ],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
+    }
+}
+
+fn sse(event: &str, data: &str) -> String {
+    format!("event: {event}\ndata: {data}\n\n")
+}
+
+#[tokio::test]
+async fn test_stream_yields_deltas_and_final_usage() {
+    let server = MockServer::start().await;
+
+    let body = [
+        sse(
+            "message_start",
+            r#"{"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":9,"output_tokens":1}}}"#,
+        ),
+        sse(
+            "content_block_start",
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+        ),
+        sse("ping", r#"{"type":"ping"}"#),
+        sse(
+            "content_block_delta",
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hel"}}"#,
+        ),
+        sse(
+            "content_block_delta",
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"lo"}}"#,
+        ),
+        sse(
+            "content_block_stop",
+            r#"{"type":"content_block_stop","index":0}"#,
+        ),
+        sse(
+            "message_delta",
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":3}}"#,
+        ),
+        sse("message_stop", r#"{"type":"message_stop"}"#),
+    ]
+    .concat();
+
+    Mock::given(method("POST"))
+        .and(path("/messages"))
+        .and(body_partial_json(serde_json::json!({
+            "stream": true,
+            "system": "Be brief"
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(body),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let mut stream = provider.chat_stream(create_test_request()).await.unwrap();
+
+    let mut deltas = Vec::new();
+    let mut last = None;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        deltas.push(chunk.delta.clone());
+        last = Some(chunk);
+    }
+
+    assert_eq!(deltas.concat(), "Hello");
+    let last = last.unwrap();
+    assert_eq!(last.finish_reason.as_deref(), Some("end_turn"));
+    let usage = last.usage.unwrap();
+    assert_eq!(usage.prompt_tokens, 9);
+    assert_eq!(usage.completion_tokens, 3);
+    assert_eq!(usage.total_tokens, 12);
+}
+
+#[tokio::test]
+async fn test_stream_error_event_ends_stream() {
+    let server = MockServer::start().await;
+
+    let body = [
+        sse(
+            "content_block_delta",
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#,
+        ),
+        sse(
+            "error",
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        ),
+    ]
+    .concat();
+
+    Mock::given(method("POST"))
+        .and(path("/messages"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(body),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let mut stream = provider.chat_stream(create_test_request()).await.unwrap();
+
+    assert_eq!(stream.next().await.unwrap().unwrap().delta, "Hi");
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(InferenceError::RateLimit))
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_stream_open_maps_overloaded_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/messages"))
+        .respond_with(ResponseTemplate::new(529).set_body_string("Overloaded"))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let result = provider.chat_stream(create_test_request()).await;
+
+    assert!(matches!(result, Err(InferenceError::RateLimit)));
+}
+
+#[tokio::test]
+async fn test_request_options_are_mapped() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/messages"))
+        .and(body_partial_json(serde_json::json!({
+            "max_tokens": 256,
+            "temperature": 0.0,
+            "stop_sequences": ["END"]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"content": [{"type": "text", "text": "{}"}], "usage": {"input_tokens": 3, "output_tokens": 1}}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let request = create_test_request().with_options(
+        RequestOptions::new()
+            .with_temperature(0.0)
+            .with_max_tokens(256)
+            .with_stop(vec!["END".to_string()])
+            .with_response_format(ResponseFormat::Json),
+    );
+
+    let response = provider.chat(request).await.unwrap();
+    assert_eq!(response.content, "{}");
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    let system = body["system"].as_str().unwrap();
+    assert!(system.starts_with("Be brief"));
+    assert!(system.contains("JSON"));
+}
+
+#[tokio::test]
+async fn test_unsupported_seed_is_rejected() {
+    let server = MockServer::start().await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let request = create_test_request().with_options(RequestOptions::new().with_seed(1));
+
+    let result = provider.chat(request).await;
+    assert!(matches!(result, Err(InferenceError::InvalidRequest(_))));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
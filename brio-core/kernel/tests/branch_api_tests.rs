@@ -0,0 +1,469 @@
+//! Tests for the branch REST API and branch execution.
+
+use anyhow::{Result, anyhow};
+use brio_kernel::api::api_router;
+use brio_kernel::branch_manager::BranchId;
+use brio_kernel::host::BrioHostState;
+use brio_kernel::inference::{ChatRequest, ChatResponse, InferenceError, LLMProvider};
+use brio_kernel::infrastructure::config::SandboxSettings;
+use brio_kernel::mesh::{MeshMessage, Payload};
+use brio_kernel::ws::{BranchEvent, BroadcastMessage, WsMessage, broadcaster::BroadcastReceiver};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+// =============================================================================
+// Test Helpers
+// =============================================================================
+
+struct MockProvider;
+
+#[async_trait::async_trait]
+impl LLMProvider for MockProvider {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        Ok(ChatResponse {
+            content: "Mock response".to_string(),
+            usage: None,
+            tool_calls: Vec::new(),
+        })
+    }
+}
+
+struct TestServer {
+    host: Arc<BrioHostState>,
+    base_url: String,
+    client: reqwest::Client,
+    workspace: TempDir,
+}
+
+impl TestServer {
+    async fn start() -> Result<Self> {
+        let workspace = TempDir::new()?;
+        std::fs::write(workspace.path().join("README.md"), "hello")?;
+
+        let registry = brio_kernel::inference::ProviderRegistry::new();
+        registry.register_arc("default", Arc::new(MockProvider));
+        registry.set_default("default");
+        let sandbox = SandboxSettings {
+            allowed_paths: vec![workspace.path().to_string_lossy().into_owned()],
+            ..Default::default()
+        };
+        let host = Arc::new(BrioHostState::new("sqlite::memory:", registry, None, sandbox).await?);
+
+        let app = api_router().with_state(host.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        Ok(Self {
+            host,
+            base_url: format!("http://{addr}"),
+            client: reqwest::Client::new(),
+            workspace,
+        })
+    }
+
+    /// Registers a mock agent that records its task context and replies with `reply`.
+    fn register_agent(
+        &self,
+        id: &str,
+        reply: Result<&'static str, &'static str>,
+    ) -> mpsc::UnboundedReceiver<Value> {
+        let (tx, mut rx) = mpsc::channel::<MeshMessage>(10);
+        let (seen_tx, seen_rx) = mpsc::unbounded_channel();
+        self.host.register_component(id.to_string(), tx);
+
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let Payload::Json(context) = &msg.payload {
+                    let _ = seen_tx.send(serde_json::from_str::<Value>(context).unwrap());
+                }
+                let response = reply
+                    .map(|r| Payload::Json(Box::new(r.to_string())))
+                    .map_err(ToString::to_string);
+                let _ = msg.reply_tx.send(response);
+            }
+        });
+
+        seen_rx
+    }
+
+    async fn create_branch(&self, name: &str, agents: Value) -> Result<Value> {
+        let response = self
+            .client
+            .post(format!("{}/api/v1/branches", self.base_url))
+            .json(&json!({
+                "source": {
+                    "type": "base",
+                    "path": self.workspace.path().to_string_lossy(),
+                },
+                "config": {
+                    "name": name,
+                    "agents": agents,
+                },
+            }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+        Ok(response.json().await?)
+    }
+
+    async fn execute(&self, branch_id: &str, body: Value) -> Result<reqwest::Response> {
+        Ok(self
+            .client
+            .post(format!(
+                "{}/api/v1/branches/{branch_id}/execute",
+                self.base_url
+            ))
+            .json(&body)
+            .send()
+            .await?)
+    }
+
+    async fn active_sessions(&self) -> Result<usize> {
+        let sessions: Value = self
+            .client
+            .get(format!("{}/api/v1/sessions", self.base_url))
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(sessions["sessions"].as_array().map_or(0, Vec::len))
+    }
+
+    /// Requests a merge of a branch and returns the merge request id.
+    async fn request_merge(&self, branch_id: &str) -> Result<String> {
+        let merge: Value = self
+            .client
+            .post(format!(
+                "{}/api/v1/branches/{branch_id}/merge",
+                self.base_url
+            ))
+            .json(&json!({}))
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(merge["merge_request_id"].as_str().unwrap().to_string())
+    }
+
+    async fn approve(&self, merge_request_id: &str) -> Result<reqwest::Response> {
+        Ok(self
+            .client
+            .post(format!(
+                "{}/api/v1/merge-requests/{merge_request_id}/approve",
+                self.base_url
+            ))
+            .send()
+            .await?)
+    }
+
+    async fn get_branch(&self, branch_id: &str) -> Result<Value> {
+        Ok(self
+            .client
+            .get(format!("{}/api/v1/branches/{branch_id}", self.base_url))
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+}
+
+/// Waits for the terminal execution event of a branch.
+async fn wait_for_outcome(events: &mut BroadcastReceiver) -> Result<BranchEvent> {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let BroadcastMessage::Message(WsMessage::BranchEvent(event)) = events.recv().await? {
+                if matches!(
+                    event,
+                    BranchEvent::ExecutionCompleted { .. } | BranchEvent::ExecutionFailed { .. }
+                ) {
+                    return Ok(event);
+                }
+            }
+        }
+    })
+    .await
+    .map_err(|_| anyhow!("Timed out waiting for branch execution"))?
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_branch_routes_are_mounted() -> Result<()> {
+    let server = TestServer::start().await?;
+
+    let response = server
+        .client
+        .get(format!("{}/api/v1/branches", server.base_url))
+        .send()
+        .await?;
+    assert_eq!(response.status(), 200);
+    let branches: Vec<Value> = response.json().await?;
+    assert!(branches.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_branch_runs_agents() -> Result<()> {
+    let server = TestServer::start().await?;
+    let mut coder = server.register_agent("coder", Ok("patched"));
+    let mut events = server.host.broadcaster().subscribe();
+
+    let branch = server
+        .create_branch(
+            "feature",
+            json!([{ "agent_id": "coder", "task_override": "fix the bug" }]),
+        )
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+
+    let response = server.execute(branch_id, json!({})).await?;
+    assert_eq!(response.status(), 200);
+    let started: Value = response.json().await?;
+    assert_eq!(started["status"], "running");
+
+    let outcome = wait_for_outcome(&mut events).await?;
+    assert!(matches!(outcome, BranchEvent::ExecutionCompleted { .. }));
+
+    let context = coder.recv().await.unwrap();
+    assert_eq!(context["description"], "fix the bug");
+    assert_eq!(context["branch_id"], branch_id);
+    assert_eq!(context["session_id"], started["session_id"]);
+
+    let finished = server.get_branch(branch_id).await?;
+    assert_eq!(finished["status"], "completed");
+
+    let result = server
+        .host
+        .branch_manager()
+        .get_branch_result(&BranchId::new(branch_id.to_string())?)
+        .await?
+        .expect("branch result recorded");
+    assert_eq!(result.agent_results.len(), 1);
+    assert_eq!(result.agent_results[0].output, "patched");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_branch_applies_filter_and_task_override() -> Result<()> {
+    let server = TestServer::start().await?;
+    let mut coder = server.register_agent("coder", Ok("done"));
+    let mut reviewer = server.register_agent("reviewer", Ok("lgtm"));
+    let mut events = server.host.broadcaster().subscribe();
+
+    let branch = server
+        .create_branch(
+            "filtered",
+            json!([{ "agent_id": "coder" }, { "agent_id": "reviewer" }]),
+        )
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+
+    server
+        .execute(
+            branch_id,
+            json!({ "agents": ["reviewer"], "task_description": "review only" }),
+        )
+        .await?;
+    wait_for_outcome(&mut events).await?;
+
+    assert_eq!(reviewer.recv().await.unwrap()["description"], "review only");
+    assert!(coder.try_recv().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_branch_reports_agent_failure() -> Result<()> {
+    let server = TestServer::start().await?;
+    let _coder = server.register_agent("coder", Err("compile error"));
+    let mut events = server.host.broadcaster().subscribe();
+
+    let branch = server
+        .create_branch("broken", json!([{ "agent_id": "coder" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+
+    server.execute(branch_id, json!({})).await?;
+
+    match wait_for_outcome(&mut events).await? {
+        BranchEvent::ExecutionFailed {
+            failed_agent,
+            error,
+            ..
+        } => {
+            assert_eq!(failed_agent.as_deref(), Some("coder"));
+            assert!(error.contains("compile error"));
+        }
+        other => panic!("Expected ExecutionFailed, got {other:?}"),
+    }
+
+    let finished = server.get_branch(branch_id).await?;
+    assert_eq!(finished["status"], "failed");
+    assert_eq!(server.active_sessions().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_approved_merge_commits_branch_session() -> Result<()> {
+    let server = TestServer::start().await?;
+    let _coder = server.register_agent("coder", Ok("patched"));
+    let mut events = server.host.broadcaster().subscribe();
+
+    let branch = server
+        .create_branch("to-merge", json!([{ "agent_id": "coder" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+    let started: Value = server.execute(branch_id, json!({})).await?.json().await?;
+    wait_for_outcome(&mut events).await?;
+
+    // The completed branch keeps its session until the merge is decided
+    server
+        .host
+        .session_files(started["session_id"].as_str().unwrap())?
+        .write_file("CHANGES.md", "patched")?;
+
+    let merge_request_id = server.request_merge(branch_id).await?;
+    let response = server.approve(&merge_request_id).await?;
+    assert_eq!(response.status(), 200);
+
+    assert_eq!(
+        std::fs::read_to_string(server.workspace.path().join("CHANGES.md"))?,
+        "patched"
+    );
+    assert_eq!(server.active_sessions().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merge_of_running_branch_cannot_be_approved() -> Result<()> {
+    let server = TestServer::start().await?;
+    let (tx, _rx) = mpsc::channel::<MeshMessage>(10);
+    server.host.register_component("slow".to_string(), tx);
+
+    let branch = server
+        .create_branch("unfinished", json!([{ "agent_id": "slow" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+    let started: Value = server.execute(branch_id, json!({})).await?.json().await?;
+    server
+        .host
+        .session_files(started["session_id"].as_str().unwrap())?
+        .write_file("PARTIAL.md", "half done")?;
+
+    let merge_request_id = server.request_merge(branch_id).await?;
+    assert_eq!(server.approve(&merge_request_id).await?.status(), 409);
+    assert!(!server.workspace.path().join("PARTIAL.md").exists());
+    assert_eq!(server.active_sessions().await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merge_request_is_approved_once() -> Result<()> {
+    let server = TestServer::start().await?;
+    let _coder = server.register_agent("coder", Ok("patched"));
+    let mut events = server.host.broadcaster().subscribe();
+
+    let branch = server
+        .create_branch("approved-twice", json!([{ "agent_id": "coder" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+    server.execute(branch_id, json!({})).await?;
+    wait_for_outcome(&mut events).await?;
+
+    let merge_request_id = server.request_merge(branch_id).await?;
+    assert_eq!(server.approve(&merge_request_id).await?.status(), 200);
+    assert_eq!(server.approve(&merge_request_id).await?.status(), 409);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_abort_cancels_running_agents() -> Result<()> {
+    let server = TestServer::start().await?;
+    // The first agent holds on to its request until the test answers it
+    let (tx, mut slow_rx) = mpsc::channel::<MeshMessage>(10);
+    server.host.register_component("slow".to_string(), tx);
+    let mut next_seen = server.register_agent("next", Ok("done"));
+
+    let branch = server
+        .create_branch(
+            "to-abort",
+            json!([{ "agent_id": "slow", "priority": 1 }, { "agent_id": "next", "priority": 0 }]),
+        )
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+    assert_eq!(server.execute(branch_id, json!({})).await?.status(), 200);
+    let pending = tokio::time::timeout(Duration::from_secs(10), slow_rx.recv())
+        .await?
+        .expect("slow agent called");
+
+    let response = server
+        .client
+        .post(format!(
+            "{}/api/v1/branches/{branch_id}/abort",
+            server.base_url
+        ))
+        .send()
+        .await?;
+    assert_eq!(response.status(), 200);
+    let aborted: Value = response.json().await?;
+    assert_eq!(aborted["status"], "aborted");
+    assert_eq!(server.active_sessions().await?, 0);
+
+    // Nobody waits for the agent's reply any more, and the next agent never runs
+    assert!(
+        pending
+            .reply_tx
+            .send(Ok(Payload::Json(Box::new("late".to_string()))))
+            .is_err()
+    );
+    let next = tokio::time::timeout(Duration::from_millis(200), next_seen.recv()).await;
+    assert!(next.is_err(), "agent dispatched after abort");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_execute_running_branch_is_rejected() -> Result<()> {
+    let server = TestServer::start().await?;
+    // An agent that never answers keeps the branch running
+    let (tx, _rx) = mpsc::channel::<MeshMessage>(10);
+    server.host.register_component("slow".to_string(), tx);
+
+    let branch = server
+        .create_branch("busy", json!([{ "agent_id": "slow" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+
+    assert_eq!(server.execute(branch_id, json!({})).await?.status(), 200);
+    assert_eq!(server.execute(branch_id, json!({})).await?.status(), 409);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_executes_start_branch_once() -> Result<()> {
+    let server = TestServer::start().await?;
+    let (tx, _rx) = mpsc::channel::<MeshMessage>(10);
+    server.host.register_component("slow".to_string(), tx);
+
+    let branch = server
+        .create_branch("raced", json!([{ "agent_id": "slow" }]))
+        .await?;
+    let branch_id = branch["id"].as_str().unwrap();
+
+    let (first, second) = tokio::join!(
+        server.execute(branch_id, json!({})),
+        server.execute(branch_id, json!({}))
+    );
+    let mut statuses = [first?.status().as_u16(), second?.status().as_u16()];
+    statuses.sort_unstable();
+    assert_eq!(statuses, [200, 409]);
+    // The session of the losing caller is rolled back
+    assert_eq!(server.active_sessions().await?, 1);
+    Ok(())
+}
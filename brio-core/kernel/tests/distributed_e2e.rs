@@ -6,10 +6,13 @@
 use brio_kernel::host::{BrioHostState, MeshHandler};
 use brio_kernel::inference::ProviderRegistry;
 use brio_kernel::infrastructure::config::SandboxSettings;
-use brio_kernel::mesh::Payload;
+use brio_kernel::mesh::grpc::mesh_transport_client::MeshTransportClient;
+use brio_kernel::mesh::grpc::{PublishRequest, publish_request};
 use brio_kernel::mesh::service::MeshService;
 use brio_kernel::mesh::types::{NodeAddress, NodeId, NodeInfo};
+use brio_kernel::mesh::{Membership, MembershipConfig, NodeState, Payload};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -49,7 +52,8 @@ async fn spawn_node(id: &str, port: u16) -> (Arc<BrioHostState>, String) {
     // Spawn server
     let state_clone = state.clone();
     let addr = addr_str.parse().unwrap();
-    let service = MeshService::new(state_clone, node_id);
+    let service = MeshService::new(state_clone, node_id)
+        .with_address(NodeAddress::new(&addr_str).expect("valid address"));
 
     tokio::spawn(async move {
         tonic::transport::Server::builder()
@@ -113,3 +117,253 @@ async fn test_distributed_call() {
         Payload::Binary(_) => panic!("Unexpected payload type"),
     }
 }
+
+fn membership(node: &BrioHostState, id: &str, addr: &str, bootstrap: &[&str]) -> Membership {
+    let local = NodeInfo::new(
+        NodeId::try_from_str(id).expect("valid node id"),
+        NodeAddress::new(addr).expect("valid address"),
+        vec![],
+        0,
+    )
+    .expect("valid node info");
+    let config = MembershipConfig {
+        heartbeat_interval: Duration::from_secs(1),
+        suspect_after: Duration::from_millis(20),
+        dead_after: Duration::from_millis(50),
+        bootstrap_nodes: bootstrap
+            .iter()
+            .map(|addr| NodeAddress::new(addr).expect("valid address"))
+            .collect(),
+    };
+    let router = node.remote_router().expect("distributed mode").clone();
+    Membership::new(local, router, config)
+}
+
+fn member_ids(node: &BrioHostState) -> Vec<String> {
+    node.remote_router()
+        .expect("distributed mode")
+        .members()
+        .into_iter()
+        .map(|(info, _)| info.id().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_nodes_discover_each_other_through_bootstrap() {
+    let (node_a, addr_a) = spawn_node("node-a", 50057).await;
+    let (node_b, addr_b) = spawn_node("node-b", 50058).await;
+    let (node_c, addr_c) = spawn_node("node-c", 50059).await;
+
+    let membership_b = membership(&node_b, "node-b", &addr_b, &[&addr_a]);
+    assert!(membership_b.join().await);
+    assert_eq!(member_ids(&node_a), ["node-b"]);
+
+    // Node C learns about B from A's membership list
+    let membership_c = membership(&node_c, "node-c", &addr_c, &[&addr_a]);
+    assert!(membership_c.join().await);
+    assert_eq!(member_ids(&node_c), ["node-a", "node-b"]);
+
+    // Node B learns about C by pulling A's membership list
+    membership_b.tick().await;
+    assert_eq!(member_ids(&node_b), ["node-a", "node-c"]);
+}
+
+#[tokio::test]
+async fn test_unreachable_nodes_are_marked_dead() {
+    let (node_a, addr_a) = spawn_node("node-d", 50060).await;
+    let (_node_b, addr_b) = spawn_node("node-e", 50061).await;
+    let router = node_a.remote_router().expect("distributed mode");
+    let membership_a = membership(&node_a, "node-d", &addr_a, &[&addr_b]);
+    assert!(membership_a.join().await);
+
+    let gone = NodeId::try_from_str("node-gone").expect("valid node id");
+    node_a.register_remote_node(
+        NodeInfo::new(
+            gone.clone(),
+            NodeAddress::new("127.0.0.1:1").expect("valid address"),
+            vec![],
+            0,
+        )
+        .expect("valid node info"),
+    );
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    membership_a.tick().await;
+
+    let node_e = NodeId::try_from_str("node-e").expect("valid node id");
+    assert_eq!(router.node_state(&node_e), Some(NodeState::Alive));
+    assert_eq!(router.node_state(&gone), Some(NodeState::Dead));
+    assert!(
+        node_a
+            .mesh_call(
+                "node-gone/echo",
+                "ping",
+                Payload::Json(Box::new("hello".to_string())),
+            )
+            .await
+            .is_err()
+    );
+}
+
+fn spawn_echo(node: &BrioHostState, name: &'static str) {
+    let (tx, mut rx) = mpsc::channel(1);
+    node.register_component("echo".to_string(), tx);
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let reply = Payload::Json(Box::new(format!("echo from {name}")));
+            msg.reply_tx.send(Ok(reply)).unwrap();
+        }
+    });
+}
+
+#[tokio::test]
+async fn test_calls_are_routed_by_capability() {
+    let (node_a, addr_a) = spawn_node("node-f", 50062).await;
+    let (node_b, addr_b) = spawn_node("node-g", 50063).await;
+    spawn_echo(&node_b, "node-g");
+
+    let membership_a = membership(&node_a, "node-f", &addr_a, &[&addr_b]);
+    assert!(membership_a.join().await);
+
+    // An unreachable node advertising the component is tried first
+    let mut unreachable = NodeInfo::new(
+        NodeId::try_from_str("node-0").expect("valid node id"),
+        NodeAddress::new("127.0.0.1:1").expect("valid address"),
+        vec![],
+        0,
+    )
+    .expect("valid node info");
+    unreachable.add_capability("echo".to_string());
+    node_a.register_remote_node(unreachable);
+    let candidates: Vec<String> = node_a
+        .remote_router()
+        .expect("distributed mode")
+        .candidates("echo")
+        .into_iter()
+        .map(|candidate| candidate.node_id.to_string())
+        .collect();
+    assert_eq!(candidates, ["node-0", "node-g"]);
+
+    let response = node_a
+        .mesh_call("echo", "ping", Payload::Json(Box::new("hello".to_string())))
+        .await
+        .expect("Mesh call failed");
+    match response {
+        Payload::Json(s) => assert_eq!(*s, "echo from node-g"),
+        Payload::Binary(_) => panic!("Unexpected payload type"),
+    }
+
+    assert!(
+        node_a
+            .mesh_call(
+                "missing",
+                "ping",
+                Payload::Json(Box::new("hello".to_string()))
+            )
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_failed_remote_calls_are_not_retried_on_other_nodes() {
+    let (node_a, addr_a) = spawn_node("node-k", 50067).await;
+    let (node_b, addr_b) = spawn_node("node-l", 50068).await;
+    let (node_c, addr_c) = spawn_node("node-m", 50069).await;
+    let calls = Arc::new(AtomicUsize::new(0));
+    for node in [&node_b, &node_c] {
+        let (tx, mut rx) = mpsc::channel(1);
+        node.register_component("echo".to_string(), tx);
+        let calls = calls.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                calls.fetch_add(1, Ordering::SeqCst);
+                msg.reply_tx.send(Err("echo broke".to_string())).unwrap();
+            }
+        });
+    }
+
+    let membership_a = membership(&node_a, "node-k", &addr_a, &[&addr_b, &addr_c]);
+    assert!(membership_a.join().await);
+    assert_eq!(
+        node_a
+            .remote_router()
+            .expect("distributed mode")
+            .candidates("echo")
+            .len(),
+        2
+    );
+
+    // The call reached a node, so running it on the other one could run it twice
+    let error = node_a
+        .mesh_call("echo", "ping", Payload::Json(Box::new("hello".to_string())))
+        .await
+        .expect_err("the remote component failed");
+    assert!(error.to_string().contains("echo broke"));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+async fn logged_topics(node: &BrioHostState) -> Vec<String> {
+    node.event_delivery()
+        .log()
+        .topics()
+        .await
+        .expect("read event log")
+        .into_iter()
+        .map(|summary| summary.topic)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_events_reach_subscribers_on_other_nodes() {
+    let (node_a, addr_a) = spawn_node("node-h", 50064).await;
+    let (node_b, addr_b) = spawn_node("node-i", 50065).await;
+    let (node_c, addr_c) = spawn_node("node-j", 50066).await;
+    node_b
+        .event_bus()
+        .subscribe("proposal:*".to_string(), "foreman".to_string());
+
+    // Subscriptions are learned from heartbeats
+    let membership_a = membership(&node_a, "node-h", &addr_a, &[&addr_b, &addr_c]);
+    assert!(membership_a.join().await);
+    membership_a.tick().await;
+    let router = node_a.remote_router().expect("distributed mode");
+    let subscribers: Vec<String> = router
+        .subscribers("proposal:milestones")
+        .into_iter()
+        .map(|node| node.to_string())
+        .collect();
+    assert_eq!(subscribers, ["node-i"]);
+    assert!(router.subscribers("branch:created").is_empty());
+
+    let event = node_a
+        .publish_event(
+            "proposal:milestones",
+            Payload::Json(Box::new("{\"milestones\":[]}".to_string())),
+        )
+        .await
+        .expect("publish");
+    let mut delivered = false;
+    for _ in 0..50 {
+        if logged_topics(&node_b).await == ["proposal:milestones"] {
+            delivered = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(delivered, "event did not reach the subscribed node");
+    assert!(logged_topics(&node_c).await.is_empty());
+
+    // Retried deliveries of the same event are acknowledged but not handled again
+    let mut client = MeshTransportClient::connect(format!("http://{addr_b}"))
+        .await
+        .expect("connect to node-i");
+    let retry = PublishRequest {
+        node_id: "node-h".to_string(),
+        event_id: event.id.clone(),
+        topic: event.topic.clone(),
+        payload: Some(publish_request::Payload::Json("{}".to_string())),
+    };
+    let response = client.publish(retry).await.expect("publish retry");
+    assert!(response.into_inner().duplicate);
+}
@@ -1,9 +1,15 @@
 //! Tests for the WASM engine module.
 
 use anyhow::Result;
-use brio_kernel::engine::{WasmEngine, create_engine_config, create_linker};
+use brio_kernel::engine::limits::apply_limits;
+use brio_kernel::engine::{ResourceLimits, WasmEngine, create_engine_config, create_linker};
 use brio_kernel::host::BrioHostState;
-use brio_kernel::inference::{ChatRequest, ChatResponse, InferenceError, LLMProvider};
+use brio_kernel::inference::{
+    ChatChunk, ChatRequest, ChatResponse, ChatStream, InferenceError, LLMProvider,
+};
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // =============================================================================
 // Mock Provider
@@ -63,11 +69,31 @@ async fn wasm_engine_should_prepare_store_with_host_state() -> Result<()> {
 
     let host_state =
         BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
-    let _store = wasm_engine.prepare_store(host_state);
+    let _store = wasm_engine.prepare_store(host_state)?;
 
     Ok(())
 }
 
+#[tokio::test]
+async fn wasm_engine_should_prepare_store_without_fuel_consumption() -> Result<()> {
+    let mut config = create_engine_config();
+    config.consume_fuel(false);
+    let engine = wasmtime::Engine::new(&config)?;
+    let wasm_engine = WasmEngine::new(create_linker(&engine)?)?;
+
+    let host_state =
+        BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+    let mut store = wasm_engine.prepare_store(host_state)?;
+
+    // A fuel budget cannot be enforced without fuel consumption
+    let budget = ResourceLimits {
+        fuel: Some(1_000),
+        ..ResourceLimits::unlimited()
+    };
+    assert!(apply_limits(&mut store, &budget).is_err());
+    Ok(())
+}
+
 #[test]
 fn wasm_engine_should_provide_linker_access() {
     let config = create_engine_config();
@@ -106,7 +132,7 @@ async fn instantiate_should_succeed_for_empty_component() -> Result<()> {
 
     let host_state =
         BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
-    let mut store = wasm_engine.prepare_store(host_state);
+    let mut store = wasm_engine.prepare_store(host_state)?;
 
     // Should be able to instantiate
     let instance = wasm_engine
@@ -137,6 +163,214 @@ async fn engine_should_support_multiple_stores_concurrently() {
         .await
         .unwrap();
 
-    let _store1 = wasm_engine.prepare_store(host1);
-    let _store2 = wasm_engine.prepare_store(host2);
+    let _store1 = wasm_engine.prepare_store(host1).unwrap();
+    let _store2 = wasm_engine.prepare_store(host2).unwrap();
+}
+
+// =============================================================================
+// Planner Host Interface Tests
+// =============================================================================
+
+/// Component that calls `planner.decompose("Ship it")` and returns the step count.
+const PLANNER_WAT: &str = r#"(component
+    (import "brio:core/planner" (instance $planner
+        (type $subtask (record (field "id" string) (field "description" string)
+            (field "depends-on" (list string)) (field "outputs" (list string))
+            (field "capability" (option string))))
+        (export "subtask" (type $s (eq $subtask)))
+        (type $plan (record (field "steps" (list $s))))
+        (export "plan" (type $p (eq $plan)))
+        (export "decompose" (func (param "objective" string) (result (result $p (error string)))))
+    ))
+    (core module $mem
+        (memory (export "memory") 1)
+        (global $bump (mut i32) (i32.const 1024))
+        (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump
+                (i32.and (i32.add (i32.add (local.get $p) (local.get 3)) (i32.const 7)) (i32.const -8)))
+            (local.get $p))
+    )
+    (core instance $mi (instantiate $mem))
+    (core func $decompose (canon lower (func $planner "decompose") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (core module $m
+        (import "env" "memory" (memory 1))
+        (import "host" "decompose" (func $decompose (param i32 i32 i32)))
+        (data (i32.const 16) "Ship it")
+        (func (export "count-steps") (result i32)
+            (call $decompose (i32.const 16) (i32.const 7) (i32.const 64))
+            (if (result i32) (i32.load8_u (i32.const 64))
+                (then (i32.const -1))
+                (else (i32.load (i32.const 72)))))
+    )
+    (core instance $i (instantiate $m
+        (with "env" (instance $mi))
+        (with "host" (instance (export "decompose" (func $decompose))))))
+    (func (export "count-steps") (result s32) (canon lift (core func $i "count-steps")))
+)"#;
+
+struct PlanProvider;
+
+#[async_trait::async_trait]
+impl LLMProvider for PlanProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        assert!(request.messages[0].content.contains("Ship it"));
+        Ok(ChatResponse::new(
+            r#"{"steps": [{"id": "build", "description": "Build"}, {"id": "tag", "description": "Tag"}]}"#,
+        ))
+    }
+}
+
+async fn count_planned_steps(permissions: Vec<String>) -> Result<i32> {
+    let engine = wasmtime::Engine::new(&create_engine_config())?;
+    let wasm_engine = WasmEngine::new(create_linker(&engine)?)?;
+    let component = wasmtime::component::Component::new(&engine, PLANNER_WAT)?;
+
+    let host_state = BrioHostState::with_provider("sqlite::memory:", Box::new(PlanProvider))
+        .await?
+        .with_plugin_context("planner-test".to_string(), permissions);
+    let mut store = wasm_engine.prepare_store(host_state)?;
+    let instance = wasm_engine
+        .linker()
+        .instantiate_async(&mut store, &component)
+        .await?;
+
+    let count_steps = instance.get_typed_func::<(), (i32,)>(&mut store, "count-steps")?;
+    let (count,) = count_steps.call_async(&mut store, ()).await?;
+    count_steps.post_return_async(&mut store).await?;
+    Ok(count)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn planner_host_decomposes_with_default_provider() -> Result<()> {
+    assert_eq!(
+        count_planned_steps(vec!["ai:inference".to_string()]).await?,
+        2
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn planner_host_requires_inference_permission() -> Result<()> {
+    assert_eq!(count_planned_steps(vec![]).await?, -1);
+    Ok(())
+}
+
+// =============================================================================
+// Inference Stream Tests
+// =============================================================================
+
+/// Component that opens a completion stream on model "mock" and traps
+/// without dropping it. Returns -1 if the stream could not be opened.
+const STREAM_TRAP_WAT: &str = r#"(component
+    (import "brio:core/inference" (instance $inference
+        (type $role (variant (case "system") (case "user") (case "assistant") (case "tool")))
+        (export "role" (type $r (eq $role)))
+        (type $tool-call (record (field "id" string) (field "name" string) (field "arguments" string)))
+        (export "tool-call" (type $tc (eq $tool-call)))
+        (type $message (record (field "role" $r) (field "content" string)
+            (field "tool-calls" (list $tc)) (field "tool-call-id" (option string))))
+        (export "message" (type $m (eq $message)))
+        (type $error (variant (case "provider-error" string) (case "rate-limit")
+            (case "context-length-exceeded") (case "budget-exceeded" string)))
+        (export "inference-error" (type $e (eq $error)))
+        (export "completion-stream" (type $s (sub resource)))
+        (export "chat-stream" (func (param "model" string) (param "messages" (list $m))
+            (result (result (own $s) (error $e)))))
+    ))
+    (core module $mem
+        (memory (export "memory") 1)
+        (global $bump (mut i32) (i32.const 1024))
+        (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump
+                (i32.and (i32.add (i32.add (local.get $p) (local.get 3)) (i32.const 7)) (i32.const -8)))
+            (local.get $p))
+    )
+    (core instance $mi (instantiate $mem))
+    (core func $chat-stream (canon lower (func $inference "chat-stream") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (core module $m
+        (import "env" "memory" (memory 1))
+        (import "host" "chat-stream" (func $chat-stream (param i32 i32 i32 i32 i32)))
+        (data (i32.const 16) "mock")
+        (func (export "open-and-trap") (result i32)
+            (call $chat-stream (i32.const 16) (i32.const 4) (i32.const 0) (i32.const 0) (i32.const 64))
+            (if (i32.load8_u (i32.const 64))
+                (then (return (i32.const -1))))
+            unreachable)
+    )
+    (core instance $i (instantiate $m
+        (with "env" (instance $mi))
+        (with "host" (instance (export "chat-stream" (func $chat-stream))))))
+    (func (export "open-and-trap") (result s32) (canon lift (core func $i "open-and-trap")))
+)"#;
+
+/// Provider whose streams never finish, counting how many are open.
+struct HangingStreamProvider {
+    open: Arc<AtomicUsize>,
+}
+
+/// Marks a stream of [`HangingStreamProvider`] as closed when dropped.
+struct OpenStreamGuard(Arc<AtomicUsize>);
+
+impl Drop for OpenStreamGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait::async_trait]
+impl LLMProvider for HangingStreamProvider {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        Err(InferenceError::ProviderError("Streaming only".to_string()))
+    }
+
+    async fn chat_stream(&self, _request: ChatRequest) -> Result<ChatStream, InferenceError> {
+        self.open.fetch_add(1, Ordering::SeqCst);
+        let guard = OpenStreamGuard(Arc::clone(&self.open));
+        Ok(ChatStream::new(
+            futures_util::stream::pending::<Result<ChatChunk, InferenceError>>().map(
+                move |chunk| {
+                    let _ = &guard;
+                    chunk
+                },
+            ),
+        ))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn streams_are_closed_with_the_store_of_a_trapped_guest() -> Result<()> {
+    let open = Arc::new(AtomicUsize::new(0));
+    let provider = HangingStreamProvider {
+        open: Arc::clone(&open),
+    };
+    let engine = wasmtime::Engine::new(&create_engine_config())?;
+    let wasm_engine = WasmEngine::new(create_linker(&engine)?)?;
+    let component = wasmtime::component::Component::new(&engine, STREAM_TRAP_WAT)?;
+
+    let host_state = BrioHostState::with_provider("sqlite::memory:", Box::new(provider))
+        .await?
+        .with_plugin_context("stream-test".to_string(), vec!["ai:inference".to_string()]);
+    let mut store = wasm_engine.prepare_store(host_state.clone())?;
+    let instance = wasm_engine
+        .linker()
+        .instantiate_async(&mut store, &component)
+        .await?;
+
+    let open_and_trap = instance.get_typed_func::<(), (i32,)>(&mut store, "open-and-trap")?;
+    let error = open_and_trap
+        .call_async(&mut store, ())
+        .await
+        .expect_err("guest should trap");
+    assert!(format!("{error:?}").contains("unreachable"));
+    assert_eq!(open.load(Ordering::SeqCst), 1);
+    assert_eq!(store.data().inference_streams().len(), 1);
+    assert!(host_state.inference_streams().is_empty());
+
+    drop(store);
+    assert_eq!(open.load(Ordering::SeqCst), 0);
+    Ok(())
 }
@@ -0,0 +1,193 @@
+//! Tests for guest resource limits.
+//!
+//! Runs a hand-written agent component whose behaviour is selected by the
+//! first byte of the task description: `l` spins forever, `g` grows its
+//! memory by 100 pages, anything else returns `"done"`. A variant of it
+//! spins forever while being instantiated.
+
+use anyhow::Result;
+use brio_kernel::engine::create_engine_config;
+use brio_kernel::engine::runner::{AgentRunner, TaskContext};
+use brio_kernel::engine::{GuestError, ResourceLimits};
+use brio_kernel::host::BrioHostState;
+use brio_kernel::inference::{ChatRequest, ChatResponse, InferenceError, LLMProvider};
+use brio_kernel::registry::PluginRegistry;
+use std::time::Duration;
+use tempfile::{TempDir, tempdir};
+
+const AGENT_WAT: &str = r#"(component
+    (core module $m
+        (memory (export "memory") 1)
+        (global $bump (mut i32) (i32.const 1024))
+        (data (i32.const 16) "done")
+        (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump
+                (i32.and (i32.add (i32.add (local.get $p) (local.get 3)) (i32.const 7)) (i32.const -8)))
+            (local.get $p))
+        (func (export "run")
+            (param $tp i32) (param $tl i32) (param $dp i32) (param $dl i32) (param $fp i32) (param $fl i32)
+            (result i32)
+            (local $c i32)
+            (local.set $c (i32.load8_u (local.get $dp)))
+            (if (i32.eq (local.get $c) (i32.const 108))
+                (then (loop $spin (br $spin))))
+            (if (i32.eq (local.get $c) (i32.const 103))
+                (then (drop (memory.grow (i32.const 100)))))
+            (i32.store8 (i32.const 0) (i32.const 0))
+            (i32.store (i32.const 4) (i32.const 16))
+            (i32.store (i32.const 8) (i32.const 4))
+            (i32.const 0))
+        (func (export "handle-event") (param i32 i32 i32 i32 i32))
+    )
+    (core instance $i (instantiate $m))
+    (type $ctx (record (field "task-id" string) (field "description" string) (field "input-files" (list string))))
+    (type $payload (variant (case "json" string) (case "binary" (list u8))))
+    (func $run (param "context" $ctx) (result (result string (error string)))
+        (canon lift (core func $i "run") (memory $i "memory") (realloc (func $i "realloc"))))
+    (func $handle (param "topic" string) (param "data" $payload)
+        (canon lift (core func $i "handle-event") (memory $i "memory") (realloc (func $i "realloc"))))
+    (instance $runner (export "task-context" (type $ctx)) (export "run" (func $run)))
+    (instance $handler (export "payload" (type $payload)) (export "handle-event" (func $handle)))
+    (export "brio:core/agent-runner" (instance $runner))
+    (export "brio:core/event-handler" (instance $handler))
+)"#;
+
+// =============================================================================
+// Test Helpers
+// =============================================================================
+
+struct MockProvider;
+
+#[async_trait::async_trait]
+impl LLMProvider for MockProvider {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        Err(InferenceError::ProviderError("Mock".to_string()))
+    }
+}
+
+/// Returns the test agent with a start function that spins forever.
+fn spinning_start_wat() -> String {
+    AGENT_WAT.replace(
+        r#"(func (export "handle-event")"#,
+        r#"(func $spin (loop $l (br $l)))
+        (start $spin)
+        (func (export "handle-event")"#,
+    )
+}
+
+/// Registers `wat` as the `agent` plugin with the given manifest limits.
+async fn register_agent(
+    wat: &str,
+    default_limits: ResourceLimits,
+    manifest_limits: &str,
+) -> Result<(TempDir, PluginRegistry)> {
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("agent.wasm"), wat)?;
+    std::fs::write(
+        dir.path().join("agent.toml"),
+        format!("id = \"agent\"\nversion = \"1.0.0\"\n\n[limits]\n{manifest_limits}"),
+    )?;
+
+    let engine = wasmtime::Engine::new(&create_engine_config())?;
+    let mut registry = PluginRegistry::new(engine)?.with_default_limits(default_limits);
+    let report = registry.load_from_directory(dir.path()).await?;
+    assert!(report.is_clean(), "{:?}", report.failures);
+    Ok((dir, registry))
+}
+
+/// Registers the test agent with the given manifest limits and runs it with
+/// `description`, which selects its behaviour.
+async fn run_agent(
+    default_limits: ResourceLimits,
+    manifest_limits: &str,
+    description: &str,
+) -> Result<Result<String, GuestError>> {
+    let (_dir, registry) = register_agent(AGENT_WAT, default_limits, manifest_limits).await?;
+
+    let metadata = registry.get("agent").expect("agent registered");
+    let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+    let runner = AgentRunner::new(registry.engine().clone()).with_limits(metadata.limits);
+    let context = TaskContext {
+        task_id: "task".to_string(),
+        description: description.to_string(),
+        input_files: vec![],
+    };
+
+    Ok(runner
+        .run_agent(&registry.prepare("agent")?, host, context)
+        .await)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[tokio::test(flavor = "multi_thread")]
+async fn agent_within_limits_completes() -> Result<()> {
+    let output = run_agent(ResourceLimits::default(), "fuel = 1000000", "hello").await?;
+    assert_eq!(output?, "done");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn looping_agent_times_out() -> Result<()> {
+    let result = run_agent(ResourceLimits::default(), "timeout_ms = 100", "loop").await?;
+    assert!(matches!(result, Err(GuestError::Timeout(t)) if t == Duration::from_millis(100)));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn looping_agent_runs_out_of_fuel() -> Result<()> {
+    let result = run_agent(ResourceLimits::unlimited(), "fuel = 100000", "loop").await?;
+    assert!(matches!(result, Err(GuestError::OutOfFuel)));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn memory_growth_beyond_limit_traps() -> Result<()> {
+    let result = run_agent(
+        ResourceLimits::default(),
+        "max_memory_bytes = 65536",
+        "grow",
+    )
+    .await?;
+    assert!(matches!(
+        result,
+        Err(GuestError::MemoryLimitExceeded { limit: 65536 })
+    ));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn manifest_limits_are_capped_by_kernel_defaults() -> Result<()> {
+    let ceiling = ResourceLimits {
+        timeout_ms: Some(50),
+        ..ResourceLimits::unlimited()
+    };
+    let result = run_agent(ceiling, "timeout_ms = 10000", "loop").await?;
+    assert!(matches!(result, Err(GuestError::Timeout(t)) if t == Duration::from_millis(50)));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn spinning_instantiation_times_out() -> Result<()> {
+    let (_dir, registry) = register_agent(
+        &spinning_start_wat(),
+        ResourceLimits::default(),
+        "timeout_ms = 100",
+    )
+    .await?;
+    let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+
+    let error = registry
+        .instantiate("agent", host)
+        .await
+        .expect_err("instantiation should time out");
+    assert!(
+        matches!(error.downcast_ref::<GuestError>(), Some(GuestError::Timeout(t)) if *t == Duration::from_millis(100)),
+        "Expected timeout, got {error:?}"
+    );
+    Ok(())
+}
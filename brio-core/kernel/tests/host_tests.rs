@@ -2,7 +2,9 @@
 
 use anyhow::Result;
 use brio_kernel::host::{BrioHostState, MeshHandler};
-use brio_kernel::inference::{ChatRequest, ChatResponse, InferenceError, LLMProvider};
+use brio_kernel::inference::{
+    ChatRequest, ChatResponse, InferenceError, LLMProvider, RequestOptions,
+};
 use brio_kernel::mesh::{MeshMessage, Payload};
 use std::sync::Arc;
 use tokio::sync::mpsc;
@@ -19,6 +21,7 @@ impl LLMProvider for MockProvider {
         Ok(ChatResponse {
             content: "Mock response".to_string(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -35,6 +38,89 @@ async fn test_host_state_creation() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_host_state_applies_schema_migrations() -> Result<()> {
+    let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO tasks (content, priority, status, parent_id) VALUES (?, 10, 'pending', NULL)",
+    )
+    .bind("migrated task")
+    .execute(host.db())
+    .await?;
+    assert_eq!(result.rows_affected(), 1);
+
+    let versions: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(host.db())
+            .await?;
+    assert_eq!(versions, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_host_state_creates_missing_database_file() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let db_path = dir.path().join("brio.db");
+    let db_url = format!("sqlite://{}", db_path.display());
+
+    let _host = BrioHostState::with_provider(&db_url, Box::new(MockProvider)).await?;
+    assert!(db_path.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_branches_survive_host_restart() -> Result<()> {
+    use brio_kernel::branch_manager::{BranchResult, ExecutionStrategy, MergeRequestStatus};
+
+    let dir = tempfile::tempdir()?;
+    let db_url = format!("sqlite://{}", dir.path().join("brio.db").display());
+
+    let (branch_id, merge_request_id) = {
+        let host = BrioHostState::with_provider(&db_url, Box::new(MockProvider)).await?;
+        let manager = host.branch_manager();
+        let branch = manager
+            .create_branch(
+                "persisted".to_string(),
+                vec![],
+                ExecutionStrategy::Sequential,
+                false,
+                "union".to_string(),
+            )
+            .await?;
+        manager
+            .start_execution(&branch.id, "session".to_string())
+            .await?;
+        manager
+            .finish_execution(BranchResult {
+                branch_id: branch.id.clone(),
+                file_changes: vec![],
+                agent_results: vec![],
+                created_at: chrono::Utc::now(),
+            })
+            .await?;
+        let merge_request = manager
+            .request_merge(&branch.id, "union".to_string(), true)
+            .await?;
+        host.db().close().await;
+        (branch.id, merge_request.id)
+    };
+
+    let host = BrioHostState::with_provider(&db_url, Box::new(MockProvider)).await?;
+    let manager = host.branch_manager();
+
+    let branches = manager.list_branches(None, None).await?;
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_id);
+    assert_eq!(branches[0].name, "persisted");
+
+    let approved = manager
+        .approve_merge(&merge_request_id, "reviewer".to_string())
+        .await?;
+    assert_eq!(approved.status, MergeRequestStatus::Approved);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_host_state_broadcaster_access() -> Result<()> {
     let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
@@ -260,3 +346,234 @@ async fn test_binary_payload_routing() -> Result<()> {
 
     Ok(())
 }
+
+// =============================================================================
+// Inference Stream Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_inference_stream_broadcasts_deltas() -> Result<()> {
+    use brio_kernel::ws::{BroadcastMessage, WsMessage};
+
+    let mut host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+    let mut events = host.broadcaster().subscribe();
+
+    let request = ChatRequest {
+        model: "mock".to_string(),
+        messages: vec![],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
+    };
+    let id = host.open_inference_stream(request).await?;
+    let broadcast_id = host.inference_streams().broadcast_id(id).expect("open");
+
+    let chunk = host.next_inference_chunk(id).await?.expect("chunk");
+    assert_eq!(chunk.delta, "Mock response");
+    assert!(host.next_inference_chunk(id).await?.is_none());
+
+    let BroadcastMessage::Message(WsMessage::InferenceDelta(delta)) = events.recv().await? else {
+        panic!("expected inference delta");
+    };
+    assert_eq!(delta.stream_id(), broadcast_id);
+    assert_eq!(delta.delta(), "Mock response");
+    assert!(delta.is_done());
+
+    host.close_inference_stream(id);
+    assert!(host.next_inference_chunk(id).await.is_err());
+    let again = tokio::time::timeout(std::time::Duration::from_millis(50), events.recv()).await;
+    assert!(again.is_err(), "finished stream reported done twice");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_inference_streams_of_different_stores_broadcast_distinct_ids() -> Result<()> {
+    use brio_kernel::ws::{BroadcastMessage, WsMessage};
+
+    let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MockProvider)).await?;
+    let mut events = host.broadcaster().subscribe();
+    let mut first = host.clone();
+    let mut second = host.clone();
+
+    let first_id = first
+        .open_inference_stream(ChatRequest::with_message("mock", "Hi"))
+        .await?;
+    let second_id = second
+        .open_inference_stream(ChatRequest::with_message("mock", "Hi"))
+        .await?;
+    assert_eq!(first_id, second_id);
+    let first_broadcast = first.inference_streams().broadcast_id(first_id);
+    let second_broadcast = second.inference_streams().broadcast_id(second_id);
+    assert_ne!(first_broadcast, second_broadcast);
+
+    // A stream dropped before it finished still tells clients it is done.
+    second.close_inference_stream(second_id);
+    let BroadcastMessage::Message(WsMessage::InferenceDelta(delta)) = events.recv().await? else {
+        panic!("expected inference delta");
+    };
+    assert_eq!(Some(delta.stream_id()), second_broadcast);
+    assert!(delta.is_done());
+    assert!(delta.delta().is_empty());
+    Ok(())
+}
+
+// =============================================================================
+// Usage Accounting Tests
+// =============================================================================
+
+struct MeteredProvider;
+
+#[async_trait::async_trait]
+impl LLMProvider for MeteredProvider {
+    async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, InferenceError> {
+        Ok(ChatResponse::with_usage("Metered response", 60, 20))
+    }
+}
+
+#[tokio::test]
+async fn test_task_budget_stops_inference() -> Result<()> {
+    use brio_kernel::planner::PlannerError;
+    use brio_kernel::usage::{ModelPrice, PriceTable, TokenBudgets, UsageFilter, UsageTracker};
+
+    let host = BrioHostState::with_provider("sqlite::memory:", Box::new(MeteredProvider))
+        .await?
+        .with_usage_tracker(UsageTracker::new(
+            PriceTable::new().with_price(ModelPrice::new("gpt-*", 1.0, 2.0)),
+            TokenBudgets {
+                per_task: Some(100),
+                per_plugin: None,
+            },
+        ));
+    let agent = host
+        .with_plugin_context("coder".to_string(), vec!["ai:inference".to_string()])
+        .with_task_context("17");
+    let provider = agent.inference().expect("provider");
+
+    for _ in 0..2 {
+        agent
+            .chat_within_budget(provider.as_ref(), ChatRequest::with_message("gpt-4", "Hi"))
+            .await?;
+    }
+    let result = agent
+        .chat_within_budget(provider.as_ref(), ChatRequest::with_message("gpt-4", "Hi"))
+        .await;
+    assert!(
+        matches!(result, Err(InferenceError::BudgetExceeded(_))),
+        "Expected budget error, got {result:?}"
+    );
+
+    let entries = host
+        .usage_ledger()
+        .entries(&UsageFilter::task("17"))
+        .await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].plugin_id, "coder");
+    assert_eq!(entries[0].requests, 2);
+    assert_eq!(entries[0].total_tokens, 160);
+    assert!((entries[0].cost_usd - 0.0002).abs() < 1e-9);
+
+    // Other tasks of the plugin are unaffected
+    agent
+        .with_task_context("18")
+        .chat_within_budget(provider.as_ref(), ChatRequest::with_message("gpt-4", "Hi"))
+        .await?;
+
+    // Planning is a completion of the task like any other
+    let planned = agent.plan("Ship the release").await;
+    assert!(
+        matches!(
+            planned,
+            Err(PlannerError::Inference(InferenceError::BudgetExceeded(_)))
+        ),
+        "Expected budget error, got {planned:?}"
+    );
+    agent
+        .with_task_context("19")
+        .plan("Ship the release")
+        .await?;
+    let entries = host
+        .usage_ledger()
+        .entries(&UsageFilter::task("19"))
+        .await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].requests, 1);
+    Ok(())
+}
+
+// =============================================================================
+// Event Log Tests
+// =============================================================================
+
+/// Waits until `check` holds for the subscriber offsets.
+async fn wait_for_offsets(
+    host: &BrioHostState,
+    check: impl Fn(&[brio_kernel::events::SubscriberOffset]) -> bool,
+) -> Result<()> {
+    for _ in 0..100 {
+        if check(&host.event_delivery().log().offsets().await?) {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    anyhow::bail!("event offsets did not reach the expected state")
+}
+
+#[tokio::test]
+async fn test_failed_events_are_retried_across_restarts_then_dead_lettered() -> Result<()> {
+    use brio_kernel::events::{DeadLetterFilter, RetryPolicy};
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir()?;
+    let db_url = format!("sqlite://{}", dir.path().join("brio.db").display());
+
+    // "ghost" is not a loaded plugin, so every delivery to it fails
+    {
+        let host = BrioHostState::with_provider(&db_url, Box::new(MockProvider))
+            .await?
+            .with_event_retry(RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_secs(60),
+                max_backoff: Duration::from_secs(60),
+            });
+        host.subscribe_plugin("ghost", "branch:*").await?;
+        host.publish_event(
+            "branch:merged",
+            Payload::Json(Box::new("{\"branch\":\"b1\"}".to_string())),
+        )
+        .await?;
+        wait_for_offsets(&host, |offsets| offsets[0].attempts == 1).await?;
+        host.db().close().await;
+    }
+
+    let host = BrioHostState::with_provider(&db_url, Box::new(MockProvider))
+        .await?
+        .with_event_retry(RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+        });
+    assert_eq!(host.resume_event_delivery().await?, 1);
+    assert_eq!(host.event_bus().subscribers("branch:created"), ["ghost"]);
+
+    wait_for_offsets(&host, |offsets| offsets[0].lag == 0).await?;
+    let dead_letters = host
+        .event_delivery()
+        .log()
+        .dead_letters(&DeadLetterFilter::default())
+        .await?;
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].subscriber, "ghost");
+    assert_eq!(dead_letters[0].topic, "branch:merged");
+    assert_eq!(dead_letters[0].attempts, 2);
+    assert!(dead_letters[0].error.contains("not loaded"));
+
+    let topics: Vec<String> = host
+        .event_delivery()
+        .log()
+        .topics()
+        .await?
+        .into_iter()
+        .map(|summary| summary.topic)
+        .collect();
+    assert_eq!(topics, ["branch:merged", "dead-letter:branch:merged"]);
+    Ok(())
+}
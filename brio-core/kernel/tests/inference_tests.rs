@@ -1,7 +1,7 @@
 //! Tests for the inference module types and error handling.
 
 use brio_kernel::inference::{
-    ChatRequest, ChatResponse, InferenceError, LLMProvider, Message, Role, Usage,
+    ChatRequest, ChatResponse, InferenceError, LLMProvider, Message, RequestOptions, Role, Usage,
 };
 
 // =============================================================================
@@ -23,6 +23,8 @@ fn message_should_construct_with_role_and_content() {
     let msg = Message {
         role: Role::User,
         content: "Hello, world!".to_string(),
+        tool_calls: Vec::new(),
+        tool_call_id: None,
     };
 
     assert!(matches!(msg.role, Role::User));
@@ -50,12 +52,18 @@ fn chat_request_should_construct_with_model_and_messages() {
             Message {
                 role: Role::System,
                 content: "You are helpful.".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             },
             Message {
                 role: Role::User,
                 content: "Hi!".to_string(),
+                tool_calls: Vec::new(),
+                tool_call_id: None,
             },
         ],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
     };
 
     assert_eq!(request.model, "gpt-4");
@@ -71,6 +79,7 @@ fn chat_response_should_include_usage_when_provided() {
             completion_tokens: 1,
             total_tokens: 6,
         }),
+        tool_calls: Vec::new(),
     };
 
     assert_eq!(response.content, "Hello!");
@@ -83,6 +92,7 @@ fn chat_response_should_allow_optional_usage() {
     let response = ChatResponse {
         content: "Response".to_string(),
         usage: None,
+        tool_calls: Vec::new(),
     };
 
     assert!(response.usage.is_none());
@@ -154,6 +164,8 @@ fn message_should_serialize_to_valid_json() {
     let msg = Message {
         role: Role::User,
         content: "Test message".to_string(),
+        tool_calls: Vec::new(),
+        tool_call_id: None,
     };
 
     let json = serde_json::to_string(&msg).unwrap();
@@ -200,6 +212,7 @@ impl LLMProvider for TestMockProvider {
         Ok(ChatResponse {
             content: self.response.clone(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -215,7 +228,11 @@ async fn mock_provider_should_return_configured_response() {
         messages: vec![Message {
             role: Role::User,
             content: "Hello".to_string(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
     };
 
     let response = provider.chat(request).await.unwrap();
@@ -240,6 +257,8 @@ async fn failing_provider_should_return_error() {
     let request = ChatRequest {
         model: "test-model".to_string(),
         messages: vec![],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
     };
 
     let result = provider.chat(request).await;
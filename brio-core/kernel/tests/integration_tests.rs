@@ -17,6 +17,7 @@ impl LLMProvider for MockProvider {
         Ok(ChatResponse {
             content: "Mock response".to_string(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
@@ -31,7 +32,7 @@ async fn host_should_initialize_and_instantiate_component() -> Result<()> {
     let linker = create_linker(&engine)?;
     let wasm_engine = WasmEngine::new(linker)?;
 
-    let mut store = wasm_engine.prepare_store(host_state);
+    let mut store = wasm_engine.prepare_store(host_state)?;
 
     let component = wasmtime::component::Component::new(&engine, r"(component)")?;
     let _instance = wasm_engine
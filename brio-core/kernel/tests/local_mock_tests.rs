@@ -0,0 +1,382 @@
+//! HTTP mock tests for the local model server provider.
+//!
+//! Uses wiremock to simulate Ollama and llama.cpp servers.
+
+use brio_kernel::inference::{
+    ChatRequest, CircuitBreakerConfig, InferenceError, LLMProvider, LocalConfig, LocalModel,
+    LocalProvider, LocalServerKind, RequestOptions, ResponseFormat,
+};
+use futures_util::StreamExt;
+use reqwest::Url;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn create_provider_with_mock_server(server: &MockServer, kind: LocalServerKind) -> LocalProvider {
+    let config = LocalConfig::new(kind, Url::parse(&format!("{}/", server.uri())).unwrap())
+        .with_max_retries(0); // Disable retries for faster tests
+    LocalProvider::new(config)
+}
+
+fn create_test_request() -> ChatRequest {
+    ChatRequest::with_message("llama3.1:8b", "Hello")
+}
+
+// =============================================================================
+// Ollama Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_ollama_chat_sends_native_request() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "llama3.1:8b",
+            "stream": false,
+            "format": "json",
+            "keep_alive": "30m",
+            "options": { "temperature": 0.0, "num_predict": 32 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "model": "llama3.1:8b",
+            "message": { "role": "assistant", "content": "{\"ok\":true}" },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 12,
+            "eval_count": 4
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = LocalProvider::new(
+        LocalConfig::new(
+            LocalServerKind::Ollama,
+            Url::parse(&format!("{}/", server.uri())).unwrap(),
+        )
+        .with_keep_alive("30m")
+        .with_max_retries(0),
+    );
+    let options = RequestOptions::new()
+        .with_temperature(0.0)
+        .with_max_tokens(32)
+        .with_response_format(ResponseFormat::Json);
+
+    let response = provider
+        .chat(create_test_request().with_options(options))
+        .await
+        .unwrap();
+
+    assert_eq!(response.content, "{\"ok\":true}");
+    let usage = response.usage.unwrap();
+    assert_eq!(usage.prompt_tokens, 12);
+    assert_eq!(usage.completion_tokens, 4);
+    assert_eq!(usage.total_tokens, 16);
+}
+
+#[tokio::test]
+async fn test_ollama_tool_calls_are_mapped() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [
+                    { "function": { "name": "read_file", "arguments": { "path": "src/lib.rs" } } }
+                ]
+            },
+            "done": true
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::Ollama);
+    let response = provider.chat(create_test_request()).await.unwrap();
+
+    assert_eq!(response.tool_calls.len(), 1);
+    assert_eq!(response.tool_calls[0].id, "call_0");
+    assert_eq!(response.tool_calls[0].name, "read_file");
+    assert_eq!(
+        response.tool_calls[0].parsed_arguments().unwrap()["path"],
+        "src/lib.rs"
+    );
+}
+
+#[tokio::test]
+async fn test_ollama_missing_model_returns_provider_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": "model \"llama3.1:8b\" not found, try pulling it first"
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::Ollama);
+    let result = provider.chat(create_test_request()).await;
+
+    assert!(
+        matches!(&result, Err(InferenceError::ProviderError(msg)) if msg.contains("not found")),
+        "Expected ProviderError for missing model, got {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_ollama_stream_yields_deltas_and_final_usage() {
+    let server = MockServer::start().await;
+
+    let body = concat!(
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"Hel\"},\"done\":false}\n",
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"lo\"},\"done\":false}\n",
+        "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,",
+        "\"done_reason\":\"stop\",\"prompt_eval_count\":5,\"eval_count\":2}\n",
+    );
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .and(body_partial_json(serde_json::json!({ "stream": true })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/x-ndjson")
+                .set_body_string(body),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::Ollama);
+    let mut stream = provider.chat_stream(create_test_request()).await.unwrap();
+
+    let mut deltas = Vec::new();
+    let mut last = None;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        deltas.push(chunk.delta.clone());
+        last = Some(chunk);
+    }
+
+    assert_eq!(deltas.concat(), "Hello");
+    let last = last.unwrap();
+    assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    assert_eq!(last.usage.unwrap().total_tokens, 7);
+}
+
+#[tokio::test]
+async fn test_ollama_stream_truncated_body_returns_network_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"done\":false}\n",
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::Ollama);
+    let result = provider
+        .chat_stream(create_test_request())
+        .await
+        .unwrap()
+        .collect_response()
+        .await;
+
+    assert!(matches!(result, Err(InferenceError::NetworkError(_))));
+}
+
+#[tokio::test]
+async fn test_ollama_lists_pulled_models() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "models": [
+                { "name": "llama3.1:8b", "size": 4_661_224_676_u64, "digest": "abc" },
+                { "name": "qwen2.5-coder:7b" }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::Ollama);
+    let models = provider.list_models().await.unwrap();
+
+    assert_eq!(
+        models,
+        vec![
+            LocalModel {
+                name: "llama3.1:8b".to_string(),
+                size: Some(4_661_224_676),
+            },
+            LocalModel {
+                name: "qwen2.5-coder:7b".to_string(),
+                size: None,
+            },
+        ]
+    );
+}
+
+// =============================================================================
+// llama.cpp Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_llama_cpp_chat_uses_openai_endpoint() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hi from llama.cpp" } }],
+            "usage": { "prompt_tokens": 3, "completion_tokens": 4, "total_tokens": 7 }
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::LlamaCpp);
+    let response = provider.chat(create_test_request()).await.unwrap();
+
+    assert_eq!(response.content, "Hi from llama.cpp");
+    assert_eq!(response.usage.unwrap().total_tokens, 7);
+}
+
+#[tokio::test]
+async fn test_llama_cpp_stream_uses_server_sent_events() {
+    let server = MockServer::start().await;
+
+    let body = concat!(
+        "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(body),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::LlamaCpp);
+    let response = provider
+        .chat_stream(create_test_request())
+        .await
+        .unwrap()
+        .collect_response()
+        .await
+        .unwrap();
+
+    assert_eq!(response.content, "Hi");
+}
+
+#[tokio::test]
+async fn test_llama_cpp_context_overflow_returns_context_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {
+                "code": 400,
+                "message": "the request exceeds the available context size",
+                "type": "exceed_context_size_error"
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::LlamaCpp);
+    let result = provider.chat(create_test_request()).await;
+
+    assert!(matches!(result, Err(InferenceError::ContextLengthExceeded)));
+}
+
+#[tokio::test]
+async fn test_llama_cpp_lists_loaded_model() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [{ "id": "qwen2.5-coder-7b-q4_k_m.gguf", "object": "model" }]
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server, LocalServerKind::LlamaCpp);
+    let models = provider.list_models().await.unwrap();
+
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].name, "qwen2.5-coder-7b-q4_k_m.gguf");
+}
+
+// =============================================================================
+// Resilience Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_server_errors_are_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("server busy"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "message": { "role": "assistant", "content": "Recovered" },
+            "done": true
+        })))
+        .mount(&server)
+        .await;
+
+    let provider = LocalProvider::new(
+        LocalConfig::new(
+            LocalServerKind::Ollama,
+            Url::parse(&format!("{}/", server.uri())).unwrap(),
+        )
+        .with_max_retries(1)
+        .with_base_delay_ms(1),
+    );
+
+    let response = provider.chat(create_test_request()).await.unwrap();
+    assert_eq!(response.content, "Recovered");
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_opens_after_failures() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/chat"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("crashed"))
+        .mount(&server)
+        .await;
+
+    let provider = LocalProvider::new(
+        LocalConfig::new(
+            LocalServerKind::Ollama,
+            Url::parse(&format!("{}/", server.uri())).unwrap(),
+        )
+        .with_max_retries(0)
+        .with_circuit_breaker(CircuitBreakerConfig::new().with_failure_threshold(1)),
+    );
+
+    let first = provider.chat(create_test_request()).await;
+    assert!(matches!(first, Err(InferenceError::ProviderError(_))));
+
+    let second = provider.chat(create_test_request()).await;
+    assert!(matches!(second, Err(InferenceError::CircuitBreakerOpen(_))));
+}
@@ -18,6 +18,7 @@ impl brio_kernel::inference::LLMProvider for DummyProvider {
         Ok(brio_kernel::inference::ChatResponse {
             content: String::new(),
             usage: None,
+            tool_calls: Vec::new(),
         })
     }
 }
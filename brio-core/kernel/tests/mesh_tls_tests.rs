@@ -0,0 +1,263 @@
+//! Mutual TLS tests for the mesh transport.
+//!
+//! Spawns nodes serving the mesh over mutual TLS with certificates issued by
+//! a throwaway cluster CA, and checks that node identities and the call
+//! policy are enforced.
+
+use brio_kernel::host::{BrioHostState, MeshHandler};
+use brio_kernel::inference::ProviderRegistry;
+use brio_kernel::infrastructure::config::SandboxSettings;
+use brio_kernel::mesh::grpc::mesh_transport_client::MeshTransportClient;
+use brio_kernel::mesh::grpc::mesh_transport_server::MeshTransportServer;
+use brio_kernel::mesh::grpc::{PublishRequest, publish_request};
+use brio_kernel::mesh::service::MeshService;
+use brio_kernel::mesh::types::{NodeAddress, NodeId, NodeInfo};
+use brio_kernel::mesh::{CallPolicy, MeshTls, Payload};
+use rcgen::{BasicConstraints, CertificateParams, IsCa, Issuer, KeyPair};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tonic::transport::Channel;
+
+/// Cluster CA issuing node certificates.
+struct ClusterCa {
+    pem: String,
+    issuer: Issuer<'static, KeyPair>,
+}
+
+impl ClusterCa {
+    fn new() -> Self {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let key = KeyPair::generate().unwrap();
+        let pem = params.self_signed(&key).unwrap().pem();
+        Self {
+            pem,
+            issuer: Issuer::new(params, key),
+        }
+    }
+
+    /// Issues a certificate for the node `name`, reachable at `localhost`.
+    fn node_tls(&self, name: &str) -> MeshTls {
+        let params =
+            CertificateParams::new(vec![name.to_string(), "localhost".to_string()]).unwrap();
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, &self.issuer).unwrap();
+        MeshTls::from_pem(&self.pem, cert.pem(), key.serialize_pem())
+    }
+}
+
+async fn wait_for_server(port: u16) {
+    for i in 0..20 {
+        if tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10 * (i + 1))).await;
+    }
+    panic!("Server failed to start within timeout");
+}
+
+/// Spawns a node serving the mesh over TLS with the identity `tls`.
+async fn spawn_tls_node(
+    id: &str,
+    port: u16,
+    tls: MeshTls,
+    policy: CallPolicy,
+) -> (Arc<BrioHostState>, String) {
+    let node_id = NodeId::try_from_str(id).unwrap();
+    let addr = format!("localhost:{port}");
+    let state = BrioHostState::new_distributed(
+        "sqlite::memory:",
+        ProviderRegistry::new(),
+        None,
+        node_id.clone(),
+        SandboxSettings::default(),
+    )
+    .await
+    .unwrap()
+    .with_mesh_tls(tls.clone());
+    let state = Arc::new(state);
+
+    let service = MeshService::new(state.clone(), node_id)
+        .with_address(NodeAddress::new(&addr).unwrap())
+        .with_policy(policy);
+    let server = tonic::transport::Server::builder()
+        .tls_config(tls.server_config())
+        .unwrap()
+        .add_service(MeshTransportServer::new(service))
+        .serve(([127, 0, 0, 1], port).into());
+    tokio::spawn(async move { server.await.unwrap() });
+    wait_for_server(port).await;
+
+    (state, addr)
+}
+
+fn spawn_echo(node: &BrioHostState, component: &str) {
+    let (tx, mut rx) = mpsc::channel(1);
+    node.register_component(component.to_string(), tx);
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            msg.reply_tx
+                .send(Ok(Payload::Json(Box::new("pong".to_string()))))
+                .unwrap();
+        }
+    });
+}
+
+fn local_info(id: &str, addr: &str) -> NodeInfo {
+    NodeInfo::new(
+        NodeId::try_from_str(id).unwrap(),
+        NodeAddress::new(addr).unwrap(),
+        vec![],
+        0,
+    )
+    .unwrap()
+}
+
+fn ping() -> Payload {
+    Payload::Json(Box::new("ping".to_string()))
+}
+
+#[tokio::test]
+async fn test_calls_over_mutual_tls_respect_the_policy() {
+    let ca = ClusterCa::new();
+    let policy = CallPolicy::new(HashMap::from([(
+        "tls-a".to_string(),
+        vec!["echo".to_string()],
+    )]));
+    let (node_a, addr_a) = spawn_tls_node("tls-a", 50070, ca.node_tls("tls-a"), policy).await;
+    let (node_b, addr_b) =
+        spawn_tls_node("tls-b", 50071, ca.node_tls("tls-b"), CallPolicy::default()).await;
+    spawn_echo(&node_a, "echo");
+    spawn_echo(&node_a, "secret");
+
+    let router_b = node_b.remote_router().unwrap();
+    router_b
+        .join(
+            &NodeAddress::new(&addr_a).unwrap(),
+            &local_info("tls-b", &addr_b),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("join over TLS");
+    assert_eq!(node_a.remote_router().unwrap().members().len(), 1);
+
+    // Node B is not allowed to call anything on node A
+    let denied = node_b.mesh_call("tls-a/echo", "ping", ping()).await;
+    assert!(denied.unwrap_err().to_string().contains("may not call"));
+
+    // Node A is allowed to call its own echo on node B
+    spawn_echo(&node_b, "echo");
+    let response = node_a
+        .mesh_call("tls-b/echo", "ping", ping())
+        .await
+        .expect("call over TLS");
+    assert!(matches!(response, Payload::Json(s) if *s == "pong"));
+}
+
+#[tokio::test]
+async fn test_nodes_cannot_claim_another_identity() {
+    let ca = ClusterCa::new();
+    let (_node_a, addr_a) =
+        spawn_tls_node("tls-c", 50072, ca.node_tls("tls-c"), CallPolicy::default()).await;
+
+    // Holds a valid cluster certificate for "tls-intruder" but claims "tls-d"
+    let impostor = BrioHostState::new_distributed(
+        "sqlite::memory:",
+        ProviderRegistry::new(),
+        None,
+        NodeId::try_from_str("tls-d").unwrap(),
+        SandboxSettings::default(),
+    )
+    .await
+    .unwrap()
+    .with_mesh_tls(ca.node_tls("tls-intruder"));
+    let joined = impostor
+        .remote_router()
+        .unwrap()
+        .join(
+            &NodeAddress::new(&addr_a).unwrap(),
+            &local_info("tls-d", "localhost:50073"),
+            Duration::from_secs(5),
+        )
+        .await;
+    assert!(joined.unwrap_err().to_string().contains("does not belong"));
+
+    // Certificates from another CA are refused during the handshake
+    let outsider = BrioHostState::new_distributed(
+        "sqlite::memory:",
+        ProviderRegistry::new(),
+        None,
+        NodeId::try_from_str("tls-e").unwrap(),
+        SandboxSettings::default(),
+    )
+    .await
+    .unwrap()
+    .with_mesh_tls(ClusterCa::new().node_tls("tls-e"));
+    let joined = outsider
+        .remote_router()
+        .unwrap()
+        .join(
+            &NodeAddress::new(&addr_a).unwrap(),
+            &local_info("tls-e", "localhost:50074"),
+            Duration::from_secs(5),
+        )
+        .await;
+    assert!(joined.is_err());
+}
+
+#[tokio::test]
+async fn test_events_respect_the_policy_of_their_subscribers() {
+    let ca = ClusterCa::new();
+    let policy = CallPolicy::new(HashMap::from([(
+        "tls-g".to_string(),
+        vec!["foreman".to_string()],
+    )]));
+    let (node_f, _addr_f) = spawn_tls_node("tls-f", 50075, ca.node_tls("tls-f"), policy).await;
+    node_f
+        .event_bus()
+        .subscribe("proposal:*".to_string(), "foreman".to_string());
+    node_f
+        .event_bus()
+        .subscribe("branch:*".to_string(), "reviewer".to_string());
+
+    let tls = ca.node_tls("tls-g");
+    let channel = Channel::from_static("https://localhost:50075")
+        .tls_config(tls.client_config(Some(&NodeId::try_from_str("tls-f").unwrap())))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let mut client = MeshTransportClient::new(channel);
+    let event = |id: &str, topic: &str| PublishRequest {
+        node_id: "tls-g".to_string(),
+        event_id: id.to_string(),
+        topic: topic.to_string(),
+        payload: Some(publish_request::Payload::Json("{}".to_string())),
+    };
+
+    client
+        .publish(event("event-1", "proposal:milestones"))
+        .await
+        .expect("foreman may be called by tls-g");
+    let denied = client
+        .publish(event("event-2", "branch:merged"))
+        .await
+        .unwrap_err();
+    assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+
+    let topics: Vec<String> = node_f
+        .event_delivery()
+        .log()
+        .topics()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|summary| summary.topic)
+        .collect();
+    assert_eq!(topics, ["proposal:milestones"]);
+}
@@ -3,11 +3,15 @@
 //! Uses wiremock to simulate various HTTP responses from the `OpenAI` API.
 
 use brio_kernel::inference::{
-    ChatRequest, InferenceError, LLMProvider, Message, OpenAIConfig, OpenAIProvider, Role,
+    ChatRequest, InferenceError, LLMProvider, Message, OpenAIConfig, OpenAIProvider, RateLimiter,
+    RequestOptions, ResponseFormat, Role, ToolDefinition,
 };
+use futures_util::StreamExt;
 use reqwest::Url;
 use secrecy::SecretString;
-use wiremock::matchers::{method, path};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{body_partial_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 fn create_provider_with_mock_server(server: &MockServer) -> OpenAIProvider {
@@ -25,7 +29,11 @@ fn create_test_request() -> ChatRequest {
         messages: vec![Message {
             role: Role::User,
             content: "Hello".to_string(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }],
+        tools: Vec::new(),
+        options: RequestOptions::default(),
     }
 }
 
@@ -52,6 +60,47 @@ async fn test_rate_limit_returns_rate_limit_error() {
     assert!(matches!(result.unwrap_err(), InferenceError::RateLimit));
 }
 
+#[tokio::test]
+async fn test_retry_honors_retry_after() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "1")
+                .insert_header("x-ratelimit-remaining-requests", "0")
+                .insert_header("x-ratelimit-reset-requests", "1s"),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"choices": [{"message": {"role": "assistant", "content": "Done"}}]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let limiter = Arc::new(RateLimiter::new("openai"));
+    let provider = OpenAIProvider::new(
+        OpenAIConfig::new(
+            SecretString::new("test-api-key".into()),
+            Url::parse(&format!("{}/", server.uri())).unwrap(),
+        )
+        .with_max_retries(1)
+        .with_base_delay_ms(1)
+        .with_rate_limiter(Arc::clone(&limiter)),
+    );
+
+    let started = Instant::now();
+    let response = provider.chat(create_test_request()).await.unwrap();
+
+    assert_eq!(response.content, "Done");
+    assert!(started.elapsed() >= Duration::from_millis(900));
+}
+
 // =============================================================================
 // Server Error Tests
 // =============================================================================
@@ -227,3 +276,200 @@ async fn test_successful_response_parses_correctly() {
     assert_eq!(usage.completion_tokens, 8);
     assert_eq!(usage.total_tokens, 18);
 }
+
+// =============================================================================
+// Tool Calling Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_tool_calls_round_trip() {
+    let server = MockServer::start().await;
+
+    let response_body = r#"{
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "read_file", "arguments": "{\"path\":\"src/lib.rs\"}"}
+                }]
+            }
+        }]
+    }"#;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{"type": "function", "function": {"name": "read_file"}}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(response_body))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let request = create_test_request().with_tools(vec![ToolDefinition::new(
+        "read_file",
+        "Read a file",
+        serde_json::json!({
+            "type": "object",
+            "properties": {"path": {"type": "string"}},
+            "required": ["path"]
+        }),
+    )]);
+
+    let response = provider.chat(request).await.unwrap();
+
+    assert!(response.has_tool_calls());
+    assert_eq!(response.tool_calls[0].id, "call_1");
+    assert_eq!(response.tool_calls[0].name, "read_file");
+    assert_eq!(
+        response.tool_calls[0].parsed_arguments().unwrap()["path"],
+        "src/lib.rs"
+    );
+}
+
+// =============================================================================
+// Request Option Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_request_options_are_sent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "temperature": 0.0,
+            "max_tokens": 64,
+            "stop": ["END"],
+            "seed": 42,
+            "response_format": {"type": "json_object"}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"choices": [{"message": {"role": "assistant", "content": "{\"ok\":true}"}}]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let request = create_test_request().with_options(
+        RequestOptions::new()
+            .with_temperature(0.0)
+            .with_max_tokens(64)
+            .with_stop(vec!["END".to_string()])
+            .with_seed(42)
+            .with_response_format(ResponseFormat::Json),
+    );
+
+    let response = provider.chat(request).await.unwrap();
+    assert_eq!(response.content, r#"{"ok":true}"#);
+}
+
+#[tokio::test]
+async fn test_invalid_options_are_rejected_before_sending() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let request = create_test_request().with_options(RequestOptions::new().with_temperature(3.0));
+
+    let result = provider.chat(request).await;
+    assert!(matches!(result, Err(InferenceError::InvalidRequest(_))));
+}
+
+// =============================================================================
+// Streaming Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_stream_yields_deltas_and_final_usage() {
+    let server = MockServer::start().await;
+
+    let body = concat!(
+        "data: {\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":\"\"}}]}\n\n",
+        "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+        "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo\"},\"finish_reason\":\"stop\"}]}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":2,\"total_tokens\":7}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "stream": true,
+            "stream_options": { "include_usage": true }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(body),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let mut stream = provider.chat_stream(create_test_request()).await.unwrap();
+
+    let mut deltas = Vec::new();
+    let mut last = None;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.unwrap();
+        deltas.push(chunk.delta.clone());
+        last = Some(chunk);
+    }
+
+    assert_eq!(deltas.concat(), "Hello");
+    let last = last.unwrap();
+    assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    assert_eq!(last.usage.unwrap().total_tokens, 7);
+}
+
+#[tokio::test]
+async fn test_stream_open_maps_rate_limit() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(429).set_body_string("Rate limit exceeded"))
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let result = provider.chat_stream(create_test_request()).await;
+
+    assert!(matches!(result, Err(InferenceError::RateLimit)));
+}
+
+#[tokio::test]
+async fn test_stream_truncated_body_returns_network_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_string(
+                    "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"}}]}\n\n",
+                ),
+        )
+        .mount(&server)
+        .await;
+
+    let provider = create_provider_with_mock_server(&server);
+    let result = provider
+        .chat_stream(create_test_request())
+        .await
+        .unwrap()
+        .collect_response()
+        .await;
+
+    assert!(matches!(result, Err(InferenceError::NetworkError(_))));
+}
@@ -1,26 +1,46 @@
 //! Tests for the WASM plugin registry.
 //!
-//! Tests plugin discovery, loading from directories, and filtering
-//! of non-WASM files.
+//! Tests plugin discovery, loading from directories, filtering
+//! of non-WASM files and sidecar manifest handling.
 
 use brio_kernel::engine::linker::create_engine_config;
-use brio_kernel::registry::PluginRegistry;
+use brio_kernel::registry::{ComponentCache, PluginLoadError, PluginRegistry};
 use std::fs::File;
+use std::path::Path;
 use tempfile::tempdir;
 use wasmtime::Engine;
 
+/// A minimal component exporting an (empty) agent-runner interface.
+///
+/// The text format is accepted by `Component::from_file`.
+const AGENT_COMPONENT_WAT: &str = r#"(component
+    (instance $runner)
+    (export "brio:core/agent-runner" (instance $runner))
+)"#;
+
+fn new_registry() -> anyhow::Result<PluginRegistry> {
+    let config = create_engine_config();
+    PluginRegistry::new(Engine::new(&config)?)
+}
+
+fn write_plugin(dir: &Path, name: &str, manifest: &str) -> anyhow::Result<()> {
+    std::fs::write(dir.join(format!("{name}.wasm")), AGENT_COMPONENT_WAT)?;
+    std::fs::write(dir.join(format!("{name}.toml")), manifest)?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn registry_should_scan_and_load_wasm_files() -> anyhow::Result<()> {
     let dir = tempdir()?;
     let plugins_path = dir.path();
 
-    File::create(plugins_path.join("agent_alpha.wasm"))?;
-    File::create(plugins_path.join("agent_beta.wasm"))?;
+    std::fs::write(plugins_path.join("agent_alpha.wasm"), AGENT_COMPONENT_WAT)?;
+    std::fs::write(plugins_path.join("agent_beta.wasm"), AGENT_COMPONENT_WAT)?;
     File::create(plugins_path.join("README.txt"))?; // Should be ignored
 
     let config = create_engine_config();
     let engine = Engine::new(&config)?;
-    let mut registry = PluginRegistry::new(engine);
+    let mut registry = PluginRegistry::new(engine)?;
 
     registry.load_from_directory(plugins_path).await?;
 
@@ -33,3 +53,222 @@ async fn registry_should_scan_and_load_wasm_files() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn registry_applies_manifest_metadata() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    write_plugin(
+        dir.path(),
+        "coder",
+        r#"
+        id = "coder-agent"
+        version = "0.2.0"
+        description = "Writes code"
+        permissions = ["fs:write", "ai:inference"]
+        exports = ["brio:core/agent-runner"]
+        capabilities = ["code-generation"]
+        "#,
+    )?;
+
+    let mut registry = new_registry()?;
+    let report = registry.load_from_directory(dir.path()).await?;
+    assert!(
+        report.is_clean(),
+        "unexpected failures: {:?}",
+        report.failures
+    );
+
+    let plugin = registry
+        .get("coder-agent")
+        .expect("registered under manifest id");
+    assert_eq!(plugin.version.as_deref(), Some("0.2.0"));
+    assert_eq!(plugin.description.as_deref(), Some("Writes code"));
+    assert_eq!(plugin.permissions, vec!["fs:write", "ai:inference"]);
+    assert!(plugin.has_capability("code-generation"));
+    assert!(registry.get("coder").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_reports_failures_per_plugin() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    write_plugin(
+        dir.path(),
+        "good",
+        "id = \"good\"\nversion = \"1.0.0\"\nexports = [\"brio:core/agent-runner\"]",
+    )?;
+    write_plugin(
+        dir.path(),
+        "greedy",
+        "id = \"greedy\"\nversion = \"1.0.0\"\npermissions = [\"net:raw\"]",
+    )?;
+    write_plugin(
+        dir.path(),
+        "tool",
+        "id = \"tool\"\nversion = \"1.0.0\"\nexports = [\"brio:core/tool\"]",
+    )?;
+    write_plugin(dir.path(), "broken", "id = \"broken\"\nversion =")?;
+
+    let mut registry = new_registry()?;
+    let report = registry.load_from_directory(dir.path()).await?;
+
+    assert_eq!(report.loaded, vec!["good".to_string()]);
+    assert_eq!(report.failures.len(), 3);
+
+    let failure = |name: &str| {
+        report
+            .failures
+            .iter()
+            .find(|f| f.path.file_stem().and_then(|s| s.to_str()) == Some(name))
+            .map_or_else(|| panic!("no failure recorded for {name}"), |f| &f.error)
+    };
+    assert!(matches!(
+        failure("greedy"),
+        PluginLoadError::UnknownPermission { .. }
+    ));
+    assert!(matches!(
+        failure("tool"),
+        PluginLoadError::MissingExport { export, .. } if export == "brio:core/tool"
+    ));
+    assert!(matches!(
+        failure("broken"),
+        PluginLoadError::ManifestParse(_)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_rejects_invalid_component_with_manifest() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    File::create(dir.path().join("empty.wasm"))?;
+    std::fs::write(
+        dir.path().join("empty.toml"),
+        "id = \"empty\"\nversion = \"1.0.0\"",
+    )?;
+
+    let mut registry = new_registry()?;
+    let report = registry.load_from_directory(dir.path()).await?;
+
+    assert!(report.loaded.is_empty());
+    assert!(matches!(
+        report.failures[0].error,
+        PluginLoadError::InvalidComponent { .. }
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_rejects_duplicate_ids() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    write_plugin(dir.path(), "a", "id = \"agent\"\nversion = \"1.0.0\"")?;
+    write_plugin(dir.path(), "b", "id = \"agent\"\nversion = \"2.0.0\"")?;
+
+    let mut registry = new_registry()?;
+    let report = registry.load_from_directory(dir.path()).await?;
+
+    assert_eq!(report.loaded, vec!["agent".to_string()]);
+    assert!(matches!(
+        &report.failures[0].error,
+        PluginLoadError::DuplicateId { id, .. } if id == "agent"
+    ));
+    assert_eq!(
+        registry.get("agent").and_then(|p| p.version).as_deref(),
+        Some("1.0.0")
+    );
+    Ok(())
+}
+
+fn cache_entries(dir: &Path) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?)
+}
+
+#[tokio::test]
+async fn registry_caches_compiled_components_on_disk() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    let cache = tempdir()?;
+    write_plugin(
+        dir.path(),
+        "coder",
+        "id = \"coder\"\nversion = \"1.0.0\"\nexports = [\"brio:core/agent-runner\"]",
+    )?;
+
+    let mut registry = new_registry()?.with_cache_dir(cache.path());
+    assert!(registry.load_from_directory(dir.path()).await?.is_clean());
+    let entries = cache_entries(cache.path())?;
+    assert_eq!(entries.len(), 1);
+
+    // A second registry reuses the cached artifact instead of writing a new one
+    let mut reloaded = new_registry()?.with_cache_dir(cache.path());
+    assert!(reloaded.load_from_directory(dir.path()).await?.is_clean());
+    assert_eq!(cache_entries(cache.path())?, entries);
+    reloaded.prepare("coder")?;
+    Ok(())
+}
+
+#[test]
+fn concurrent_compiles_share_one_cache_entry() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    let cache_dir = tempdir()?;
+    let component = dir.path().join("coder.wasm");
+    std::fs::write(&component, AGENT_COMPONENT_WAT)?;
+    let engine = Engine::new(&create_engine_config())?;
+    let cache = ComponentCache::new(cache_dir.path());
+
+    std::thread::scope(|scope| {
+        let compiles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| cache.load_or_compile(&engine, &component)))
+            .collect();
+        for compile in compiles {
+            compile.join().expect("compile thread panicked")?;
+        }
+        anyhow::Ok(())
+    })?;
+
+    // Temporary files are renamed into place, leaving only the entry
+    assert_eq!(cache_entries(cache_dir.path())?.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_recovers_from_corrupt_cache_entry() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    let cache = tempdir()?;
+    write_plugin(dir.path(), "coder", "id = \"coder\"\nversion = \"1.0.0\"")?;
+
+    let mut registry = new_registry()?.with_cache_dir(cache.path());
+    registry.load_from_directory(dir.path()).await?;
+    let entry = cache_entries(cache.path())?.remove(0);
+    std::fs::write(&entry, b"not a compiled component")?;
+
+    let mut reloaded = new_registry()?.with_cache_dir(cache.path());
+    assert!(reloaded.load_from_directory(dir.path()).await?.is_clean());
+    assert_ne!(std::fs::read(&entry)?, b"not a compiled component");
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_validates_plugins_without_manifest() -> anyhow::Result<()> {
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("legacy.wasm"), AGENT_COMPONENT_WAT)?;
+    File::create(dir.path().join("empty.wasm"))?;
+
+    let mut registry = new_registry()?;
+    let report = registry.load_from_directory(dir.path()).await?;
+
+    assert_eq!(report.loaded, vec!["legacy".to_string()]);
+    assert!(matches!(
+        &report.failures[0].error,
+        PluginLoadError::InvalidComponent { plugin, .. } if plugin == "empty"
+    ));
+    assert_eq!(
+        registry.get("legacy").map(|p| p.exports),
+        Some(vec!["brio:core/agent-runner".to_string()])
+    );
+
+    registry.prepare("legacy")?;
+    assert!(registry.prepare("empty").is_err());
+    assert!(registry.prepare("missing").is_err());
+    Ok(())
+}
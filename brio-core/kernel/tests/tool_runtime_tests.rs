@@ -0,0 +1,349 @@
+//! Tests for the tool runtime and the `session-fs-ops` host interface.
+//!
+//! Runs a hand-written tool component. When it receives a session id, it
+//! writes its params to `out.txt` through `session-fs-ops` and returns the
+//! file read back; without a session it returns `"no-session"`. Whether the
+//! tool declares `requires-session` is substituted into the component.
+
+use anyhow::Result;
+use brio_kernel::engine::GuestError;
+use brio_kernel::engine::create_engine_config;
+use brio_kernel::engine::tools::{ToolCall, ToolError};
+use brio_kernel::host::{BrioHostState, MeshHandler, ToolInvoker};
+use brio_kernel::inference::ProviderRegistry;
+use brio_kernel::infrastructure::config::SandboxSettings;
+use brio_kernel::mesh::Payload;
+use brio_kernel::registry::PluginRegistry;
+use std::sync::Arc;
+use tempfile::{TempDir, tempdir};
+
+const TOOL_WAT: &str = r#"(component
+    (import "brio:core/session-fs-ops" (instance $fs
+        (export "read-file" (func (param "session-id" string) (param "path" string)
+            (result (result string (error string)))))
+        (export "write-file" (func (param "session-id" string) (param "path" string) (param "content" string)
+            (result (result (error string)))))
+    ))
+    (core module $mem
+        (memory (export "memory") 1)
+        (global $bump (mut i32) (i32.const 1024))
+        (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump
+                (i32.and (i32.add (i32.add (local.get $p) (local.get 3)) (i32.const 7)) (i32.const -8)))
+            (local.get $p))
+    )
+    (core instance $mi (instantiate $mem))
+    (core func $write (canon lower (func $fs "write-file") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (core func $read (canon lower (func $fs "read-file") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (core module $m
+        (import "env" "memory" (memory 1))
+        (import "host" "write-file" (func $write (param i32 i32 i32 i32 i32 i32 i32)))
+        (import "host" "read-file" (func $read (param i32 i32 i32 i32 i32)))
+        (data (i32.const 64) "out.txt")
+        (data (i32.const 80) "echo")
+        (data (i32.const 96) "echo tool")
+        (data (i32.const 112) "1.0.0")
+        (data (i32.const 128) "no-session")
+        (func (export "info") (result i32)
+            (i32.store (i32.const 256) (i32.const 80))
+            (i32.store (i32.const 260) (i32.const 4))
+            (i32.store (i32.const 264) (i32.const 96))
+            (i32.store (i32.const 268) (i32.const 9))
+            (i32.store (i32.const 272) (i32.const 112))
+            (i32.store (i32.const 276) (i32.const 5))
+            (i32.store8 (i32.const 280) (i32.const REQUIRES_SESSION))
+            (i32.const 256))
+        (func (export "execute")
+            (param $pp i32) (param $pl i32) (param $some i32) (param $sp i32) (param $sl i32)
+            (result i32)
+            (if (i32.eqz (local.get $some))
+                (then
+                    (i32.store8 (i32.const 300) (i32.const 0))
+                    (i32.store (i32.const 304) (i32.const 128))
+                    (i32.store (i32.const 308) (i32.const 10))
+                    (return (i32.const 300))))
+            (call $write (local.get $sp) (local.get $sl) (i32.const 64) (i32.const 7)
+                (local.get $pp) (local.get $pl) (i32.const 320))
+            (if (i32.load8_u (i32.const 320))
+                (then (return (i32.const 320))))
+            (call $read (local.get $sp) (local.get $sl) (i32.const 64) (i32.const 7) (i32.const 340))
+            (i32.const 340))
+    )
+    (core instance $i (instantiate $m
+        (with "env" (instance $mi))
+        (with "host" (instance (export "write-file" (func $write)) (export "read-file" (func $read))))))
+    (type $info (record (field "name" string) (field "description" string) (field "version" string)
+        (field "requires-session" bool)))
+    (func $info (result $info)
+        (canon lift (core func $i "info") (memory $mi "memory")))
+    (func $execute (param "params" string) (param "session-id" (option string))
+        (result (result string (error string)))
+        (canon lift (core func $i "execute") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (instance $tool (export "tool-info" (type $info)) (export "info" (func $info))
+        (export "execute" (func $execute)))
+    (export "brio:core/tool" (instance $tool))
+)"#;
+
+/// A stand-in for the shell tool that reports the session directory it would
+/// run commands in, looked up through `session-fs` like the real tool.
+const SHELL_WAT: &str = r#"(component
+    (import "brio:core/session-fs" (instance $fs
+        (export "get-session-path" (func (param "session-id" string)
+            (result (result string (error string)))))
+    ))
+    (core module $mem
+        (memory (export "memory") 1)
+        (global $bump (mut i32) (i32.const 1024))
+        (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+            (local $p i32)
+            (local.set $p (global.get $bump))
+            (global.set $bump
+                (i32.and (i32.add (i32.add (local.get $p) (local.get 3)) (i32.const 7)) (i32.const -8)))
+            (local.get $p))
+    )
+    (core instance $mi (instantiate $mem))
+    (core func $path (canon lower (func $fs "get-session-path") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (core module $m
+        (import "env" "memory" (memory 1))
+        (import "host" "get-session-path" (func $path (param i32 i32 i32)))
+        (data (i32.const 80) "shell")
+        (data (i32.const 96) "shell stub")
+        (data (i32.const 112) "0.2.0")
+        (func (export "info") (result i32)
+            (i32.store (i32.const 256) (i32.const 80))
+            (i32.store (i32.const 260) (i32.const 5))
+            (i32.store (i32.const 264) (i32.const 96))
+            (i32.store (i32.const 268) (i32.const 10))
+            (i32.store (i32.const 272) (i32.const 112))
+            (i32.store (i32.const 276) (i32.const 5))
+            (i32.store8 (i32.const 280) (i32.const 1))
+            (i32.const 256))
+        (func (export "execute")
+            (param $pp i32) (param $pl i32) (param $some i32) (param $sp i32) (param $sl i32)
+            (result i32)
+            (call $path (local.get $sp) (local.get $sl) (i32.const 340))
+            (i32.const 340))
+    )
+    (core instance $i (instantiate $m
+        (with "env" (instance $mi))
+        (with "host" (instance (export "get-session-path" (func $path))))))
+    (type $info (record (field "name" string) (field "description" string) (field "version" string)
+        (field "requires-session" bool)))
+    (func $info (result $info)
+        (canon lift (core func $i "info") (memory $mi "memory")))
+    (func $execute (param "params" string) (param "session-id" (option string))
+        (result (result string (error string)))
+        (canon lift (core func $i "execute") (memory $mi "memory") (realloc (func $mi "realloc"))))
+    (instance $tool (export "tool-info" (type $info)) (export "info" (func $info))
+        (export "execute" (func $execute)))
+    (export "brio:core/tool" (instance $tool))
+)"#;
+
+/// Manifest shipped with the shell tool.
+const SHELL_MANIFEST: &str = include_str!("../../components/tools/shell-tool/shell_tool.toml");
+
+// =============================================================================
+// Test Helpers
+// =============================================================================
+
+struct Fixture {
+    host: BrioHostState,
+    workspace: TempDir,
+    _plugins: TempDir,
+}
+
+/// Registers the test tool under the id `echo` and creates a host using it.
+async fn fixture(requires_session: bool, permissions: &str) -> Result<Fixture> {
+    let plugins = tempdir()?;
+    let wat = TOOL_WAT.replace("REQUIRES_SESSION", if requires_session { "1" } else { "0" });
+    std::fs::write(plugins.path().join("echo.wasm"), wat)?;
+    std::fs::write(
+        plugins.path().join("echo.toml"),
+        format!(
+            "id = \"echo\"\nversion = \"1.0.0\"\npermissions = [{permissions}]\nexports = [\"brio:core/tool\"]"
+        ),
+    )?;
+
+    let engine = wasmtime::Engine::new(&create_engine_config())?;
+    let mut registry = PluginRegistry::new(engine)?;
+    let report = registry.load_from_directory(plugins.path()).await?;
+    assert!(report.is_clean(), "{:?}", report.failures);
+
+    let host = BrioHostState::new(
+        "sqlite::memory:",
+        ProviderRegistry::new(),
+        Some(Arc::new(registry)),
+        SandboxSettings::default(),
+    )
+    .await?;
+
+    Ok(Fixture {
+        host,
+        workspace: tempdir()?,
+        _plugins: plugins,
+    })
+}
+
+impl Fixture {
+    fn begin_session(&self) -> Result<String> {
+        Ok(self
+            .host
+            .begin_session(&self.workspace.path().to_string_lossy())?)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[tokio::test(flavor = "multi_thread")]
+async fn session_aware_tool_writes_inside_its_session() -> Result<()> {
+    let fixture = fixture(true, r#""fs:read", "fs:write""#).await?;
+    let session_id = fixture.begin_session()?;
+
+    let output = fixture
+        .host
+        .call_tool("echo", "hello", Some(&session_id))
+        .await?;
+    assert_eq!(output, "hello");
+
+    let session_root = fixture
+        .host
+        .session_files(&session_id)?
+        .root()
+        .to_path_buf();
+    assert_eq!(
+        std::fs::read_to_string(session_root.join("out.txt"))?,
+        "hello"
+    );
+    assert!(!fixture.workspace.path().join("out.txt").exists());
+
+    fixture.host.commit_session(&session_id)?;
+    assert_eq!(
+        std::fs::read_to_string(fixture.workspace.path().join("out.txt"))?,
+        "hello"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tool_info_is_reported() -> Result<()> {
+    let fixture = fixture(true, "").await?;
+    let info = fixture.host.tool_info("echo").await?;
+    assert_eq!(info.name, "echo");
+    assert_eq!(info.version, "1.0.0");
+    assert!(info.requires_session);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn session_aware_tool_requires_a_session() -> Result<()> {
+    let fixture = fixture(true, r#""fs:read", "fs:write""#).await?;
+    let result = fixture.host.call_tool("echo", "hello", None).await;
+    assert!(matches!(result, Err(ToolError::SessionRequired { tool }) if tool == "echo"));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn standard_tool_does_not_receive_the_session() -> Result<()> {
+    let fixture = fixture(false, r#""fs:read", "fs:write""#).await?;
+    let session_id = fixture.begin_session()?;
+
+    let output = fixture
+        .host
+        .call_tool("echo", "hello", Some(&session_id))
+        .await?;
+    assert_eq!(output, "no-session");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn session_fs_ops_require_permissions() -> Result<()> {
+    let fixture = fixture(true, r#""fs:read""#).await?;
+    let session_id = fixture.begin_session()?;
+
+    let result = fixture
+        .host
+        .call_tool("echo", "hello", Some(&session_id))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ToolError::Guest(GuestError::Failed(message))) if message.contains("fs:write")
+    ));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unknown_session_is_rejected() -> Result<()> {
+    let fixture = fixture(true, r#""fs:read", "fs:write""#).await?;
+    let result = fixture
+        .host
+        .call_tool("echo", "hello", Some("missing"))
+        .await;
+    assert!(matches!(
+        result,
+        Err(ToolError::Guest(GuestError::Failed(message))) if message.contains("Session not found")
+    ));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tools_are_callable_over_the_mesh() -> Result<()> {
+    let fixture = fixture(true, r#""fs:read", "fs:write""#).await?;
+    let session_id = fixture.begin_session()?;
+    let call = ToolCall {
+        params: "over the mesh".to_string(),
+        session_id: Some(session_id),
+    };
+
+    let response = fixture
+        .host
+        .mesh_call(
+            "echo",
+            "execute",
+            Payload::Json(Box::new(serde_json::to_string(&call)?)),
+        )
+        .await?;
+    assert!(matches!(response, Payload::Json(s) if *s == "over the mesh"));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn verification_commands_run_in_the_session_with_the_shipped_shell_manifest() -> Result<()> {
+    let plugins = tempdir()?;
+    std::fs::write(plugins.path().join("shell_tool.wasm"), SHELL_WAT)?;
+    std::fs::write(plugins.path().join("shell_tool.toml"), SHELL_MANIFEST)?;
+
+    let engine = wasmtime::Engine::new(&create_engine_config())?;
+    let mut registry = PluginRegistry::new(engine)?;
+    let report = registry.load_from_directory(plugins.path()).await?;
+    assert!(report.is_clean(), "{:?}", report.failures);
+    let host = BrioHostState::new(
+        "sqlite::memory:",
+        ProviderRegistry::new(),
+        Some(Arc::new(registry)),
+        SandboxSettings::default(),
+    )
+    .await?;
+    let workspace = tempdir()?;
+    let session_id = host.begin_session(&workspace.path().to_string_lossy())?;
+
+    // The payload the supervisor's verifier sends for a `command` step.
+    let call = ToolCall {
+        params: serde_json::to_string(&["cargo", "test"])?,
+        session_id: Some(session_id.clone()),
+    };
+    let response = host
+        .mesh_call(
+            "shell",
+            "execute",
+            Payload::Json(Box::new(serde_json::to_string(&call)?)),
+        )
+        .await?;
+
+    let session_root = host.session_files(&session_id)?.root().to_path_buf();
+    assert!(matches!(response, Payload::Json(dir) if *dir == session_root.to_string_lossy()));
+    Ok(())
+}
@@ -23,7 +23,7 @@ async fn wasm_engine_instantiates_empty_component() -> Result<()> {
     let wasm_engine = create_wasm_engine(&engine)?;
     let component = load_empty_component(&engine)?;
 
-    let mut store = wasm_engine.prepare_store(host_state);
+    let mut store = wasm_engine.prepare_store(host_state)?;
     wasm_engine
         .linker()
         .instantiate_async(&mut store, &component)